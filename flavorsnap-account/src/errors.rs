@@ -0,0 +1,42 @@
+use soroban_sdk::contracterror;
+
+/// Errors returned by `CreatorAccountContract`.
+///
+/// Discriminants are part of the public ABI and must never be renumbered;
+/// new conditions are appended at the end.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AccountError {
+    /// The account would not have exactly three distinct signer keys.
+    InvalidSigners = 1,
+    /// A signature is from a key that is not one of the account's signers.
+    UnknownSigner = 2,
+    /// Signatures are not in strictly ascending public key order.
+    SignaturesOutOfOrder = 3,
+    /// Fewer signers than the threshold signed.
+    NotEnoughSignatures = 4,
+}
+
+impl AccountError {
+    pub fn from_code(code: u32) -> Option<Self> {
+        let error = match code {
+            1 => AccountError::InvalidSigners,
+            2 => AccountError::UnknownSigner,
+            3 => AccountError::SignaturesOutOfOrder,
+            4 => AccountError::NotEnoughSignatures,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Short human-readable description, surfaced through `error_message`
+    pub fn message(&self) -> &'static str {
+        match self {
+            AccountError::InvalidSigners => "account needs three distinct signers",
+            AccountError::UnknownSigner => "unknown signer",
+            AccountError::SignaturesOutOfOrder => "signatures out of order",
+            AccountError::NotEnoughSignatures => "not enough signatures",
+        }
+    }
+}
@@ -0,0 +1,125 @@
+#![no_std]
+
+use soroban_sdk::{
+    auth::{Context, CustomAccountInterface}, contract, contractimpl, contracttype, crypto::Hash, symbol_short, BytesN,
+    Env, String, Vec
+};
+
+mod errors;
+
+pub use errors::AccountError;
+
+/// Keys guarding one account
+pub const SIGNER_COUNT: u32 = 3;
+/// Signatures needed to authorize anything, replacing a signer included
+pub const THRESHOLD: u32 = 2;
+
+/// One signer's ed25519 signature over the authorization payload
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountSignature {
+    pub public_key: BytesN<32>,
+    pub signature: BytesN<64>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Signers,
+}
+
+/// Smart wallet for creators holding valuable IP: three ed25519 keys, e.g.
+/// the creator's phone, a backup kept offline and a trusted guardian, any two
+/// of which authorize the account. A lost key is recovered from by the other
+/// two replacing it, so no single key is ever a point of failure. The account
+/// address can own IPs, sign as an IP signer or hold revenue like any other.
+#[contract]
+pub struct CreatorAccountContract;
+
+#[contractimpl]
+impl CreatorAccountContract {
+    pub fn __constructor(env: Env, signers: Vec<BytesN<32>>) -> Result<(), AccountError> {
+        Self::require_valid_signers(&signers)?;
+        env.storage().instance().set(&DataKey::Signers, &signers);
+        Ok(())
+    }
+
+    /// Describe an `AccountError` code returned by this contract
+    pub fn error_message(env: Env, code: u32) -> String {
+        let message = AccountError::from_code(code)
+            .map(|error| error.message())
+            .unwrap_or("unknown error");
+        String::from_str(&env, message)
+    }
+
+    pub fn signers(env: Env) -> Vec<BytesN<32>> {
+        env.storage().instance().get(&DataKey::Signers).unwrap()
+    }
+
+    /// Swap signer key `old` for `new`, as when a key is lost or rotated
+    /// (Requires the account's own authorization, so `THRESHOLD` signers)
+    pub fn replace_signer(env: Env, old: BytesN<32>, new: BytesN<32>) -> Result<(), AccountError> {
+        env.current_contract_address().require_auth();
+
+        let mut signers = Self::signers(env.clone());
+        let index = signers.first_index_of(&old).ok_or(AccountError::UnknownSigner)?;
+        signers.set(index, new.clone());
+        Self::require_valid_signers(&signers)?;
+        env.storage().instance().set(&DataKey::Signers, &signers);
+
+        env.events().publish((symbol_short!("signer"),), (old, new));
+        Ok(())
+    }
+}
+
+#[contractimpl]
+impl CustomAccountInterface for CreatorAccountContract {
+    type Signature = Vec<AccountSignature>;
+    type Error = AccountError;
+
+    /// Accept the payload if at least `THRESHOLD` distinct signers signed it,
+    /// their signatures sorted by public key. Any invalid signature aborts.
+    #[allow(non_snake_case)]
+    fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signatures: Vec<AccountSignature>,
+        _auth_contexts: Vec<Context>,
+    ) -> Result<(), AccountError> {
+        let signers = Self::signers(env.clone());
+        let payload = signature_payload.to_bytes().into();
+
+        let mut previous: Option<BytesN<32>> = None;
+        for signature in signatures.iter() {
+            if previous.is_some_and(|previous| previous >= signature.public_key) {
+                return Err(AccountError::SignaturesOutOfOrder);
+            }
+            if !signers.contains(&signature.public_key) {
+                return Err(AccountError::UnknownSigner);
+            }
+            env.crypto().ed25519_verify(&signature.public_key, &payload, &signature.signature);
+            previous = Some(signature.public_key);
+        }
+        if signatures.len() < THRESHOLD {
+            return Err(AccountError::NotEnoughSignatures);
+        }
+        Ok(())
+    }
+}
+
+impl CreatorAccountContract {
+    fn require_valid_signers(signers: &Vec<BytesN<32>>) -> Result<(), AccountError> {
+        if signers.len() != SIGNER_COUNT {
+            return Err(AccountError::InvalidSigners);
+        }
+        for (index, signer) in signers.iter().enumerate() {
+            if signers.last_index_of(&signer) != Some(index as u32) {
+                return Err(AccountError::InvalidSigners);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,114 @@
+#![cfg(test)]
+
+use super::*;
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{vec, Address, IntoVal};
+
+/// Three signer keys, sorted by public key as signatures must be
+fn signing_keys() -> [SigningKey; 3] {
+    let mut keys = [1u8, 2, 3].map(|seed| SigningKey::from_bytes(&[seed; 32]));
+    keys.sort_by_key(|key| key.verifying_key().to_bytes());
+    keys
+}
+
+fn public_key(env: &Env, key: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &key.verifying_key().to_bytes())
+}
+
+fn sign(env: &Env, key: &SigningKey, payload: &BytesN<32>) -> AccountSignature {
+    AccountSignature {
+        public_key: public_key(env, key),
+        signature: BytesN::from_array(env, &key.sign(&payload.to_array()).to_bytes()),
+    }
+}
+
+/// Signatures of `keys` over `payload`, in the order the account expects
+fn sign_sorted(env: &Env, keys: &[&SigningKey], payload: &BytesN<32>) -> Vec<AccountSignature> {
+    let mut keys = keys.to_vec();
+    keys.sort_by_key(|key| key.verifying_key().to_bytes());
+    let mut signatures = Vec::new(env);
+    for key in keys {
+        signatures.push_back(sign(env, key, payload));
+    }
+    signatures
+}
+
+fn setup(env: &Env) -> (Address, [SigningKey; 3]) {
+    let keys = signing_keys();
+    let signers = vec![env, public_key(env, &keys[0]), public_key(env, &keys[1]), public_key(env, &keys[2])];
+    (env.register(CreatorAccountContract, (signers,)), keys)
+}
+
+fn check_auth(env: &Env, account: &Address, payload: &BytesN<32>, signatures: Vec<AccountSignature>) -> Result<(), AccountError> {
+    env.try_invoke_contract_check_auth::<AccountError>(account, payload, signatures.into_val(env), &Vec::new(env))
+        .map_err(|error| error.unwrap())
+}
+
+#[test]
+fn test_any_two_of_three_signers_authorize() {
+    let env = Env::default();
+    let (account, keys) = setup(&env);
+    let payload = BytesN::from_array(&env, &[7; 32]);
+
+    for (first, second) in [(0, 1), (0, 2), (1, 2)] {
+        let signatures = vec![&env, sign(&env, &keys[first], &payload), sign(&env, &keys[second], &payload)];
+        assert_eq!(check_auth(&env, &account, &payload, signatures), Ok(()));
+    }
+    let all = vec![&env, sign(&env, &keys[0], &payload), sign(&env, &keys[1], &payload), sign(&env, &keys[2], &payload)];
+    assert_eq!(check_auth(&env, &account, &payload, all), Ok(()));
+
+    let one = vec![&env, sign(&env, &keys[2], &payload)];
+    assert_eq!(check_auth(&env, &account, &payload, one), Err(AccountError::NotEnoughSignatures));
+    assert_eq!(check_auth(&env, &account, &payload, Vec::new(&env)), Err(AccountError::NotEnoughSignatures));
+}
+
+#[test]
+fn test_signatures_must_be_sorted_distinct_and_from_signers() {
+    let env = Env::default();
+    let (account, keys) = setup(&env);
+    let payload = BytesN::from_array(&env, &[7; 32]);
+
+    let reversed = vec![&env, sign(&env, &keys[1], &payload), sign(&env, &keys[0], &payload)];
+    assert_eq!(check_auth(&env, &account, &payload, reversed), Err(AccountError::SignaturesOutOfOrder));
+    let repeated = vec![&env, sign(&env, &keys[0], &payload), sign(&env, &keys[0], &payload)];
+    assert_eq!(check_auth(&env, &account, &payload, repeated), Err(AccountError::SignaturesOutOfOrder));
+
+    let stranger = SigningKey::from_bytes(&[0xff; 32]);
+    let signatures = sign_sorted(&env, &[&keys[0], &stranger], &payload);
+    assert_eq!(check_auth(&env, &account, &payload, signatures), Err(AccountError::UnknownSigner));
+
+    // A signer's signature over anything else aborts authorization
+    let other = BytesN::from_array(&env, &[8; 32]);
+    let forged = vec![&env, sign(&env, &keys[0], &payload), sign(&env, &keys[1], &other)];
+    assert!(env.try_invoke_contract_check_auth::<AccountError>(&account, &payload, forged.into_val(&env), &Vec::new(&env)).is_err());
+}
+
+#[test]
+fn test_lost_key_is_replaced_by_the_other_two() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (account_id, keys) = setup(&env);
+    let account = CreatorAccountContractClient::new(&env, &account_id);
+
+    // keys[0] is lost; a fresh key takes its slot and the lost one no longer signs
+    let replacement = SigningKey::from_bytes(&[4; 32]);
+    account.replace_signer(&public_key(&env, &keys[0]), &public_key(&env, &replacement));
+    assert_eq!(env.auths()[0].0, account_id);
+    assert_eq!(account.signers().first_index_of(public_key(&env, &replacement)), Some(0));
+
+    let payload = BytesN::from_array(&env, &[7; 32]);
+    let lost = vec![&env, sign(&env, &keys[0], &payload), sign(&env, &keys[1], &payload)];
+    assert_eq!(check_auth(&env, &account_id, &payload, lost), Err(AccountError::UnknownSigner));
+    let recovered = sign_sorted(&env, &[&keys[1], &replacement], &payload);
+    assert_eq!(check_auth(&env, &account_id, &payload, recovered), Ok(()));
+
+    assert_eq!(
+        account.try_replace_signer(&public_key(&env, &keys[0]), &public_key(&env, &keys[1])),
+        Err(Ok(AccountError::UnknownSigner))
+    );
+    assert_eq!(
+        account.try_replace_signer(&public_key(&env, &keys[1]), &public_key(&env, &keys[2])),
+        Err(Ok(AccountError::InvalidSigners))
+    );
+    assert_eq!(account.error_message(&3), String::from_str(&env, "signatures out of order"));
+}
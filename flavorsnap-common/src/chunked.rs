@@ -0,0 +1,124 @@
+use crate::PageCursor;
+use core::marker::PhantomData;
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val, Vec};
+
+/// Members per storage page, small enough that a page of the longest keys the
+/// contracts index stays far below the ledger entry size limit
+pub const CHUNK_SIZE: u32 = 64;
+
+/// List in persistent storage, split into pages of `CHUNK_SIZE` members so no
+/// single entry grows with the list. Members are appended and only leave by
+/// `swap_remove`, which keeps every page but the last full. The length lives under
+/// `key` and page `n` under `(key, n)`, so a contract gives each index one key
+/// of its own `DataKey` and never has to name the pages.
+pub struct ChunkedVec<K, T> {
+    key: K,
+    member: PhantomData<T>,
+}
+
+impl<K, T> ChunkedVec<K, T>
+where
+    K: IntoVal<Env, Val> + Clone,
+    Val: TryFromVal<Env, K>,
+    T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    pub fn new(key: K) -> Self {
+        ChunkedVec { key, member: PhantomData }
+    }
+
+    pub fn len(&self, env: &Env) -> u32 {
+        env.storage().persistent().get(&self.key).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self, env: &Env) -> bool {
+        self.len(env) == 0
+    }
+
+    pub fn get(&self, env: &Env, index: u32) -> Option<T> {
+        if index >= self.len(env) {
+            return None;
+        }
+        self.read_page(env, index / CHUNK_SIZE).get(index % CHUNK_SIZE)
+    }
+
+    /// Append `value`, returning its index
+    pub fn push(&self, env: &Env, value: T) -> u32 {
+        let index = self.len(env);
+        let mut page = self.read_page(env, index / CHUNK_SIZE);
+        page.push_back(value);
+        self.write_page(env, index / CHUNK_SIZE, &page);
+        env.storage().persistent().set(&self.key, &(index + 1));
+        index
+    }
+
+    /// Overwrite the member at `index`, returning false if there is none
+    pub fn set(&self, env: &Env, index: u32, value: T) -> bool {
+        if index >= self.len(env) {
+            return false;
+        }
+        let mut page = self.read_page(env, index / CHUNK_SIZE);
+        page.set(index % CHUNK_SIZE, value);
+        self.write_page(env, index / CHUNK_SIZE, &page);
+        true
+    }
+
+    /// Remove and return the member at `index`, moving the last member into
+    /// its place. Callers that track positions re-read `index` afterwards.
+    pub fn swap_remove(&self, env: &Env, index: u32) -> Option<T> {
+        let len = self.len(env);
+        if index >= len {
+            return None;
+        }
+        let last = len - 1;
+        let last_no = last / CHUNK_SIZE;
+        let mut last_page = self.read_page(env, last_no);
+        let moved = last_page.pop_back()?;
+        let removed = if index == last {
+            moved
+        } else if index / CHUNK_SIZE == last_no {
+            let removed = last_page.get(index % CHUNK_SIZE)?;
+            last_page.set(index % CHUNK_SIZE, moved);
+            removed
+        } else {
+            let mut page = self.read_page(env, index / CHUNK_SIZE);
+            let removed = page.get(index % CHUNK_SIZE)?;
+            page.set(index % CHUNK_SIZE, moved);
+            self.write_page(env, index / CHUNK_SIZE, &page);
+            removed
+        };
+
+        if last_page.is_empty() {
+            env.storage().persistent().remove(&(self.key.clone(), last_no));
+        } else {
+            self.write_page(env, last_no, &last_page);
+        }
+        env.storage().persistent().set(&self.key, &last);
+        Some(removed)
+    }
+
+    /// Members at the positions `cursor` covers, reading each page once
+    pub fn page(&self, env: &Env, cursor: &PageCursor) -> Vec<T> {
+        let mut members = Vec::new(env);
+        let mut loaded: Option<(u32, Vec<T>)> = None;
+        for index in cursor.range() {
+            let page_no = index / CHUNK_SIZE;
+            if loaded.as_ref().is_none_or(|(loaded_no, _)| *loaded_no != page_no) {
+                loaded = Some((page_no, self.read_page(env, page_no)));
+            }
+            if let Some(member) = loaded.as_ref().and_then(|(_, page)| page.get(index % CHUNK_SIZE)) {
+                members.push_back(member);
+            }
+        }
+        members
+    }
+
+    fn read_page(&self, env: &Env, page_no: u32) -> Vec<T> {
+        env.storage().persistent()
+            .get(&(self.key.clone(), page_no))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn write_page(&self, env: &Env, page_no: u32, page: &Vec<T>) {
+        env.storage().persistent().set(&(self.key.clone(), page_no), page);
+    }
+}
@@ -0,0 +1,41 @@
+#![no_std]
+
+//! Vocabulary shared by the FlavorSnap contracts, so cross-contract calls
+//! encode ids, amounts and licenses the same way on both sides
+
+mod chunked;
+mod license;
+mod merkle;
+mod page;
+mod rounding;
+mod split;
+
+pub use chunked::{ChunkedVec, CHUNK_SIZE};
+pub use license::{AppealStatus, License, LicenseType};
+pub use merkle::{merkle_root, MerkleAnchor};
+pub use page::{IdCursor, PageCursor};
+pub use rounding::{bps_of, mul_div, Rounding};
+pub use split::{split, split_amounts, SplitShare};
+
+/// Id of an IP registered with a licensing contract
+pub type IpId = u64;
+
+/// Id of a license certificate, unique within its licensing contract
+pub type LicenseId = u64;
+
+/// Fixed-point share in basis points, from 0 to `MAX_BPS`
+pub type Bps = u32;
+
+pub const MAX_BPS: Bps = 10_000;
+
+/// `MAX_BPS` widened for token arithmetic: `amount * bps / BPS_DENOMINATOR`
+pub const BPS_DENOMINATOR: i128 = MAX_BPS as i128;
+
+/// Classifier confidence as fixed-point basis points, from 0 (no confidence)
+/// to `MAX_CONFIDENCE_BPS` (certainty)
+pub type ConfidenceBps = Bps;
+
+pub const MAX_CONFIDENCE_BPS: ConfidenceBps = MAX_BPS;
+
+#[cfg(test)]
+mod test;
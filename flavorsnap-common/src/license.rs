@@ -0,0 +1,58 @@
+use crate::{IpId, LicenseId};
+use soroban_sdk::{contracttype, Address, BytesN, Symbol};
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LicenseType {
+    /// Sole license on the IP; no other license of any tier may be active
+    Exclusive,
+    /// Legacy shared license, superseded by the usage tiers below
+    NonExclusive,
+    /// Private, non-commercial use; shared
+    Personal,
+    /// News and commentary use; shared
+    Editorial,
+    /// Use in commercial products and advertising; shared
+    Commercial,
+    /// Broadcast use; at most one active broadcast license per IP, alongside
+    /// any number of shared ones
+    Broadcast,
+}
+
+/// Where a revoked licensee's appeal stands
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AppealStatus {
+    NotFiled,
+    Pending,
+    /// The revocation was overturned and the license reinstated
+    Upheld,
+    Rejected,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct License {
+    pub license_id: LicenseId,
+    pub licensee: Address,
+    pub ip_id: IpId,
+    pub license_type: LicenseType,
+    pub is_active: bool,
+    pub issued_at: u64,
+    /// Ledger sequence the license was issued in
+    pub issued_ledger: u32,
+    /// Position in the issuing contract's order of issuance, strictly
+    /// increasing across all its licenses, so two issued in the same ledger
+    /// still have a provable order
+    pub sequence: u64,
+    /// Last royalty or usage report paid on the license, or `issued_at`
+    pub last_activity_at: u64,
+    pub expires_at: Option<u64>,
+    pub terms_hash: Option<BytesN<32>>,
+    pub price_paid: i128,
+    /// Why the license was revoked, kept after a successful appeal
+    pub revocation_reason: Option<Symbol>,
+    /// Last moment `appeal_revocation` is accepted, if the IP offers appeals
+    pub appeal_deadline: Option<u64>,
+    pub appeal: AppealStatus,
+}
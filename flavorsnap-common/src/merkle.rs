@@ -0,0 +1,64 @@
+use soroban_sdk::{contracttype, Bytes, BytesN, Env, Vec};
+
+/// Published by a contract over a batch of its records, for mirroring proof
+/// of them onto other chains or timestamping services. The contract itself
+/// emits it, so anyone reading the event from that contract id trusts only
+/// the ledger, not whoever asked for the anchor.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerkleAnchor {
+    /// `merkle_root` of the batch's record leaves, in the order requested
+    pub root: BytesN<32>,
+    pub leaf_count: u32,
+    pub ledger_sequence: u32,
+    pub timestamp: u64,
+}
+
+impl MerkleAnchor {
+    /// Anchor `leaves` at the current ledger. Panics if there are none.
+    pub fn new(env: &Env, leaves: &Vec<BytesN<32>>) -> Self {
+        MerkleAnchor {
+            root: merkle_root(env, leaves),
+            leaf_count: leaves.len(),
+            ledger_sequence: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+        }
+    }
+}
+
+/// Root of the binary Merkle tree over `leaves`, recomputable off-chain:
+///
+/// ```text
+/// node(leaf)         = sha256(0x00 || leaf)
+/// node(left, right)  = sha256(0x01 || left || right)
+/// ```
+///
+/// Levels pair nodes left to right; an unpaired last node moves up a level
+/// unchanged. The prefixes keep a leaf from being passed off as an inner
+/// node. Panics if `leaves` is empty.
+pub fn merkle_root(env: &Env, leaves: &Vec<BytesN<32>>) -> BytesN<32> {
+    assert!(!leaves.is_empty(), "merkle tree needs a leaf");
+
+    let mut level = Vec::new(env);
+    for leaf in leaves.iter() {
+        let mut preimage = Bytes::from_array(env, &[0]);
+        preimage.extend_from_array(&leaf.to_array());
+        level.push_back(BytesN::from(env.crypto().sha256(&preimage)));
+    }
+    while level.len() > 1 {
+        let mut parents = Vec::new(env);
+        for index in (0..level.len()).step_by(2) {
+            let left = level.get_unchecked(index);
+            let Some(right) = level.get(index + 1) else {
+                parents.push_back(left);
+                continue;
+            };
+            let mut preimage = Bytes::from_array(env, &[1]);
+            preimage.extend_from_array(&left.to_array());
+            preimage.extend_from_array(&right.to_array());
+            parents.push_back(BytesN::from(env.crypto().sha256(&preimage)));
+        }
+        level = parents;
+    }
+    level.get_unchecked(0)
+}
@@ -0,0 +1,50 @@
+use core::ops::Range;
+use soroban_sdk::Vec;
+
+/// Resume point of a listing kept in ascending id order: the last id a page
+/// returned, or 0 for the first page. Unlike a position it stays valid when
+/// ids are removed from, or appended to, the listing between pages.
+pub type IdCursor = u64;
+
+/// One page of a listing: at most `limit` positions from
+/// `start`, capped at the contract's page size and the listing's length
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PageCursor {
+    start: u32,
+    end: u32,
+    len: u32,
+}
+
+impl PageCursor {
+    pub fn new(start: u32, limit: u32, max_page: u32, len: u32) -> Self {
+        let end = len.min(start.saturating_add(limit.min(max_page)));
+        PageCursor { start, end, len }
+    }
+
+    /// Page of at most `limit` ids following `cursor` in `ids`, which must be
+    /// ascending and start above 0
+    pub fn after(ids: &Vec<u64>, cursor: IdCursor, limit: u32, max_page: u32) -> Self {
+        let start = match ids.binary_search(cursor) {
+            Ok(position) => position + 1,
+            Err(position) => position,
+        };
+        Self::new(start, limit, max_page, ids.len())
+    }
+
+    /// Positions this page covers
+    pub fn range(&self) -> Range<u32> {
+        self.start..self.end
+    }
+
+    /// Where the following page starts, or `None` once the listing is exhausted
+    pub fn next_start(&self) -> Option<u32> {
+        (self.end < self.len).then_some(self.end)
+    }
+
+    /// Cursor to pass for the page following this one of `ids`, or `None`
+    /// once the listing is exhausted
+    pub fn next_after(&self, ids: &Vec<u64>) -> Option<IdCursor> {
+        self.next_start()?;
+        Some(if self.end == 0 { 0 } else { ids.get_unchecked(self.end - 1) })
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{Bps, BPS_DENOMINATOR};
+use soroban_sdk::contracttype;
+
+/// How a fractional token amount is rounded to whole units
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Toward negative infinity; plain integer division for non-negative amounts
+    Floor,
+    /// Toward positive infinity
+    Ceil,
+    /// To the nearest unit, ties to the even one (banker's rounding)
+    HalfEven,
+}
+
+/// `amount * bps / BPS_DENOMINATOR`, rounded by `rounding`
+pub fn bps_of(amount: i128, bps: Bps, rounding: Rounding) -> i128 {
+    mul_div(amount, bps as i128, BPS_DENOMINATOR, rounding)
+}
+
+/// `value * numerator / denominator`, rounded by `rounding`. Panics on
+/// overflow or a non-positive `denominator`.
+pub fn mul_div(value: i128, numerator: i128, denominator: i128, rounding: Rounding) -> i128 {
+    assert!(denominator > 0, "denominator must be positive");
+    let product = value.checked_mul(numerator).expect("amount overflow");
+    let quotient = product.div_euclid(denominator);
+    let remainder = product.rem_euclid(denominator);
+    if remainder == 0 {
+        return quotient;
+    }
+    match rounding {
+        Rounding::Floor => quotient,
+        Rounding::Ceil => quotient + 1,
+        Rounding::HalfEven => match (remainder * 2).cmp(&denominator) {
+            core::cmp::Ordering::Less => quotient,
+            core::cmp::Ordering::Greater => quotient + 1,
+            core::cmp::Ordering::Equal => quotient + (quotient & 1),
+        },
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{bps_of, Bps, Rounding, MAX_BPS};
+use soroban_sdk::{token, Address, Env, Vec};
+
+/// A recipient of a split and its share of the amount
+pub type SplitShare = (Address, Bps);
+
+/// Divide `amount` among `shares`, which must add up to `MAX_BPS`. Each part
+/// is the share's `bps_of` the amount, never more than is left, and the last
+/// recipient takes whatever remains, so the parts always add up to `amount`.
+/// Panics on a negative `amount` or shares not adding up to `MAX_BPS`.
+pub fn split_amounts(amount: i128, shares: &Vec<SplitShare>, rounding: Rounding) -> Vec<i128> {
+    assert!(amount >= 0, "negative split amount");
+    let total: u64 = shares.iter().map(|(_, bps)| bps as u64).sum();
+    assert!(total == MAX_BPS as u64, "split shares must add up to MAX_BPS");
+
+    let mut parts = Vec::new(shares.env());
+    let mut remaining = amount;
+    for (index, (_, bps)) in shares.iter().enumerate() {
+        let part = if index as u32 + 1 == shares.len() {
+            remaining
+        } else {
+            bps_of(amount, bps, rounding).min(remaining)
+        };
+        parts.push_back(part);
+        remaining -= part;
+    }
+    parts
+}
+
+/// Pay `amount` of `token` from `from` to `recipients`, divided by
+/// `split_amounts` rounding down, so rounding dust goes to the last recipient.
+/// Empty parts and parts owed to `from` itself are not transferred. Unless
+/// `from` is the calling contract paying out of its own balance, it must
+/// authorize every transfer. Returns the parts.
+pub fn split(env: &Env, token: &Address, from: &Address, recipients: &Vec<SplitShare>, amount: i128) -> Vec<i128> {
+    let parts = split_amounts(amount, recipients, Rounding::Floor);
+    let token = token::Client::new(env, token);
+    for ((recipient, _), part) in recipients.iter().zip(parts.iter()) {
+        if part > 0 && recipient != *from {
+            token.transfer(from, &recipient, &part);
+        }
+    }
+    parts
+}
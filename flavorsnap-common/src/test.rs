@@ -0,0 +1,368 @@
+use super::*;
+use soroban_sdk::{
+    contract, contracttype, testutils::{Address as _, EnvTestConfig}, token, vec, xdr::ToXdr, Address, Bytes, BytesN, Env, String, Vec
+};
+
+/// Contract whose storage the chunked vectors under test live in
+#[contract]
+struct Host;
+
+#[contracttype]
+#[derive(Clone)]
+enum TestKey {
+    Members,
+    Other,
+}
+
+#[test]
+fn test_page_cursor_walks_listing() {
+    let first = PageCursor::new(0, 3, 50, 7);
+    assert_eq!((first.range(), first.next_start()), (0..3, Some(3)));
+
+    // Limits above the page size are capped
+    let capped = PageCursor::new(3, 100, 2, 7);
+    assert_eq!((capped.range(), capped.next_start()), (3..5, Some(5)));
+
+    let last = PageCursor::new(5, 3, 50, 7);
+    assert_eq!((last.range(), last.next_start()), (5..7, None));
+
+    let past_end = PageCursor::new(9, 3, 50, 7);
+    assert!(past_end.range().is_empty());
+    assert_eq!(past_end.next_start(), None);
+}
+
+#[test]
+fn test_bps_constants_agree() {
+    assert_eq!(BPS_DENOMINATOR, MAX_BPS as i128);
+    assert_eq!(MAX_CONFIDENCE_BPS, MAX_BPS);
+}
+
+#[test]
+fn test_rounding_at_boundaries() {
+    use Rounding::{Ceil, Floor, HalfEven};
+    // (amount, bps, floor, ceil, half-even)
+    let cases: [(i128, Bps, i128, i128, i128); 12] = [
+        (0, 5_000, 0, 0, 0),
+        (1, 0, 0, 0, 0),
+        (1, 1, 0, 1, 0),
+        (1, 4_999, 0, 1, 0),
+        (1, 5_000, 0, 1, 0),
+        (1, 5_001, 0, 1, 1),
+        (1, MAX_BPS, 1, 1, 1),
+        (3, 5_000, 1, 2, 2),
+        (5, 5_000, 2, 3, 2),
+        (10_000, 1, 1, 1, 1),
+        (-1, 5_000, -1, 0, 0),
+        (-3, 5_000, -2, -1, -2),
+    ];
+    for (amount, bps, floor, ceil, half_even) in cases {
+        assert_eq!(
+            (bps_of(amount, bps, Floor), bps_of(amount, bps, Ceil), bps_of(amount, bps, HalfEven)),
+            (floor, ceil, half_even),
+            "{amount} at {bps} bps"
+        );
+    }
+
+    let max_amount = i128::MAX / BPS_DENOMINATOR;
+    assert_eq!(bps_of(max_amount, MAX_BPS, Ceil), max_amount);
+    assert_eq!(mul_div(7, 1, 2, HalfEven), 4);
+    assert_eq!(mul_div(9, 1, 2, HalfEven), 4);
+}
+
+#[test]
+#[should_panic(expected = "amount overflow")]
+fn test_rounding_rejects_overflow() {
+    bps_of(i128::MAX, 2, Rounding::Floor);
+}
+
+#[test]
+fn test_rounding_complements_conserve_every_amount() {
+    for amount in 0..=100i128 {
+        for bps in 0..=MAX_BPS {
+            let exact_times_denominator = amount * bps as i128;
+            let floor = bps_of(amount, bps, Rounding::Floor);
+            let ceil = bps_of(amount, bps, Rounding::Ceil);
+            let half_even = bps_of(amount, bps, Rounding::HalfEven);
+
+            assert!(floor * BPS_DENOMINATOR <= exact_times_denominator);
+            assert!(ceil * BPS_DENOMINATOR >= exact_times_denominator);
+            assert!(ceil - floor <= 1);
+            assert!((half_even * BPS_DENOMINATOR - exact_times_denominator).abs() * 2 <= BPS_DENOMINATOR);
+
+            // A share and its complement rounded the opposite way split the
+            // amount exactly, so no atom is created or stranded
+            let complement = MAX_BPS - bps;
+            assert_eq!(floor + bps_of(amount, complement, Rounding::Ceil), amount);
+            assert_eq!(ceil + bps_of(amount, complement, Rounding::Floor), amount);
+        }
+    }
+}
+
+/// Deterministic xorshift stream, so failing walks can be replayed by seed
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound
+    }
+}
+
+#[test]
+fn test_split_amounts_conserve_every_amount() {
+    let env = Env::new_with_config(EnvTestConfig { capture_snapshot_at_drop: false });
+    env.cost_estimate().budget().reset_unlimited();
+    for seed in 1..=100u64 {
+        let mut rng = Rng(seed);
+        // Random shares adding up to MAX_BPS, some of them empty
+        let mut shares = Vec::new(&env);
+        let mut left = MAX_BPS;
+        for _ in 0..rng.below(6) {
+            let bps = rng.below(left as u64 + 1) as Bps;
+            shares.push_back((Address::generate(&env), bps));
+            left -= bps;
+        }
+        shares.push_back((Address::generate(&env), left));
+
+        for amount in (0..=100i128).chain([9_999, 10_001, i128::MAX / BPS_DENOMINATOR]) {
+            for rounding in [Rounding::Floor, Rounding::Ceil, Rounding::HalfEven] {
+                let parts = split_amounts(amount, &shares, rounding);
+                assert_eq!(parts.len(), shares.len());
+                assert_eq!(parts.iter().sum::<i128>(), amount, "seed {seed}: {amount} not conserved");
+                for (index, ((_, bps), part)) in shares.iter().zip(parts.iter()).enumerate() {
+                    assert!(part >= 0, "seed {seed}: negative part");
+                    // Rounded down, nothing runs out early and shares are exact
+                    if index as u32 + 1 < shares.len() {
+                        assert!(part <= bps_of(amount, bps, rounding));
+                        assert!(rounding != Rounding::Floor || part == bps_of(amount, bps, rounding));
+                    }
+                }
+            }
+        }
+    }
+
+    // Shares rounded up never hand out more than the amount
+    let three_ways = vec![&env, (Address::generate(&env), 3_333), (Address::generate(&env), 3_333), (Address::generate(&env), 3_334)];
+    assert_eq!(split_amounts(1, &three_ways, Rounding::Ceil), vec![&env, 1, 0, 0]);
+    assert_eq!(split_amounts(1, &three_ways, Rounding::Floor), vec![&env, 0, 0, 1]);
+}
+
+#[test]
+#[should_panic(expected = "split shares must add up to MAX_BPS")]
+fn test_split_amounts_rejects_partial_shares() {
+    let env = Env::default();
+    split_amounts(100, &vec![&env, (Address::generate(&env), 9_999)], Rounding::Floor);
+}
+
+#[test]
+#[should_panic(expected = "negative split amount")]
+fn test_split_amounts_rejects_negative_amount() {
+    let env = Env::default();
+    split_amounts(-1, &vec![&env, (Address::generate(&env), MAX_BPS)], Rounding::Floor);
+}
+
+#[test]
+fn test_split_transfers_parts_with_dust_to_last() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token = token::Client::new(&env, &token_address);
+    let (payer, creator, treasury) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+    token::StellarAssetClient::new(&env, &token_address).mint(&payer, &1_000);
+
+    // The payer's own part stays with it
+    let recipients = vec![&env, (creator.clone(), 3_333), (payer.clone(), 3_333), (treasury.clone(), 3_334)];
+    assert_eq!(split(&env, &token_address, &payer, &recipients, 100), vec![&env, 33, 33, 34]);
+    assert_eq!((token.balance(&payer), token.balance(&creator), token.balance(&treasury)), (933, 33, 34));
+
+    // Nothing moves for empty parts
+    assert_eq!(split(&env, &token_address, &payer, &recipients, 0), vec![&env, 0, 0, 0]);
+    assert_eq!(token.balance(&payer), 933);
+}
+
+#[test]
+fn test_merkle_root_matches_hand_built_tree() {
+    let env = Env::default();
+    let hash = |bytes: &[u8]| BytesN::<32>::from(env.crypto().sha256(&Bytes::from_slice(&env, bytes)));
+    let node = |leaf: &BytesN<32>| {
+        let mut preimage = [0u8; 33];
+        preimage[1..].copy_from_slice(&leaf.to_array());
+        hash(&preimage)
+    };
+    let parent = |left: &BytesN<32>, right: &BytesN<32>| {
+        let mut preimage = [1u8; 65];
+        preimage[1..33].copy_from_slice(&left.to_array());
+        preimage[33..].copy_from_slice(&right.to_array());
+        hash(&preimage)
+    };
+    let [a, b, c] = [1u8, 2, 3].map(|byte| BytesN::from_array(&env, &[byte; 32]));
+
+    assert_eq!(merkle_root(&env, &vec![&env, a.clone()]), node(&a));
+    let ab = parent(&node(&a), &node(&b));
+    assert_eq!(merkle_root(&env, &vec![&env, a.clone(), b.clone()]), ab);
+    // The unpaired leaf moves up unhashed by its missing sibling
+    assert_eq!(merkle_root(&env, &vec![&env, a.clone(), b.clone(), c.clone()]), parent(&ab, &node(&c)));
+
+    // Order matters, and a leaf is never mistaken for a subtree
+    assert_ne!(merkle_root(&env, &vec![&env, b.clone(), a.clone()]), ab);
+    assert_ne!(merkle_root(&env, &vec![&env, node(&a), node(&b)]), merkle_root(&env, &vec![&env, ab]));
+}
+
+#[test]
+#[should_panic(expected = "merkle tree needs a leaf")]
+fn test_merkle_root_rejects_empty_batch() {
+    let env = Env::default();
+    merkle_root(&env, &Vec::new(&env));
+}
+
+#[test]
+fn test_id_cursor_survives_removals_between_pages() {
+    let env = Env::default();
+    for seed in 1..=300u64 {
+        let mut rng = Rng(seed);
+        let mut ids = Vec::new(&env);
+        let mut next_id = 1;
+        for _ in 0..rng.below(30) {
+            ids.push_back(next_id);
+            next_id += 1 + rng.below(3);
+        }
+        let initial = ids.clone();
+        let mut removed = Vec::<u64>::new(&env);
+
+        let mut returned = Vec::<u64>::new(&env);
+        let mut cursor = Some(0);
+        while let Some(after) = cursor {
+            let page = PageCursor::after(&ids, after, 1 + rng.below(5) as u32, 4);
+            for position in page.range() {
+                returned.push_back(ids.get_unchecked(position));
+            }
+            cursor = page.next_after(&ids);
+
+            // Remove and append ids anywhere in the listing before the next page
+            for _ in 0..rng.below(3) {
+                if !ids.is_empty() {
+                    let position = rng.below(ids.len() as u64) as u32;
+                    removed.push_back(ids.get_unchecked(position));
+                    ids.remove(position);
+                }
+            }
+            if rng.below(2) == 0 {
+                ids.push_back(next_id);
+                next_id += 1;
+            }
+        }
+
+        // Strictly ascending: nothing returned twice
+        for position in 1..returned.len() {
+            let (previous, id) = (returned.get_unchecked(position - 1), returned.get_unchecked(position));
+            assert!(previous < id, "seed {seed}: duplicate or out of order");
+        }
+        // Every id listed throughout the walk was returned
+        for id in initial.iter().filter(|id| !removed.contains(id)) {
+            assert!(returned.contains(id), "seed {seed}: skipped id {id}");
+        }
+    }
+}
+
+#[test]
+fn test_chunked_vec_pages_across_chunks() {
+    let env = Env::default();
+    let host = env.register(Host, ());
+    env.as_contract(&host, || {
+        let members = ChunkedVec::<_, u64>::new(TestKey::Members);
+        assert!(members.is_empty(&env));
+        assert_eq!(members.get(&env, 0), None);
+
+        for id in 0..150u64 {
+            assert_eq!(members.push(&env, id * 10), id as u32);
+        }
+        assert_eq!(members.len(&env), 150);
+        assert_eq!(members.get(&env, 64), Some(640));
+        assert_eq!(members.get(&env, 150), None);
+
+        // Pages straddling a chunk boundary come back in order
+        let page = members.page(&env, &PageCursor::new(60, 10, 50, 150));
+        assert_eq!(page, Vec::from_array(&env, [600, 610, 620, 630, 640, 650, 660, 670, 680, 690]));
+        assert!(members.set(&env, 65, 7));
+        assert!(!members.set(&env, 150, 7));
+        assert_eq!(members.get(&env, 65), Some(7));
+
+        // Lists under other keys are independent
+        assert!(ChunkedVec::<_, u64>::new(TestKey::Other).is_empty(&env));
+    });
+}
+
+#[test]
+fn test_chunked_vec_swap_remove() {
+    let env = Env::default();
+    let host = env.register(Host, ());
+    env.as_contract(&host, || {
+        let members = ChunkedVec::<_, u64>::new(TestKey::Members);
+        for id in 0..66u64 {
+            members.push(&env, id);
+        }
+
+        // The last member fills the hole left on an earlier page
+        assert_eq!(members.swap_remove(&env, 3), Some(3));
+        assert_eq!(members.len(&env), 65);
+        assert_eq!(members.get(&env, 3), Some(65));
+
+        // Removing the last member empties the trailing page
+        assert_eq!(members.swap_remove(&env, 64), Some(64));
+        assert_eq!(members.get(&env, 64), None);
+        assert!(!env.storage().persistent().has(&(TestKey::Members, 1u32)));
+
+        // Within the last page
+        assert_eq!(members.swap_remove(&env, 10), Some(10));
+        assert_eq!(members.get(&env, 10), Some(63));
+        assert_eq!(members.len(&env), 63);
+        assert_eq!(members.swap_remove(&env, 63), None);
+
+        // Pushing after removals reuses the freed slot
+        assert_eq!(members.push(&env, 99), 63);
+        assert_eq!(members.get(&env, 63), Some(99));
+    });
+}
+
+#[test]
+fn test_chunked_vec_stress_keeps_entries_bounded() {
+    // Too large a ledger to be worth a snapshot
+    let env = Env::new_with_config(EnvTestConfig { capture_snapshot_at_drop: false });
+    env.cost_estimate().budget().reset_unlimited();
+    let host = env.register(Host, ());
+    env.as_contract(&host, || {
+        let members = ChunkedVec::<_, u64>::new(TestKey::Members);
+        let total = 20_000u32;
+        for id in 0..total {
+            members.push(&env, id as u64);
+        }
+        assert_eq!(members.len(&env), total);
+
+        // No page outgrows a chunk, however long the list
+        for page_no in [0, total / CHUNK_SIZE / 2, (total - 1) / CHUNK_SIZE] {
+            let page: Vec<u64> = env.storage().persistent().get(&(TestKey::Members, page_no)).unwrap();
+            assert!(page.len() <= CHUNK_SIZE);
+            assert_eq!(page.get_unchecked(0), (page_no * CHUNK_SIZE) as u64);
+        }
+        let last = members.page(&env, &PageCursor::new(total - 5, 50, 50, total));
+        assert_eq!(last, Vec::from_array(&env, [19_995, 19_996, 19_997, 19_998, 19_999]));
+    });
+}
+
+#[test]
+fn test_chunk_of_longest_keys_fits_one_ledger_entry() {
+    let env = Env::default();
+    let host = env.register(Host, ());
+    env.as_contract(&host, || {
+        // 128-byte image hashes are the longest members any index holds
+        let hashes = ChunkedVec::<_, String>::new(TestKey::Members);
+        for _ in 0..CHUNK_SIZE {
+            hashes.push(&env, String::from_bytes(&env, &[b'f'; 128]));
+        }
+        let page: Vec<String> = env.storage().persistent().get(&(TestKey::Members, 0u32)).unwrap();
+        assert_eq!(page.len(), CHUNK_SIZE);
+        assert!(page.to_xdr(&env).len() < 16 * 1024);
+    });
+}
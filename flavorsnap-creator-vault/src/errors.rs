@@ -0,0 +1,46 @@
+use soroban_sdk::contracterror;
+
+/// Errors returned by `CreatorVaultContract`.
+///
+/// Discriminants are part of the public ABI and must never be renumbered;
+/// new conditions are appended at the end.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum VaultError {
+    /// The caller is not the vault owner.
+    Unauthorized = 1,
+    /// Splits are empty, too many, name a member twice or do not sum to 100%.
+    InvalidSplits = 2,
+    /// The amount is zero or negative.
+    InvalidAmount = 3,
+    /// The member's balance does not cover the requested amount.
+    InsufficientBalance = 4,
+    /// No vesting schedule exists under this id.
+    ScheduleNotFound = 5,
+}
+
+impl VaultError {
+    pub fn from_code(code: u32) -> Option<Self> {
+        let error = match code {
+            1 => VaultError::Unauthorized,
+            2 => VaultError::InvalidSplits,
+            3 => VaultError::InvalidAmount,
+            4 => VaultError::InsufficientBalance,
+            5 => VaultError::ScheduleNotFound,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Short human-readable description, surfaced through `error_message`
+    pub fn message(&self) -> &'static str {
+        match self {
+            VaultError::Unauthorized => "caller is not authorized",
+            VaultError::InvalidSplits => "invalid payout splits",
+            VaultError::InvalidAmount => "invalid amount",
+            VaultError::InsufficientBalance => "insufficient balance",
+            VaultError::ScheduleNotFound => "vesting schedule not found",
+        }
+    }
+}
@@ -0,0 +1,256 @@
+#![no_std]
+
+use flavorsnap_common::{split_amounts, Bps, Rounding, SplitShare, MAX_BPS};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, vec, Address, Env, String, Vec};
+
+mod errors;
+
+pub use errors::VaultError;
+
+pub const BPS_DENOMINATOR: Bps = MAX_BPS;
+/// Most members one vault splits revenue between
+pub const MAX_SPLITS: u32 = 10;
+
+/// Share of every deposit credited to `member`, in basis points
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Split {
+    pub member: Address,
+    pub bps: Bps,
+}
+
+/// `total` of `token` released to `beneficiary` linearly over `duration`
+/// seconds from `start`; `claimed` is what has been paid out so far.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingSchedule {
+    pub beneficiary: Address,
+    pub token: Address,
+    pub total: i128,
+    pub claimed: i128,
+    pub start: u64,
+    pub duration: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Owner,
+    Splits,
+    /// Withdrawable balance of (member, token)
+    Balance(Address, Address),
+    /// Cumulative deposits of (source, ip_id, token)
+    Revenue(Address, u64, Address),
+    ScheduleCount,
+    Schedule(u32),
+}
+
+/// Collects a creator's license and royalty revenue, attributes it to the IP
+/// it was earned on and splits it between the creator's team members. The
+/// owner can lock part of their own share into vesting schedules.
+#[contract]
+pub struct CreatorVaultContract;
+
+#[contractimpl]
+impl CreatorVaultContract {
+    /// Set the vault owner, who initially receives all deposits
+    pub fn __constructor(env: Env, owner: Address) {
+        let splits = vec![&env, Split { member: owner.clone(), bps: BPS_DENOMINATOR }];
+        env.storage().instance().set(&DataKey::Owner, &owner);
+        env.storage().instance().set(&DataKey::Splits, &splits);
+    }
+
+    pub fn get_owner(env: Env) -> Address {
+        Self::read_owner(&env)
+    }
+
+    /// Describe a `VaultError` code returned by this contract
+    pub fn error_message(env: Env, code: u32) -> String {
+        let message = VaultError::from_code(code)
+            .map(|error| error.message())
+            .unwrap_or("unknown error");
+        String::from_str(&env, message)
+    }
+
+    /// Replace the payout splits applied to future deposits (Only callable by
+    /// owner). Splits must name distinct members, at most `MAX_SPLITS` of them,
+    /// with non-zero shares summing to 100%.
+    pub fn set_splits(env: Env, owner: Address, splits: Vec<Split>) -> Result<(), VaultError> {
+        Self::require_owner(&env, &owner)?;
+
+        if splits.is_empty() || splits.len() > MAX_SPLITS {
+            return Err(VaultError::InvalidSplits);
+        }
+        let mut total = 0;
+        for (i, split) in splits.iter().enumerate() {
+            if split.bps == 0 || splits.iter().skip(i + 1).any(|other| other.member == split.member) {
+                return Err(VaultError::InvalidSplits);
+            }
+            total += split.bps;
+        }
+        if total != BPS_DENOMINATOR {
+            return Err(VaultError::InvalidSplits);
+        }
+
+        env.storage().instance().set(&DataKey::Splits, &splits);
+        env.events().publish((symbol_short!("splits"),), splits);
+        Ok(())
+    }
+
+    pub fn get_splits(env: Env) -> Vec<Split> {
+        env.storage().instance().get(&DataKey::Splits).unwrap()
+    }
+
+    /// Transfer `amount` of `token` from `from` into the vault as revenue of
+    /// `ip_id` and credit it to the members by their splits. Rounding dust
+    /// goes to the owner.
+    pub fn deposit(env: Env, from: Address, token: Address, ip_id: u64, amount: i128) -> Result<(), VaultError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(VaultError::InvalidAmount);
+        }
+
+        token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &amount);
+
+        let revenue_key = DataKey::Revenue(from.clone(), ip_id, token.clone());
+        let revenue: i128 = env.storage().persistent().get(&revenue_key).unwrap_or(0);
+        env.storage().persistent().set(&revenue_key, &(revenue + amount));
+
+        // The owner, last on an empty share, takes the rounding dust
+        let mut shares: Vec<SplitShare> = Vec::new(&env);
+        for split in Self::get_splits(env.clone()).iter() {
+            shares.push_back((split.member, split.bps));
+        }
+        shares.push_back((Self::read_owner(&env), 0));
+        for ((member, _), part) in shares.iter().zip(split_amounts(amount, &shares, Rounding::Floor).iter()) {
+            if part > 0 {
+                Self::credit(&env, &member, &token, part);
+            }
+        }
+
+        env.events().publish((symbol_short!("deposit"), from, ip_id), (token, amount));
+        Ok(())
+    }
+
+    /// Total revenue `source` has deposited for `ip_id` in `token`
+    pub fn ip_revenue(env: Env, source: Address, ip_id: u64, token: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::Revenue(source, ip_id, token)).unwrap_or(0)
+    }
+
+    /// Amount of `token` `member` can withdraw
+    pub fn balance(env: Env, member: Address, token: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::Balance(member, token)).unwrap_or(0)
+    }
+
+    pub fn withdraw(env: Env, member: Address, token: Address, amount: i128) -> Result<(), VaultError> {
+        member.require_auth();
+        if amount <= 0 {
+            return Err(VaultError::InvalidAmount);
+        }
+        Self::debit(&env, &member, &token, amount)?;
+
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &member, &amount);
+        env.events().publish((symbol_short!("withdraw"), member), (token, amount));
+        Ok(())
+    }
+
+    /// Move `amount` of the owner's balance into a schedule vesting linearly to
+    /// `beneficiary` over `duration` seconds from `start` (Only callable by
+    /// owner). A zero `duration` releases everything at `start`.
+    pub fn create_schedule(
+        env: Env,
+        owner: Address,
+        token: Address,
+        beneficiary: Address,
+        amount: i128,
+        start: u64,
+        duration: u64,
+    ) -> Result<u32, VaultError> {
+        Self::require_owner(&env, &owner)?;
+        if amount <= 0 {
+            return Err(VaultError::InvalidAmount);
+        }
+        Self::debit(&env, &owner, &token, amount)?;
+
+        let id: u32 = env.storage().instance().get(&DataKey::ScheduleCount).unwrap_or(0);
+        let schedule = VestingSchedule { beneficiary: beneficiary.clone(), token, total: amount, claimed: 0, start, duration };
+        env.storage().persistent().set(&DataKey::Schedule(id), &schedule);
+        env.storage().instance().set(&DataKey::ScheduleCount, &(id + 1));
+
+        env.events().publish((symbol_short!("vesting"), id), (beneficiary, amount));
+        Ok(id)
+    }
+
+    pub fn get_schedule(env: Env, schedule_id: u32) -> Option<VestingSchedule> {
+        env.storage().persistent().get(&DataKey::Schedule(schedule_id))
+    }
+
+    /// Amount of the schedule released so far, claimed or not
+    pub fn vested_amount(env: Env, schedule_id: u32) -> Result<i128, VaultError> {
+        let schedule = Self::get_schedule(env.clone(), schedule_id).ok_or(VaultError::ScheduleNotFound)?;
+        Ok(Self::vested(&env, &schedule))
+    }
+
+    /// Pay the beneficiary everything vested and not yet claimed. Returns the
+    /// amount paid.
+    pub fn claim_vested(env: Env, schedule_id: u32) -> Result<i128, VaultError> {
+        let mut schedule = Self::get_schedule(env.clone(), schedule_id).ok_or(VaultError::ScheduleNotFound)?;
+        schedule.beneficiary.require_auth();
+
+        let amount = Self::vested(&env, &schedule) - schedule.claimed;
+        if amount > 0 {
+            schedule.claimed += amount;
+            env.storage().persistent().set(&DataKey::Schedule(schedule_id), &schedule);
+            token::Client::new(&env, &schedule.token)
+                .transfer(&env.current_contract_address(), &schedule.beneficiary, &amount);
+        }
+
+        env.events().publish((symbol_short!("vest_clm"), schedule_id), amount);
+        Ok(amount)
+    }
+}
+
+impl CreatorVaultContract {
+    fn read_owner(env: &Env) -> Address {
+        env.storage().instance().get(&DataKey::Owner).unwrap()
+    }
+
+    fn require_owner(env: &Env, caller: &Address) -> Result<(), VaultError> {
+        caller.require_auth();
+        if Self::read_owner(env) != *caller {
+            return Err(VaultError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn credit(env: &Env, member: &Address, token: &Address, amount: i128) {
+        let key = DataKey::Balance(member.clone(), token.clone());
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(balance + amount));
+    }
+
+    fn debit(env: &Env, member: &Address, token: &Address, amount: i128) -> Result<(), VaultError> {
+        let key = DataKey::Balance(member.clone(), token.clone());
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if balance < amount {
+            return Err(VaultError::InsufficientBalance);
+        }
+        env.storage().persistent().set(&key, &(balance - amount));
+        Ok(())
+    }
+
+    fn vested(env: &Env, schedule: &VestingSchedule) -> i128 {
+        let now = env.ledger().timestamp();
+        if now < schedule.start {
+            return 0;
+        }
+        let elapsed = now - schedule.start;
+        if elapsed >= schedule.duration {
+            return schedule.total;
+        }
+        schedule.total * elapsed as i128 / schedule.duration as i128
+    }
+}
+
+#[cfg(test)]
+mod test;
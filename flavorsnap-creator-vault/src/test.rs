@@ -0,0 +1,62 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{testutils::{Address as _, Ledger}, token, Address, Env};
+
+#[test]
+fn test_deposits_split_between_members_and_vest() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let owner = Address::generate(&env);
+    let editor = Address::generate(&env);
+    let source = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token_id = env.register_stellar_asset_contract_v2(token_admin).address();
+    token::StellarAssetClient::new(&env, &token_id).mint(&source, &10_000);
+    let token_client = token::Client::new(&env, &token_id);
+
+    let vault_id = env.register(CreatorVaultContract, (owner.clone(),));
+    let client = CreatorVaultContractClient::new(&env, &vault_id);
+
+    let bad = vec![&env, Split { member: owner.clone(), bps: 6_000 }];
+    assert_eq!(client.try_set_splits(&owner, &bad), Err(Ok(VaultError::InvalidSplits)));
+    let splits = vec![
+        &env,
+        Split { member: owner.clone(), bps: 6_667 },
+        Split { member: editor.clone(), bps: 3_333 },
+    ];
+    assert_eq!(client.try_set_splits(&editor, &splits), Err(Ok(VaultError::Unauthorized)));
+    client.set_splits(&owner, &splits);
+
+    client.deposit(&source, &token_id, &7, &1_000);
+    client.deposit(&source, &token_id, &8, &500);
+    assert_eq!(client.ip_revenue(&source, &7, &token_id), 1_000);
+    assert_eq!(client.ip_revenue(&source, &8, &token_id), 500);
+    assert_eq!(token_client.balance(&vault_id), 1_500);
+    // 666 + 333 (+1 dust) and 333 + 166 (+1 dust)
+    assert_eq!(client.balance(&owner, &token_id), 1_001);
+    assert_eq!(client.balance(&editor, &token_id), 499);
+
+    let res = client.try_withdraw(&editor, &token_id, &500);
+    assert_eq!(res, Err(Ok(VaultError::InsufficientBalance)));
+    client.withdraw(&editor, &token_id, &499);
+    assert_eq!(token_client.balance(&editor), 499);
+
+    // Vest 1_000 of the owner's share to the editor over 100s
+    let id = client.create_schedule(&owner, &token_id, &editor, &1_000, &1_100, &100);
+    assert_eq!(client.balance(&owner, &token_id), 1);
+    assert_eq!(client.claim_vested(&id), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_125);
+    assert_eq!(client.vested_amount(&id), 250);
+    assert_eq!(client.claim_vested(&id), 250);
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+    assert_eq!(client.claim_vested(&id), 750);
+    assert_eq!(client.claim_vested(&id), 0);
+    assert_eq!(token_client.balance(&editor), 1_499);
+    assert_eq!(client.get_schedule(&id).unwrap().claimed, 1_000);
+
+    assert_eq!(client.try_claim_vested(&9), Err(Ok(VaultError::ScheduleNotFound)));
+}
@@ -0,0 +1,99 @@
+//! Albums: several shots of one meal registered as a single classified record
+
+use crate::{
+    Album, ConfidenceBps, DataKey, FoodRegistryContract, FoodRegistryContractArgs, FoodRegistryContractClient,
+    RegistryError, Role
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, BytesN, Env, String, Vec};
+
+const MAX_ALBUM_SIZE: u32 = 20;
+
+#[contractimpl]
+impl FoodRegistryContract {
+    /// Register up to 20 image hashes of one meal under taxonomy category
+    /// `category_id` and return the album id. Member hashes may not already
+    /// belong to an entry or another album; the album counts once towards
+    /// category stats (Only callable by a Verifier)
+    pub fn register_album(
+        env: Env,
+        verifier: Address,
+        submitter: Address,
+        image_hashes: Vec<String>,
+        category_id: u32,
+        confidence: ConfidenceBps,
+        model_id: u32,
+        metadata_hash: Option<BytesN<32>>,
+    ) -> Result<u32, RegistryError> {
+        if Self::is_paused(env.clone()) {
+            return Err(RegistryError::Paused);
+        }
+
+        verifier.require_auth();
+        Self::require_role(&env, Role::Verifier, &verifier)?;
+        Self::require_valid_confidence(confidence)?;
+        Self::consume_rate_limit(&env, &verifier)?;
+
+        if image_hashes.is_empty() {
+            return Err(RegistryError::InvalidAlbum);
+        }
+        if image_hashes.len() > MAX_ALBUM_SIZE {
+            return Err(RegistryError::CapReached);
+        }
+        if !env.storage().persistent().has(&DataKey::Model(model_id)) {
+            return Err(RegistryError::ModelNotFound);
+        }
+        let category = Self::get_category(env.clone(), category_id)?;
+
+        let album_id = env.storage().instance().get(&DataKey::AlbumCount).unwrap_or(0u32) + 1;
+        for (position, image_hash) in image_hashes.iter().enumerate() {
+            if image_hashes.first_index_of(&image_hash) != Some(position as u32) {
+                return Err(RegistryError::InvalidAlbum);
+            }
+            Self::require_valid_image_hash(&image_hash)?;
+            if env.storage().persistent().has(&DataKey::RemovedEntry(image_hash.clone())) {
+                return Err(RegistryError::EntryRemoved);
+            }
+            let member_key = DataKey::AlbumMember(image_hash.clone());
+            if env.storage().persistent().has(&DataKey::Entry(image_hash)) || env.storage().persistent().has(&member_key) {
+                return Err(RegistryError::EntryAlreadyExists);
+            }
+            env.storage().persistent().set(&member_key, &album_id);
+        }
+
+        let album = Album {
+            classification: category.name.clone(),
+            category_id,
+            confidence,
+            model_id,
+            timestamp: env.ledger().timestamp(),
+            verifier,
+            submitter,
+            image_hashes: image_hashes.clone(),
+            metadata_hash,
+        };
+        env.storage().persistent().set(&DataKey::Album(album_id), &album);
+        env.storage().instance().set(&DataKey::AlbumCount, &album_id);
+
+        let mut stats = Self::get_category_stats(env.clone(), category.name.clone());
+        stats.entry_count += 1;
+        stats.confidence_sum += confidence as u64;
+        env.storage().persistent().set(&DataKey::CategoryStats(category.name.clone()), &stats);
+        Self::count_daily(&env, |stats| stats.verifications += 1);
+
+        env.events().publish((symbol_short!("album"), category.name, album_id), image_hashes.len());
+        Ok(album_id)
+    }
+
+    pub fn get_album(env: Env, album_id: u32) -> Result<Album, RegistryError> {
+        env.storage().persistent().get(&DataKey::Album(album_id)).ok_or(RegistryError::AlbumNotFound)
+    }
+
+    /// The album `image_hash` was registered in, if any
+    pub fn get_album_of(env: Env, image_hash: String) -> Option<u32> {
+        env.storage().persistent().get(&DataKey::AlbumMember(image_hash))
+    }
+
+    pub fn is_album_member(env: Env, album_id: u32, image_hash: String) -> bool {
+        Self::get_album_of(env, image_hash) == Some(album_id)
+    }
+}
@@ -0,0 +1,74 @@
+//! Anchoring exports: Merkle roots over batches of entries, published by the
+//! registry on demand so partners can mirror proof of registrations onto
+//! other chains or timestamping services without trusting an exporter
+
+use crate::{
+    FoodRegistryContract, FoodRegistryContractArgs, FoodRegistryContractClient, MerkleAnchor, RegistryError,
+    MAX_BATCH_SIZE
+};
+use soroban_sdk::{contractimpl, symbol_short, xdr::ToXdr, Bytes, BytesN, Env, String, Vec};
+
+/// Domain separator of entry leaves
+const ANCHOR_DOMAIN: &[u8] = b"flavorsnap-entry-anchor-v1";
+
+#[contractimpl]
+impl FoodRegistryContract {
+    /// Publish the Merkle root over the current entries of `image_hashes`, at
+    /// most 50, as an `anchor` event. Callable by anyone. The leaves are
+    /// `entry_anchor_leaf` of each image in the order given; a hash that
+    /// `get_food_entry` would not return fails the whole batch.
+    pub fn anchor_entries(env: Env, image_hashes: Vec<String>) -> Result<MerkleAnchor, RegistryError> {
+        if image_hashes.is_empty() {
+            return Err(RegistryError::EmptyBatch);
+        }
+        if image_hashes.len() > MAX_BATCH_SIZE {
+            return Err(RegistryError::CapReached);
+        }
+
+        let mut leaves = Vec::new(&env);
+        for image_hash in image_hashes.iter() {
+            leaves.push_back(Self::entry_anchor_leaf(env.clone(), image_hash)?);
+        }
+        let anchor = MerkleAnchor::new(&env, &leaves);
+
+        env.events().publish((symbol_short!("anchor"),), anchor.clone());
+        Ok(anchor)
+    }
+
+    /// Leaf committing to a public entry, recomputable off-chain as
+    ///
+    /// ```text
+    /// sha256(
+    ///     "flavorsnap-entry-anchor-v1"
+    ///     || image_hash        String, XDR of its ScVal
+    ///     || classification    String, XDR of its ScVal
+    ///     || confidence        u32, big-endian
+    ///     || model_id          u32, big-endian
+    ///     || timestamp         u64, big-endian
+    ///     || verifier          Address, XDR of its ScVal
+    ///     || submitter         Address, XDR of its ScVal
+    ///     || metadata_hash     u8 0 if unbound, else u8 1 || 32 bytes
+    /// )
+    /// ```
+    pub fn entry_anchor_leaf(env: Env, image_hash: String) -> Result<BytesN<32>, RegistryError> {
+        let entry = Self::get_food_entry(env.clone(), image_hash.clone())?;
+
+        let mut preimage = Bytes::from_slice(&env, ANCHOR_DOMAIN);
+        preimage.append(&image_hash.to_xdr(&env));
+        preimage.append(&entry.classification.to_xdr(&env));
+        preimage.extend_from_array(&entry.confidence.to_be_bytes());
+        preimage.extend_from_array(&entry.model_id.to_be_bytes());
+        preimage.extend_from_array(&entry.timestamp.to_be_bytes());
+        preimage.append(&entry.verifier.to_xdr(&env));
+        preimage.append(&entry.submitter.to_xdr(&env));
+        match entry.metadata_hash {
+            Some(metadata_hash) => {
+                preimage.push_back(1);
+                preimage.extend_from_array(&metadata_hash.to_array());
+            }
+            None => preimage.push_back(0),
+        }
+
+        Ok(env.crypto().sha256(&preimage).into())
+    }
+}
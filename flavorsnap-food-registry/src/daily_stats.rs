@@ -0,0 +1,32 @@
+//! Per-day activity counters, one small entry per UTC day, for dashboards
+//! that want health metrics without running an indexer
+
+use crate::{DailyStats, DataKey, FoodRegistryContract, FoodRegistryContractArgs, FoodRegistryContractClient};
+use soroban_sdk::{contractimpl, Env};
+
+/// Length of a `get_daily_stats` bucket in ledger-timestamp seconds
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+#[contractimpl]
+impl FoodRegistryContract {
+    /// Activity counted on day `day_index`, the ledger timestamp divided by
+    /// `SECONDS_PER_DAY`. Days without activity read as zeroes.
+    pub fn get_daily_stats(env: Env, day_index: u64) -> DailyStats {
+        env.storage().persistent().get(&DataKey::DailyStats(day_index)).unwrap_or_default()
+    }
+
+    /// Day index of the current ledger timestamp
+    pub fn current_day(env: Env) -> u64 {
+        env.ledger().timestamp() / SECONDS_PER_DAY
+    }
+}
+
+impl FoodRegistryContract {
+    /// Apply `bump` to today's counters
+    pub(crate) fn count_daily(env: &Env, bump: impl FnOnce(&mut DailyStats)) {
+        let day_index = Self::current_day(env.clone());
+        let mut stats = Self::get_daily_stats(env.clone(), day_index);
+        bump(&mut stats);
+        env.storage().persistent().set(&DataKey::DailyStats(day_index), &stats);
+    }
+}
@@ -0,0 +1,146 @@
+use soroban_sdk::contracterror;
+
+/// Errors returned by `FoodRegistryContract`.
+///
+/// Discriminants are part of the public ABI and must never be renumbered;
+/// new conditions are appended at the end.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RegistryError {
+    /// Reserved: the registry was already initialized.
+    AlreadyInitialized = 1,
+    /// Reserved: the registry has not been initialized.
+    NotInitialized = 2,
+    /// The caller lacks the role required for this call.
+    Unauthorized = 3,
+    /// An entry is already registered for this image hash.
+    EntryAlreadyExists = 4,
+    /// No entry is registered for this image hash.
+    EntryNotFound = 5,
+    /// Registrations are halted by a Pauser.
+    Paused = 6,
+    /// A fee or payment amount is negative or otherwise out of range.
+    InvalidAmount = 7,
+    /// A time-bounded record has expired.
+    Expired = 8,
+    /// A configured capacity or per-call limit would be exceeded.
+    CapReached = 9,
+    /// No classifier model is registered under this id.
+    ModelNotFound = 10,
+    /// The entry's verification has lapsed and must be refreshed with `reverify_entry`.
+    Stale = 11,
+    /// The entry is private to its submitter and verifiers.
+    EntryPrivate = 12,
+    /// No taxonomy category is registered under this id.
+    CategoryNotFound = 13,
+    /// A taxonomy category with this name already exists.
+    CategoryExists = 14,
+    /// The parent category is already at the deepest taxonomy level.
+    TaxonomyTooDeep = 15,
+    /// Free-form classifications are disabled; register with a taxonomy id.
+    LegacyPathDisabled = 16,
+    /// Confidence is above 10000 basis points.
+    InvalidConfidence = 17,
+    /// Labels are empty, repeat a category, or their confidences sum above 10000 basis points.
+    InvalidLabels = 18,
+    /// No album is registered under this id.
+    AlbumNotFound = 19,
+    /// The album is empty or lists an image twice.
+    InvalidAlbum = 20,
+    /// No tenant exists under this id
+    TenantNotFound = 21,
+    /// The verifier registered its maximum number of entries within the rate-limit window
+    RateLimited = 22,
+    /// The verifier was not revoked as compromised
+    VerifierNotCompromised = 23,
+    /// The image hash is empty, longer than 128 bytes, or not lowercase letters, digits and dashes
+    InvalidImageHash = 24,
+    /// The entry was removed by an Admin and its image hash cannot be registered again
+    EntryRemoved = 25,
+    /// Redaction positions do not locate the entry being redacted
+    InvalidRedaction = 26,
+    /// No submission is pending for the image hash
+    SubmissionNotFound = 27,
+    /// A batch to anchor lists no entries.
+    EmptyBatch = 28,
+    /// Revoking the role would leave the registry without an Admin.
+    LastAdmin = 29,
+    /// The submission was rejected as a duplicate of an image that is not registered.
+    NotDuplicate = 30,
+}
+
+impl RegistryError {
+    pub fn from_code(code: u32) -> Option<Self> {
+        let error = match code {
+            1 => RegistryError::AlreadyInitialized,
+            2 => RegistryError::NotInitialized,
+            3 => RegistryError::Unauthorized,
+            4 => RegistryError::EntryAlreadyExists,
+            5 => RegistryError::EntryNotFound,
+            6 => RegistryError::Paused,
+            7 => RegistryError::InvalidAmount,
+            8 => RegistryError::Expired,
+            9 => RegistryError::CapReached,
+            10 => RegistryError::ModelNotFound,
+            11 => RegistryError::Stale,
+            12 => RegistryError::EntryPrivate,
+            13 => RegistryError::CategoryNotFound,
+            14 => RegistryError::CategoryExists,
+            15 => RegistryError::TaxonomyTooDeep,
+            16 => RegistryError::LegacyPathDisabled,
+            17 => RegistryError::InvalidConfidence,
+            18 => RegistryError::InvalidLabels,
+            19 => RegistryError::AlbumNotFound,
+            20 => RegistryError::InvalidAlbum,
+            21 => RegistryError::TenantNotFound,
+            22 => RegistryError::RateLimited,
+            23 => RegistryError::VerifierNotCompromised,
+            24 => RegistryError::InvalidImageHash,
+            25 => RegistryError::EntryRemoved,
+            26 => RegistryError::InvalidRedaction,
+            27 => RegistryError::SubmissionNotFound,
+            28 => RegistryError::EmptyBatch,
+            29 => RegistryError::LastAdmin,
+            30 => RegistryError::NotDuplicate,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Short human-readable description, surfaced through `error_message`
+    pub fn message(&self) -> &'static str {
+        match self {
+            RegistryError::AlreadyInitialized => "already initialized",
+            RegistryError::NotInitialized => "not initialized",
+            RegistryError::Unauthorized => "caller is not authorized",
+            RegistryError::EntryAlreadyExists => "entry already exists",
+            RegistryError::EntryNotFound => "entry not found",
+            RegistryError::Paused => "registry is paused",
+            RegistryError::InvalidAmount => "invalid amount",
+            RegistryError::Expired => "expired",
+            RegistryError::CapReached => "cap reached",
+            RegistryError::ModelNotFound => "model not found",
+            RegistryError::Stale => "entry verification is stale",
+            RegistryError::EntryPrivate => "entry is private",
+            RegistryError::CategoryNotFound => "category not found",
+            RegistryError::CategoryExists => "category already exists",
+            RegistryError::TaxonomyTooDeep => "taxonomy too deep",
+            RegistryError::LegacyPathDisabled => "legacy classifications disabled",
+            RegistryError::InvalidConfidence => "confidence exceeds 10000 bps",
+            RegistryError::InvalidLabels => "invalid label distribution",
+            RegistryError::AlbumNotFound => "album not found",
+            RegistryError::InvalidAlbum => "invalid album",
+            RegistryError::TenantNotFound => "tenant not found",
+            RegistryError::RateLimited => "verifier rate limit reached",
+            RegistryError::VerifierNotCompromised => "verifier not marked compromised",
+            RegistryError::InvalidImageHash => "malformed image hash",
+            RegistryError::EntryRemoved => "entry was removed",
+            RegistryError::InvalidRedaction => "redaction positions do not match entry",
+            RegistryError::SubmissionNotFound => "no submission pending for this image",
+            RegistryError::EmptyBatch => "batch is empty",
+            RegistryError::LastAdmin => "cannot revoke the last admin",
+            RegistryError::NotDuplicate => "image is not registered, so not a duplicate",
+        }
+    }
+}
@@ -1,87 +1,980 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 
+use flavorsnap_common::{split, ChunkedVec, PageCursor, SplitShare, BPS_DENOMINATOR, MAX_BPS};
+use flavorsnap_rbac::{self as rbac, audit};
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, String
+    contract, contractimpl, contracttype, symbol_short, vec, Address, Bytes, BytesN, Env, IntoVal, String,
+    Symbol, Val, Vec
 };
 
-#[contracterror]
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
-#[repr(u32)]
-pub enum RegistryError {
-    AlreadyInitialized = 1,
-    NotInitialized = 2,
-    Unauthorized = 3,
-    EntryAlreadyExists = 4,
-    EntryNotFound = 5,
-}
+mod albums;
+mod anchors;
+mod daily_stats;
+mod errors;
+mod meta;
+mod quarantine;
+mod rate_limit;
+mod redaction;
+mod submissions;
+mod taxonomy;
+mod tenants;
+
+pub use errors::RegistryError;
+pub use flavorsnap_common::{ConfidenceBps, MerkleAnchor, MAX_CONFIDENCE_BPS};
+pub use flavorsnap_rbac::{audit::AuditEntry, Role};
+pub use daily_stats::SECONDS_PER_DAY;
+pub use submissions::MAX_REJECTIONS;
+
+const MAX_BATCH_SIZE: u32 = 50;
+
+const MAX_LABELS: u32 = 5;
+
+/// Longest accepted image hash, enough for a hex-encoded SHA-512 digest
+const MAX_IMAGE_HASH_LEN: usize = 128;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FoodEntry {
     pub classification: String,
-    pub confidence: u32,
+    /// Taxonomy category, `None` for entries registered under a free-form string
+    pub category_id: Option<u32>,
+    pub confidence: ConfidenceBps,
+    pub model_id: u32,
+    pub timestamp: u64,
+    pub valid_until: Option<u64>,
+    pub verifier: Address,
+    pub submitter: Address,
+    pub private: bool,
+    pub metadata_hash: Option<BytesN<32>>,
+    /// Registered by a verifier later revoked as compromised; trust is reduced
+    /// until the entry is re-verified
+    pub quarantined: bool,
+}
+
+/// Whether an image hash could be registered now, from `can_register`
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RegistrationStatus {
+    Available,
+    /// Registered as an entry or album member
+    Exists,
+    /// Registered by a verifier revoked as compromised, pending re-verification
+    Quarantined,
+    /// Removed by an Admin; the hash cannot be registered again
+    Removed,
+    /// Not in the canonical form `insert_entry` accepts
+    Invalid,
+}
+
+/// What `lookup_food_entry` found under an image hash, so callers can branch
+/// on a missing entry without trapping an error
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum EntryLookup {
+    Found(FoodEntry),
+    NotFound,
+    /// Registered, but its verification lapsed
+    Stale,
+    /// Registered as private; readable through `get_private_entry`
+    Private,
+}
+
+/// One candidate classification of a multi-label entry
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Label {
+    pub category_id: Option<u32>,
+    pub classification: String,
+    pub confidence: ConfidenceBps,
+}
+
+/// What stays of an entry redacted on request: enough for category stats and
+/// registration counts to keep adding up, keyed by a salted blind hash instead
+/// of the image hash and without submitter or metadata
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RedactedEntry {
+    pub classification: String,
+    pub category_id: Option<u32>,
+    pub confidence: ConfidenceBps,
+    pub model_id: u32,
+    pub timestamp: u64,
+    pub redacted_at: u64,
+}
+
+/// Several image hashes of one meal classified together
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Album {
+    pub classification: String,
+    pub category_id: u32,
+    pub confidence: ConfidenceBps,
+    pub model_id: u32,
     pub timestamp: u64,
     pub verifier: Address,
+    pub submitter: Address,
+    pub image_hashes: Vec<String>,
+    pub metadata_hash: Option<BytesN<32>>,
+}
+
+/// What the public may see of any entry, private or not
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EntrySummary {
+    pub timestamp: u64,
+    pub private: bool,
+}
+
+/// An entry keyed by its image hash, as exported for state migration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EntryRecord {
+    pub image_hash: String,
+    pub entry: FoodEntry,
+}
+
+/// A node of the food taxonomy. `depth` is 0 for cuisines, 1 for dishes and
+/// 2 for variants.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Category {
+    pub id: u32,
+    pub name: String,
+    pub parent: Option<u32>,
+    pub depth: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClassifierModel {
+    pub version: String,
+    pub weights_hash: BytesN<32>,
+    pub uri: String,
+    pub registered_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CategoryStats {
+    pub entry_count: u32,
+    pub confidence_sum: u64,
+    pub successful_challenges: u32,
+}
+
+/// Registry activity within one day, bucketed by ledger timestamp.
+/// Verifications count entries and albums alike; challenges count upheld ones.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DailyStats {
+    pub submissions: u32,
+    pub verifications: u32,
+    pub rejections: u32,
+    pub challenges: u32,
+}
+
+/// Per-call price of `get_food_entry_paid`, split between the entry's verifier and the treasury
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LookupFee {
+    pub token: Address,
+    pub amount: i128,
+    pub treasury: Address,
+    pub verifier_share_bps: u32,
+}
+
+/// Fee a submitter escrows at `submit_for_verification`, split between the
+/// verifying verifier and the treasury once the image is registered
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubmissionFee {
+    pub token: Address,
+    pub amount: i128,
+    pub treasury: Address,
+    pub verifier_share_bps: u32,
+}
+
+/// An image awaiting verification and the fee escrowed for it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Submission {
+    pub submitter: Address,
+    pub fee_paid: i128,
+    pub submitted_at: u64,
+}
+
+/// Why a verifier turned a submission down
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RejectionReason {
+    NotFood,
+    Blurry,
+    /// Already registered, under this or another image hash; the fee is refunded
+    Duplicate,
+    Inappropriate,
+}
+
+/// The latest rejection of an image hash, kept for its submitter to inspect
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rejection {
+    pub submitter: Address,
+    pub verifier: Address,
+    pub reason: RejectionReason,
+    pub rejected_at: u64,
+}
+
+/// A partner app sharing the deployed registry. Its entries and roles live in
+/// their own namespace, administered by `admin`; `paused` halts registration
+/// for this tenant only.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tenant {
+    pub id: u32,
+    pub name: String,
+    pub admin: Address,
+    pub paused: bool,
+}
+
+/// At most `max_entries` registrations per verifier within any span of
+/// `window_ledgers` ledgers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateLimit {
+    pub max_entries: u32,
+    pub window_ledgers: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegistryConfig {
+    pub admin: Address,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
-    Admin,
+    Config,
+    Paused,
     Entry(String),
+    ModelCount,
+    Model(u32),
+    /// Chunked list of the image hashes a model classified
+    ModelEntries(u32),
+    CategoryValidity(String),
+    CategoryStats(String),
+    LookupFee,
+    /// Chunked list of image hashes in registration order
+    EntryIndex,
+    CategoryCount,
+    Category(u32),
+    CategoryByName(String),
+    LegacyDisabled,
+    EntryLabels(String),
+    AlbumCount,
+    Album(u32),
+    AlbumMember(String),
+    TenantCount,
+    Tenant(u32),
+    TenantEntry(u32, String),
+    RateLimit,
+    /// Ledger sequences of a verifier's registrations inside the current window
+    VerifierWindow(Address),
+    /// Timestamp from which a compromised verifier's entries are quarantined
+    CompromisedSince(Address),
+    /// Tombstone of an entry removed by an Admin
+    RemovedEntry(String),
+    /// Integrity stub of a redacted entry, keyed by its blind hash in hex
+    RedactedEntry(String),
+    /// Set when submitters may redact their own entries
+    SelfRedaction,
+    SubmissionFee,
+    /// Pending submission of an image hash
+    Submission(String),
+    /// Fee terms a pending submission's escrow was taken under
+    SubmissionEscrow(String),
+    /// Latest rejection of an image hash
+    Rejection(String),
+    /// Times an image hash has been rejected
+    RejectionCount(String),
+    /// Activity counters of a day index
+    DailyStats(u64),
 }
 
+/// Entry events (`new_ent`, `paid_lkp`, `reverify`, `chal_up`, `migrated`,
+/// `quarntn`, `removed`) are published under `(name, classification, image_hash)` topics
+/// so RPC filters can follow one category, or one image, without scanning the
+/// whole registry. `redacted` carries the entry's blind hash in place of the
+/// image hash.
 #[contract]
 pub struct FoodRegistryContract;
 
 #[contractimpl]
 impl FoodRegistryContract {
-    pub fn initialize(env: Env, admin: Address) -> Result<(), RegistryError> {
-        if env.storage().persistent().has(&DataKey::Admin) {
-            return Err(RegistryError::AlreadyInitialized);
+    /// Set the registry admin at deploy time
+    pub fn __constructor(env: Env, admin: Address) {
+        let config = RegistryConfig { admin: admin.clone() };
+        env.storage().instance().set(&DataKey::Config, &config);
+        rbac::grant_role(&env, Role::Admin, &admin);
+    }
+
+    pub fn get_config(env: Env) -> RegistryConfig {
+        env.storage().instance().get(&DataKey::Config).unwrap()
+    }
+
+    /// Grant a role (Only callable by an Admin)
+    pub fn grant_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        rbac::grant_role(&env, role, &account);
+        Self::audit(&env, symbol_short!("role_grnt"), &admin, (role, account));
+        Ok(())
+    }
+
+    /// Revoke a role (Only callable by an Admin). The last Admin cannot be
+    /// revoked.
+    pub fn revoke_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+        if rbac::is_last_admin(&env, role, &account) {
+            return Err(RegistryError::LastAdmin);
+        }
+
+        rbac::revoke_role(&env, role, &account);
+        Self::audit(&env, symbol_short!("role_rvk"), &admin, (role, account));
+        Ok(())
+    }
+
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        rbac::has_role(&env, role, &account)
+    }
+
+    /// Halt new registrations (Only callable by a Pauser)
+    pub fn pause(env: Env, pauser: Address) -> Result<(), RegistryError> {
+        pauser.require_auth();
+        Self::require_role(&env, Role::Pauser, &pauser)?;
+
+        env.storage().instance().set(&DataKey::Paused, &true);
+        Self::audit(&env, symbol_short!("paused"), &pauser, ());
+        env.events().publish((symbol_short!("paused"),), pauser);
+        Ok(())
+    }
+
+    /// Resume registrations (Only callable by a Pauser)
+    pub fn unpause(env: Env, pauser: Address) -> Result<(), RegistryError> {
+        pauser.require_auth();
+        Self::require_role(&env, Role::Pauser, &pauser)?;
+
+        env.storage().instance().remove(&DataKey::Paused);
+        Self::audit(&env, symbol_short!("unpaused"), &pauser, ());
+        env.events().publish((symbol_short!("unpaused"),), pauser);
+        Ok(())
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::Paused)
+    }
+
+    /// Privileged actions in the order they were taken, at most 50 from sequence
+    /// number `start` (Only callable by an Admin or Auditor). Entries are
+    /// append-only and never rewritten.
+    pub fn get_audit_log(env: Env, viewer: Address, start: u32, limit: u32) -> Result<Vec<AuditEntry>, RegistryError> {
+        viewer.require_auth();
+        if !rbac::can_audit(&env, &viewer) {
+            return Err(RegistryError::Unauthorized);
         }
-        env.storage().persistent().set(&DataKey::Admin, &admin);
+        Ok(audit::read(&env, start, limit.min(MAX_BATCH_SIZE)))
+    }
+
+    pub fn get_audit_log_len(env: Env) -> u32 {
+        audit::len(&env)
+    }
+
+    /// Convert a whole-percent confidence, as some entries registered before
+    /// confidence moved to basis points carry, into `ConfidenceBps`
+    pub fn confidence_from_percent(percent: u32) -> Result<ConfidenceBps, RegistryError> {
+        let bps = percent.checked_mul(100).ok_or(RegistryError::InvalidConfidence)?;
+        Self::require_valid_confidence(bps)?;
+        Ok(bps)
+    }
+
+    /// Describe a `RegistryError` code returned by this contract
+    pub fn error_message(env: Env, code: u32) -> String {
+        let message = RegistryError::from_code(code)
+            .map(|error| error.message())
+            .unwrap_or("unknown error");
+        String::from_str(&env, message)
+    }
+
+    /// Record a classifier model release and return its id (Only callable by an Admin)
+    pub fn register_model(
+        env: Env,
+        admin: Address,
+        version: String,
+        weights_hash: BytesN<32>,
+        uri: String,
+    ) -> Result<u32, RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        let model_id = env.storage().instance().get(&DataKey::ModelCount).unwrap_or(0u32) + 1;
+        let model = ClassifierModel {
+            version: version.clone(),
+            weights_hash,
+            uri,
+            registered_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::Model(model_id), &model);
+        env.storage().instance().set(&DataKey::ModelCount, &model_id);
+        Self::audit(&env, symbol_short!("model"), &admin, (model_id,));
+
+        env.events().publish((symbol_short!("model"), model_id), version);
+        Ok(model_id)
+    }
+
+    pub fn get_model(env: Env, model_id: u32) -> Result<ClassifierModel, RegistryError> {
+        Self::lookup_model(env, model_id).ok_or(RegistryError::ModelNotFound)
+    }
+
+    pub fn lookup_model(env: Env, model_id: u32) -> Option<ClassifierModel> {
+        env.storage().persistent().get(&DataKey::Model(model_id))
+    }
+
+    /// Make entries of `classification` expire `validity_secs` after each verification;
+    /// `None` keeps them valid indefinitely (Only callable by an Admin)
+    pub fn set_category_validity(
+        env: Env,
+        admin: Address,
+        classification: String,
+        validity_secs: Option<u64>,
+    ) -> Result<(), RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        let key = DataKey::CategoryValidity(classification.clone());
+        match validity_secs {
+            Some(secs) => env.storage().persistent().set(&key, &secs),
+            None => env.storage().persistent().remove(&key),
+        }
+        Self::audit(&env, symbol_short!("validity"), &admin, (classification, validity_secs));
         Ok(())
     }
 
+    pub fn get_category_validity(env: Env, classification: String) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::CategoryValidity(classification))
+    }
+
+    /// Register a verified classification for `image_hash` under taxonomy
+    /// category `category_id`; the entry's `classification` is the category's
+    /// name. Submitters wanting their images unlinkable should register under
+    /// `salted_key`.
     pub fn register_food_entry(
         env: Env,
+        verifier: Address,
+        submitter: Address,
+        image_hash: String,
+        category_id: u32,
+        confidence: ConfidenceBps,
+        model_id: u32,
+        metadata_hash: Option<BytesN<32>>,
+    ) -> Result<(), RegistryError> {
+        let category = Self::get_category(env.clone(), category_id)?;
+        Self::insert_entry(
+            &env,
+            verifier,
+            submitter,
+            image_hash,
+            category.name,
+            Some(category_id),
+            confidence,
+            model_id,
+            metadata_hash,
+        )
+    }
+
+    /// Register the classifier's full distribution for `image_hash`: up to 5
+    /// distinct taxonomy categories with confidences summing to at most 10000
+    /// bps. The most confident label fills the entry's `classification` and
+    /// `confidence` and alone counts towards category stats.
+    pub fn register_multi_label_entry(
+        env: Env,
+        verifier: Address,
+        submitter: Address,
+        image_hash: String,
+        labels: Vec<(u32, ConfidenceBps)>,
+        model_id: u32,
+        metadata_hash: Option<BytesN<32>>,
+    ) -> Result<(), RegistryError> {
+        if labels.is_empty() {
+            return Err(RegistryError::InvalidLabels);
+        }
+        if labels.len() > MAX_LABELS {
+            return Err(RegistryError::CapReached);
+        }
+
+        let mut distribution: Vec<Label> = Vec::new(&env);
+        let mut total: u32 = 0;
+        let mut top = 0;
+        for (category_id, confidence) in labels.iter() {
+            Self::require_valid_confidence(confidence)?;
+            if distribution.iter().any(|label| label.category_id == Some(category_id)) {
+                return Err(RegistryError::InvalidLabels);
+            }
+            total += confidence;
+            if confidence > distribution.get(top).map_or(0, |label| label.confidence) {
+                top = distribution.len();
+            }
+            let category = Self::get_category(env.clone(), category_id)?;
+            distribution.push_back(Label { category_id: Some(category_id), classification: category.name, confidence });
+        }
+        if total > MAX_CONFIDENCE_BPS {
+            return Err(RegistryError::InvalidLabels);
+        }
+
+        let top = distribution.get_unchecked(top);
+        Self::insert_entry(
+            &env,
+            verifier,
+            submitter,
+            image_hash.clone(),
+            top.classification,
+            top.category_id,
+            top.confidence,
+            model_id,
+            metadata_hash,
+        )?;
+        env.storage().persistent().set(&DataKey::EntryLabels(image_hash), &distribution);
+        Ok(())
+    }
+
+    /// The full label distribution of a public entry, most confident label
+    /// included; single-label entries return just their classification
+    pub fn get_entry_labels(env: Env, image_hash: String) -> Result<Vec<Label>, RegistryError> {
+        let entry = Self::get_food_entry(env.clone(), image_hash.clone())?;
+        let labels = env.storage().persistent().get(&DataKey::EntryLabels(image_hash));
+        Ok(labels.unwrap_or_else(|| {
+            vec![
+                &env,
+                Label { category_id: entry.category_id, classification: entry.classification, confidence: entry.confidence }
+            ]
+        }))
+    }
+
+    /// Register an entry under a free-form classification string. Deprecated:
+    /// new integrations should use `register_food_entry` with a taxonomy id,
+    /// and the path is closed for good by `disable_legacy_classifications`.
+    pub fn register_food_entry_legacy(
+        env: Env,
+        verifier: Address,
+        submitter: Address,
         image_hash: String,
         classification: String,
-        confidence: u32,
+        confidence: ConfidenceBps,
+        model_id: u32,
+        metadata_hash: Option<BytesN<32>>,
     ) -> Result<(), RegistryError> {
-        let admin: Address = env.storage().persistent().get(&DataKey::Admin)
-            .ok_or(RegistryError::NotInitialized)?;
-        
+        if env.storage().instance().has(&DataKey::LegacyDisabled) {
+            return Err(RegistryError::LegacyPathDisabled);
+        }
+        Self::insert_entry(&env, verifier, submitter, image_hash, classification, None, confidence, model_id, metadata_hash)
+    }
+
+    /// Pre-flight check of `image_hash` against the registry, so clients can
+    /// skip registrations bound to fail with `EntryAlreadyExists`
+    pub fn can_register(env: Env, image_hash: String) -> RegistrationStatus {
+        if Self::require_valid_image_hash(&image_hash).is_err() {
+            return RegistrationStatus::Invalid;
+        }
+        let storage = env.storage().persistent();
+        if storage.has(&DataKey::RemovedEntry(image_hash.clone())) {
+            return RegistrationStatus::Removed;
+        }
+        match storage.get::<_, FoodEntry>(&DataKey::Entry(image_hash.clone())) {
+            Some(entry) if entry.quarantined => RegistrationStatus::Quarantined,
+            Some(_) => RegistrationStatus::Exists,
+            None if storage.has(&DataKey::AlbumMember(image_hash)) => RegistrationStatus::Exists,
+            None => RegistrationStatus::Available,
+        }
+    }
+
+    /// Fetch a public entry; lapsed verifications are reported as `Stale` and
+    /// private entries as `EntryPrivate`
+    pub fn get_food_entry(env: Env, image_hash: String) -> Result<FoodEntry, RegistryError> {
+        match Self::lookup_food_entry(env, image_hash) {
+            EntryLookup::Found(entry) => Ok(entry),
+            EntryLookup::NotFound => Err(RegistryError::EntryNotFound),
+            EntryLookup::Stale => Err(RegistryError::Stale),
+            EntryLookup::Private => Err(RegistryError::EntryPrivate),
+        }
+    }
+
+    /// `get_food_entry` as a status instead of an error
+    pub fn lookup_food_entry(env: Env, image_hash: String) -> EntryLookup {
+        match Self::read_current_entry(&env, image_hash) {
+            Ok(entry) if entry.private => EntryLookup::Private,
+            Ok(entry) => EntryLookup::Found(entry),
+            Err(RegistryError::Stale) => EntryLookup::Stale,
+            Err(_) => EntryLookup::NotFound,
+        }
+    }
+
+    /// Set or clear the metered lookup price (Only callable by an Admin)
+    pub fn set_lookup_fee(env: Env, admin: Address, fee: Option<LookupFee>) -> Result<(), RegistryError> {
         admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        match &fee {
+            Some(fee) => {
+                if fee.amount < 0 || fee.verifier_share_bps as i128 > BPS_DENOMINATOR {
+                    return Err(RegistryError::InvalidAmount);
+                }
+                env.storage().instance().set(&DataKey::LookupFee, fee);
+            }
+            None => env.storage().instance().remove(&DataKey::LookupFee),
+        }
+        Self::audit(&env, symbol_short!("lkp_fee"), &admin, (fee,));
+        Ok(())
+    }
+
+    pub fn get_lookup_fee(env: Env) -> Option<LookupFee> {
+        env.storage().instance().get(&DataKey::LookupFee)
+    }
+
+    /// Fetch a public entry, paying the configured lookup fee from `caller`
+    pub fn get_food_entry_paid(env: Env, caller: Address, image_hash: String) -> Result<FoodEntry, RegistryError> {
+        caller.require_auth();
+
+        let entry = Self::get_food_entry(env.clone(), image_hash.clone())?;
+
+        if let Some(fee) = Self::get_lookup_fee(env.clone()) {
+            let recipients = Self::fee_recipients(&env, &entry.verifier, fee.verifier_share_bps, &fee.treasury);
+            split(&env, &fee.token, &caller, &recipients, fee.amount);
+
+            env.events().publish(
+                (symbol_short!("paid_lkp"), entry.classification.clone(), image_hash),
+                (caller, fee.amount)
+            );
+        }
+
+        Ok(entry)
+    }
+
+    /// Fetch an entry, including a private one, as its submitter, a Verifier or
+    /// an Auditor
+    pub fn get_private_entry(env: Env, viewer: Address, image_hash: String) -> Result<FoodEntry, RegistryError> {
+        viewer.require_auth();
+
+        let entry = Self::read_current_entry(&env, image_hash)?;
+        if entry.private
+            && entry.submitter != viewer
+            && !rbac::has_role(&env, Role::Verifier, &viewer)
+            && !rbac::has_role(&env, Role::Auditor, &viewer)
+        {
+            return Err(RegistryError::Unauthorized);
+        }
+        Ok(entry)
+    }
+
+    /// Existence and registration time of an entry, without classification details
+    pub fn get_entry_summary(env: Env, image_hash: String) -> Result<EntrySummary, RegistryError> {
+        let entry: FoodEntry = env.storage().persistent()
+            .get(&DataKey::Entry(image_hash))
+            .ok_or(RegistryError::EntryNotFound)?;
+        Ok(EntrySummary {
+            timestamp: entry.timestamp,
+            private: entry.private,
+        })
+    }
+
+    /// Hide or reveal an entry's classification details (Only callable by its submitter)
+    pub fn set_entry_private(env: Env, submitter: Address, image_hash: String, private: bool) -> Result<(), RegistryError> {
+        submitter.require_auth();
+
+        let key = DataKey::Entry(image_hash);
+        let mut entry: FoodEntry = env.storage().persistent().get(&key).ok_or(RegistryError::EntryNotFound)?;
+        if entry.submitter != submitter {
+            return Err(RegistryError::Unauthorized);
+        }
+
+        entry.private = private;
+        env.storage().persistent().set(&key, &entry);
+        Ok(())
+    }
+
+    /// Registry key for an image registered under a submitter-held salt:
+    /// lowercase hex of sha256(salt || image_hash)
+    pub fn salted_key(env: Env, image_hash: BytesN<32>, salt: BytesN<32>) -> String {
+        let mut preimage = Bytes::from_array(&env, &salt.to_array());
+        preimage.extend_from_array(&image_hash.to_array());
+        Self::hex_key(&env, &env.crypto().sha256(&preimage).to_array())
+    }
+
+    /// Refresh an entry's verification and confidence, restarting its validity period
+    /// and lifting any quarantine. Secondary labels, which described the previous
+    /// verification, are dropped (Only callable by a Verifier)
+    pub fn reverify_entry(
+        env: Env,
+        verifier: Address,
+        image_hash: String,
+        confidence: ConfidenceBps,
+    ) -> Result<(), RegistryError> {
+        if Self::is_paused(env.clone()) {
+            return Err(RegistryError::Paused);
+        }
+        Self::require_valid_confidence(confidence)?;
+
+        verifier.require_auth();
+        Self::require_role(&env, Role::Verifier, &verifier)?;
+
+        let key = DataKey::Entry(image_hash.clone());
+        let mut entry: FoodEntry = env.storage().persistent().get(&key).ok_or(RegistryError::EntryNotFound)?;
 
+        let stats_key = DataKey::CategoryStats(entry.classification.clone());
+        let mut stats = Self::get_category_stats(env.clone(), entry.classification.clone());
+        stats.confidence_sum = stats.confidence_sum - entry.confidence as u64 + confidence as u64;
+        env.storage().persistent().set(&stats_key, &stats);
+
+        entry.confidence = confidence;
+        entry.timestamp = env.ledger().timestamp();
+        entry.valid_until = Self::validity_deadline(&env, &entry.classification);
+        entry.verifier = verifier.clone();
+        entry.quarantined = false;
+        env.storage().persistent().set(&key, &entry);
+        env.storage().persistent().remove(&DataKey::EntryLabels(image_hash.clone()));
+
+        env.events().publish((symbol_short!("reverify"), entry.classification, image_hash), verifier);
+        Ok(())
+    }
+
+    /// Resolve up to 50 entries in one call; unknown, stale and private hashes come back as `None`
+    pub fn get_food_entries(env: Env, image_hashes: Vec<String>) -> Result<Vec<Option<FoodEntry>>, RegistryError> {
+        if image_hashes.len() > MAX_BATCH_SIZE {
+            return Err(RegistryError::CapReached);
+        }
+
+        let mut entries = Vec::new(&env);
+        for image_hash in image_hashes.iter() {
+            let entry = match Self::lookup_food_entry(env.clone(), image_hash) {
+                EntryLookup::Found(entry) => Some(entry),
+                _ => None,
+            };
+            entries.push_back(entry);
+        }
+        Ok(entries)
+    }
+
+    /// List image hashes classified by `model_id` in registration order, at
+    /// most 50 per call. Redacted entries are listed by their blind hash key.
+    pub fn get_entries_by_model(env: Env, model_id: u32, start: u32, limit: u32) -> Vec<String> {
+        let index = Self::model_index(model_id);
+        index.page(&env, &PageCursor::new(start, limit, MAX_BATCH_SIZE, index.len(&env)))
+    }
+
+    /// Export entries in registration order, at most 50 per call, with the cursor
+    /// to continue from or `None` when done. Entries are exported raw, stale
+    /// and private ones included, for migrating to a new registry.
+    pub fn export_entries(env: Env, cursor: u32, limit: u32) -> (Vec<EntryRecord>, Option<u32>) {
+        let index = Self::entry_index();
+        let cursor = PageCursor::new(cursor, limit, MAX_BATCH_SIZE, index.len(&env));
+
+        let mut page = Vec::new(&env);
+        for image_hash in index.page(&env, &cursor).iter() {
+            if let Some(entry) = env.storage().persistent().get(&DataKey::Entry(image_hash.clone())) {
+                page.push_back(EntryRecord { image_hash, entry });
+            }
+        }
+
+        (page, cursor.next_start())
+    }
+
+    /// Record that a challenge against an entry's classification was upheld
+    /// (Only callable by an Arbiter)
+    pub fn uphold_challenge(env: Env, arbiter: Address, image_hash: String) -> Result<(), RegistryError> {
+        arbiter.require_auth();
+        Self::require_role(&env, Role::Arbiter, &arbiter)?;
+
+        let entry: FoodEntry = env.storage().persistent()
+            .get(&DataKey::Entry(image_hash.clone()))
+            .ok_or(RegistryError::EntryNotFound)?;
+
+        let mut stats = Self::get_category_stats(env.clone(), entry.classification.clone());
+        stats.successful_challenges += 1;
+        env.storage().persistent().set(&DataKey::CategoryStats(entry.classification.clone()), &stats);
+        Self::count_daily(&env, |stats| stats.challenges += 1);
+        Self::audit(&env, symbol_short!("chal_up"), &arbiter, (image_hash.clone(),));
+
+        env.events().publish((symbol_short!("chal_up"), entry.classification, image_hash), arbiter);
+        Ok(())
+    }
+
+    /// Calibration aggregates for a classification: entries, summed confidence and upheld challenges
+    pub fn get_category_stats(env: Env, classification: String) -> CategoryStats {
+        env.storage().persistent()
+            .get(&DataKey::CategoryStats(classification))
+            .unwrap_or_default()
+    }
+
+    /// Check an off-chain metadata document hash against the one bound at registration
+    pub fn verify_metadata(env: Env, image_hash: String, provided_hash: BytesN<32>) -> Result<bool, RegistryError> {
+        let entry = Self::get_food_entry(env, image_hash)?;
+        Ok(entry.metadata_hash == Some(provided_hash))
+    }
+}
+
+impl FoodRegistryContract {
+    fn insert_entry(
+        env: &Env,
+        verifier: Address,
+        submitter: Address,
+        image_hash: String,
+        classification: String,
+        category_id: Option<u32>,
+        confidence: ConfidenceBps,
+        model_id: u32,
+        metadata_hash: Option<BytesN<32>>,
+    ) -> Result<(), RegistryError> {
+        if Self::is_paused(env.clone()) {
+            return Err(RegistryError::Paused);
+        }
+
+        verifier.require_auth();
+        Self::require_role(env, Role::Verifier, &verifier)?;
+        Self::require_valid_confidence(confidence)?;
+        Self::require_valid_image_hash(&image_hash)?;
+        Self::consume_rate_limit(env, &verifier)?;
+
+        if env.storage().persistent().has(&DataKey::RemovedEntry(image_hash.clone())) {
+            return Err(RegistryError::EntryRemoved);
+        }
         let key = DataKey::Entry(image_hash.clone());
-        if env.storage().persistent().has(&key) {
+        if env.storage().persistent().has(&key) || env.storage().persistent().has(&DataKey::AlbumMember(image_hash.clone())) {
             return Err(RegistryError::EntryAlreadyExists);
         }
+        if !env.storage().persistent().has(&DataKey::Model(model_id)) {
+            return Err(RegistryError::ModelNotFound);
+        }
 
         let entry = FoodEntry {
             classification: classification.clone(),
+            category_id,
             confidence,
+            model_id,
             timestamp: env.ledger().timestamp(),
-            verifier: admin,
+            valid_until: Self::validity_deadline(env, &classification),
+            verifier,
+            submitter,
+            private: false,
+            metadata_hash,
+            quarantined: false,
         };
 
         env.storage().persistent().set(&key, &entry);
 
+        let mut stats = Self::get_category_stats(env.clone(), classification.clone());
+        stats.entry_count += 1;
+        stats.confidence_sum += confidence as u64;
+        env.storage().persistent().set(&DataKey::CategoryStats(classification.clone()), &stats);
+
+        Self::model_index(model_id).push(env, image_hash.clone());
+        Self::entry_index().push(env, image_hash.clone());
+        Self::settle_submission(env, &image_hash, &entry.verifier, &entry.submitter);
+        Self::count_daily(env, |stats| stats.verifications += 1);
+
         env.events().publish(
-            (symbol_short!("new_ent"), image_hash), 
-            classification
+            (symbol_short!("new_ent"), classification, image_hash),
+            confidence
         );
 
         Ok(())
     }
 
-    pub fn get_food_entry(env: Env, image_hash: String) -> Result<FoodEntry, RegistryError> {
-        let key = DataKey::Entry(image_hash);
-        env.storage().persistent().get(&key).ok_or(RegistryError::EntryNotFound)
+    /// Image hashes of registry entries in registration order
+    fn entry_index() -> ChunkedVec<DataKey, String> {
+        ChunkedVec::new(DataKey::EntryIndex)
+    }
+
+    /// Image hashes classified by `model_id` in registration order
+    fn model_index(model_id: u32) -> ChunkedVec<DataKey, String> {
+        ChunkedVec::new(DataKey::ModelEntries(model_id))
+    }
+
+    /// Lowercase hex of a 32-byte digest, the canonical form of a registry key
+    fn hex_key(env: &Env, digest: &[u8; 32]) -> String {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        let mut hex = [0u8; 64];
+        for (i, byte) in digest.iter().enumerate() {
+            hex[2 * i] = HEX[(byte >> 4) as usize];
+            hex[2 * i + 1] = HEX[(byte & 0x0f) as usize];
+        }
+        String::from_bytes(env, &hex)
+    }
+
+    fn read_current_entry(env: &Env, image_hash: String) -> Result<FoodEntry, RegistryError> {
+        let entry: FoodEntry = env.storage().persistent()
+            .get(&DataKey::Entry(image_hash))
+            .ok_or(RegistryError::EntryNotFound)?;
+        if entry.valid_until.is_some_and(|deadline| env.ledger().timestamp() > deadline) {
+            return Err(RegistryError::Stale);
+        }
+        Ok(entry)
+    }
+
+    fn validity_deadline(env: &Env, classification: &String) -> Option<u64> {
+        let validity_secs: u64 = env.storage().persistent()
+            .get(&DataKey::CategoryValidity(classification.clone()))?;
+        Some(env.ledger().timestamp().saturating_add(validity_secs))
+    }
+
+    /// Append a privileged action by `actor` to the audit log
+    fn audit(env: &Env, action: Symbol, actor: &Address, details: impl IntoVal<Env, Vec<Val>>) {
+        audit::record(env, action, actor, details.into_val(env));
+    }
+
+    fn require_valid_confidence(confidence: ConfidenceBps) -> Result<(), RegistryError> {
+        if confidence > MAX_CONFIDENCE_BPS {
+            return Err(RegistryError::InvalidConfidence);
+        }
+        Ok(())
+    }
+
+    /// Split of a verification fee: `verifier_share_bps` to the verifier and
+    /// the rest, rounding dust included, to the treasury
+    pub(crate) fn fee_recipients(
+        env: &Env,
+        verifier: &Address,
+        verifier_share_bps: u32,
+        treasury: &Address,
+    ) -> Vec<SplitShare> {
+        vec![env, (verifier.clone(), verifier_share_bps), (treasury.clone(), MAX_BPS - verifier_share_bps)]
+    }
+
+    /// Accept only canonical image hashes: 1 to 128 bytes of lowercase ASCII
+    /// letters, digits and dashes, so one image cannot be registered twice
+    /// under differently cased or padded keys
+    fn require_valid_image_hash(image_hash: &String) -> Result<(), RegistryError> {
+        let len = image_hash.len() as usize;
+        if len == 0 || len > MAX_IMAGE_HASH_LEN {
+            return Err(RegistryError::InvalidImageHash);
+        }
+        let mut bytes = [0u8; MAX_IMAGE_HASH_LEN];
+        image_hash.copy_into_slice(&mut bytes[..len]);
+        if !bytes[..len].iter().all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || *byte == b'-') {
+            return Err(RegistryError::InvalidImageHash);
+        }
+        Ok(())
+    }
+
+    fn require_role(env: &Env, role: Role, account: &Address) -> Result<(), RegistryError> {
+        if !rbac::has_role(env, role, account) {
+            return Err(RegistryError::Unauthorized);
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod test;
+#[cfg(test)]
+mod test_budget;
@@ -0,0 +1,43 @@
+//! Self-description for tooling and indexers probing a deployment before use
+
+use crate::{FoodRegistryContract, FoodRegistryContractArgs, FoodRegistryContractClient};
+use soroban_sdk::{contractimpl, vec, Env, Symbol, Vec};
+
+/// Storage schema version of this build; bump it whenever a stored type or key
+/// changes shape in a way `export_entries` consumers must handle
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Minor and patch parts of `version`, bumped for interface additions and fixes
+/// that leave the storage schema alone
+const VERSION_MINOR: u32 = 1;
+const VERSION_PATCH: u32 = 0;
+
+#[contractimpl]
+impl FoodRegistryContract {
+    /// `(major, minor, patch)`, where `major` is the storage schema version
+    pub fn version() -> (u32, u32, u32) {
+        (SCHEMA_VERSION, VERSION_MINOR, VERSION_PATCH)
+    }
+
+    pub fn name(env: Env) -> Symbol {
+        Symbol::new(&env, "food_registry")
+    }
+
+    /// Capabilities of this build
+    pub fn supported_features(env: Env) -> Vec<Symbol> {
+        vec![
+            &env,
+            Symbol::new(&env, "albums"),
+            Symbol::new(&env, "anchors"),
+            Symbol::new(&env, "daily_stats"),
+            Symbol::new(&env, "labels"),
+            Symbol::new(&env, "lookups"),
+            Symbol::new(&env, "quarantine"),
+            Symbol::new(&env, "rate_limit"),
+            Symbol::new(&env, "redaction"),
+            Symbol::new(&env, "submissions"),
+            Symbol::new(&env, "taxonomy"),
+            Symbol::new(&env, "tenants"),
+        ]
+    }
+}
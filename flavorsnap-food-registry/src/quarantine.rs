@@ -0,0 +1,100 @@
+//! Emergency revocation of compromised verifiers, quarantine of the entries
+//! they registered, and removal of entries that must not stay listed
+
+use crate::{
+    DataKey, FoodEntry, FoodRegistryContract, FoodRegistryContractArgs, FoodRegistryContractClient, RegistryError,
+    Role, MAX_BATCH_SIZE
+};
+use flavorsnap_common::PageCursor;
+use flavorsnap_rbac as rbac;
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, String};
+
+#[contractimpl]
+impl FoodRegistryContract {
+    /// Remove `verifier`'s role (Only callable by an Admin). With `compromised`
+    /// set, its entries registered at or after `since` become eligible for
+    /// `quarantine_entries`.
+    pub fn revoke_verifier(
+        env: Env,
+        admin: Address,
+        verifier: Address,
+        compromised: bool,
+        since: u64,
+    ) -> Result<(), RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        rbac::revoke_role(&env, Role::Verifier, &verifier);
+        if compromised {
+            env.storage().persistent().set(&DataKey::CompromisedSince(verifier.clone()), &since);
+        }
+        Self::audit(&env, symbol_short!("vrf_rvk"), &admin, (verifier, compromised, since));
+        Ok(())
+    }
+
+    /// Timestamp from which `verifier`'s entries are quarantined, if it was
+    /// revoked as compromised
+    pub fn get_compromised_since(env: Env, verifier: Address) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::CompromisedSince(verifier))
+    }
+
+    /// Quarantine the compromised `verifier`'s entries among up to 50 registry
+    /// entries starting at `cursor`, in registration order, and return the
+    /// cursor to continue from, or `None` once the registry has been swept
+    /// (Only callable by an Admin). Tenant entries are not indexed here and
+    /// are left to their tenant's admin.
+    pub fn quarantine_entries(
+        env: Env,
+        admin: Address,
+        verifier: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<Option<u32>, RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        let since = Self::get_compromised_since(env.clone(), verifier.clone())
+            .ok_or(RegistryError::VerifierNotCompromised)?;
+
+        let index = Self::entry_index();
+        let cursor = PageCursor::new(cursor, limit, MAX_BATCH_SIZE, index.len(&env));
+        for image_hash in index.page(&env, &cursor).iter() {
+            let key = DataKey::Entry(image_hash.clone());
+            let Some(mut entry) = env.storage().persistent().get::<_, FoodEntry>(&key) else {
+                continue;
+            };
+            if entry.verifier != verifier || entry.timestamp < since || entry.quarantined {
+                continue;
+            }
+
+            entry.quarantined = true;
+            env.storage().persistent().set(&key, &entry);
+            env.events().publish((symbol_short!("quarntn"), entry.classification, image_hash), verifier.clone());
+        }
+
+        Ok(cursor.next_start())
+    }
+
+    /// Delete an entry and bar its image hash from being registered again
+    /// (Only callable by an Admin)
+    pub fn remove_entry(env: Env, admin: Address, image_hash: String) -> Result<(), RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        let key = DataKey::Entry(image_hash.clone());
+        let entry: FoodEntry = env.storage().persistent().get(&key).ok_or(RegistryError::EntryNotFound)?;
+
+        let mut stats = Self::get_category_stats(env.clone(), entry.classification.clone());
+        stats.entry_count -= 1;
+        stats.confidence_sum -= entry.confidence as u64;
+        env.storage().persistent().set(&DataKey::CategoryStats(entry.classification.clone()), &stats);
+
+        env.storage().persistent().remove(&key);
+        env.storage().persistent().remove(&DataKey::EntryLabels(image_hash.clone()));
+        env.storage().persistent().set(&DataKey::RemovedEntry(image_hash.clone()), &env.ledger().timestamp());
+        Self::audit(&env, symbol_short!("ent_rm"), &admin, (image_hash.clone(),));
+
+        env.events().publish((symbol_short!("removed"), entry.classification, image_hash), admin);
+        Ok(())
+    }
+}
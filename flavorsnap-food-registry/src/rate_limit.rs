@@ -0,0 +1,87 @@
+//! Per-verifier registration rate limit, containing the damage a stolen
+//! verifier key can do before the role is revoked
+
+use crate::{
+    DataKey, FoodRegistryContract, FoodRegistryContractArgs, FoodRegistryContractClient, RateLimit, RegistryError, Role
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+/// Largest `max_entries` a rate limit may allow, bounding each verifier's window log
+const MAX_RATE_LIMIT_ENTRIES: u32 = 1_000;
+
+#[contractimpl]
+impl FoodRegistryContract {
+    /// Set or clear the per-verifier rate limit applied to entry, album and
+    /// tenant entry registration (Only callable by an Admin). `max_entries`
+    /// must be between 1 and 1000 and `window_ledgers` non-zero.
+    pub fn set_rate_limit(env: Env, admin: Address, limit: Option<RateLimit>) -> Result<(), RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        match &limit {
+            Some(limit) => {
+                if limit.max_entries == 0 || limit.max_entries > MAX_RATE_LIMIT_ENTRIES || limit.window_ledgers == 0 {
+                    return Err(RegistryError::InvalidAmount);
+                }
+                env.storage().instance().set(&DataKey::RateLimit, limit);
+            }
+            None => env.storage().instance().remove(&DataKey::RateLimit),
+        }
+        Self::audit(&env, symbol_short!("rate_lim"), &admin, (limit,));
+        Ok(())
+    }
+
+    pub fn get_rate_limit(env: Env) -> Option<RateLimit> {
+        env.storage().instance().get(&DataKey::RateLimit)
+    }
+
+    /// Registrations `verifier` has made inside the current window
+    pub fn get_verifier_usage(env: Env, verifier: Address) -> u32 {
+        match Self::get_rate_limit(env.clone()) {
+            Some(limit) => Self::recent_registrations(&env, &verifier, &limit).len(),
+            None => 0,
+        }
+    }
+
+    /// Forget `verifier`'s registrations so far, lifting a rate limit it hit
+    /// (Only callable by an Admin)
+    pub fn reset_rate_limit(env: Env, admin: Address, verifier: Address) -> Result<(), RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        env.storage().persistent().remove(&DataKey::VerifierWindow(verifier.clone()));
+        Self::audit(&env, symbol_short!("rate_rst"), &admin, (verifier,));
+        Ok(())
+    }
+}
+
+impl FoodRegistryContract {
+    /// Count one registration by `verifier` against the rate limit, failing
+    /// with `RateLimited` once its window is full
+    pub(crate) fn consume_rate_limit(env: &Env, verifier: &Address) -> Result<(), RegistryError> {
+        let Some(limit) = Self::get_rate_limit(env.clone()) else {
+            return Ok(());
+        };
+
+        let mut recent = Self::recent_registrations(env, verifier, &limit);
+        if recent.len() >= limit.max_entries {
+            return Err(RegistryError::RateLimited);
+        }
+        recent.push_back(env.ledger().sequence());
+        env.storage().persistent().set(&DataKey::VerifierWindow(verifier.clone()), &recent);
+        Ok(())
+    }
+
+    /// Ledger sequences of `verifier`'s registrations within the last
+    /// `window_ledgers` ledgers, oldest first
+    fn recent_registrations(env: &Env, verifier: &Address, limit: &RateLimit) -> Vec<u32> {
+        let mut recent: Vec<u32> = env.storage().persistent()
+            .get(&DataKey::VerifierWindow(verifier.clone()))
+            .unwrap_or(Vec::new(env));
+        let oldest = env.ledger().sequence().saturating_sub(limit.window_ledgers - 1);
+        while recent.first().is_some_and(|ledger| ledger < oldest) {
+            recent.pop_front();
+        }
+        recent
+    }
+}
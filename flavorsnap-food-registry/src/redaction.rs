@@ -0,0 +1,95 @@
+//! Redaction of entries on a data subject's deletion request: the entry is
+//! re-keyed to a salted blind hash and reduced to an integrity stub, so the
+//! image hash leaves contract state while counts and category stats stand
+
+use crate::{
+    DataKey, FoodEntry, FoodRegistryContract, FoodRegistryContractArgs, FoodRegistryContractClient, RedactedEntry,
+    RegistryError, Role
+};
+use flavorsnap_rbac as rbac;
+use soroban_sdk::{contractimpl, symbol_short, Address, BytesN, Env, String};
+
+#[contractimpl]
+impl FoodRegistryContract {
+    /// Let submitters redact their own entries, or leave redaction to Admins
+    /// (Only callable by an Admin)
+    pub fn set_self_redaction(env: Env, admin: Address, enabled: bool) -> Result<(), RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        if enabled {
+            env.storage().instance().set(&DataKey::SelfRedaction, &true);
+        } else {
+            env.storage().instance().remove(&DataKey::SelfRedaction);
+        }
+        Self::audit(&env, symbol_short!("self_rdct"), &admin, (enabled,));
+        Ok(())
+    }
+
+    pub fn is_self_redaction_enabled(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::SelfRedaction)
+    }
+
+    /// Redact an entry, replacing `image_hash` everywhere the registry stores
+    /// it with the hex of `blind_hash` and keeping only a `RedactedEntry` stub
+    /// (Only callable by an Admin, or by the entry's submitter once self
+    /// redaction is enabled). `blind_hash` should be sha256(salt || image_hash)
+    /// under a salt the requester keeps off-chain, so only they can later link
+    /// the stub to the image. `entry_index` and `model_index` are the entry's
+    /// positions in `export_entries` and `get_entries_by_model`. The hash may
+    /// be registered again afterwards. Returns the stub's key.
+    pub fn redact_entry(
+        env: Env,
+        caller: Address,
+        image_hash: String,
+        blind_hash: BytesN<32>,
+        entry_index: u32,
+        model_index: u32,
+    ) -> Result<String, RegistryError> {
+        caller.require_auth();
+
+        let key = DataKey::Entry(image_hash.clone());
+        let entry: FoodEntry = env.storage().persistent().get(&key).ok_or(RegistryError::EntryNotFound)?;
+        let self_redaction = entry.submitter == caller && Self::is_self_redaction_enabled(env.clone());
+        if !self_redaction && !rbac::has_role(&env, Role::Admin, &caller) {
+            return Err(RegistryError::Unauthorized);
+        }
+
+        let blind_key = Self::hex_key(&env, &blind_hash.to_array());
+        let stub_key = DataKey::RedactedEntry(blind_key.clone());
+        if env.storage().persistent().has(&stub_key) || env.storage().persistent().has(&DataKey::Entry(blind_key.clone())) {
+            return Err(RegistryError::EntryAlreadyExists);
+        }
+
+        let slots = [(Self::entry_index(), entry_index), (Self::model_index(entry.model_id), model_index)];
+        for (index, position) in slots.iter() {
+            if index.get(&env, *position) != Some(image_hash.clone()) {
+                return Err(RegistryError::InvalidRedaction);
+            }
+        }
+        for (index, position) in slots.iter() {
+            index.set(&env, *position, blind_key.clone());
+        }
+
+        let stub = RedactedEntry {
+            classification: entry.classification.clone(),
+            category_id: entry.category_id,
+            confidence: entry.confidence,
+            model_id: entry.model_id,
+            timestamp: entry.timestamp,
+            redacted_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&stub_key, &stub);
+        env.storage().persistent().remove(&key);
+        env.storage().persistent().remove(&DataKey::EntryLabels(image_hash));
+        Self::audit(&env, symbol_short!("redact"), &caller, (blind_key.clone(),));
+
+        env.events().publish((symbol_short!("redacted"), entry.classification, blind_key.clone()), caller);
+        Ok(blind_key)
+    }
+
+    /// The stub left by a redaction, by the blind hash key `redact_entry` returned
+    pub fn get_redacted_entry(env: Env, blind_key: String) -> Result<RedactedEntry, RegistryError> {
+        env.storage().persistent().get(&DataKey::RedactedEntry(blind_key)).ok_or(RegistryError::EntryNotFound)
+    }
+}
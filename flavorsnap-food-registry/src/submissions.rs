@@ -0,0 +1,163 @@
+//! Images queued by their submitters for a verifier to register, with an
+//! optional fee escrowed at submission and paid out on verification
+
+use crate::{
+    DataKey, FoodRegistryContract, FoodRegistryContractArgs, FoodRegistryContractClient, RegistrationStatus,
+    RegistryError, Rejection, RejectionReason, Role, Submission, SubmissionFee, BPS_DENOMINATOR
+};
+use flavorsnap_common::split;
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Env, String};
+
+/// Rejections after which an image hash can no longer be submitted
+pub const MAX_REJECTIONS: u32 = 3;
+
+#[contractimpl]
+impl FoodRegistryContract {
+    /// Set or clear the fee charged at `submit_for_verification` (Only
+    /// callable by an Admin). Pending submissions keep the terms they were
+    /// made under.
+    pub fn set_submission_fee(env: Env, admin: Address, fee: Option<SubmissionFee>) -> Result<(), RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        match &fee {
+            Some(fee) => {
+                if fee.amount < 0 || fee.verifier_share_bps as i128 > BPS_DENOMINATOR {
+                    return Err(RegistryError::InvalidAmount);
+                }
+                env.storage().instance().set(&DataKey::SubmissionFee, fee);
+            }
+            None => env.storage().instance().remove(&DataKey::SubmissionFee),
+        }
+        Self::audit(&env, symbol_short!("sub_fee"), &admin, (fee,));
+        Ok(())
+    }
+
+    pub fn get_submission_fee(env: Env) -> Option<SubmissionFee> {
+        env.storage().instance().get(&DataKey::SubmissionFee)
+    }
+
+    /// Queue `image_hash` for verification, escrowing the submission fee from
+    /// `submitter`. When a verifier registers the image for `submitter` the
+    /// fee is split between that verifier and the treasury; if it is
+    /// registered for someone else, or rejected as a duplicate, it is refunded.
+    /// A hash rejected `MAX_REJECTIONS` times cannot be submitted again.
+    pub fn submit_for_verification(env: Env, submitter: Address, image_hash: String) -> Result<(), RegistryError> {
+        if Self::is_paused(env.clone()) {
+            return Err(RegistryError::Paused);
+        }
+        submitter.require_auth();
+        Self::require_valid_image_hash(&image_hash)?;
+
+        let key = DataKey::Submission(image_hash.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(RegistryError::EntryAlreadyExists);
+        }
+        match Self::can_register(env.clone(), image_hash.clone()) {
+            RegistrationStatus::Available => {}
+            RegistrationStatus::Removed => return Err(RegistryError::EntryRemoved),
+            _ => return Err(RegistryError::EntryAlreadyExists),
+        }
+        if Self::get_rejection_count(env.clone(), image_hash.clone()) >= MAX_REJECTIONS {
+            return Err(RegistryError::CapReached);
+        }
+
+        let fee = Self::get_submission_fee(env.clone()).filter(|fee| fee.amount > 0);
+        if let Some(fee) = &fee {
+            token::Client::new(&env, &fee.token).transfer(&submitter, &env.current_contract_address(), &fee.amount);
+            env.storage().persistent().set(&DataKey::SubmissionEscrow(image_hash.clone()), fee);
+        }
+        let submission = Submission {
+            submitter: submitter.clone(),
+            fee_paid: fee.map_or(0, |fee| fee.amount),
+            submitted_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&key, &submission);
+        Self::count_daily(&env, |stats| stats.submissions += 1);
+
+        env.events().publish((symbol_short!("submitted"), image_hash), submitter);
+        Ok(())
+    }
+
+    /// Fee terms escrowed for a pending submission, if it paid one
+    pub fn get_submission_escrow(env: Env, image_hash: String) -> Option<SubmissionFee> {
+        env.storage().persistent().get(&DataKey::SubmissionEscrow(image_hash))
+    }
+
+    pub fn get_submission(env: Env, image_hash: String) -> Option<Submission> {
+        env.storage().persistent().get(&DataKey::Submission(image_hash))
+    }
+
+    /// Turn down a pending submission for `reason`, recording it for the
+    /// submitter (Only callable by a Verifier). A `Duplicate` must point at an
+    /// image that is already registered, or fails with `NotDuplicate`. The fee
+    /// is refunded whenever the image is already registered, whatever the
+    /// reason, and otherwise paid out as for a verification, to `verifier`.
+    pub fn reject_submission(
+        env: Env,
+        verifier: Address,
+        image_hash: String,
+        reason: RejectionReason,
+    ) -> Result<(), RegistryError> {
+        verifier.require_auth();
+        Self::require_role(&env, Role::Verifier, &verifier)?;
+
+        let submission = Self::get_submission(env.clone(), image_hash.clone()).ok_or(RegistryError::SubmissionNotFound)?;
+        let registered = Self::can_register(env.clone(), image_hash.clone()) != RegistrationStatus::Available;
+        if reason == RejectionReason::Duplicate && !registered {
+            return Err(RegistryError::NotDuplicate);
+        }
+        let rejection = Rejection {
+            submitter: submission.submitter.clone(),
+            verifier: verifier.clone(),
+            reason,
+            rejected_at: env.ledger().timestamp(),
+        };
+        let count = Self::get_rejection_count(env.clone(), image_hash.clone());
+        env.storage().persistent().set(&DataKey::Rejection(image_hash.clone()), &rejection);
+        env.storage().persistent().set(&DataKey::RejectionCount(image_hash.clone()), &(count + 1));
+        Self::count_daily(&env, |stats| stats.rejections += 1);
+
+        Self::close_submission(&env, &image_hash, submission, &verifier, registered);
+        env.events().publish((symbol_short!("rejected"), image_hash), (verifier, reason));
+        Ok(())
+    }
+
+    /// Why the image hash was last rejected, if it ever was
+    pub fn get_rejection(env: Env, image_hash: String) -> Option<Rejection> {
+        env.storage().persistent().get(&DataKey::Rejection(image_hash))
+    }
+
+    pub fn get_rejection_count(env: Env, image_hash: String) -> u32 {
+        env.storage().persistent().get(&DataKey::RejectionCount(image_hash)).unwrap_or(0)
+    }
+}
+
+impl FoodRegistryContract {
+    /// Close any pending submission of a freshly registered image, paying its
+    /// fee out if it was registered for the submitter and refunding it if not
+    pub(crate) fn settle_submission(env: &Env, image_hash: &String, verifier: &Address, submitter: &Address) {
+        if let Some(submission) = Self::get_submission(env.clone(), image_hash.clone()) {
+            let refund = submission.submitter != *submitter;
+            Self::close_submission(env, image_hash, submission, verifier, refund);
+        }
+    }
+
+    fn close_submission(env: &Env, image_hash: &String, submission: Submission, verifier: &Address, refund: bool) {
+        let escrow_key = DataKey::SubmissionEscrow(image_hash.clone());
+        let fee: Option<SubmissionFee> = env.storage().persistent().get(&escrow_key);
+        env.storage().persistent().remove(&DataKey::Submission(image_hash.clone()));
+        env.storage().persistent().remove(&escrow_key);
+
+        if let Some(fee) = fee {
+            let contract = env.current_contract_address();
+            if refund {
+                token::Client::new(env, &fee.token).transfer(&contract, &submission.submitter, &fee.amount);
+            } else {
+                let recipients = Self::fee_recipients(env, verifier, fee.verifier_share_bps, &fee.treasury);
+                split(env, &fee.token, &contract, &recipients, fee.amount);
+            }
+        }
+        env.events().publish((symbol_short!("sub_close"), image_hash.clone()), (submission.submitter, submission.fee_paid, refund));
+    }
+}
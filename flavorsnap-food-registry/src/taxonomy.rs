@@ -0,0 +1,162 @@
+//! Managed hierarchy of food categories (Cuisine → Dish → Variant) and the
+//! migration of free-form classifications onto it
+
+use crate::{
+    Category, DataKey, FoodEntry, FoodRegistryContract, FoodRegistryContractArgs, FoodRegistryContractClient,
+    RegistryError, Role, MAX_BATCH_SIZE
+};
+use flavorsnap_common::PageCursor;
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, String, Vec};
+
+/// Levels below the root cuisines: a category at depth 2 is a variant and takes no children
+const MAX_CATEGORY_DEPTH: u32 = 2;
+
+#[contractimpl]
+impl FoodRegistryContract {
+    /// Add a category under `parent`, or a top-level cuisine when `None`, and
+    /// return its id. Names are unique across the whole taxonomy (Only callable
+    /// by an Admin)
+    pub fn add_category(
+        env: Env,
+        admin: Address,
+        name: String,
+        parent: Option<u32>,
+    ) -> Result<u32, RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        let name_key = DataKey::CategoryByName(name.clone());
+        if env.storage().persistent().has(&name_key) {
+            return Err(RegistryError::CategoryExists);
+        }
+        let depth = match parent {
+            Some(parent_id) => {
+                let parent = Self::get_category(env.clone(), parent_id)?;
+                if parent.depth >= MAX_CATEGORY_DEPTH {
+                    return Err(RegistryError::TaxonomyTooDeep);
+                }
+                parent.depth + 1
+            }
+            None => 0,
+        };
+
+        let id = env.storage().instance().get(&DataKey::CategoryCount).unwrap_or(0u32) + 1;
+        let category = Category { id, name: name.clone(), parent, depth };
+        env.storage().persistent().set(&DataKey::Category(id), &category);
+        env.storage().persistent().set(&name_key, &id);
+        env.storage().instance().set(&DataKey::CategoryCount, &id);
+
+        Self::audit(&env, symbol_short!("category"), &admin, (id, name.clone(), parent));
+        env.events().publish((symbol_short!("category"), id), name);
+        Ok(id)
+    }
+
+    pub fn get_category(env: Env, id: u32) -> Result<Category, RegistryError> {
+        Self::lookup_category(env, id).ok_or(RegistryError::CategoryNotFound)
+    }
+
+    pub fn lookup_category(env: Env, id: u32) -> Option<Category> {
+        env.storage().persistent().get(&DataKey::Category(id))
+    }
+
+    /// Id of the category registered under exactly `name`
+    pub fn find_category(env: Env, name: String) -> Result<u32, RegistryError> {
+        Self::lookup_category_id(env, name).ok_or(RegistryError::CategoryNotFound)
+    }
+
+    /// `find_category` as an `Option` instead of an error
+    pub fn lookup_category_id(env: Env, name: String) -> Option<u32> {
+        env.storage().persistent().get(&DataKey::CategoryByName(name))
+    }
+
+    /// The category and its ancestors, from the top-level cuisine down to `id`
+    pub fn resolve_category(env: Env, id: u32) -> Result<Vec<Category>, RegistryError> {
+        let mut path = Vec::new(&env);
+        let mut next = Some(id);
+        while let Some(id) = next {
+            let category = Self::get_category(env.clone(), id)?;
+            next = category.parent;
+            path.push_front(category);
+        }
+        Ok(path)
+    }
+
+    /// Rewrite free-form entries whose classification appears in `mapping`
+    /// onto the mapped taxonomy category, moving their contribution to the
+    /// category stats along. Walks up to `limit` (max 50) entries in
+    /// registration order from `cursor` and returns the cursor to continue
+    /// from, or `None` when done (Only callable by an Admin)
+    pub fn migrate_entries_to_taxonomy(
+        env: Env,
+        admin: Address,
+        mapping: Vec<(String, u32)>,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<Option<u32>, RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        if mapping.len() > MAX_BATCH_SIZE {
+            return Err(RegistryError::CapReached);
+        }
+        let mut targets = Vec::new(&env);
+        for (classification, category_id) in mapping.iter() {
+            targets.push_back((classification, Self::get_category(env.clone(), category_id)?));
+        }
+
+        let index = Self::entry_index();
+        let page = PageCursor::new(cursor, limit, MAX_BATCH_SIZE, index.len(&env));
+
+        let mut migrated: u32 = 0;
+        for image_hash in index.page(&env, &page).iter() {
+            let key = DataKey::Entry(image_hash.clone());
+            let Some(mut entry) = env.storage().persistent().get::<_, FoodEntry>(&key) else {
+                continue;
+            };
+            if entry.category_id.is_some() {
+                continue;
+            }
+            let Some((_, category)) = targets.iter().find(|(classification, _)| *classification == entry.classification) else {
+                continue;
+            };
+
+            let previous = entry.classification.clone();
+            if previous != category.name {
+                let mut stats = Self::get_category_stats(env.clone(), previous.clone());
+                stats.entry_count -= 1;
+                stats.confidence_sum -= entry.confidence as u64;
+                env.storage().persistent().set(&DataKey::CategoryStats(previous.clone()), &stats);
+
+                let mut stats = Self::get_category_stats(env.clone(), category.name.clone());
+                stats.entry_count += 1;
+                stats.confidence_sum += entry.confidence as u64;
+                env.storage().persistent().set(&DataKey::CategoryStats(category.name.clone()), &stats);
+            }
+
+            entry.classification = category.name.clone();
+            entry.category_id = Some(category.id);
+            env.storage().persistent().set(&key, &entry);
+
+            env.events().publish((symbol_short!("migrated"), category.name, image_hash), (previous, category.id));
+            migrated += 1;
+        }
+
+        Self::audit(&env, symbol_short!("tax_mig"), &admin, (cursor, migrated));
+        Ok(page.next_start())
+    }
+
+    /// Permanently close `register_food_entry_legacy` once existing entries
+    /// are migrated (Only callable by an Admin)
+    pub fn disable_legacy_classifications(env: Env, admin: Address) -> Result<(), RegistryError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        env.storage().instance().set(&DataKey::LegacyDisabled, &true);
+        Self::audit(&env, symbol_short!("legcy_off"), &admin, ());
+        Ok(())
+    }
+
+    pub fn is_legacy_disabled(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::LegacyDisabled)
+    }
+}
@@ -0,0 +1,163 @@
+//! Tenants: partner apps sharing one deployed registry without their entries,
+//! roles or config colliding.
+//!
+//! Tenant ids start at 1; the registry's original namespace is tenant 0 and
+//! keeps its existing keys and entrypoints, so deployed data stays readable.
+//! Models, taxonomy and category validity are shared by all tenants, while
+//! tenant entries stay out of the default namespace's stats, model index and
+//! export.
+
+use crate::{
+    ConfidenceBps, DataKey, FoodEntry, FoodRegistryContract, FoodRegistryContractArgs, FoodRegistryContractClient,
+    RegistryError, Role, Tenant
+};
+use flavorsnap_rbac as rbac;
+use soroban_sdk::{contractimpl, symbol_short, Address, BytesN, Env, String};
+
+#[contractimpl]
+impl FoodRegistryContract {
+    /// Create a tenant administered by `admin` and return its id (Only
+    /// callable by an Admin of the registry)
+    pub fn create_tenant(env: Env, super_admin: Address, name: String, admin: Address) -> Result<u32, RegistryError> {
+        super_admin.require_auth();
+        Self::require_role(&env, Role::Admin, &super_admin)?;
+
+        let id = env.storage().instance().get::<_, u32>(&DataKey::TenantCount).unwrap_or(0) + 1;
+        let tenant = Tenant { id, name, admin: admin.clone(), paused: false };
+        env.storage().persistent().set(&DataKey::Tenant(id), &tenant);
+        env.storage().instance().set(&DataKey::TenantCount, &id);
+        Self::audit(&env, symbol_short!("tenant"), &super_admin, (id, admin.clone()));
+
+        env.events().publish((symbol_short!("tenant"), id), admin);
+        Ok(id)
+    }
+
+    pub fn get_tenant(env: Env, tenant_id: u32) -> Result<Tenant, RegistryError> {
+        env.storage().persistent().get(&DataKey::Tenant(tenant_id)).ok_or(RegistryError::TenantNotFound)
+    }
+
+    /// Rename, hand over or pause a tenant (Only callable by the tenant's admin
+    /// or an Admin of the registry). The id cannot change.
+    pub fn set_tenant_config(env: Env, caller: Address, tenant: Tenant) -> Result<(), RegistryError> {
+        Self::require_tenant_admin(&env, &caller, tenant.id)?;
+
+        env.storage().persistent().set(&DataKey::Tenant(tenant.id), &tenant);
+        Self::audit(&env, symbol_short!("tnt_cfg"), &caller, (tenant.id, tenant.admin, tenant.paused));
+        Ok(())
+    }
+
+    /// Grant a role within a tenant (Only callable by the tenant's admin or an
+    /// Admin of the registry)
+    pub fn grant_tenant_role(
+        env: Env,
+        caller: Address,
+        tenant_id: u32,
+        role: Role,
+        account: Address,
+    ) -> Result<(), RegistryError> {
+        Self::require_tenant_admin(&env, &caller, tenant_id)?;
+
+        rbac::grant_tenant_role(&env, tenant_id, role, &account);
+        Self::audit(&env, symbol_short!("role_grnt"), &caller, (role, account, tenant_id));
+        Ok(())
+    }
+
+    /// Revoke a role within a tenant (Only callable by the tenant's admin or an
+    /// Admin of the registry)
+    pub fn revoke_tenant_role(
+        env: Env,
+        caller: Address,
+        tenant_id: u32,
+        role: Role,
+        account: Address,
+    ) -> Result<(), RegistryError> {
+        Self::require_tenant_admin(&env, &caller, tenant_id)?;
+
+        rbac::revoke_tenant_role(&env, tenant_id, role, &account);
+        Self::audit(&env, symbol_short!("role_rvk"), &caller, (role, account, tenant_id));
+        Ok(())
+    }
+
+    pub fn has_tenant_role(env: Env, tenant_id: u32, role: Role, account: Address) -> bool {
+        rbac::has_tenant_role(&env, tenant_id, role, &account)
+    }
+
+    /// Register a verified classification for `image_hash` in a tenant's
+    /// namespace under taxonomy category `category_id` (Only callable by a
+    /// Verifier of that tenant). Publishes `tnt_ent` under `(name, tenant_id,
+    /// image_hash)` topics.
+    pub fn register_tenant_entry(
+        env: Env,
+        verifier: Address,
+        tenant_id: u32,
+        submitter: Address,
+        image_hash: String,
+        category_id: u32,
+        confidence: ConfidenceBps,
+        model_id: u32,
+        metadata_hash: Option<BytesN<32>>,
+    ) -> Result<(), RegistryError> {
+        let tenant = Self::get_tenant(env.clone(), tenant_id)?;
+        if tenant.paused || Self::is_paused(env.clone()) {
+            return Err(RegistryError::Paused);
+        }
+
+        verifier.require_auth();
+        if !rbac::has_tenant_role(&env, tenant_id, Role::Verifier, &verifier) {
+            return Err(RegistryError::Unauthorized);
+        }
+        Self::require_valid_confidence(confidence)?;
+        Self::require_valid_image_hash(&image_hash)?;
+        Self::consume_rate_limit(&env, &verifier)?;
+
+        let key = DataKey::TenantEntry(tenant_id, image_hash.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(RegistryError::EntryAlreadyExists);
+        }
+        if !env.storage().persistent().has(&DataKey::Model(model_id)) {
+            return Err(RegistryError::ModelNotFound);
+        }
+        let category = Self::get_category(env.clone(), category_id)?;
+
+        let entry = FoodEntry {
+            valid_until: Self::validity_deadline(&env, &category.name),
+            classification: category.name.clone(),
+            category_id: Some(category_id),
+            confidence,
+            model_id,
+            timestamp: env.ledger().timestamp(),
+            verifier,
+            submitter,
+            private: false,
+            metadata_hash,
+            quarantined: false,
+        };
+        env.storage().persistent().set(&key, &entry);
+
+        env.events().publish((symbol_short!("tnt_ent"), tenant_id, image_hash), (category.name, confidence));
+        Ok(())
+    }
+
+    /// Fetch an entry from a tenant's namespace; lapsed verifications are
+    /// reported as `Stale`
+    pub fn get_tenant_entry(env: Env, tenant_id: u32, image_hash: String) -> Result<FoodEntry, RegistryError> {
+        let entry: FoodEntry = env.storage().persistent()
+            .get(&DataKey::TenantEntry(tenant_id, image_hash))
+            .ok_or(RegistryError::EntryNotFound)?;
+        if entry.valid_until.is_some_and(|deadline| env.ledger().timestamp() > deadline) {
+            return Err(RegistryError::Stale);
+        }
+        Ok(entry)
+    }
+}
+
+impl FoodRegistryContract {
+    fn require_tenant_admin(env: &Env, caller: &Address, tenant_id: u32) -> Result<(), RegistryError> {
+        caller.require_auth();
+        let tenant = Self::get_tenant(env.clone(), tenant_id)?;
+        if tenant.admin != *caller && !rbac::has_role(env, Role::Admin, caller) {
+            return Err(RegistryError::Unauthorized);
+        }
+        Ok(())
+    }
+}
@@ -1,27 +1,1218 @@
 #[cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{
+    symbol_short, testutils::{Address as _, Events, Ledger}, token, vec, xdr::ToXdr, Address, Bytes, BytesN, Env, FromVal,
+    IntoVal, String, Vec
+};
+
+fn register_test_model(env: &Env, client: &FoodRegistryContractClient, admin: &Address) -> u32 {
+    client.register_model(
+        admin,
+        &String::from_str(env, "v1.0.0"),
+        &BytesN::from_array(env, &[9; 32]),
+        &String::from_str(env, "ipfs://model-v1"),
+    )
+}
 
 #[test]
 fn test_food_registry_flow() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let contract_id = env.register(FoodRegistryContract, ());
-    let client = FoodRegistryContractClient::new(&env, &contract_id);
-
     let admin = Address::generate(&env);
-    client.initialize(&admin);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    assert_eq!(client.get_config().admin, admin);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
 
     let img_hash = String::from_str(&env, "abc123hash");
     let dish = String::from_str(&env, "Sushi");
-    client.register_food_entry(&img_hash, &dish, &95);
+    client.register_food_entry_legacy(&verifier, &submitter, &img_hash, &dish, &95, &model_id, &None);
 
     let entry = client.get_food_entry(&img_hash);
     assert_eq!(entry.classification, dish);
     assert_eq!(entry.confidence, 95);
-    assert_eq!(entry.verifier, admin);
+    assert_eq!(entry.verifier, verifier);
 
-    let res = client.try_register_food_entry(&img_hash, &dish, &95);
+    let res = client.try_register_food_entry_legacy(&verifier, &submitter, &img_hash, &dish, &95, &model_id, &None);
     assert!(res.is_err());
 }
+
+#[test]
+fn test_roles_gate_registration_and_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let img_hash = String::from_str(&env, "abc123hash");
+    let dish = String::from_str(&env, "Sushi");
+
+    // Not yet a verifier
+    let res = client.try_register_food_entry_legacy(&verifier, &submitter, &img_hash, &dish, &95, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+
+    // Only admins manage roles
+    let res = client.try_grant_role(&verifier, &Role::Verifier, &verifier);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    client.grant_role(&admin, &Role::Pauser, &pauser);
+    assert!(client.has_role(&Role::Verifier, &verifier));
+
+    client.pause(&pauser);
+    let res = client.try_register_food_entry_legacy(&verifier, &submitter, &img_hash, &dish, &95, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::Paused)));
+
+    client.unpause(&pauser);
+    client.register_food_entry_legacy(&verifier, &submitter, &img_hash, &dish, &95, &model_id, &None);
+
+    client.revoke_role(&admin, &Role::Verifier, &verifier);
+    assert!(!client.has_role(&Role::Verifier, &verifier));
+
+    // The last Admin stays
+    assert_eq!(client.try_revoke_role(&admin, &Role::Admin, &admin), Err(Ok(RegistryError::LastAdmin)));
+    client.grant_role(&admin, &Role::Admin, &pauser);
+    client.revoke_role(&pauser, &Role::Admin, &admin);
+    assert!(!client.has_role(&Role::Admin, &admin));
+}
+
+#[test]
+fn test_verify_metadata_hash() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let img_hash = String::from_str(&env, "abc123hash");
+    let dish = String::from_str(&env, "Sushi");
+    let metadata_hash = BytesN::from_array(&env, &[7; 32]);
+    client.register_food_entry_legacy(&verifier, &submitter, &img_hash, &dish, &95, &model_id, &Some(metadata_hash.clone()));
+
+    assert!(client.verify_metadata(&img_hash, &metadata_hash));
+    assert!(!client.verify_metadata(&img_hash, &BytesN::from_array(&env, &[8; 32])));
+
+    let missing = String::from_str(&env, "missing");
+    assert_eq!(client.try_verify_metadata(&missing, &metadata_hash), Err(Ok(RegistryError::EntryNotFound)));
+}
+
+#[test]
+fn test_error_codes_documented() {
+    let env = Env::default();
+    let contract_id = env.register(FoodRegistryContract, (Address::generate(&env),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+
+    for code in 1..=30u32 {
+        let error = RegistryError::from_code(code).unwrap();
+        assert_eq!(error as u32, code);
+        assert_eq!(client.error_message(&code), String::from_str(&env, error.message()));
+    }
+    assert_eq!(RegistryError::from_code(0), None);
+    assert_eq!(RegistryError::from_code(31), None);
+    assert_eq!(client.error_message(&31), String::from_str(&env, "unknown error"));
+
+    let missing = String::from_str(&env, "missing");
+    assert_eq!(client.try_get_food_entry(&missing), Err(Ok(RegistryError::EntryNotFound)));
+}
+
+#[test]
+fn test_contract_metadata_views() {
+    let env = Env::default();
+    let contract_id = env.register(FoodRegistryContract, (Address::generate(&env),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.version().0, meta::SCHEMA_VERSION);
+    assert_eq!(client.name(), Symbol::new(&env, "food_registry"));
+    let features = client.supported_features();
+    assert!(features.contains(Symbol::new(&env, "tenants")));
+    assert!(features.contains(Symbol::new(&env, "quarantine")));
+}
+
+#[test]
+fn test_get_food_entries_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let sushi = String::from_str(&env, "sushi-hash");
+    let ramen = String::from_str(&env, "ramen-hash");
+    let missing = String::from_str(&env, "missing-hash");
+    client.register_food_entry_legacy(&verifier, &submitter, &sushi, &String::from_str(&env, "Sushi"), &95, &model_id, &None);
+    client.register_food_entry_legacy(&verifier, &submitter, &ramen, &String::from_str(&env, "Ramen"), &88, &model_id, &None);
+
+    let entries = client.get_food_entries(&vec![&env, sushi.clone(), missing.clone(), ramen.clone()]);
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries.get(0).unwrap().unwrap().classification, String::from_str(&env, "Sushi"));
+    assert_eq!(entries.get(1).unwrap(), None);
+    assert_eq!(entries.get(2).unwrap().unwrap().confidence, 88);
+
+    let mut oversized = Vec::new(&env);
+    for _ in 0..51 {
+        oversized.push_back(missing.clone());
+    }
+    assert_eq!(client.try_get_food_entries(&oversized), Err(Ok(RegistryError::CapReached)));
+}
+
+#[test]
+fn test_anchor_entries_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let sushi = String::from_str(&env, "sushi-hash");
+    let ramen = String::from_str(&env, "ramen-hash");
+    let sushi_label = String::from_str(&env, "Sushi");
+    client.register_food_entry_legacy(&verifier, &submitter, &sushi, &sushi_label, &95, &model_id, &None);
+    client.register_food_entry_legacy(&verifier, &submitter, &ramen, &String::from_str(&env, "Ramen"), &88, &model_id, &None);
+
+    // Leaves are recomputable from the public entry
+    let mut preimage = Bytes::from_slice(&env, b"flavorsnap-entry-anchor-v1");
+    preimage.append(&sushi.clone().to_xdr(&env));
+    preimage.append(&sushi_label.to_xdr(&env));
+    preimage.extend_from_array(&95u32.to_be_bytes());
+    preimage.extend_from_array(&model_id.to_be_bytes());
+    preimage.extend_from_array(&client.get_food_entry(&sushi).timestamp.to_be_bytes());
+    preimage.append(&verifier.clone().to_xdr(&env));
+    preimage.append(&submitter.clone().to_xdr(&env));
+    preimage.push_back(0);
+    let leaf: BytesN<32> = env.crypto().sha256(&preimage).into();
+    assert_eq!(client.entry_anchor_leaf(&sushi), leaf);
+
+    let anchor = client.anchor_entries(&vec![&env, sushi.clone(), ramen.clone()]);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(event.1, (symbol_short!("anchor"),).into_val(&env));
+    assert_eq!(MerkleAnchor::from_val(&env, &event.2), anchor);
+    let leaves = vec![&env, leaf, client.entry_anchor_leaf(&ramen)];
+    assert_eq!((anchor.root, anchor.leaf_count), (flavorsnap_common::merkle_root(&env, &leaves), 2));
+
+    assert_eq!(client.try_anchor_entries(&Vec::new(&env)), Err(Ok(RegistryError::EmptyBatch)));
+    let missing = String::from_str(&env, "missing-hash");
+    assert_eq!(client.try_anchor_entries(&vec![&env, sushi.clone(), missing]), Err(Ok(RegistryError::EntryNotFound)));
+    let mut oversized = Vec::new(&env);
+    for _ in 0..51 {
+        oversized.push_back(sushi.clone());
+    }
+    assert_eq!(client.try_anchor_entries(&oversized), Err(Ok(RegistryError::CapReached)));
+
+    // Private entries stay out of public anchors
+    client.set_entry_private(&submitter, &ramen, &true);
+    assert_eq!(client.try_anchor_entries(&vec![&env, sushi, ramen]), Err(Ok(RegistryError::EntryPrivate)));
+}
+
+#[test]
+fn test_model_provenance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+
+    let weights = BytesN::from_array(&env, &[7; 32]);
+    let version = String::from_str(&env, "v2.1.0");
+    let res = client.try_register_model(&verifier, &version, &weights, &String::from_str(&env, "ipfs://v2"));
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+
+    let v1 = register_test_model(&env, &client, &admin);
+    let v2 = client.register_model(&admin, &version, &weights, &String::from_str(&env, "ipfs://v2"));
+    assert_ne!(v1, v2);
+    assert_eq!(client.get_model(&v2).weights_hash, weights);
+    assert_eq!(client.try_get_model(&99), Err(Ok(RegistryError::ModelNotFound)));
+
+    let dish = String::from_str(&env, "Pizza");
+    let res = client.try_register_food_entry_legacy(&verifier, &submitter, &String::from_str(&env, "h0"), &dish, &90, &99, &None);
+    assert_eq!(res, Err(Ok(RegistryError::ModelNotFound)));
+
+    let hashes = vec![
+        &env,
+        String::from_str(&env, "h1"),
+        String::from_str(&env, "h2"),
+        String::from_str(&env, "h3"),
+    ];
+    client.register_food_entry_legacy(&verifier, &submitter, &hashes.get(0).unwrap(), &dish, &90, &v1, &None);
+    client.register_food_entry_legacy(&verifier, &submitter, &hashes.get(1).unwrap(), &dish, &91, &v2, &None);
+    client.register_food_entry_legacy(&verifier, &submitter, &hashes.get(2).unwrap(), &dish, &92, &v1, &None);
+
+    assert_eq!(client.get_food_entry(&hashes.get(1).unwrap()).model_id, v2);
+
+    let by_v1 = client.get_entries_by_model(&v1, &0, &10);
+    assert_eq!(by_v1, vec![&env, hashes.get(0).unwrap(), hashes.get(2).unwrap()]);
+    assert_eq!(client.get_entries_by_model(&v1, &1, &10), vec![&env, hashes.get(2).unwrap()]);
+    assert_eq!(client.get_entries_by_model(&v2, &0, &10).len(), 1);
+    assert_eq!(client.get_entries_by_model(&99, &0, &10).len(), 0);
+}
+
+#[test]
+fn test_entries_go_stale_and_reverify() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let menu_item = String::from_str(&env, "Menu Item");
+    let day = 24 * 60 * 60;
+    let res = client.try_set_category_validity(&verifier, &menu_item, &Some(day));
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+    client.set_category_validity(&admin, &menu_item, &Some(day));
+    assert_eq!(client.get_category_validity(&menu_item), Some(day));
+
+    let dish_hash = String::from_str(&env, "burger-hash");
+    let staple_hash = String::from_str(&env, "rice-hash");
+    client.register_food_entry_legacy(&verifier, &submitter, &dish_hash, &menu_item, &80, &model_id, &None);
+    client.register_food_entry_legacy(&verifier, &submitter, &staple_hash, &String::from_str(&env, "Rice"), &99, &model_id, &None);
+    assert_eq!(client.get_food_entry(&dish_hash).valid_until, Some(day));
+
+    env.ledger().with_mut(|li| li.timestamp = day + 1);
+    assert_eq!(client.try_get_food_entry(&dish_hash), Err(Ok(RegistryError::Stale)));
+    assert_eq!(client.get_food_entry(&staple_hash).valid_until, None);
+
+    client.reverify_entry(&verifier, &dish_hash, &85);
+    let entry = client.get_food_entry(&dish_hash);
+    assert_eq!(entry.confidence, 85);
+    assert_eq!(entry.valid_until, Some(2 * day + 1));
+
+    let res = client.try_reverify_entry(&verifier, &String::from_str(&env, "unknown"), &85);
+    assert_eq!(res, Err(Ok(RegistryError::EntryNotFound)));
+}
+
+#[test]
+fn test_category_calibration_stats() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let sushi = String::from_str(&env, "Sushi");
+    assert_eq!(client.get_category_stats(&sushi), CategoryStats::default());
+
+    let first = String::from_str(&env, "sushi-1");
+    let second = String::from_str(&env, "sushi-2");
+    client.register_food_entry_legacy(&verifier, &submitter, &first, &sushi, &90, &model_id, &None);
+    client.register_food_entry_legacy(&verifier, &submitter, &second, &sushi, &70, &model_id, &None);
+    client.register_food_entry_legacy(&verifier, &submitter, &String::from_str(&env, "ramen-1"), &String::from_str(&env, "Ramen"), &50, &model_id, &None);
+
+    client.reverify_entry(&verifier, &second, &80);
+
+    let res = client.try_uphold_challenge(&arbiter, &first);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+    client.grant_role(&admin, &Role::Arbiter, &arbiter);
+    client.uphold_challenge(&arbiter, &first);
+
+    let stats = client.get_category_stats(&sushi);
+    assert_eq!(stats.entry_count, 2);
+    assert_eq!(stats.confidence_sum, 170);
+    assert_eq!(stats.successful_challenges, 1);
+}
+
+#[test]
+fn test_private_entries_and_salted_keys() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    // Salted keys are deterministic per salt and unlinkable across salts
+    let image = BytesN::from_array(&env, &[3; 32]);
+    let key = client.salted_key(&image, &BytesN::from_array(&env, &[1; 32]));
+    assert_eq!(key.len(), 64);
+    assert_eq!(key, client.salted_key(&image, &BytesN::from_array(&env, &[1; 32])));
+    assert_ne!(key, client.salted_key(&image, &BytesN::from_array(&env, &[2; 32])));
+
+    let dish = String::from_str(&env, "Tacos");
+    client.register_food_entry_legacy(&verifier, &submitter, &key, &dish, &90, &model_id, &None);
+
+    let res = client.try_set_entry_private(&stranger, &key, &true);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+    client.set_entry_private(&submitter, &key, &true);
+
+    assert_eq!(client.try_get_food_entry(&key), Err(Ok(RegistryError::EntryPrivate)));
+    assert_eq!(client.get_food_entries(&vec![&env, key.clone()]).get(0).unwrap(), None);
+    let summary = client.get_entry_summary(&key);
+    assert!(summary.private);
+    assert_eq!(summary.timestamp, 0);
+
+    assert_eq!(client.get_private_entry(&submitter, &key).classification, dish);
+    assert_eq!(client.get_private_entry(&verifier, &key).classification, dish);
+    assert_eq!(client.try_get_private_entry(&stranger, &key), Err(Ok(RegistryError::Unauthorized)));
+    client.grant_role(&admin, &Role::Auditor, &stranger);
+    assert_eq!(client.get_private_entry(&stranger, &key).classification, dish);
+
+    client.set_entry_private(&submitter, &key, &false);
+    assert_eq!(client.get_food_entry(&key).classification, dish);
+}
+
+#[test]
+fn test_paid_lookup_splits_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let consumer = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&consumer, &100);
+
+    let key = String::from_str(&env, "paella-hash");
+    client.register_food_entry_legacy(&verifier, &submitter, &key, &String::from_str(&env, "Paella"), &93, &model_id, &None);
+
+    // Free until a fee is configured
+    client.get_food_entry_paid(&consumer, &key);
+    assert_eq!(token_client.balance(&consumer), 100);
+
+    let fee = LookupFee {
+        token: token_address.clone(),
+        amount: 10,
+        treasury: treasury.clone(),
+        verifier_share_bps: 7_000,
+    };
+    let invalid = LookupFee { verifier_share_bps: 10_001, ..fee.clone() };
+    assert_eq!(client.try_set_lookup_fee(&admin, &Some(invalid)), Err(Ok(RegistryError::InvalidAmount)));
+    assert_eq!(client.try_set_lookup_fee(&consumer, &Some(fee.clone())), Err(Ok(RegistryError::Unauthorized)));
+    client.set_lookup_fee(&admin, &Some(fee));
+
+    assert_eq!(client.get_food_entry_paid(&consumer, &key).confidence, 93);
+    assert_eq!(token_client.balance(&consumer), 90);
+    assert_eq!(token_client.balance(&verifier), 7);
+    assert_eq!(token_client.balance(&treasury), 3);
+
+    // Misses are not charged
+    let res = client.try_get_food_entry_paid(&consumer, &String::from_str(&env, "missing"));
+    assert_eq!(res, Err(Ok(RegistryError::EntryNotFound)));
+    assert_eq!(token_client.balance(&consumer), 90);
+}
+
+#[test]
+fn test_submission_fee_split_on_verification_and_refunded_for_duplicates() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let other = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+    token::StellarAssetClient::new(&env, &token_address).mint(&submitter, &100);
+    token::StellarAssetClient::new(&env, &token_address).mint(&other, &100);
+
+    let fee = SubmissionFee {
+        token: token_address.clone(),
+        amount: 20,
+        treasury: treasury.clone(),
+        verifier_share_bps: 6_000,
+    };
+    let invalid = SubmissionFee { amount: -1, ..fee.clone() };
+    assert_eq!(client.try_set_submission_fee(&admin, &Some(invalid)), Err(Ok(RegistryError::InvalidAmount)));
+    assert_eq!(client.try_set_submission_fee(&submitter, &Some(fee.clone())), Err(Ok(RegistryError::Unauthorized)));
+    client.set_submission_fee(&admin, &Some(fee.clone()));
+    assert_eq!(client.get_submission_fee(), Some(fee.clone()));
+
+    // Escrowed at submission, split on verification
+    let ramen = String::from_str(&env, "ramen-hash");
+    client.submit_for_verification(&submitter, &ramen);
+    assert_eq!(client.get_submission(&ramen).unwrap().fee_paid, 20);
+    assert_eq!(client.get_submission_escrow(&ramen), Some(fee));
+    assert_eq!(token_client.balance(&contract_id), 20);
+    let res = client.try_submit_for_verification(&other, &ramen);
+    assert_eq!(res, Err(Ok(RegistryError::EntryAlreadyExists)));
+
+    client.register_food_entry_legacy(&verifier, &submitter, &ramen, &String::from_str(&env, "Ramen"), &90, &model_id, &None);
+    assert_eq!(client.get_submission(&ramen), None);
+    assert_eq!(client.get_submission_escrow(&ramen), None);
+    assert_eq!(token_client.balance(&verifier), 12);
+    assert_eq!(token_client.balance(&treasury), 8);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    // Registered hashes cannot be submitted again
+    let res = client.try_submit_for_verification(&other, &ramen);
+    assert_eq!(res, Err(Ok(RegistryError::EntryAlreadyExists)));
+
+    // A submission made redundant by another registration is refunded on rejection
+    let pho = String::from_str(&env, "pho-hash");
+    client.submit_for_verification(&other, &pho);
+    assert_eq!(token_client.balance(&other), 80);
+    let soups = client.add_category(&admin, &String::from_str(&env, "Soups"), &None);
+    client.register_album(&verifier, &submitter, &vec![&env, pho.clone()], &soups, &9_000, &model_id, &None);
+    assert_eq!(client.try_reject_submission(&submitter, &pho, &RejectionReason::Duplicate), Err(Ok(RegistryError::Unauthorized)));
+    client.reject_submission(&verifier, &pho, &RejectionReason::Duplicate);
+    assert_eq!(token_client.balance(&other), 100);
+    assert_eq!(client.try_reject_submission(&verifier, &pho, &RejectionReason::Duplicate), Err(Ok(RegistryError::SubmissionNotFound)));
+
+    // Registered for a different submitter: refunded as a duplicate
+    let taco = String::from_str(&env, "taco-hash");
+    client.submit_for_verification(&other, &taco);
+    client.register_food_entry_legacy(&verifier, &submitter, &taco, &String::from_str(&env, "Taco"), &88, &model_id, &None);
+    assert_eq!(token_client.balance(&other), 100);
+    assert_eq!(token_client.balance(&verifier), 12);
+
+    // Already registered, the fee is refunded whatever reason is given
+    let udon = String::from_str(&env, "udon-hash");
+    client.submit_for_verification(&other, &udon);
+    client.register_album(&verifier, &submitter, &vec![&env, udon.clone()], &soups, &9_000, &model_id, &None);
+    client.reject_submission(&verifier, &udon, &RejectionReason::Blurry);
+    assert_eq!(token_client.balance(&other), 100);
+
+    // Rejected on its merits: the fee is earned, and an unregistered image
+    // cannot be passed off as a duplicate to get it back
+    let junk = String::from_str(&env, "junk-hash");
+    client.submit_for_verification(&other, &junk);
+    let res = client.try_reject_submission(&verifier, &junk, &RejectionReason::Duplicate);
+    assert_eq!(res, Err(Ok(RegistryError::NotDuplicate)));
+    client.reject_submission(&verifier, &junk, &RejectionReason::NotFood);
+    assert_eq!(token_client.balance(&other), 80);
+    assert_eq!(token_client.balance(&verifier), 24);
+    assert_eq!(token_client.balance(&treasury), 16);
+}
+
+#[test]
+fn test_rejections_are_recorded_and_limit_resubmission() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+
+    let key = String::from_str(&env, "blurry-hash");
+    assert_eq!(client.get_rejection(&key), None);
+
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    client.submit_for_verification(&submitter, &key);
+    client.reject_submission(&verifier, &key, &RejectionReason::Blurry);
+    let rejection = client.get_rejection(&key).unwrap();
+    assert_eq!(rejection.submitter, submitter);
+    assert_eq!(rejection.verifier, verifier);
+    assert_eq!(rejection.reason, RejectionReason::Blurry);
+    assert_eq!(rejection.rejected_at, 500);
+    assert_eq!(client.get_submission(&key), None);
+
+    // The latest reason replaces the last; the count keeps climbing
+    client.submit_for_verification(&submitter, &key);
+    client.reject_submission(&verifier, &key, &RejectionReason::Inappropriate);
+    client.submit_for_verification(&submitter, &key);
+    client.reject_submission(&verifier, &key, &RejectionReason::NotFood);
+    assert_eq!(client.get_rejection(&key).unwrap().reason, RejectionReason::NotFood);
+    assert_eq!(client.get_rejection_count(&key), MAX_REJECTIONS);
+
+    let res = client.try_submit_for_verification(&submitter, &key);
+    assert_eq!(res, Err(Ok(RegistryError::CapReached)));
+}
+
+#[test]
+fn test_daily_stats_bucket_activity_by_day() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    client.grant_role(&admin, &Role::Arbiter, &arbiter);
+    let model_id = register_test_model(&env, &client, &admin);
+    let dish = String::from_str(&env, "Curry");
+
+    env.ledger().with_mut(|li| li.timestamp = 3 * SECONDS_PER_DAY + 10);
+    assert_eq!(client.current_day(), 3);
+    let [curry, naan, rice] = ["curry-hash", "naan-hash", "rice-hash"].map(|hash| String::from_str(&env, hash));
+    client.submit_for_verification(&submitter, &curry);
+    client.submit_for_verification(&submitter, &naan);
+    client.register_food_entry_legacy(&verifier, &submitter, &curry, &dish, &90, &model_id, &None);
+    client.reject_submission(&verifier, &naan, &RejectionReason::Blurry);
+
+    env.ledger().with_mut(|li| li.timestamp = 4 * SECONDS_PER_DAY);
+    client.register_food_entry_legacy(&verifier, &submitter, &rice, &dish, &80, &model_id, &None);
+    client.uphold_challenge(&arbiter, &curry);
+
+    let day_three = DailyStats { submissions: 2, verifications: 1, rejections: 1, challenges: 0 };
+    let day_four = DailyStats { submissions: 0, verifications: 1, rejections: 0, challenges: 1 };
+    assert_eq!(client.get_daily_stats(&3), day_three);
+    assert_eq!(client.get_daily_stats(&4), day_four);
+    assert_eq!(client.get_daily_stats(&5), DailyStats::default());
+}
+
+#[test]
+fn test_export_entries() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let hashes = [
+        String::from_str(&env, "hash-a"),
+        String::from_str(&env, "hash-b"),
+        String::from_str(&env, "hash-c"),
+    ];
+    for image_hash in hashes.iter() {
+        client.register_food_entry_legacy(&verifier, &submitter, image_hash, &String::from_str(&env, "Ramen"), &90, &model_id, &None);
+    }
+    client.set_entry_private(&submitter, &hashes[1], &true);
+
+    let (page, cursor) = client.export_entries(&0, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get_unchecked(0).image_hash, hashes[0]);
+    assert!(page.get_unchecked(1).entry.private);
+    assert_eq!(cursor, Some(2));
+
+    let (page, cursor) = client.export_entries(&2, &2);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get_unchecked(0).image_hash, hashes[2]);
+    assert_eq!(cursor, None);
+}
+
+#[test]
+fn test_entry_indexes_page_across_chunks() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let total = flavorsnap_common::CHUNK_SIZE + 6;
+    let hash = |i: u32| {
+        let name = [b'd', b'-', b'0' + (i / 10) as u8, b'0' + (i % 10) as u8];
+        String::from_bytes(&env, &name)
+    };
+    for i in 0..total {
+        client.register_food_entry_legacy(&verifier, &submitter, &hash(i), &String::from_str(&env, "Pho"), &90, &model_id, &None);
+    }
+
+    // Pages straddling the first chunk boundary come back whole and in order
+    let start = flavorsnap_common::CHUNK_SIZE - 3;
+    let (page, cursor) = client.export_entries(&start, &6);
+    for (offset, record) in page.iter().enumerate() {
+        assert_eq!(record.image_hash, hash(start + offset as u32));
+    }
+    assert_eq!(page.len(), 6);
+    assert_eq!(cursor, Some(start + 6));
+
+    let by_model = client.get_entries_by_model(&model_id, &start, &50);
+    assert_eq!(by_model.len(), total - start);
+    assert_eq!(by_model.get_unchecked(by_model.len() - 1), hash(total - 1));
+}
+
+#[test]
+fn test_entry_event_topics_carry_classification() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let img_hash = String::from_str(&env, "abc123hash");
+    let dish = String::from_str(&env, "Sushi");
+    client.register_food_entry_legacy(&verifier, &Address::generate(&env), &img_hash, &dish, &95, &model_id, &None);
+
+    let event = env.events().all().last().unwrap();
+    assert_eq!(event.0, contract_id);
+    assert_eq!(event.1, (symbol_short!("new_ent"), dish.clone(), img_hash.clone()).into_val(&env));
+    assert_eq!(u32::from_val(&env, &event.2), 95);
+
+    client.reverify_entry(&verifier, &img_hash, &90);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(event.1, (symbol_short!("reverify"), dish, img_hash).into_val(&env));
+}
+
+#[test]
+fn test_admin_actions_audit_logged() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+
+    let model_id = register_test_model(&env, &client, &admin);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    client.grant_role(&admin, &Role::Pauser, &admin);
+    client.pause(&admin);
+
+    assert_eq!(client.get_audit_log_len(), 4);
+    assert_eq!(client.try_get_audit_log(&verifier, &0, &10), Err(Ok(RegistryError::Unauthorized)));
+    let log = client.get_audit_log(&admin, &1, &10);
+    assert_eq!(log.len(), 3);
+
+    let grant = log.get(0).unwrap();
+    assert_eq!(grant.seq, 1);
+    assert_eq!(grant.action, symbol_short!("role_grnt"));
+    assert_eq!(grant.actor, admin);
+    assert_eq!(grant.timestamp, 1_000);
+    assert_eq!(Role::from_val(&env, &grant.details.get(0).unwrap()), Role::Verifier);
+    assert_eq!(Address::from_val(&env, &grant.details.get(1).unwrap()), verifier);
+
+    let model = client.get_audit_log(&admin, &0, &1).get(0).unwrap();
+    assert_eq!(model.action, symbol_short!("model"));
+    assert_eq!(u32::from_val(&env, &model.details.get(0).unwrap()), model_id);
+    assert_eq!(log.get(2).unwrap().action, symbol_short!("paused"));
+}
+
+#[test]
+fn test_taxonomy_categories_and_registration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let japanese = client.add_category(&admin, &String::from_str(&env, "Japanese"), &None);
+    let sushi = client.add_category(&admin, &String::from_str(&env, "Sushi"), &Some(japanese));
+    let nigiri = client.add_category(&admin, &String::from_str(&env, "Nigiri"), &Some(sushi));
+
+    // Only admins manage the taxonomy; names are unique and at most three levels deep
+    let res = client.try_add_category(&verifier, &String::from_str(&env, "Thai"), &None);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+    let res = client.try_add_category(&admin, &String::from_str(&env, "Sushi"), &Some(japanese));
+    assert_eq!(res, Err(Ok(RegistryError::CategoryExists)));
+    let res = client.try_add_category(&admin, &String::from_str(&env, "Salmon"), &Some(nigiri));
+    assert_eq!(res, Err(Ok(RegistryError::TaxonomyTooDeep)));
+    let res = client.try_add_category(&admin, &String::from_str(&env, "Maki"), &Some(99));
+    assert_eq!(res, Err(Ok(RegistryError::CategoryNotFound)));
+
+    let path = client.resolve_category(&nigiri);
+    assert_eq!(path.len(), 3);
+    assert_eq!(path.get(0).unwrap().id, japanese);
+    assert_eq!(path.get(1).unwrap().parent, Some(japanese));
+    assert_eq!(path.get(2).unwrap().depth, 2);
+    assert_eq!(client.find_category(&String::from_str(&env, "Sushi")), sushi);
+    assert_eq!(client.try_resolve_category(&99), Err(Ok(RegistryError::CategoryNotFound)));
+
+    let img_hash = String::from_str(&env, "abc123hash");
+    let res = client.try_register_food_entry(&verifier, &submitter, &img_hash, &99, &95, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::CategoryNotFound)));
+    client.register_food_entry(&verifier, &submitter, &img_hash, &nigiri, &95, &model_id, &None);
+
+    let entry = client.get_food_entry(&img_hash);
+    assert_eq!(entry.category_id, Some(nigiri));
+    assert_eq!(entry.classification, String::from_str(&env, "Nigiri"));
+    assert_eq!(client.get_category_stats(&entry.classification).entry_count, 1);
+}
+
+#[test]
+fn test_migrate_legacy_entries_to_taxonomy() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let upper = String::from_str(&env, "Sushi");
+    let lower = String::from_str(&env, "sushi");
+    let pizza = String::from_str(&env, "Pizza");
+    let hashes = ["h1", "h2", "h3"].map(|hash| String::from_str(&env, hash));
+    client.register_food_entry_legacy(&verifier, &submitter, &hashes[0], &upper, &90, &model_id, &None);
+    client.register_food_entry_legacy(&verifier, &submitter, &hashes[1], &lower, &70, &model_id, &None);
+    client.register_food_entry_legacy(&verifier, &submitter, &hashes[2], &pizza, &80, &model_id, &None);
+
+    let japanese = client.add_category(&admin, &String::from_str(&env, "Japanese"), &None);
+    let sushi = client.add_category(&admin, &upper, &Some(japanese));
+    let mapping = vec![&env, (upper.clone(), sushi), (lower.clone(), sushi)];
+
+    let res = client.try_migrate_entries_to_taxonomy(&verifier, &mapping, &0, &2);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+    let res = client.try_migrate_entries_to_taxonomy(&admin, &vec![&env, (pizza.clone(), 99)], &0, &2);
+    assert_eq!(res, Err(Ok(RegistryError::CategoryNotFound)));
+
+    assert_eq!(client.migrate_entries_to_taxonomy(&admin, &mapping, &0, &2), Some(2));
+    let (_, topics, data) = env.events().all().get(0).unwrap();
+    assert_eq!(topics, (symbol_short!("migrated"), upper.clone(), hashes[0].clone()).into_val(&env));
+    assert_eq!(<(String, u32)>::from_val(&env, &data), (upper.clone(), sushi));
+    assert_eq!(client.migrate_entries_to_taxonomy(&admin, &mapping, &2, &2), None);
+
+    // Both spellings now count towards the canonical category
+    let entry = client.get_food_entry(&hashes[1]);
+    assert_eq!(entry.classification, upper);
+    assert_eq!(entry.category_id, Some(sushi));
+    assert_eq!(client.get_category_stats(&upper).entry_count, 2);
+    assert_eq!(client.get_category_stats(&upper).confidence_sum, 160);
+    assert_eq!(client.get_category_stats(&lower).entry_count, 0);
+
+    // Unmapped entries are left alone
+    assert_eq!(client.get_food_entry(&hashes[2]).category_id, None);
+
+    client.disable_legacy_classifications(&admin);
+    assert!(client.is_legacy_disabled());
+    let res = client.try_register_food_entry_legacy(&verifier, &submitter, &String::from_str(&env, "h4"), &pizza, &80, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::LegacyPathDisabled)));
+}
+
+#[test]
+fn test_confidence_bounded_in_basis_points() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let img_hash = String::from_str(&env, "abc123hash");
+    let dish = String::from_str(&env, "Sushi");
+    let res = client.try_register_food_entry_legacy(&verifier, &submitter, &img_hash, &dish, &4_000_000_000, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::InvalidConfidence)));
+    client.register_food_entry_legacy(&verifier, &submitter, &img_hash, &dish, &MAX_CONFIDENCE_BPS, &model_id, &None);
+
+    let res = client.try_reverify_entry(&verifier, &img_hash, &(MAX_CONFIDENCE_BPS + 1));
+    assert_eq!(res, Err(Ok(RegistryError::InvalidConfidence)));
+    assert_eq!(client.get_food_entry(&img_hash).confidence, 10_000);
+
+    assert_eq!(client.confidence_from_percent(&95), 9_500);
+    assert_eq!(client.try_confidence_from_percent(&101), Err(Ok(RegistryError::InvalidConfidence)));
+    assert_eq!(client.try_confidence_from_percent(&u32::MAX), Err(Ok(RegistryError::InvalidConfidence)));
+}
+
+#[test]
+fn test_multi_label_entry_distribution() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+
+    let noodles = client.add_category(&admin, &String::from_str(&env, "Noodles"), &None);
+    let ramen = client.add_category(&admin, &String::from_str(&env, "Ramen"), &Some(noodles));
+    let pho = client.add_category(&admin, &String::from_str(&env, "Pho"), &Some(noodles));
+
+    let img_hash = String::from_str(&env, "bowl");
+    let res = client.try_register_multi_label_entry(&verifier, &submitter, &img_hash, &vec![&env], &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::InvalidLabels)));
+    let res = client.try_register_multi_label_entry(&verifier, &submitter, &img_hash, &vec![&env, (ramen, 8_000), (pho, 2_500)], &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::InvalidLabels)));
+    let res = client.try_register_multi_label_entry(&verifier, &submitter, &img_hash, &vec![&env, (ramen, 4_000), (ramen, 1_000)], &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::InvalidLabels)));
+
+    client.register_multi_label_entry(&verifier, &submitter, &img_hash, &vec![&env, (pho, 1_500), (ramen, 8_000)], &model_id, &None);
+
+    // The top label stays in the single-label fields
+    let entry = client.get_food_entry(&img_hash);
+    assert_eq!(entry.category_id, Some(ramen));
+    assert_eq!(entry.classification, String::from_str(&env, "Ramen"));
+    assert_eq!(entry.confidence, 8_000);
+    assert_eq!(client.get_category_stats(&String::from_str(&env, "Pho")).entry_count, 0);
+
+    let labels = client.get_entry_labels(&img_hash);
+    assert_eq!(labels.len(), 2);
+    assert_eq!(labels.get(0).unwrap(), Label { category_id: Some(pho), classification: String::from_str(&env, "Pho"), confidence: 1_500 });
+
+    // Re-verification replaces the distribution with the single refreshed label
+    client.reverify_entry(&verifier, &img_hash, &9_000);
+    let labels = client.get_entry_labels(&img_hash);
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels.get(0).unwrap().confidence, 9_000);
+}
+
+#[test]
+fn test_album_groups_shots_of_one_meal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+    let tacos = client.add_category(&admin, &String::from_str(&env, "Tacos"), &None);
+
+    let [front, side, top] = ["front", "side", "top"].map(|hash| String::from_str(&env, hash));
+    let res = client.try_register_album(&submitter, &submitter, &vec![&env, front.clone()], &tacos, &9_000, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+    let res = client.try_register_album(&verifier, &submitter, &vec![&env], &tacos, &9_000, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::InvalidAlbum)));
+    let res = client.try_register_album(&verifier, &submitter, &vec![&env, front.clone(), front.clone()], &tacos, &9_000, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::InvalidAlbum)));
+
+    let album_id = client.register_album(&verifier, &submitter, &vec![&env, front.clone(), side.clone()], &tacos, &9_000, &model_id, &None);
+    let album = client.get_album(&album_id);
+    assert_eq!(album.image_hashes, vec![&env, front.clone(), side.clone()]);
+    assert_eq!(album.classification, String::from_str(&env, "Tacos"));
+    assert_eq!(client.get_category_stats(&album.classification).entry_count, 1);
+    assert_eq!(client.try_get_album(&(album_id + 1)), Err(Ok(RegistryError::AlbumNotFound)));
+
+    assert!(client.is_album_member(&album_id, &side));
+    assert!(!client.is_album_member(&album_id, &top));
+    assert_eq!(client.get_album_of(&front), Some(album_id));
+
+    // A shot belongs to one album or entry only
+    let res = client.try_register_album(&verifier, &submitter, &vec![&env, top.clone(), front.clone()], &tacos, &9_000, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::EntryAlreadyExists)));
+    let res = client.try_register_food_entry(&verifier, &submitter, &side, &tacos, &9_000, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::EntryAlreadyExists)));
+}
+
+#[test]
+fn test_tenants_keep_entries_and_roles_apart() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let acme_admin = Address::generate(&env);
+    let acme_verifier = Address::generate(&env);
+    let globex_admin = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    let model_id = register_test_model(&env, &client, &admin);
+    let ramen = client.add_category(&admin, &String::from_str(&env, "Ramen"), &None);
+
+    let acme_name = String::from_str(&env, "acme");
+    assert_eq!(client.try_create_tenant(&acme_admin, &acme_name, &acme_admin), Err(Ok(RegistryError::Unauthorized)));
+    let acme = client.create_tenant(&admin, &acme_name, &acme_admin);
+    let globex = client.create_tenant(&admin, &String::from_str(&env, "globex"), &globex_admin);
+    assert_eq!((acme, globex), (1, 2));
+
+    let res = client.try_grant_tenant_role(&globex_admin, &acme, &Role::Verifier, &acme_verifier);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+    client.grant_tenant_role(&acme_admin, &acme, &Role::Verifier, &acme_verifier);
+    assert!(client.has_tenant_role(&acme, &Role::Verifier, &acme_verifier));
+    assert!(!client.has_role(&Role::Verifier, &acme_verifier));
+
+    // The same hash lives independently in each namespace
+    let img_hash = String::from_str(&env, "shared-hash");
+    client.register_tenant_entry(&acme_verifier, &acme, &submitter, &img_hash, &ramen, &9_000, &model_id, &None);
+    let res = client.try_register_tenant_entry(&acme_verifier, &globex, &submitter, &img_hash, &ramen, &9_000, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+    let res = client.try_register_food_entry(&acme_verifier, &submitter, &img_hash, &ramen, &9_000, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+
+    assert_eq!(client.get_tenant_entry(&acme, &img_hash).confidence, 9_000);
+    assert_eq!(client.try_get_tenant_entry(&globex, &img_hash), Err(Ok(RegistryError::EntryNotFound)));
+    assert_eq!(client.try_get_food_entry(&img_hash), Err(Ok(RegistryError::EntryNotFound)));
+    assert_eq!(client.get_category_stats(&String::from_str(&env, "Ramen")).entry_count, 0);
+
+    // A paused tenant stops registering without affecting others
+    let mut config = client.get_tenant(&acme);
+    config.paused = true;
+    assert_eq!(client.try_set_tenant_config(&globex_admin, &config), Err(Ok(RegistryError::Unauthorized)));
+    client.set_tenant_config(&acme_admin, &config);
+    let other = String::from_str(&env, "other-hash");
+    let res = client.try_register_tenant_entry(&acme_verifier, &acme, &submitter, &other, &ramen, &9_000, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::Paused)));
+    assert_eq!(client.try_get_tenant(&3), Err(Ok(RegistryError::TenantNotFound)));
+}
+
+#[test]
+fn test_verifier_rate_limit_sliding_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+    let dish = client.add_category(&admin, &String::from_str(&env, "Suya"), &None);
+    let register = |hash: &str| {
+        client.try_register_food_entry(&verifier, &submitter, &String::from_str(&env, hash), &dish, &90, &model_id, &None)
+    };
+
+    let bad = RateLimit { max_entries: 0, window_ledgers: 10 };
+    assert_eq!(client.try_set_rate_limit(&admin, &Some(bad)), Err(Ok(RegistryError::InvalidAmount)));
+    client.set_rate_limit(&admin, &Some(RateLimit { max_entries: 2, window_ledgers: 10 }));
+
+    assert!(register("h1").is_ok());
+    env.ledger().with_mut(|li| li.sequence_number = 105);
+    assert!(register("h2").is_ok());
+    assert_eq!(register("h3"), Err(Ok(RegistryError::RateLimited)));
+    assert_eq!(client.get_verifier_usage(&verifier), 2);
+
+    // The first registration leaves the window after 10 ledgers
+    env.ledger().with_mut(|li| li.sequence_number = 110);
+    assert!(register("h3").is_ok());
+    assert_eq!(register("h4"), Err(Ok(RegistryError::RateLimited)));
+
+    client.reset_rate_limit(&admin, &verifier);
+    assert_eq!(client.get_verifier_usage(&verifier), 0);
+    assert!(register("h4").is_ok());
+
+    client.set_rate_limit(&admin, &None);
+    assert!(register("h5").is_ok());
+    assert!(register("h6").is_ok());
+}
+
+#[test]
+fn test_compromised_verifier_entries_quarantined() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let honest = Address::generate(&env);
+    let stolen = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &honest);
+    client.grant_role(&admin, &Role::Verifier, &stolen);
+    let model_id = register_test_model(&env, &client, &admin);
+    let dish = client.add_category(&admin, &String::from_str(&env, "Egusi"), &None);
+    let hash = |i: u32| {
+        let mut name = [b'h', b'0'];
+        name[1] += i as u8;
+        String::from_bytes(&env, &name)
+    };
+
+    // Entries 0-1 predate the compromise, 2-4 were made with the stolen key
+    for i in 0..5 {
+        env.ledger().with_mut(|li| li.timestamp = 100 * i as u64);
+        client.register_food_entry(&stolen, &submitter, &hash(i), &dish, &90, &model_id, &None);
+    }
+    client.register_food_entry(&honest, &submitter, &hash(5), &dish, &90, &model_id, &None);
+
+    let res = client.try_quarantine_entries(&admin, &stolen, &0, &10);
+    assert_eq!(res, Err(Ok(RegistryError::VerifierNotCompromised)));
+    client.revoke_verifier(&admin, &stolen, &true, &200);
+    assert!(!client.has_role(&Role::Verifier, &stolen));
+    assert_eq!(client.get_compromised_since(&stolen), Some(200));
+
+    assert_eq!(client.quarantine_entries(&admin, &stolen, &0, &3), Some(3));
+    assert_eq!(client.quarantine_entries(&admin, &stolen, &3, &3), None);
+    let quarantined = (0..6).map(|i| client.get_food_entry(&hash(i)).quarantined);
+    assert!(quarantined.eq([false, false, true, true, true, false]));
+
+    // Re-verification by a trusted verifier restores the entry
+    client.reverify_entry(&honest, &hash(3), &95);
+    assert!(!client.get_food_entry(&hash(3)).quarantined);
+    assert_eq!(client.can_register(&hash(2)), RegistrationStatus::Quarantined);
+    assert_eq!(client.can_register(&hash(3)), RegistrationStatus::Exists);
+}
+
+#[test]
+fn test_can_register_preflight_and_hash_normalization() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+    let dish = client.add_category(&admin, &String::from_str(&env, "Suya"), &None);
+    let suya = String::from_str(&env, "suya-1");
+    let plate = String::from_str(&env, "suya-plate");
+
+    // Only lowercase letters, digits and dashes, up to 128 bytes
+    for malformed in ["", "Suya-1", "suya 1", "suya_1", &"a".repeat(129)] {
+        let malformed = String::from_str(&env, malformed);
+        assert_eq!(client.can_register(&malformed), RegistrationStatus::Invalid);
+        let res = client.try_register_food_entry(&verifier, &submitter, &malformed, &dish, &90, &model_id, &None);
+        assert_eq!(res, Err(Ok(RegistryError::InvalidImageHash)));
+    }
+    assert_eq!(client.can_register(&String::from_str(&env, &"a".repeat(128))), RegistrationStatus::Available);
+
+    assert_eq!(client.can_register(&suya), RegistrationStatus::Available);
+    client.register_food_entry(&verifier, &submitter, &suya, &dish, &90, &model_id, &None);
+    client.register_album(&verifier, &submitter, &vec![&env, plate.clone()], &dish, &90, &model_id, &None);
+    assert_eq!(client.can_register(&suya), RegistrationStatus::Exists);
+    assert_eq!(client.can_register(&plate), RegistrationStatus::Exists);
+
+    // Removed hashes stay barred from registration
+    client.remove_entry(&admin, &suya);
+    assert_eq!(client.can_register(&suya), RegistrationStatus::Removed);
+    assert_eq!(client.try_get_food_entry(&suya), Err(Ok(RegistryError::EntryNotFound)));
+    assert_eq!(client.get_category_stats(&String::from_str(&env, "Suya")).entry_count, 1);
+    let res = client.try_register_food_entry(&verifier, &submitter, &suya, &dish, &90, &model_id, &None);
+    assert_eq!(res, Err(Ok(RegistryError::EntryRemoved)));
+    assert_eq!(client.try_remove_entry(&admin, &suya), Err(Ok(RegistryError::EntryNotFound)));
+}
+
+#[test]
+fn test_redaction_rekeys_entry_to_blind_hash_stub() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+    let dish = client.add_category(&admin, &String::from_str(&env, "Jollof"), &None);
+    let first = String::from_str(&env, "jollof-1");
+    let selfie = String::from_str(&env, "jollof-selfie");
+    client.register_food_entry(&verifier, &submitter, &first, &dish, &80, &model_id, &None);
+    client.register_food_entry(&verifier, &submitter, &selfie, &dish, &90, &model_id, &None);
+    let blind_hash = BytesN::from_array(&env, &[7; 32]);
+
+    // Submitters need self redaction enabled, and positions must locate the entry
+    let res = client.try_redact_entry(&submitter, &selfie, &blind_hash, &1, &1);
+    assert_eq!(res, Err(Ok(RegistryError::Unauthorized)));
+    client.set_self_redaction(&admin, &true);
+    let res = client.try_redact_entry(&submitter, &selfie, &blind_hash, &0, &1);
+    assert_eq!(res, Err(Ok(RegistryError::InvalidRedaction)));
+
+    let blind_key = client.redact_entry(&submitter, &selfie, &blind_hash, &1, &1);
+    assert_eq!(blind_key.len(), 64);
+    assert_eq!(client.try_get_food_entry(&selfie), Err(Ok(RegistryError::EntryNotFound)));
+    let stub = client.get_redacted_entry(&blind_key);
+    assert_eq!((stub.confidence, stub.model_id), (90, model_id));
+    assert_eq!(stub.classification, String::from_str(&env, "Jollof"));
+
+    // Counts and stats still include the entry, now under its blind key
+    let stats = client.get_category_stats(&String::from_str(&env, "Jollof"));
+    assert_eq!((stats.entry_count, stats.confidence_sum), (2, 170));
+    assert_eq!(client.get_entries_by_model(&model_id, &0, &10), vec![&env, first.clone(), blind_key.clone()]);
+    let (exported, _) = client.export_entries(&0, &10);
+    assert_eq!(exported.len(), 1);
+
+    // The image hash is free again, while the blind key cannot be reused
+    assert_eq!(client.can_register(&selfie), RegistrationStatus::Available);
+    let res = client.try_redact_entry(&admin, &first, &blind_hash, &0, &0);
+    assert_eq!(res, Err(Ok(RegistryError::EntryAlreadyExists)));
+}
+
+#[test]
+fn test_lookups_report_status_instead_of_errors() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = register_test_model(&env, &client, &admin);
+    let suya = client.add_category(&admin, &String::from_str(&env, "Suya"), &None);
+    let fufu = client.add_category(&admin, &String::from_str(&env, "Fufu"), &None);
+    client.set_category_validity(&admin, &String::from_str(&env, "Fufu"), &Some(100));
+
+    let public = String::from_str(&env, "suya-1");
+    let hidden = String::from_str(&env, "suya-2");
+    let lapsing = String::from_str(&env, "fufu-1");
+    client.register_food_entry(&verifier, &submitter, &public, &suya, &90, &model_id, &None);
+    client.register_food_entry(&verifier, &submitter, &hidden, &suya, &90, &model_id, &None);
+    client.register_food_entry(&verifier, &submitter, &lapsing, &fufu, &90, &model_id, &None);
+    client.set_entry_private(&submitter, &hidden, &true);
+    env.ledger().with_mut(|li| li.timestamp = 1_200);
+
+    assert_eq!(client.lookup_food_entry(&public), EntryLookup::Found(client.get_food_entry(&public)));
+    assert_eq!(client.lookup_food_entry(&hidden), EntryLookup::Private);
+    assert_eq!(client.lookup_food_entry(&lapsing), EntryLookup::Stale);
+    let missing = String::from_str(&env, "missing");
+    assert_eq!(client.lookup_food_entry(&missing), EntryLookup::NotFound);
+
+    // The erroring getters keep their errors
+    assert_eq!(client.try_get_food_entry(&hidden), Err(Ok(RegistryError::EntryPrivate)));
+    assert_eq!(client.try_get_food_entry(&lapsing), Err(Ok(RegistryError::Stale)));
+    assert_eq!(client.try_get_food_entry(&missing), Err(Ok(RegistryError::EntryNotFound)));
+
+    assert_eq!(client.lookup_model(&model_id), Some(client.get_model(&model_id)));
+    assert_eq!(client.lookup_model(&99), None);
+    assert_eq!(client.lookup_category(&suya), Some(client.get_category(&suya)));
+    assert_eq!(client.lookup_category(&99), None);
+    assert_eq!(client.lookup_category_id(&String::from_str(&env, "Fufu")), Some(fufu));
+    assert_eq!(client.lookup_category_id(&String::from_str(&env, "Egusi")), None);
+}
@@ -0,0 +1,63 @@
+#![cfg(test)]
+
+//! Resource budget regression tests.
+//!
+//! Baselines are the CPU instruction and memory costs of each entrypoint as
+//! measured by the native test environment. A change that grows either cost
+//! by more than `TOLERANCE_PCT` fails; if the growth is intended, re-measure
+//! and update the baseline in the same change.
+
+use super::*;
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String};
+
+const TOLERANCE_PCT: u64 = 20;
+
+const REGISTER_FOOD_ENTRY_CPU: u64 = 346_000;
+const REGISTER_FOOD_ENTRY_MEM: u64 = 73_000;
+
+/// Assert the cost of the last top-level invocation stays within tolerance of its baseline
+fn assert_within_budget(env: &Env, name: &str, cpu_baseline: u64, mem_baseline: u64) {
+    let budget = env.cost_estimate().budget();
+    let cpu = budget.cpu_instruction_cost();
+    let mem = budget.memory_bytes_cost();
+
+    let cpu_limit = cpu_baseline * (100 + TOLERANCE_PCT) / 100;
+    let mem_limit = mem_baseline * (100 + TOLERANCE_PCT) / 100;
+    assert!(
+        cpu <= cpu_limit && mem <= mem_limit,
+        "{name}: measured cpu {cpu} / memory {mem} against baseline cpu {cpu_baseline} / memory {mem_baseline} (+{TOLERANCE_PCT}%)"
+    );
+}
+
+#[test]
+fn test_register_food_entry_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let contract_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let client = FoodRegistryContractClient::new(&env, &contract_id);
+    client.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = client.register_model(
+        &admin,
+        &String::from_str(&env, "v1.0.0"),
+        &BytesN::from_array(&env, &[9; 32]),
+        &String::from_str(&env, "ipfs://model-v1"),
+    );
+
+    let cuisine = client.add_category(&admin, &String::from_str(&env, "Japanese"), &None);
+    let category_id = client.add_category(&admin, &String::from_str(&env, "Sushi"), &Some(cuisine));
+
+    client.register_food_entry(
+        &verifier,
+        &submitter,
+        &String::from_str(&env, "abc123hash"),
+        &category_id,
+        &95,
+        &model_id,
+        &None
+    );
+    assert_within_budget(&env, "register_food_entry", REGISTER_FOOD_ENTRY_CPU, REGISTER_FOOD_ENTRY_MEM);
+}
@@ -0,0 +1,219 @@
+//! Platform configuration, roles, pausing and legal holds
+
+use crate::{
+    config, events, storage, AuditEntry, ExtError, IPError, IPLicensingContract, IPLicensingContractArgs,
+    IPLicensingContractClient, Role, BPS_DENOMINATOR
+};
+use flavorsnap_common::PageCursor;
+use flavorsnap_rbac::{self as rbac, audit};
+use soroban_sdk::{contractimpl, panic_with_error, symbol_short, Address, Env, IntoVal, String, Symbol, Val, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Set the platform admin and fee configuration at deploy time
+    pub fn __constructor(env: Env, admin: Address, treasury: Address, fee_bps: u32) {
+        if fee_bps as i128 > BPS_DENOMINATOR {
+            panic_with_error!(&env, IPError::InvalidFee);
+        }
+
+        storage::write_config(&env, &config::initial(admin.clone(), treasury, fee_bps));
+        rbac::grant_role(&env, Role::Admin, &admin);
+    }
+
+    /// Update the platform fee and treasury (Only callable by a FeeManager)
+    pub fn set_fee(env: Env, fee_manager: Address, treasury: Address, fee_bps: u32) -> Result<(), IPError> {
+        fee_manager.require_auth();
+        Self::require_role(&env, Role::FeeManager, &fee_manager)?;
+
+        if fee_bps as i128 > BPS_DENOMINATOR {
+            return Err(IPError::InvalidFee);
+        }
+
+        let mut config = storage::read_config(&env);
+        config.treasury = treasury;
+        config.fee_bps = fee_bps;
+        storage::write_config(&env, &config);
+        Self::audit(&env, symbol_short!("fee_set"), &fee_manager, (config.treasury, fee_bps));
+
+        events::fee_set(&env, fee_bps);
+        Ok(())
+    }
+
+    /// Grant a role (Only callable by an Admin)
+    pub fn grant_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), IPError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        rbac::grant_role(&env, role, &account);
+        Self::audit(&env, symbol_short!("role_grnt"), &admin, (role, account));
+        Ok(())
+    }
+
+    /// Revoke a role (Only callable by an Admin). The last Admin cannot be
+    /// revoked.
+    pub fn revoke_role(env: Env, admin: Address, role: Role, account: Address) -> Result<(), IPError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+        if rbac::is_last_admin(&env, role, &account) {
+            panic_with_error!(&env, ExtError::LastAdmin);
+        }
+
+        rbac::revoke_role(&env, role, &account);
+        Self::audit(&env, symbol_short!("role_rvk"), &admin, (role, account));
+        Ok(())
+    }
+
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        rbac::has_role(&env, role, &account)
+    }
+
+    /// Halt purchases and royalty payments (Only callable by a Pauser)
+    pub fn pause(env: Env, pauser: Address) -> Result<(), IPError> {
+        pauser.require_auth();
+        Self::require_role(&env, Role::Pauser, &pauser)?;
+
+        storage::set_paused(&env, true);
+        Self::audit(&env, symbol_short!("paused"), &pauser, ());
+        events::paused(&env, pauser);
+        Ok(())
+    }
+
+    /// Resume purchases and royalty payments (Only callable by a Pauser)
+    pub fn unpause(env: Env, pauser: Address) -> Result<(), IPError> {
+        pauser.require_auth();
+        Self::require_role(&env, Role::Pauser, &pauser)?;
+
+        storage::set_paused(&env, false);
+        Self::audit(&env, symbol_short!("unpaused"), &pauser, ());
+        events::unpaused(&env, pauser);
+        Ok(())
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        storage::is_paused(&env)
+    }
+
+    /// Place an IP under legal hold, blocking purchases, royalties, transfers and
+    /// metadata changes for that asset only (Only callable by an Admin or Arbiter)
+    pub fn freeze_ip(env: Env, caller: Address, ip_id: u64, reason: String) -> Result<(), IPError> {
+        caller.require_auth();
+        Self::require_hold_authority(&env, &caller)?;
+
+        if !storage::has_ip(&env, ip_id) {
+            return Err(IPError::IPNotFound);
+        }
+
+        storage::write_freeze_reason(&env, ip_id, &reason);
+        Self::audit(&env, symbol_short!("ip_frz"), &caller, (ip_id, reason.clone()));
+        events::ip_frozen(&env, ip_id, caller, reason);
+        Ok(())
+    }
+
+    /// Lift a legal hold (Only callable by an Admin or Arbiter)
+    pub fn unfreeze_ip(env: Env, caller: Address, ip_id: u64) -> Result<(), IPError> {
+        caller.require_auth();
+        Self::require_hold_authority(&env, &caller)?;
+
+        if !storage::is_frozen(&env, ip_id) {
+            panic_with_error!(&env, ExtError::NotFrozen);
+        }
+
+        storage::remove_freeze_reason(&env, ip_id);
+        Self::audit(&env, symbol_short!("ip_unfrz"), &caller, (ip_id,));
+        events::ip_unfrozen(&env, ip_id, caller);
+        Ok(())
+    }
+
+    /// The recorded reason if the IP is frozen
+    pub fn get_freeze_reason(env: Env, ip_id: u64) -> Option<String> {
+        storage::read_freeze_reason(&env, ip_id)
+    }
+
+    /// Every IP under legal hold with its reason, at most `max_batch_size` from
+    /// position `start`. Holds are listed in freeze order until one is lifted,
+    /// which moves the latest hold into its slot (Only callable by an Admin or Auditor)
+    pub fn list_frozen_ips(env: Env, viewer: Address, start: u32, limit: u32) -> Result<Vec<(u64, String)>, IPError> {
+        Self::require_audit_access(&env, &viewer)?;
+
+        let frozen = storage::frozen_index();
+        let cursor = PageCursor::new(start, limit, config::max_batch_size(&env), frozen.len(&env));
+        let mut holds = Vec::new(&env);
+        for ip_id in frozen.page(&env, &cursor) {
+            if let Some(reason) = storage::read_freeze_reason(&env, ip_id) {
+                holds.push_back((ip_id, reason));
+            }
+        }
+        Ok(holds)
+    }
+
+    /// Grant or withdraw a creator's verification badge (Only callable by an Admin)
+    pub fn set_creator_verified(env: Env, admin: Address, creator: Address, verified: bool) -> Result<(), IPError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        let mut profile = storage::read_creator_profile(&env, &creator).ok_or(IPError::ProfileNotFound)?;
+        profile.verified = verified;
+        storage::write_creator_profile(&env, &creator, &profile);
+        Self::audit(&env, symbol_short!("verified"), &admin, (creator.clone(), verified));
+
+        events::creator_verified(&env, creator, verified);
+        Ok(())
+    }
+
+    /// Privileged actions in the order they were taken, at most `max_batch_size`
+    /// from sequence number `start` (Only callable by an Admin or Auditor).
+    /// Entries are append-only and never rewritten.
+    pub fn get_audit_log(env: Env, viewer: Address, start: u32, limit: u32) -> Result<Vec<AuditEntry>, IPError> {
+        Self::require_audit_access(&env, &viewer)?;
+        Ok(audit::read(&env, start, limit.min(config::max_batch_size(&env))))
+    }
+
+    pub fn get_audit_log_len(env: Env) -> u32 {
+        audit::len(&env)
+    }
+
+    /// Describe an `IPError` code returned by this contract
+    pub fn error_message(env: Env, code: u32) -> String {
+        let message = IPError::from_code(code)
+            .map(|error| error.message())
+            .or_else(|| ExtError::from_code(code).map(|error| error.message()))
+            .unwrap_or("unknown error");
+        String::from_str(&env, message)
+    }
+}
+
+impl IPLicensingContract {
+    /// Append a privileged action by `actor` to the audit log
+    pub(crate) fn audit(env: &Env, action: Symbol, actor: &Address, details: impl IntoVal<Env, Vec<Val>>) {
+        audit::record(env, action, actor, details.into_val(env));
+    }
+
+    pub(crate) fn require_not_frozen(env: &Env, ip_id: u64) -> Result<(), IPError> {
+        if storage::is_frozen(env, ip_id) {
+            return Err(IPError::IPFrozen);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn require_hold_authority(env: &Env, account: &Address) -> Result<(), IPError> {
+        if !rbac::has_role(env, Role::Admin, account) && !rbac::has_role(env, Role::Arbiter, account) {
+            return Err(IPError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn require_audit_access(env: &Env, viewer: &Address) -> Result<(), IPError> {
+        viewer.require_auth();
+        if !rbac::can_audit(env, viewer) {
+            return Err(IPError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn require_role(env: &Env, role: Role, account: &Address) -> Result<(), IPError> {
+        if !rbac::has_role(env, role, account) {
+            return Err(IPError::Unauthorized);
+        }
+        Ok(())
+    }
+}
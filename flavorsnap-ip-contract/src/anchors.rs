@@ -0,0 +1,71 @@
+//! Anchoring exports: Merkle roots over batches of IP records, published by
+//! the contract on demand so partners can mirror proof of registrations onto
+//! other chains or timestamping services without trusting an exporter
+
+use crate::{
+    config, events, storage, ExtError, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient,
+    MerkleAnchor
+};
+use soroban_sdk::{contractimpl, panic_with_error, xdr::ToXdr, Bytes, BytesN, Env, Vec};
+
+/// Domain separator of IP record leaves
+const ANCHOR_DOMAIN: &[u8] = b"flavorsnap-ip-anchor-v1";
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Publish the Merkle root over the current records of `ip_ids`, at most
+    /// `max_batch_size`, as an `anchor` event. Callable by anyone. The leaves
+    /// are `ip_anchor_leaf` of each IP in the order given; an unknown or
+    /// deregistered IP fails the whole batch.
+    pub fn anchor_ips(env: Env, ip_ids: Vec<u64>) -> Result<MerkleAnchor, IPError> {
+        if ip_ids.is_empty() {
+            panic_with_error!(&env, ExtError::EmptyBatch);
+        }
+        if ip_ids.len() > config::max_batch_size(&env) {
+            return Err(IPError::CapReached);
+        }
+
+        let mut leaves = Vec::new(&env);
+        for ip_id in ip_ids.iter() {
+            leaves.push_back(Self::ip_anchor_leaf(env.clone(), ip_id)?);
+        }
+        let anchor = MerkleAnchor::new(&env, &leaves);
+
+        events::anchored(&env, anchor.clone());
+        Ok(anchor)
+    }
+
+    /// Leaf committing to the IP's current record, recomputable off-chain as
+    ///
+    /// ```text
+    /// sha256(
+    ///     "flavorsnap-ip-anchor-v1"
+    ///     || ip_id             u64, big-endian
+    ///     || owner             Address, XDR of its ScVal
+    ///     || metadata_uri      String, XDR of its ScVal
+    ///     || metadata_hash     u8 0 if unbound, else u8 1 || 32 bytes
+    ///     || verified_origin   u8
+    /// )
+    /// ```
+    pub fn ip_anchor_leaf(env: Env, ip_id: u64) -> Result<BytesN<32>, IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+        if storage::is_deregistered(&env, ip_id) {
+            return Err(IPError::IPDeregistered);
+        }
+
+        let mut preimage = Bytes::from_slice(&env, ANCHOR_DOMAIN);
+        preimage.extend_from_array(&ip_id.to_be_bytes());
+        preimage.append(&ip.owner.to_xdr(&env));
+        preimage.append(&ip.metadata_uri.to_xdr(&env));
+        match ip.metadata_hash {
+            Some(metadata_hash) => {
+                preimage.push_back(1);
+                preimage.extend_from_array(&metadata_hash.to_array());
+            }
+            None => preimage.push_back(0),
+        }
+        preimage.push_back(ip.verified_origin as u8);
+
+        Ok(env.crypto().sha256(&preimage).into())
+    }
+}
@@ -0,0 +1,449 @@
+//! IP asset registration, ownership and owner-approved settings
+
+use crate::{
+    events, storage, BondStatus, CreatorProfile, IPAsset, IPError, IPLicensingContract, IPLicensingContractArgs,
+    IPLicensingContractClient, IPReport, Rounding, BPS_DENOMINATOR
+};
+use soroban_sdk::{contractimpl, vec, Address, BytesN, Env, Map, String, Symbol, Vec};
+
+const MAX_SIGNERS: u32 = 10;
+const MAX_TAGS: u32 = 5;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Register a new IP asset, optionally binding the sha256 of its metadata document.
+    /// Up to 5 `tags` make it discoverable through `search_by_tag`. Returns the stored asset.
+    pub fn register_ip(
+        env: Env,
+        owner: Address,
+        ip_id: u64,
+        metadata_uri: String,
+        metadata_hash: Option<BytesN<32>>,
+        price_exclusive: i128,
+        price_non_exclusive: i128,
+        payment_token: Address,
+        tags: Vec<Symbol>,
+    ) -> Result<IPAsset, IPError> {
+        owner.require_auth();
+
+        Self::register(&env, owner, ip_id, metadata_uri, metadata_hash, price_exclusive, price_non_exclusive, payment_token, tags)
+    }
+
+    pub fn get_ip(env: Env, ip_id: u64) -> Result<IPAsset, IPError> {
+        storage::read_ip(&env, ip_id)
+    }
+
+    /// `get_ip` as an `Option`, for cross-contract callers probing for an IP
+    pub fn lookup_ip(env: Env, ip_id: u64) -> Option<IPAsset> {
+        storage::read_ip(&env, ip_id).ok()
+    }
+
+    /// Current owner of the IP, for cheap cross-contract seller checks
+    pub fn owner_of(env: Env, ip_id: u64) -> Result<Address, IPError> {
+        Ok(storage::read_ip(&env, ip_id)?.owner)
+    }
+
+    /// Whether `claimed_owner` owns the IP and it is still registered.
+    /// Unknown and deregistered IPs prove nothing.
+    pub fn prove_ownership(env: Env, ip_id: u64, claimed_owner: Address) -> bool {
+        if storage::is_deregistered(&env, ip_id) {
+            return false;
+        }
+        storage::read_ip(&env, ip_id).is_ok_and(|ip| ip.owner == claimed_owner)
+    }
+
+    /// The asset with its owner's creator profile, if any, and hold status
+    pub fn get_ip_report(env: Env, ip_id: u64) -> Result<IPReport, IPError> {
+        let asset = storage::read_ip(&env, ip_id)?;
+        let profile = storage::read_creator_profile(&env, &asset.owner);
+        Ok(IPReport {
+            creator_verified: profile.as_ref().is_some_and(|profile| profile.verified),
+            creator_profile_uri: profile.map(|profile| profile.profile_uri),
+            frozen: storage::is_frozen(&env, ip_id),
+            deregistered: storage::is_deregistered(&env, ip_id),
+            asset,
+        })
+    }
+
+    /// Create or update the caller's creator profile. Any change requires re-verification.
+    pub fn set_creator_profile(env: Env, creator: Address, display_name_hash: BytesN<32>, profile_uri: String) {
+        creator.require_auth();
+
+        let profile = CreatorProfile {
+            display_name_hash,
+            profile_uri,
+            verified: false,
+            updated_at: env.ledger().timestamp(),
+        };
+        storage::write_creator_profile(&env, &creator, &profile);
+
+        events::profile_set(&env, creator, profile.display_name_hash);
+    }
+
+    pub fn get_creator_profile(env: Env, creator: Address) -> Option<CreatorProfile> {
+        storage::read_creator_profile(&env, &creator)
+    }
+
+    /// Check an off-chain metadata document hash against the one bound at registration
+    pub fn verify_metadata(env: Env, ip_id: u64, provided_hash: BytesN<32>) -> Result<bool, IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+        Ok(ip.metadata_hash == Some(provided_hash))
+    }
+
+    /// Deregister an IP asset (Requires IP owner approval).
+    /// Assets that never sold a license, voucher, preview or share are deleted
+    /// outright along with their settings, so a re-registered id starts
+    /// clean; that fails with `DuplicateOperation` while offers, an auction,
+    /// an option or a bond is still open on them. Any other asset is
+    /// tombstoned so existing licenses keep working but no new ones are sold.
+    pub fn deregister_ip(env: Env, approvers: Vec<Address>, ip_id: u64) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let hard_delete = !ip.has_exclusive
+            && ip.active_licenses == 0
+            && storage::read_ip_stats(&env, ip_id).licenses_sold == 0
+            && !storage::is_undeletable(&env, ip_id);
+        if hard_delete {
+            Self::require_nothing_escrowed(&env, ip_id)?;
+            Self::remove_ip_state(&env, ip_id, &ip.owner);
+        } else {
+            if storage::is_deregistered(&env, ip_id) {
+                return Err(IPError::IPDeregistered);
+            }
+            storage::write_tombstone(&env, ip_id, env.ledger().timestamp());
+        }
+
+        events::ip_deregistered(&env, ip_id, hard_delete);
+
+        Ok(())
+    }
+
+    /// Restore a tombstoned IP asset within the configured restore window (30
+    /// days by default) of deregistration (Requires IP owner approval)
+    pub fn restore_ip(env: Env, approvers: Vec<Address>, ip_id: u64) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+
+        let deregistered_at = storage::read_tombstone(&env, ip_id).ok_or(IPError::NotDeregistered)?;
+
+        let window_ends_at = deregistered_at.saturating_add(storage::read_config(&env).restore_window_secs);
+        if env.ledger().timestamp() > window_ends_at {
+            return Err(IPError::RestoreWindowExpired);
+        }
+
+        storage::remove_tombstone(&env, ip_id);
+        events::ip_restored(&env, ip_id, ip.owner);
+
+        Ok(())
+    }
+
+    /// Replace the signer set and approval threshold (Requires IP owner approval)
+    pub fn set_signers(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        signers: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        if threshold == 0 || threshold > signers.len() || signers.len() > MAX_SIGNERS {
+            return Err(IPError::InvalidSigners);
+        }
+        for (index, signer) in signers.iter().enumerate() {
+            if signers.first_index_of(&signer) != Some(index as u32) {
+                return Err(IPError::InvalidSigners);
+            }
+        }
+
+        ip.signers = signers;
+        ip.threshold = threshold;
+        storage::write_ip(&env, ip_id, &ip);
+
+        events::signers_set(&env, ip_id, threshold);
+        Ok(())
+    }
+
+    /// Change list prices (Requires IP owner approval). Restarts any exclusive
+    /// price decay from the new price.
+    pub fn set_prices(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        price_exclusive: i128,
+        price_non_exclusive: i128,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        Self::require_sane_amount(price_exclusive, ip.token_decimals)?;
+        Self::require_sane_amount(price_non_exclusive, ip.token_decimals)?;
+
+        ip.price_exclusive = price_exclusive;
+        ip.price_non_exclusive = price_non_exclusive;
+        storage::write_ip(&env, ip_id, &ip);
+        Self::restart_price_decay(&env, ip_id);
+
+        events::prices_set(&env, ip_id, price_exclusive, price_non_exclusive);
+        Ok(())
+    }
+
+    /// Make licenses sold from now on expire `duration_secs` after purchase;
+    /// `None` sells perpetual licenses (Requires IP owner approval)
+    pub fn set_license_duration(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        duration_secs: Option<u64>,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        ip.license_duration = duration_secs;
+        storage::write_ip(&env, ip_id, &ip);
+        Ok(())
+    }
+
+    /// Let licensees revoked from now on appeal within `window_secs` of the
+    /// revocation; `None` makes revocations final (Requires IP owner approval)
+    pub fn set_appeal_window(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        window_secs: Option<u64>,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        ip.appeal_window_secs = window_secs;
+        storage::write_ip(&env, ip_id, &ip);
+        Ok(())
+    }
+
+    /// Set the share of each license sale paid to the buyer's referrer
+    /// (Requires IP owner approval)
+    pub fn set_referral_bps(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        referral_bps: u32,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let fee_bps = storage::read_config(&env).fee_bps;
+        if referral_bps as i128 + fee_bps as i128 > BPS_DENOMINATOR {
+            return Err(IPError::InvalidFee);
+        }
+
+        ip.referral_bps = referral_bps;
+        storage::write_ip(&env, ip_id, &ip);
+        Ok(())
+    }
+
+    /// Set the share of the exclusive/non-exclusive price difference refunded to
+    /// an exclusive licensee who downgrades (Requires IP owner approval).
+    /// Refunds come out of the owner's claimable balance, then their allowance.
+    pub fn set_downgrade_refund(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        refund_bps: u32,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        if refund_bps as i128 > BPS_DENOMINATOR {
+            return Err(IPError::InvalidAmount);
+        }
+
+        ip.downgrade_refund_bps = refund_bps;
+        storage::write_ip(&env, ip_id, &ip);
+        Ok(())
+    }
+
+    /// Choose how bps shares of the IP's payments round: the platform fee,
+    /// referral share, downgrade refund and royalties (Requires IP owner
+    /// approval). The owner's proceeds take whatever the shares leave, so
+    /// every payment is split without remainder.
+    pub fn set_rounding(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        rounding: Rounding,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        ip.rounding = rounding;
+        storage::write_ip(&env, ip_id, &ip);
+        Ok(())
+    }
+
+    /// Transfer ownership to `new_owner`, who becomes the sole signer (Requires
+    /// IP owner approval). The ownership proof belonged to the previous owner
+    /// and is dropped.
+    pub fn transfer_ip(env: Env, approvers: Vec<Address>, ip_id: u64, new_owner: Address) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let previous_owner = ip.owner;
+        ip.owner = new_owner.clone();
+        ip.signers = vec![&env, new_owner.clone()];
+        ip.threshold = 1;
+        ip.proof_hash = None;
+        ip.verified_origin = false;
+        storage::write_ip(&env, ip_id, &ip);
+        storage::remove_owner_ip(&env, &previous_owner, ip_id);
+        storage::add_owner_ip(&env, &new_owner, ip_id);
+
+        events::ip_transferred(&env, ip_id, previous_owner, new_owner);
+        Ok(())
+    }
+}
+
+impl IPLicensingContract {
+    pub(crate) fn register(
+        env: &Env,
+        owner: Address,
+        ip_id: u64,
+        metadata_uri: String,
+        metadata_hash: Option<BytesN<32>>,
+        price_exclusive: i128,
+        price_non_exclusive: i128,
+        payment_token: Address,
+        tags: Vec<Symbol>,
+    ) -> Result<IPAsset, IPError> {
+        let token_decimals = Self::token_decimals(env, &payment_token)?;
+        Self::require_sane_amount(price_exclusive, token_decimals)?;
+        Self::require_sane_amount(price_non_exclusive, token_decimals)?;
+        if tags.len() > MAX_TAGS {
+            return Err(IPError::CapReached);
+        }
+
+        if storage::has_ip(env, ip_id) {
+            return Err(IPError::IPAlreadyRegistered);
+        }
+
+        let mut asset = IPAsset {
+            owner: owner.clone(),
+            metadata_uri,
+            metadata_hash,
+            price_exclusive,
+            price_non_exclusive,
+            payment_token,
+            token_decimals,
+            tier_prices: Map::new(env),
+            has_exclusive: false,
+            has_broadcast: false,
+            active_licenses: 0,
+            signers: vec![env, owner.clone()],
+            threshold: 1,
+            downgrade_refund_bps: 0,
+            license_duration: None,
+            appeal_window_secs: None,
+            referral_bps: 0,
+            tags: Vec::new(env),
+            reference_priced: false,
+            push_payouts: false,
+            revenue_recipient: None,
+            revenue_to_vault: false,
+            proof_hash: None,
+            verified_origin: false,
+            terms_template: None,
+            rounding: Rounding::Floor,
+        };
+
+        for tag in tags.iter() {
+            if asset.tags.contains(&tag) {
+                continue;
+            }
+            #[cfg(feature = "search")]
+            storage::tag_index(&tag).push(env, ip_id);
+            asset.tags.push_back(tag);
+        }
+
+        storage::write_ip(env, ip_id, &asset);
+        storage::index_ip(env, ip_id);
+        storage::add_owner_ip(env, &owner, ip_id);
+
+        events::ip_registered(env, ip_id, owner);
+        Ok(asset)
+    }
+
+    /// Fail while anything a counterparty holds against the IP is still open
+    fn require_nothing_escrowed(env: &Env, ip_id: u64) -> Result<(), IPError> {
+        if storage::read_open_offers(env, ip_id) > 0
+            || storage::read_open_auction(env, ip_id).is_some()
+            || Self::live_option(env, ip_id).is_some()
+            || storage::read_handover(env, ip_id).is_some()
+            || storage::read_bond(env, ip_id).is_some_and(|bond| bond.status == BondStatus::Held)
+        {
+            return Err(IPError::DuplicateOperation);
+        }
+        Ok(())
+    }
+
+    /// Delete the asset with every setting keyed by its id alone
+    fn remove_ip_state(env: &Env, ip_id: u64, owner: &Address) {
+        storage::remove_ip(env, ip_id);
+        storage::remove_tombstone(env, ip_id);
+        storage::remove_ip_stats(env, ip_id);
+        storage::remove_owner_ip(env, owner, ip_id);
+        storage::write_attachments(env, ip_id, &Vec::new(env));
+        storage::remove_bond(env, ip_id);
+        storage::remove_price_decay(env, ip_id);
+        storage::write_token_gate(env, ip_id, &None);
+        storage::write_attestation_gate(env, ip_id, &None);
+        storage::write_licensing_calendar(env, ip_id, &None);
+        storage::write_free_claim_policy(env, ip_id, &None);
+        storage::write_dormancy_policy(env, ip_id, &None);
+        storage::write_option_terms(env, ip_id, &None);
+        storage::write_exclusive_option(env, ip_id, &None);
+        storage::write_preview_terms(env, ip_id, &None);
+        if let Some(group_id) = storage::read_conflict_group_of(env, ip_id) {
+            Self::remove_from_conflict_group(env, ip_id, group_id);
+        }
+    }
+
+    /// Require signatures from at least `threshold` distinct signers of the asset
+    pub(crate) fn require_owner_approval(ip: &IPAsset, approvers: &Vec<Address>) -> Result<(), IPError> {
+        if approvers.len() < ip.threshold {
+            return Err(IPError::Unauthorized);
+        }
+        for (index, approver) in approvers.iter().enumerate() {
+            let is_duplicate = approvers.first_index_of(&approver) != Some(index as u32);
+            if is_duplicate || !ip.signers.contains(&approver) {
+                return Err(IPError::Unauthorized);
+            }
+            approver.require_auth();
+        }
+        Ok(())
+    }
+
+    /// Whether `account` alone satisfies the asset's approval threshold
+    pub(crate) fn is_sole_approver(ip: &IPAsset, account: &Address) -> bool {
+        ip.threshold == 1 && ip.signers.contains(account)
+    }
+}
@@ -0,0 +1,76 @@
+//! Per-IP data room: named documents attached after registration, beside
+//! the single metadata URI bound at registration
+
+use crate::{
+    events, storage, Attachment, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient
+};
+use soroban_sdk::{contractimpl, Address, BytesN, Env, String, Symbol, Vec};
+
+/// Most attachments one IP can hold
+const MAX_ATTACHMENTS: u32 = 16;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Attach the document at `uri` with content hash `hash` to the IP under
+    /// `name` (Requires IP owner approval). Names are unique per IP; remove
+    /// an attachment to replace it.
+    pub fn add_attachment(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        name: Symbol,
+        uri: String,
+        hash: BytesN<32>,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let mut attachments = storage::read_attachments(&env, ip_id);
+        if attachments.iter().any(|attachment| attachment.name == name) {
+            return Err(IPError::DuplicateOperation);
+        }
+        if attachments.len() >= MAX_ATTACHMENTS {
+            return Err(IPError::CapReached);
+        }
+        attachments.push_back(Attachment {
+            name: name.clone(),
+            uri: uri.clone(),
+            hash: hash.clone(),
+            added_at: env.ledger().timestamp(),
+        });
+        storage::write_attachments(&env, ip_id, &attachments);
+
+        events::attachment_added(&env, ip_id, name, uri, hash);
+        Ok(())
+    }
+
+    /// Drop the IP's attachment named `name` (Requires IP owner approval).
+    /// Returns whether it had one.
+    pub fn remove_attachment(env: Env, approvers: Vec<Address>, ip_id: u64, name: Symbol) -> Result<bool, IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let mut attachments = storage::read_attachments(&env, ip_id);
+        let Some(index) = attachments.iter().position(|attachment| attachment.name == name) else {
+            return Ok(false);
+        };
+        attachments.remove(index as u32);
+        storage::write_attachments(&env, ip_id, &attachments);
+
+        events::attachment_removed(&env, ip_id, name);
+        Ok(true)
+    }
+
+    /// The IP's attachments in the order they were added
+    pub fn get_attachments(env: Env, ip_id: u64) -> Vec<Attachment> {
+        storage::read_attachments(&env, ip_id)
+    }
+
+    pub fn get_attachment(env: Env, ip_id: u64, name: Symbol) -> Option<Attachment> {
+        storage::read_attachments(&env, ip_id).iter().find(|attachment| attachment.name == name)
+    }
+}
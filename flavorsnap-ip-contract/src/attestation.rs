@@ -0,0 +1,18 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+/// Mirror of the record an attestation registry, such as a KYC provider's,
+/// keeps for a verified address
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    /// Depth of verification, as graded by the provider
+    pub level: u32,
+    pub expires_at: u64,
+}
+
+/// The subset of an attestation registry used to gate exclusive licenses
+#[allow(dead_code)]
+#[contractclient(name = "AttestationRegistryClient")]
+pub trait AttestationRegistry {
+    fn attestation(env: Env, subject: Address) -> Option<Attestation>;
+}
@@ -0,0 +1,293 @@
+//! Sealed-bid auctions of exclusive licenses: bids are committed as hashes
+//! behind a deposit, revealed once commits close, and won at the second price,
+//! so bidders gain nothing from watching each other or sniping
+
+use crate::{
+    events, licensing::Pricing, payments::Funding, storage, ExtError, IPError, IPLicensingContract,
+    IPLicensingContractArgs, IPLicensingContractClient, License, LicenseType, SealedAuction, SealedBid
+};
+use soroban_sdk::{contractimpl, panic_with_error, token, Address, Bytes, BytesN, Env, Vec};
+
+/// Domain separator of bid commitments
+const COMMITMENT_DOMAIN: &[u8] = b"flavorsnap-sealed-bid-v1";
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Auction the IP's free exclusive slot (Requires IP owner approval).
+    /// Bids are committed for `commit_secs`, then revealed for `reveal_secs`;
+    /// no license can be bought or optioned on the IP until the auction is
+    /// settled. Returns the auction id.
+    pub fn start_sealed_auction(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        reserve_price: i128,
+        deposit: i128,
+        commit_secs: u64,
+        reveal_secs: u64,
+    ) -> Result<u64, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+        if storage::is_deregistered(&env, ip_id) {
+            return Err(IPError::IPDeregistered);
+        }
+        Self::require_sane_amount(reserve_price, ip.token_decimals)?;
+        Self::require_sane_amount(deposit, ip.token_decimals)?;
+        if reserve_price == 0 || deposit == 0 {
+            return Err(IPError::InvalidAmount);
+        }
+        if commit_secs == 0 || reveal_secs == 0 {
+            return Err(IPError::InvalidPeriod);
+        }
+        Self::require_no_open_auction(&env, ip_id)?;
+        if ip.has_exclusive || Self::live_option(&env, ip_id).is_some() {
+            return Err(IPError::ExclusiveAlreadyIssued);
+        }
+        if ip.active_licenses > 0 {
+            return Err(IPError::ActiveLicensesExist);
+        }
+        Self::require_no_conflicting_exclusive(&env, ip_id)?;
+
+        let commit_ends_at = env.ledger().timestamp().saturating_add(commit_secs);
+        let auction = SealedAuction {
+            auction_id: storage::next_auction_id(&env),
+            ip_id,
+            seller: ip.owner,
+            token: ip.payment_token,
+            reserve_price,
+            deposit,
+            commit_ends_at,
+            reveal_ends_at: commit_ends_at.saturating_add(reveal_secs),
+            leader: None,
+            highest_bid: 0,
+            second_bid: 0,
+            settled: false,
+        };
+        storage::write_auction(&env, &auction);
+        storage::write_open_auction(&env, ip_id, Some(auction.auction_id));
+
+        events::auction_started(&env, ip_id, auction.auction_id, reserve_price, auction.reveal_ends_at);
+        Ok(auction.auction_id)
+    }
+
+    pub fn get_auction(env: Env, auction_id: u64) -> Result<SealedAuction, IPError> {
+        Ok(Self::require_auction(&env, auction_id))
+    }
+
+    /// Id of the auction holding the IP's exclusive slot, until it is settled
+    pub fn get_open_auction(env: Env, ip_id: u64) -> Option<u64> {
+        storage::read_open_auction(&env, ip_id)
+    }
+
+    pub fn get_sealed_bid(env: Env, auction_id: u64, bidder: Address) -> Result<SealedBid, IPError> {
+        Ok(Self::require_sealed_bid(&env, auction_id, &bidder))
+    }
+
+    /// Commit to a bid during the commit phase, escrowing the auction's
+    /// deposit. `commitment` is `bid_commitment(amount, salt)`; the deposit is
+    /// forfeited to the seller if the bid is never revealed. One bid per
+    /// bidder; the IP's token and attestation gates apply to bidders.
+    pub fn commit_bid(env: Env, bidder: Address, auction_id: u64, commitment: BytesN<32>) -> Result<(), IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        bidder.require_auth();
+
+        let auction = Self::require_auction(&env, auction_id);
+        if env.ledger().timestamp() >= auction.commit_ends_at {
+            return Err(IPError::DeadlineExceeded);
+        }
+        if bidder == auction.seller {
+            return Err(IPError::Unauthorized);
+        }
+        if storage::read_sealed_bid(&env, auction_id, &bidder).is_some() {
+            return Err(IPError::DuplicateOperation);
+        }
+        Self::require_gate_holder(&env, auction.ip_id, &bidder)?;
+        Self::require_attested(&env, auction.ip_id, &bidder, LicenseType::Exclusive)?;
+
+        token::Client::new(&env, &auction.token).transfer(&bidder, &env.current_contract_address(), &auction.deposit);
+        let bid = SealedBid { commitment, escrowed: auction.deposit, revealed: false };
+        storage::write_sealed_bid(&env, auction_id, &bidder, &Some(bid));
+
+        events::bid_committed(&env, auction.ip_id, bidder, auction_id);
+        Ok(())
+    }
+
+    /// Reveal a committed bid during the reveal phase, topping the escrow up
+    /// to `amount` if it exceeds the deposit. Bids below the reserve are
+    /// accepted but cannot win.
+    pub fn reveal_bid(
+        env: Env,
+        bidder: Address,
+        auction_id: u64,
+        amount: i128,
+        salt: BytesN<32>,
+    ) -> Result<(), IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        bidder.require_auth();
+
+        let mut auction = Self::require_auction(&env, auction_id);
+        let now = env.ledger().timestamp();
+        if now < auction.commit_ends_at {
+            panic_with_error!(&env, ExtError::AuctionPhase);
+        }
+        if now >= auction.reveal_ends_at {
+            return Err(IPError::DeadlineExceeded);
+        }
+        let mut bid = Self::require_sealed_bid(&env, auction_id, &bidder);
+        if bid.revealed {
+            return Err(IPError::DuplicateOperation);
+        }
+        if amount < 0 {
+            return Err(IPError::InvalidAmount);
+        }
+        if Self::bid_commitment(env.clone(), amount, salt) != bid.commitment {
+            panic_with_error!(&env, ExtError::BidCommitmentMismatch);
+        }
+
+        if amount > bid.escrowed {
+            token::Client::new(&env, &auction.token).transfer(&bidder, &env.current_contract_address(), &(amount - bid.escrowed));
+            bid.escrowed = amount;
+        }
+        bid.revealed = true;
+        storage::write_sealed_bid(&env, auction_id, &bidder, &Some(bid));
+
+        if amount >= auction.reserve_price {
+            if auction.leader.is_none() || amount > auction.highest_bid {
+                auction.second_bid = auction.highest_bid;
+                auction.highest_bid = amount;
+                auction.leader = Some(bidder.clone());
+            } else if amount > auction.second_bid {
+                auction.second_bid = amount;
+            }
+            storage::write_auction(&env, &auction);
+        }
+
+        events::bid_revealed(&env, auction.ip_id, bidder, auction_id, amount);
+        Ok(())
+    }
+
+    /// Close an auction once its reveal phase has ended, issuing the
+    /// exclusive license to the leader at the higher of the second-highest
+    /// valid bid and the reserve, and refunding the rest of their escrow.
+    /// If the license can no longer be issued (say the IP was frozen during
+    /// the auction) the auction still closes and the leader gets their whole
+    /// escrow back. Callable by anyone. Returns the license, if there was a
+    /// winner.
+    pub fn settle_sealed_auction(env: Env, auction_id: u64) -> Result<Option<License>, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        let mut auction = Self::require_auction(&env, auction_id);
+        if env.ledger().timestamp() < auction.reveal_ends_at {
+            panic_with_error!(&env, ExtError::AuctionPhase);
+        }
+        if auction.settled {
+            return Err(IPError::DuplicateOperation);
+        }
+
+        auction.settled = true;
+        storage::write_auction(&env, &auction);
+        storage::write_open_auction(&env, auction.ip_id, None);
+
+        let Some(leader) = auction.leader.clone() else {
+            events::auction_settled(&env, auction.ip_id, auction_id, None, auction.reserve_price);
+            return Ok(None);
+        };
+        let price = auction.second_bid.max(auction.reserve_price);
+        let bid = Self::require_sealed_bid(&env, auction_id, &leader);
+        storage::write_sealed_bid(&env, auction_id, &leader, &None);
+
+        let token = token::Client::new(&env, &auction.token);
+        match Self::execute_purchase_priced(
+            &env,
+            &leader,
+            auction.ip_id,
+            LicenseType::Exclusive,
+            None,
+            Pricing::Fixed(price),
+            None,
+            Funding::Escrow,
+        ) {
+            Ok(license) => {
+                if bid.escrowed > price {
+                    token.transfer(&env.current_contract_address(), &leader, &(bid.escrowed - price));
+                }
+                events::auction_settled(&env, auction.ip_id, auction_id, Some(leader), price);
+                Ok(Some(license))
+            }
+            Err(error) => {
+                token.transfer(&env.current_contract_address(), &leader, &bid.escrowed);
+                events::auction_failed(&env, auction.ip_id, auction_id, leader, error);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Take back a losing bid's escrow once the reveal phase has ended. A bid
+    /// that was never revealed forfeits its deposit to the seller's claimable
+    /// balance. Returns whether the escrow was forfeited.
+    pub fn withdraw_bid(env: Env, bidder: Address, auction_id: u64) -> Result<bool, IPError> {
+        bidder.require_auth();
+
+        let auction = Self::require_auction(&env, auction_id);
+        if env.ledger().timestamp() < auction.reveal_ends_at {
+            panic_with_error!(&env, ExtError::AuctionPhase);
+        }
+        if !auction.settled && auction.leader.as_ref() == Some(&bidder) {
+            panic_with_error!(&env, ExtError::AuctionPhase);
+        }
+        let bid = Self::require_sealed_bid(&env, auction_id, &bidder);
+        storage::write_sealed_bid(&env, auction_id, &bidder, &None);
+
+        let forfeited = !bid.revealed;
+        if forfeited {
+            let claimable = storage::read_claimable(&env, &auction.seller, &auction.token);
+            storage::write_claimable(&env, &auction.seller, &auction.token, claimable + bid.escrowed);
+        } else {
+            token::Client::new(&env, &auction.token).transfer(&env.current_contract_address(), &bidder, &bid.escrowed);
+        }
+
+        events::bid_withdrawn(&env, auction.ip_id, bidder, auction_id, bid.escrowed, forfeited);
+        Ok(forfeited)
+    }
+
+    /// Commitment to a bid of `amount` under a secret `salt`, recomputable
+    /// off-chain as
+    ///
+    /// ```text
+    /// sha256("flavorsnap-sealed-bid-v1" || amount i128, big-endian || salt)
+    /// ```
+    pub fn bid_commitment(env: Env, amount: i128, salt: BytesN<32>) -> BytesN<32> {
+        let mut preimage = Bytes::from_slice(&env, COMMITMENT_DOMAIN);
+        preimage.extend_from_array(&amount.to_be_bytes());
+        preimage.extend_from_array(&salt.to_array());
+        env.crypto().sha256(&preimage).into()
+    }
+}
+
+impl IPLicensingContract {
+    pub(crate) fn require_auction(env: &Env, auction_id: u64) -> SealedAuction {
+        storage::read_auction(env, auction_id).unwrap_or_else(|| panic_with_error!(env, ExtError::AuctionNotFound))
+    }
+
+    pub(crate) fn require_sealed_bid(env: &Env, auction_id: u64, bidder: &Address) -> SealedBid {
+        storage::read_sealed_bid(env, auction_id, bidder).unwrap_or_else(|| panic_with_error!(env, ExtError::BidNotFound))
+    }
+
+    /// Keep an auctioned slot free until the auction settles
+    pub(crate) fn require_no_open_auction(env: &Env, ip_id: u64) -> Result<(), IPError> {
+        if storage::read_open_auction(env, ip_id).is_some() {
+            return Err(IPError::ExclusiveAlreadyIssued);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+//! Licensing calendars: seasonal availability windows and blackouts during
+//! which an IP's licenses are not sold
+
+use crate::{
+    events, storage, ExtError, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient,
+    LicensingCalendar, TimeWindow
+};
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env, Vec};
+
+/// Most availability and blackout windows in one calendar, together
+const MAX_CALENDAR_WINDOWS: u32 = 16;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Restrict when the IP's licenses can be bought or claimed, or lift the
+    /// restriction with `None` (Requires IP owner approval). Windows must be
+    /// non-empty; they may overlap.
+    pub fn set_licensing_calendar(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        calendar: Option<LicensingCalendar>,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        if let Some(calendar) = &calendar {
+            if calendar.availability.len() + calendar.blackouts.len() > MAX_CALENDAR_WINDOWS {
+                return Err(IPError::CapReached);
+            }
+            let mut windows = calendar.availability.iter().chain(calendar.blackouts.iter());
+            if windows.any(|window| window.starts_at >= window.ends_at) {
+                return Err(IPError::InvalidPeriod);
+            }
+        }
+        storage::write_licensing_calendar(&env, ip_id, &calendar);
+
+        events::licensing_calendar_set(&env, ip_id, calendar);
+        Ok(())
+    }
+
+    pub fn get_licensing_calendar(env: Env, ip_id: u64) -> Option<LicensingCalendar> {
+        storage::read_licensing_calendar(&env, ip_id)
+    }
+
+    /// Whether the IP's calendar allows new licenses at the current ledger time
+    pub fn is_licensing_open(env: Env, ip_id: u64) -> bool {
+        storage::read_licensing_calendar(&env, ip_id)
+            .is_none_or(|calendar| Self::is_open_at(&calendar, env.ledger().timestamp()))
+    }
+
+    /// The current or next span during which the IP's calendar allows new
+    /// licenses, `ends_at` being `u64::MAX` if nothing closes it. `None` if it
+    /// never opens again.
+    pub fn next_licensing_window(env: Env, ip_id: u64) -> Option<TimeWindow> {
+        let now = env.ledger().timestamp();
+        let Some(calendar) = storage::read_licensing_calendar(&env, ip_id) else {
+            return Some(TimeWindow { starts_at: now, ends_at: u64::MAX });
+        };
+
+        // Openness only changes where a window starts or ends
+        let openings = calendar.availability.iter().map(|window| window.starts_at)
+            .chain(calendar.blackouts.iter().map(|window| window.ends_at));
+        let starts_at = core::iter::once(now)
+            .chain(openings.filter(|&at| at >= now))
+            .filter(|&at| Self::is_open_at(&calendar, at))
+            .min()?;
+
+        let closings = calendar.availability.iter().map(|window| window.ends_at)
+            .chain(calendar.blackouts.iter().map(|window| window.starts_at));
+        let ends_at = closings
+            .filter(|&at| at > starts_at && !Self::is_open_at(&calendar, at))
+            .min()
+            .unwrap_or(u64::MAX);
+        Some(TimeWindow { starts_at, ends_at })
+    }
+}
+
+impl IPLicensingContract {
+    /// Refuse new licenses outside the IP's calendar, if it has one
+    pub(crate) fn require_licensing_open(env: &Env, ip_id: u64) {
+        if !Self::is_licensing_open(env.clone(), ip_id) {
+            panic_with_error!(env, ExtError::LicensingClosed);
+        }
+    }
+
+    fn is_open_at(calendar: &LicensingCalendar, at: u64) -> bool {
+        let within = |window: TimeWindow| window.starts_at <= at && at < window.ends_at;
+        (calendar.availability.is_empty() || calendar.availability.iter().any(within))
+            && !calendar.blackouts.iter().any(within)
+    }
+}
@@ -0,0 +1,63 @@
+//! Versioned contract parameters, kept together in one instance entry
+
+use crate::{
+    storage, Config, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient, Role,
+    BPS_DENOMINATOR
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env};
+
+/// Schema version written by this build; bump it whenever `Config` changes shape
+pub(crate) const CONFIG_VERSION: u32 = 1;
+
+/// Upper bound on `max_batch_size`, keeping paginated calls within budget
+const MAX_BATCH_SIZE_LIMIT: u32 = 100;
+
+#[contractimpl]
+impl IPLicensingContract {
+    pub fn get_config(env: Env) -> Config {
+        storage::read_config(&env)
+    }
+
+    /// Replace the contract parameters. `config` must carry the current schema
+    /// version and admin; fee and treasury are normally changed by a FeeManager
+    /// through `set_fee` (Only callable by an Admin)
+    pub fn set_config(env: Env, admin: Address, config: Config) -> Result<(), IPError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        if config.fee_bps as i128 > BPS_DENOMINATOR {
+            return Err(IPError::InvalidFee);
+        }
+        if config.version != CONFIG_VERSION
+            || config.admin != storage::read_config(&env).admin
+            || config.max_batch_size == 0
+            || config.max_batch_size > MAX_BATCH_SIZE_LIMIT
+            || config.idempotency_ttl_ledgers == 0
+        {
+            return Err(IPError::InvalidConfig);
+        }
+
+        storage::write_config(&env, &config);
+        Self::audit(&env, symbol_short!("config"), &admin, (config.version,));
+        Ok(())
+    }
+}
+
+/// Parameters a fresh deployment starts with
+pub(crate) fn initial(admin: Address, treasury: Address, fee_bps: u32) -> Config {
+    Config {
+        version: CONFIG_VERSION,
+        admin,
+        treasury,
+        fee_bps,
+        max_batch_size: 50,
+        // Roughly one day of ledgers
+        idempotency_ttl_ledgers: 17_280,
+        restore_window_secs: 30 * 24 * 60 * 60,
+    }
+}
+
+/// Most items a paginated view returns or a batch call accepts
+pub(crate) fn max_batch_size(env: &Env) -> u32 {
+    storage::read_config(env).max_batch_size
+}
@@ -0,0 +1,114 @@
+//! Conflict groups: IPs that are legally one work (a RAW photo and its edit,
+//! say), so an exclusive license on any of them excludes exclusives on the rest
+
+use crate::{
+    config, events, storage, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient
+};
+use soroban_sdk::{contractimpl, Address, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Group `ip_ids` so that at most one of them holds an exclusive license,
+    /// live option or open auction at a time (Requires approval of the first IP's owner, who must own
+    /// every IP in the group). Returns the group id.
+    pub fn create_conflict_group(env: Env, approvers: Vec<Address>, ip_ids: Vec<u64>) -> Result<u64, IPError> {
+        if ip_ids.len() < 2 {
+            return Err(IPError::InvalidAmount);
+        }
+        if ip_ids.len() > config::max_batch_size(&env) {
+            return Err(IPError::CapReached);
+        }
+
+        let first = storage::read_ip(&env, ip_ids.get_unchecked(0))?;
+        Self::require_owner_approval(&first, &approvers)?;
+
+        let mut slot_holders = 0;
+        for (index, ip_id) in ip_ids.iter().enumerate() {
+            let ip = storage::read_ip(&env, ip_id)?;
+            if ip.owner != first.owner {
+                return Err(IPError::Unauthorized);
+            }
+            Self::require_not_frozen(&env, ip_id)?;
+            let is_duplicate = ip_ids.first_index_of(ip_id) != Some(index as u32);
+            if is_duplicate || storage::read_conflict_group_of(&env, ip_id).is_some() {
+                return Err(IPError::AlreadyGrouped);
+            }
+            if Self::holds_exclusive_slot(&env, ip_id, ip.has_exclusive) {
+                slot_holders += 1;
+            }
+        }
+        if slot_holders > 1 {
+            return Err(IPError::ExclusiveConflict);
+        }
+
+        let group_id = storage::next_conflict_group_id(&env);
+        storage::write_conflict_group(&env, group_id, &ip_ids);
+        for ip_id in ip_ids.iter() {
+            storage::write_conflict_group_of(&env, ip_id, Some(group_id));
+            events::conflict_grouped(&env, ip_id, group_id);
+        }
+        Ok(group_id)
+    }
+
+    /// Take an IP out of its conflict group (Requires IP owner approval). A
+    /// group left with a single member is dissolved.
+    pub fn leave_conflict_group(env: Env, approvers: Vec<Address>, ip_id: u64) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let group_id = storage::read_conflict_group_of(&env, ip_id).ok_or(IPError::NotGrouped)?;
+        Self::remove_from_conflict_group(&env, ip_id, group_id);
+        Ok(())
+    }
+
+    /// Id and members of the conflict group `ip_id` belongs to, if any
+    pub fn get_conflict_group(env: Env, ip_id: u64) -> Option<(u64, Vec<u64>)> {
+        let group_id = storage::read_conflict_group_of(&env, ip_id)?;
+        Some((group_id, storage::read_conflict_group(&env, group_id)))
+    }
+}
+
+impl IPLicensingContract {
+    /// Take the IP out of `group_id`, dissolving a group left with one member
+    pub(crate) fn remove_from_conflict_group(env: &Env, ip_id: u64, group_id: u64) {
+        let mut members = storage::read_conflict_group(env, group_id);
+        if let Some(index) = members.first_index_of(ip_id) {
+            members.remove(index);
+        }
+        storage::write_conflict_group_of(env, ip_id, None);
+        events::conflict_left(env, ip_id, group_id);
+
+        if members.len() == 1 {
+            let last = members.pop_back_unchecked();
+            storage::write_conflict_group_of(env, last, None);
+            events::conflict_left(env, last, group_id);
+        }
+        storage::write_conflict_group(env, group_id, &members);
+    }
+
+    /// Fail if another IP in `ip_id`'s conflict group holds the group's
+    /// exclusive slot
+    pub(crate) fn require_no_conflicting_exclusive(env: &Env, ip_id: u64) -> Result<(), IPError> {
+        let Some(group_id) = storage::read_conflict_group_of(env, ip_id) else {
+            return Ok(());
+        };
+        for member in storage::read_conflict_group(env, group_id).iter() {
+            if member == ip_id {
+                continue;
+            }
+            let has_exclusive = storage::read_ip(env, member).is_ok_and(|ip| ip.has_exclusive);
+            if Self::holds_exclusive_slot(env, member, has_exclusive) {
+                return Err(IPError::ExclusiveConflict);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the IP takes its group's exclusive slot, through an exclusive
+    /// license, a live option or an auction not yet settled
+    fn holds_exclusive_slot(env: &Env, ip_id: u64, has_exclusive: bool) -> bool {
+        has_exclusive || Self::live_option(env, ip_id).is_some() || storage::read_open_auction(env, ip_id).is_some()
+    }
+}
@@ -0,0 +1,60 @@
+//! Per-owner summary for creator apps, replacing the handful of reads and
+//! joins a home screen would otherwise make
+
+use crate::{
+    config, storage, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient, OwnerDashboard
+};
+use flavorsnap_common::PageCursor;
+use soroban_sdk::{contractimpl, Address, Env, Map, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Ids of the IPs `owner` currently holds, tombstoned ones included, at
+    /// most `max_batch_size` from position `start`. They are listed in the
+    /// order they were acquired until one leaves, which moves the latest
+    /// acquired into its slot.
+    pub fn get_owner_ips(env: Env, owner: Address, start: u32, limit: u32) -> Vec<u64> {
+        let ip_ids = storage::owner_ips(&owner);
+        let cursor = PageCursor::new(start, limit, config::max_batch_size(&env), ip_ids.len(&env));
+        ip_ids.page(&env, &cursor)
+    }
+
+    /// Counts and balances across the IPs `owner` holds, with the position to
+    /// continue from or `None` when done. IP count and unexpired offers cover
+    /// the whole account. The rest covers the page of at most `max_batch_size`
+    /// IPs from position `start`: active licenses and open disputes are summed
+    /// for callers to add up across pages, and `unclaimed` holds the owner's
+    /// balance in each payment token those IPs sell in.
+    pub fn get_owner_dashboard(env: Env, owner: Address, start: u32) -> (OwnerDashboard, Option<u32>) {
+        let ip_ids = storage::owner_ips(&owner);
+        let max = config::max_batch_size(&env);
+        let cursor = PageCursor::new(start, max, max, ip_ids.len(&env));
+        let mut dashboard = OwnerDashboard {
+            ip_count: ip_ids.len(&env),
+            active_licenses: 0,
+            unclaimed: Map::new(&env),
+            open_offers: 0,
+            open_disputes: 0,
+        };
+
+        for ip_id in ip_ids.page(&env, &cursor).iter() {
+            let Ok(ip) = storage::read_ip(&env, ip_id) else {
+                continue;
+            };
+            dashboard.active_licenses += ip.active_licenses;
+            dashboard.open_disputes += storage::read_open_disputes(&env, ip_id);
+            if !dashboard.unclaimed.contains_key(ip.payment_token.clone()) {
+                let claimable = storage::read_claimable(&env, &owner, &ip.payment_token);
+                dashboard.unclaimed.set(ip.payment_token, claimable);
+            }
+        }
+
+        let now = env.ledger().timestamp();
+        for offer_id in storage::read_owner_offers(&env, &owner).iter() {
+            if storage::read_offer(&env, offer_id).is_ok_and(|offer| offer.expires_at > now) {
+                dashboard.open_offers += 1;
+            }
+        }
+        (dashboard, cursor.next_start())
+    }
+}
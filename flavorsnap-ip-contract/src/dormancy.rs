@@ -0,0 +1,101 @@
+//! Reclaiming exclusive slots from licensees who stopped paying royalties,
+//! after an on-chain notice they can answer with a royalty payment
+
+use crate::{
+    events, storage, DormancyPolicy, IPError, IPLicensingContract, IPLicensingContractArgs,
+    IPLicensingContractClient, LicenseType
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Let the owner serve dormancy notice on an exclusive licensee silent for
+    /// `threshold_secs`, reclaimable `notice_secs` later, or stop with `None`
+    /// (Requires IP owner approval)
+    pub fn set_dormancy_policy(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        policy: Option<DormancyPolicy>,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        if policy.as_ref().is_some_and(|policy| policy.threshold_secs == 0) {
+            return Err(IPError::InvalidAmount);
+        }
+        storage::write_dormancy_policy(&env, ip_id, &policy);
+
+        let (threshold_secs, notice_secs) = policy.map_or((0, 0), |policy| (policy.threshold_secs, policy.notice_secs));
+        events::dormancy_policy_set(&env, ip_id, threshold_secs, notice_secs);
+        Ok(())
+    }
+
+    pub fn get_dormancy_policy(env: Env, ip_id: u64) -> Option<DormancyPolicy> {
+        storage::read_dormancy_policy(&env, ip_id)
+    }
+
+    /// The open dormancy notice on the IP: licensee and when it was served.
+    /// Notices the licensee answered with a royalty payment are not open.
+    pub fn get_dormancy_notice(env: Env, ip_id: u64) -> Option<(Address, u64)> {
+        let (licensee, served_at) = storage::read_dormancy_notice(&env, ip_id)?;
+        let license = storage::read_license(&env, ip_id, &licensee).ok()?;
+        (license.is_active && license.last_activity_at <= served_at).then_some((licensee, served_at))
+    }
+
+    /// Give the exclusive licensee notice that its slot will be reclaimed
+    /// unless it pays a royalty within the IP's notice period (Requires IP
+    /// owner approval). The licensee must have been silent for the IP's
+    /// dormancy threshold. Returns when the slot becomes reclaimable.
+    pub fn serve_dormancy_notice(env: Env, approvers: Vec<Address>, ip_id: u64, licensee: Address) -> Result<u64, IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let policy = storage::read_dormancy_policy(&env, ip_id).ok_or(IPError::NotDormant)?;
+        let license = storage::read_license(&env, ip_id, &licensee)?;
+        if !license.is_active || license.license_type != LicenseType::Exclusive {
+            return Err(IPError::LicenseNotFound);
+        }
+        let now = env.ledger().timestamp();
+        if now < license.last_activity_at.saturating_add(policy.threshold_secs) {
+            return Err(IPError::NotDormant);
+        }
+
+        storage::write_dormancy_notice(&env, ip_id, &Some((licensee.clone(), now)));
+        let reclaimable_at = now.saturating_add(policy.notice_secs);
+        events::dormancy_notice_served(&env, ip_id, licensee, reclaimable_at);
+        Ok(reclaimable_at)
+    }
+
+    /// Revoke the exclusive license of a licensee that stayed silent through
+    /// its dormancy notice, freeing the slot (Requires IP owner approval). The
+    /// revocation is appealable like any other.
+    pub fn reclaim_dormant_exclusive(env: Env, approvers: Vec<Address>, ip_id: u64) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let (licensee, served_at) = storage::read_dormancy_notice(&env, ip_id).ok_or(IPError::NotDormant)?;
+        let policy = storage::read_dormancy_policy(&env, ip_id).ok_or(IPError::NotDormant)?;
+        let license = storage::read_license(&env, ip_id, &licensee)?;
+        if license.last_activity_at > served_at || !license.is_active {
+            return Err(IPError::NotDormant);
+        }
+        if env.ledger().timestamp() < served_at.saturating_add(policy.notice_secs) {
+            return Err(IPError::NotDormant);
+        }
+
+        Self::deactivate_license(&env, ip_id, &mut ip, &licensee, Some(symbol_short!("dormant")))?;
+        storage::write_ip(&env, ip_id, &ip);
+        storage::write_dormancy_notice(&env, ip_id, &None);
+
+        events::license_revoked(&env, ip_id, licensee.clone(), symbol_short!("dormant"));
+        events::dormant_exclusive_reclaimed(&env, ip_id, licensee);
+        Ok(())
+    }
+}
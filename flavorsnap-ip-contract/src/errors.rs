@@ -0,0 +1,315 @@
+use soroban_sdk::contracterror;
+
+/// Errors returned by `IPLicensingContract`.
+///
+/// Discriminants are part of the public ABI and must never be renumbered;
+/// new conditions are appended at the end. The contract spec holds at most 50
+/// error cases, all in use, so conditions added since are `ExtError`s.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum IPError {
+    /// The caller lacks the role or ownership required for this call.
+    Unauthorized = 1,
+    /// `register_ip` was called with an id that is already in use.
+    IPAlreadyRegistered = 2,
+    /// No IP asset is stored under the given id.
+    IPNotFound = 3,
+    /// The IP already has an active exclusive licensee, or its exclusive
+    /// slot is held by another buyer's option or an open auction.
+    ExclusiveAlreadyIssued = 4,
+    /// An exclusive license was requested while non-exclusive licenses are active.
+    ActiveLicensesExist = 5,
+    /// The licensee holds no active license on this IP.
+    LicenseNotFound = 6,
+    /// The licensee already holds an active license on this IP.
+    LicenseAlreadyExists = 7,
+    /// Reserved: the contract was already initialized.
+    AlreadyInitialized = 8,
+    /// Purchases and royalty payments are halted by a Pauser.
+    Paused = 9,
+    /// A fee above 10000 basis points was supplied.
+    InvalidFee = 10,
+    /// The IP was deregistered and no longer accepts new licenses.
+    IPDeregistered = 11,
+    /// `restore_ip` was called on an IP that is not deregistered.
+    NotDeregistered = 12,
+    /// The configured restore window for a deregistered IP has elapsed.
+    RestoreWindowExpired = 13,
+    /// A price or payment amount is negative or otherwise out of range, or a
+    /// batch to anchor is empty.
+    InvalidAmount = 14,
+    /// A time-bounded record (license, offer, voucher, ...) has expired.
+    Expired = 15,
+    /// A configured capacity or per-call limit would be exceeded.
+    CapReached = 16,
+    /// An idempotency key was reused while still remembered.
+    DuplicateOperation = 17,
+    /// A signer set was empty, oversized, duplicated or had an unreachable threshold.
+    InvalidSigners = 18,
+    /// The IP asset is frozen under a legal hold.
+    IPFrozen = 19,
+    /// No voucher on this IP matches the supplied code.
+    VoucherNotFound = 20,
+    /// The creator has not published a profile.
+    ProfileNotFound = 21,
+    /// Bulk import was closed by `finalize_import`
+    ImportFinalized = 22,
+    /// No revenue shares were issued for the IP
+    SharesNotIssued = 23,
+    /// Revenue shares were already issued for the IP
+    SharesAlreadyIssued = 24,
+    /// Share balance or allowance is too low
+    InsufficientShares = 25,
+    /// No fresh reference price could be read from the price oracle
+    PriceUnavailable = 26,
+    /// The converted price exceeds the buyer's `max_payment`
+    SlippageExceeded = 27,
+    /// The call executed after its `deadline`
+    DeadlineExceeded = 28,
+    /// Config has the wrong schema version, a changed admin or an out-of-range
+    /// parameter, references a terms template the terms library lacks, or no
+    /// swap router is set
+    InvalidConfig = 29,
+    /// Payment token reports more decimals than prices can be validated
+    /// against, or a swap would go from the payment token to itself
+    UnsupportedToken = 30,
+    /// License was not revoked with an open appeal window, or the appeal was already filed
+    AppealClosed = 31,
+    /// No appeal is awaiting resolution on this license
+    NoPendingAppeal = 32,
+    /// The license tier is not offered on this IP or not valid for this operation
+    InvalidTier = 33,
+    /// The IP's single broadcast license is already held
+    BroadcastAlreadyIssued = 34,
+    /// No royalty report exists under this id
+    ReportNotFound = 35,
+    /// The attested proof is not the one currently on file, or a revealed
+    /// bid does not match its commitment
+    ProofMismatch = 36,
+    /// No indemnity bond is currently held for the IP
+    BondNotHeld = 37,
+    /// The bond's challenge period has not passed
+    BondLocked = 38,
+    /// The licensee does not meet the IP's token or attestation gate
+    GateNotMet = 39,
+    /// The payment memo is longer than `MAX_MEMO_LEN` bytes
+    MemoTooLong = 40,
+    /// The statement period is empty, longer than 31 days or not aligned to whole days
+    InvalidPeriod = 41,
+    /// Royalty schedule steps are empty, too many, not ascending from zero or above 10000 bps
+    InvalidSchedule = 42,
+    /// The license has no royalty schedule to apply reported usage to
+    NoRoyaltySchedule = 43,
+    /// No open offer exists under this id
+    OfferNotFound = 44,
+    /// The offer can only be released by anyone once it has expired
+    OfferNotExpired = 45,
+    /// The IP already belongs to a conflict group
+    AlreadyGrouped = 46,
+    /// Another IP of the conflict group holds an exclusive license
+    ExclusiveConflict = 47,
+    /// The IP belongs to no conflict group
+    NotGrouped = 48,
+    /// The licensee cannot cover the price from their balance
+    InsufficientBalance = 49,
+    /// The exclusive licensee paid royalties within the dormancy threshold or
+    /// since notice was served, or the notice period is still running
+    NotDormant = 50,
+}
+
+impl IPError {
+    pub fn from_code(code: u32) -> Option<Self> {
+        let error = match code {
+            1 => IPError::Unauthorized,
+            2 => IPError::IPAlreadyRegistered,
+            3 => IPError::IPNotFound,
+            4 => IPError::ExclusiveAlreadyIssued,
+            5 => IPError::ActiveLicensesExist,
+            6 => IPError::LicenseNotFound,
+            7 => IPError::LicenseAlreadyExists,
+            8 => IPError::AlreadyInitialized,
+            9 => IPError::Paused,
+            10 => IPError::InvalidFee,
+            11 => IPError::IPDeregistered,
+            12 => IPError::NotDeregistered,
+            13 => IPError::RestoreWindowExpired,
+            14 => IPError::InvalidAmount,
+            15 => IPError::Expired,
+            16 => IPError::CapReached,
+            17 => IPError::DuplicateOperation,
+            18 => IPError::InvalidSigners,
+            19 => IPError::IPFrozen,
+            20 => IPError::VoucherNotFound,
+            21 => IPError::ProfileNotFound,
+            22 => IPError::ImportFinalized,
+            23 => IPError::SharesNotIssued,
+            24 => IPError::SharesAlreadyIssued,
+            25 => IPError::InsufficientShares,
+            26 => IPError::PriceUnavailable,
+            27 => IPError::SlippageExceeded,
+            28 => IPError::DeadlineExceeded,
+            29 => IPError::InvalidConfig,
+            30 => IPError::UnsupportedToken,
+            31 => IPError::AppealClosed,
+            32 => IPError::NoPendingAppeal,
+            33 => IPError::InvalidTier,
+            34 => IPError::BroadcastAlreadyIssued,
+            35 => IPError::ReportNotFound,
+            36 => IPError::ProofMismatch,
+            37 => IPError::BondNotHeld,
+            38 => IPError::BondLocked,
+            39 => IPError::GateNotMet,
+            40 => IPError::MemoTooLong,
+            41 => IPError::InvalidPeriod,
+            42 => IPError::InvalidSchedule,
+            43 => IPError::NoRoyaltySchedule,
+            44 => IPError::OfferNotFound,
+            45 => IPError::OfferNotExpired,
+            46 => IPError::AlreadyGrouped,
+            47 => IPError::ExclusiveConflict,
+            48 => IPError::NotGrouped,
+            49 => IPError::InsufficientBalance,
+            50 => IPError::NotDormant,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Short human-readable description, surfaced through `error_message`
+    pub fn message(&self) -> &'static str {
+        match self {
+            IPError::Unauthorized => "caller is not authorized",
+            IPError::IPAlreadyRegistered => "ip id already registered",
+            IPError::IPNotFound => "ip not found",
+            IPError::ExclusiveAlreadyIssued => "exclusive license already issued, optioned or auctioned",
+            IPError::ActiveLicensesExist => "active licenses exist",
+            IPError::LicenseNotFound => "license not found",
+            IPError::LicenseAlreadyExists => "license already exists",
+            IPError::AlreadyInitialized => "already initialized",
+            IPError::Paused => "contract is paused",
+            IPError::InvalidFee => "fee exceeds 10000 bps",
+            IPError::IPDeregistered => "ip is deregistered",
+            IPError::NotDeregistered => "ip is not deregistered",
+            IPError::RestoreWindowExpired => "restore window expired",
+            IPError::InvalidAmount => "invalid amount",
+            IPError::Expired => "expired",
+            IPError::CapReached => "cap reached",
+            IPError::DuplicateOperation => "duplicate operation",
+            IPError::InvalidSigners => "invalid signer set",
+            IPError::IPFrozen => "ip is frozen",
+            IPError::VoucherNotFound => "voucher not found",
+            IPError::ProfileNotFound => "creator profile not found",
+            IPError::ImportFinalized => "import already finalized",
+            IPError::SharesNotIssued => "revenue shares not issued",
+            IPError::SharesAlreadyIssued => "revenue shares already issued",
+            IPError::InsufficientShares => "insufficient share balance or allowance",
+            IPError::PriceUnavailable => "reference price unavailable",
+            IPError::SlippageExceeded => "price exceeds max payment",
+            IPError::DeadlineExceeded => "deadline exceeded",
+            IPError::InvalidConfig => "invalid config",
+            IPError::UnsupportedToken => "unsupported payment token",
+            IPError::AppealClosed => "appeal window closed",
+            IPError::NoPendingAppeal => "no pending appeal",
+            IPError::InvalidTier => "license tier not available",
+            IPError::BroadcastAlreadyIssued => "broadcast license already issued",
+            IPError::ReportNotFound => "royalty report not found",
+            IPError::ProofMismatch => "proof does not match",
+            IPError::BondNotHeld => "no indemnity bond held",
+            IPError::BondLocked => "bond challenge period not over",
+            IPError::GateNotMet => "licensee does not meet the IP's gates",
+            IPError::MemoTooLong => "memo too long",
+            IPError::InvalidPeriod => "invalid statement period",
+            IPError::InvalidSchedule => "invalid royalty schedule",
+            IPError::NoRoyaltySchedule => "no royalty schedule",
+            IPError::OfferNotFound => "offer not found",
+            IPError::OfferNotExpired => "offer has not expired",
+            IPError::AlreadyGrouped => "IP already in a conflict group",
+            IPError::ExclusiveConflict => "exclusive license issued on a conflicting IP",
+            IPError::NotGrouped => "IP not in a conflict group",
+            IPError::InsufficientBalance => "insufficient balance for the price",
+            IPError::NotDormant => "licensee is not dormant",
+        }
+    }
+}
+
+/// Errors of `IPLicensingContract` beyond the 50 that fit in `IPError`.
+///
+/// Codes continue `IPError`'s numbering and reach callers the same way, as
+/// contract errors. Entrypoints declare `IPError` as their error type, so
+/// these are raised with `panic_with_error!`; the transaction reverts just as
+/// it does for a returned error. The same ABI rules apply.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ExtError {
+    /// Revoking the role would leave the contract without an Admin.
+    LastAdmin = 51,
+    /// The IP is not under legal hold.
+    NotFrozen = 52,
+    /// The offer would stay open longer than offers may.
+    OfferTooLong = 53,
+    /// No handover is pending on the IP.
+    HandoverNotFound = 54,
+    /// No option has been taken on the IP's exclusive slot.
+    OptionNotFound = 55,
+    /// No sealed-bid auction has this id.
+    AuctionNotFound = 56,
+    /// The bidder has no bid held in the auction.
+    BidNotFound = 57,
+    /// The auction is not in the phase this call needs: bids are revealed
+    /// after commits close, and auctions settle and release bids after
+    /// reveals close.
+    AuctionPhase = 58,
+    /// The IP sells no preview grants
+    PreviewTermsNotFound = 59,
+    /// The revealed bid and salt do not hash to the bidder's commitment.
+    BidCommitmentMismatch = 60,
+    /// The IP's licensing calendar does not allow new licenses right now.
+    LicensingClosed = 61,
+    /// No swap router has been set to pay royalties from another asset.
+    SwapRouterNotSet = 62,
+    /// A batch to anchor lists no IPs.
+    EmptyBatch = 63,
+}
+
+impl ExtError {
+    pub fn from_code(code: u32) -> Option<Self> {
+        let error = match code {
+            51 => ExtError::LastAdmin,
+            52 => ExtError::NotFrozen,
+            53 => ExtError::OfferTooLong,
+            54 => ExtError::HandoverNotFound,
+            55 => ExtError::OptionNotFound,
+            56 => ExtError::AuctionNotFound,
+            57 => ExtError::BidNotFound,
+            58 => ExtError::AuctionPhase,
+            59 => ExtError::PreviewTermsNotFound,
+            60 => ExtError::BidCommitmentMismatch,
+            61 => ExtError::LicensingClosed,
+            62 => ExtError::SwapRouterNotSet,
+            63 => ExtError::EmptyBatch,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Short human-readable description, surfaced through `error_message`
+    pub fn message(&self) -> &'static str {
+        match self {
+            ExtError::LastAdmin => "cannot revoke the last admin",
+            ExtError::NotFrozen => "IP is not frozen",
+            ExtError::OfferTooLong => "offer expiry too far in the future",
+            ExtError::HandoverNotFound => "no handover pending",
+            ExtError::OptionNotFound => "no option on this IP",
+            ExtError::AuctionNotFound => "auction not found",
+            ExtError::BidNotFound => "no bid held in this auction",
+            ExtError::AuctionPhase => "auction phase does not allow this",
+            ExtError::PreviewTermsNotFound => "IP sells no previews",
+            ExtError::BidCommitmentMismatch => "bid does not match its commitment",
+            ExtError::LicensingClosed => "licensing is closed",
+            ExtError::SwapRouterNotSet => "swap router not set",
+            ExtError::EmptyBatch => "batch is empty",
+        }
+    }
+}
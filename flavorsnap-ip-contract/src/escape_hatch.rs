@@ -0,0 +1,88 @@
+//! Last-resort exits for escrows whose release waits on a counterparty or
+//! arbiter. Every escrow the contract holds, and whether it can get stuck:
+//! - indemnity bonds wait on their challenge period and on arbiter awards,
+//!   so are reclaimable here;
+//! - claim bonds wait on the license they back, which may never end, so are
+//!   reclaimable here; once their IP is deleted they settle as a refund;
+//! - offers can be withdrawn by the offerer at any time and refunded by
+//!   anyone once expired, paused or frozen alike;
+//! - sealed bids wait on the auction's settlement, which pauses hold up:
+//!   losing bids can be withdrawn at any time once reveals close, and the
+//!   leading bid of an auction left unsettled is reclaimable here;
+//! - handovers can be cancelled by either party at any time, refunding the
+//!   successor's escrow;
+//! - refund windows hold nothing: downgrade refunds are paid in the same
+//!   call, and the deregistration restore window only delays deletion;
+//! - disputes hold nothing: appeals and disputed royalty reports only record
+//!   state, as royalties are settled in full when paid;
+//! - claimable balances can be claimed by their owner at any time.
+
+use crate::{
+    events, storage, BondStatus, ExtError, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient,
+    StuckEscrow
+};
+use soroban_sdk::{contractimpl, panic_with_error, token, Address, Env};
+
+/// How long an escrow must stay locked before its depositor may take it back
+/// regardless of holds, appeals or pauses
+pub const ESCROW_HARD_TIMEOUT_SECS: u64 = 365 * 24 * 60 * 60;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Take back `escrow` once `ESCROW_HARD_TIMEOUT_SECS` has passed since it
+    /// was last locked: an indemnity bond since its latest posting or
+    /// exclusive sale, a claim bond since its license was issued, a leading
+    /// sealed bid since the auction's reveals closed, which also closes the
+    /// auction unsold (Only callable by the depositor). Works while the IP is frozen or the
+    /// contract paused. Returns the amount returned.
+    pub fn reclaim_stuck_escrow(env: Env, depositor: Address, escrow: StuckEscrow) -> Result<i128, IPError> {
+        depositor.require_auth();
+        let now = env.ledger().timestamp();
+
+        let (token, amount) = match escrow {
+            StuckEscrow::IndemnityBond(ip_id) => {
+                let mut bond = storage::read_bond(&env, ip_id)
+                    .filter(|bond| bond.status == BondStatus::Held)
+                    .ok_or(IPError::BondNotHeld)?;
+                if bond.depositor != depositor {
+                    return Err(IPError::Unauthorized);
+                }
+                let locked_since = bond.release_at.saturating_sub(bond.challenge_period_secs);
+                if now < locked_since.saturating_add(ESCROW_HARD_TIMEOUT_SECS) {
+                    return Err(IPError::BondLocked);
+                }
+                bond.status = BondStatus::Reclaimed;
+                storage::write_bond(&env, ip_id, &bond);
+                (bond.token, bond.amount)
+            }
+            StuckEscrow::ClaimBond(ip_id) => {
+                let bond = storage::read_claim_bond(&env, ip_id, &depositor).ok_or(IPError::BondNotHeld)?;
+                let license = storage::read_license(&env, ip_id, &depositor)?;
+                if now < license.issued_at.saturating_add(ESCROW_HARD_TIMEOUT_SECS) {
+                    return Err(IPError::BondLocked);
+                }
+                storage::write_claim_bond(&env, ip_id, &depositor, &None);
+                bond
+            }
+            StuckEscrow::SealedBid(auction_id) => {
+                let mut auction = Self::require_auction(&env, auction_id);
+                let bid = Self::require_sealed_bid(&env, auction_id, &depositor);
+                if auction.settled || auction.leader.as_ref() != Some(&depositor) {
+                    panic_with_error!(&env, ExtError::AuctionPhase);
+                }
+                if now < auction.reveal_ends_at.saturating_add(ESCROW_HARD_TIMEOUT_SECS) {
+                    return Err(IPError::BondLocked);
+                }
+                auction.settled = true;
+                storage::write_auction(&env, &auction);
+                storage::write_open_auction(&env, auction.ip_id, None);
+                storage::write_sealed_bid(&env, auction_id, &depositor, &None);
+                (auction.token, bid.escrowed)
+            }
+        };
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &depositor, &amount);
+
+        events::escrow_reclaimed(&env, depositor, escrow, amount);
+        Ok(amount)
+    }
+}
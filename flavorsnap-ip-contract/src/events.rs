@@ -0,0 +1,363 @@
+//! Event publishers. Topics and payloads are part of the public interface
+//! consumed by indexers, so changes here must stay backwards compatible.
+//!
+//! Topic scheme, so RPC event filters can follow a single asset:
+//!
+//! - topic 0 is always the event name (`Symbol`)
+//! - events about one IP carry its `ip_id` (`u64`) as topic 1, followed by the
+//!   licensee or share holders involved where there is one
+//! - platform and account events (`fee_set`, `paused`, `unpaused`, `profile`,
+//!   `verified`, `agent_set`, `operator`, `bulk_rvk`, `claimed`, `anchor`)
+//!   carry no `ip_id`; their topic 1, if any, is the account concerned
+//!
+//! Subscribing to `[*, <ip_id>, **]` therefore yields an IP's whole lifecycle:
+//! `ip_reg`, `purchase`, `royalty`, `roy_swap`, `session`, `sess_end`, `memo`, `offer`, `offer_acc`, `offer_end`, `prv_terms`, `preview`, `opt_terms`, `option`, `opt_exer`, `auc_start`, `auc_bid`, `auc_rvl`, `auc_end`, `auc_fail`, `auc_wdrw`, `handover`, `hndvr_fnd`, `hndvr_ok`, `hndvr_end`, `roy_sched`, `rpt_flag`, `revoke`, `appeal`, `appeal_rs`,
+//! `downgrd`, `expired`, `prices`, `decay`, `gate`, `kyc_gate`, `calendar`, `terms_tpl`, `free_pol`, `clm_bond`, `tier_px`, `tier_move`, `signers`, `proof`, `attach`, `detach`, `origin`, `bond`, `bond_awd`, `bond_rel`, `esc_rclm`, `dormancy`, `dorm_note`, `dorm_rclm`, `conflict`, `cnfl_left`, `ip_xfer`, `ip_frz`, `ip_unfrz`,
+//! `ip_dereg`, `ip_rstr`, `referral`, `voucher`, `vch_used`, `shares`,
+//! `transfer`, `approve` and `sh_claim`.
+
+use crate::{AttestationGate, IPError, LicenseType, LicensingCalendar, MerkleAnchor, OptionTerms, PreviewTerms, StuckEscrow, TokenGate};
+use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Symbol};
+
+pub(crate) fn fee_set(env: &Env, fee_bps: u32) {
+    env.events().publish((symbol_short!("fee_set"),), fee_bps);
+}
+
+pub(crate) fn paused(env: &Env, pauser: Address) {
+    env.events().publish((symbol_short!("paused"),), pauser);
+}
+
+pub(crate) fn unpaused(env: &Env, pauser: Address) {
+    env.events().publish((symbol_short!("unpaused"),), pauser);
+}
+
+pub(crate) fn anchored(env: &Env, anchor: MerkleAnchor) {
+    env.events().publish((symbol_short!("anchor"),), anchor);
+}
+
+pub(crate) fn ip_frozen(env: &Env, ip_id: u64, caller: Address, reason: String) {
+    env.events().publish((symbol_short!("ip_frz"), ip_id), (caller, reason));
+}
+
+pub(crate) fn ip_unfrozen(env: &Env, ip_id: u64, caller: Address) {
+    env.events().publish((symbol_short!("ip_unfrz"), ip_id), caller);
+}
+
+pub(crate) fn profile_set(env: &Env, creator: Address, display_name_hash: BytesN<32>) {
+    env.events().publish((symbol_short!("profile"), creator), display_name_hash);
+}
+
+pub(crate) fn creator_verified(env: &Env, creator: Address, verified: bool) {
+    env.events().publish((symbol_short!("verified"), creator), verified);
+}
+
+pub(crate) fn purchase_agent_set(env: &Env, licensee: Address, agent: Address, approved: bool) {
+    env.events().publish((symbol_short!("agent_set"), licensee, agent), approved);
+}
+
+pub(crate) fn operator_set(env: &Env, licensee: Address, operator: Address, authorized: bool) {
+    env.events().publish((symbol_short!("operator"), licensee, operator), authorized);
+}
+
+pub(crate) fn ip_registered(env: &Env, ip_id: u64, owner: Address) {
+    env.events().publish((symbol_short!("ip_reg"), ip_id), owner);
+}
+
+pub(crate) fn license_purchased(
+    env: &Env,
+    ip_id: u64,
+    licensee: Address,
+    license_id: u64,
+    price: i128,
+    terms_template: Option<u32>,
+) {
+    env.events().publish((symbol_short!("purchase"), ip_id, licensee), (license_id, price, terms_template));
+}
+
+pub(crate) fn terms_template_set(env: &Env, ip_id: u64, template_id: Option<u32>) {
+    env.events().publish((symbol_short!("terms_tpl"), ip_id), template_id);
+}
+
+pub(crate) fn royalty_paid(env: &Env, ip_id: u64, licensee: Address, amount: i128) {
+    env.events().publish((symbol_short!("royalty"), ip_id, licensee), amount);
+}
+
+pub(crate) fn session_key_set(env: &Env, ip_id: u64, licensee: Address, key: Address, max_total: i128, expires_at: u64) {
+    env.events().publish((symbol_short!("session"), ip_id, licensee), (key, max_total, expires_at));
+}
+
+pub(crate) fn session_key_revoked(env: &Env, ip_id: u64, licensee: Address, key: Address) {
+    env.events().publish((symbol_short!("sess_end"), ip_id, licensee), key);
+}
+
+pub(crate) fn royalty_swapped(env: &Env, ip_id: u64, licensee: Address, token_in: Address, amount_in: i128, amount_out: i128) {
+    env.events().publish((symbol_short!("roy_swap"), ip_id, licensee), (token_in, amount_in, amount_out));
+}
+
+pub(crate) fn royalty_schedule_set(env: &Env, ip_id: u64, licensee: Address, steps: u32) {
+    env.events().publish((symbol_short!("roy_sched"), ip_id, licensee), steps);
+}
+
+/// Caller-supplied reference for the `purchase` (by license id) or `royalty`
+/// (by report id) event published just before it; never stored
+pub(crate) fn payment_memo(env: &Env, ip_id: u64, licensee: Address, kind: Symbol, reference: u64, memo: String) {
+    env.events().publish((symbol_short!("memo"), ip_id, licensee), (kind, reference, memo));
+}
+
+pub(crate) fn report_flagged(
+    env: &Env,
+    ip_id: u64,
+    licensee: Address,
+    report_id: u32,
+    report_hash: BytesN<32>,
+    reason: Symbol,
+) {
+    env.events().publish((symbol_short!("rpt_flag"), ip_id, licensee), (report_id, report_hash, reason));
+}
+
+pub(crate) fn license_revoked(env: &Env, ip_id: u64, licensee: Address, reason: Symbol) {
+    env.events().publish((symbol_short!("revoke"), ip_id, licensee), reason);
+}
+
+pub(crate) fn revocation_appealed(env: &Env, ip_id: u64, licensee: Address) {
+    env.events().publish((symbol_short!("appeal"), ip_id, licensee), ());
+}
+
+pub(crate) fn appeal_resolved(env: &Env, ip_id: u64, licensee: Address, reinstated: bool) {
+    env.events().publish((symbol_short!("appeal_rs"), ip_id, licensee), reinstated);
+}
+
+pub(crate) fn referral_paid(env: &Env, ip_id: u64, referrer: Address, amount: i128) {
+    env.events().publish((symbol_short!("referral"), ip_id), (referrer, amount));
+}
+
+pub(crate) fn bulk_revoked(env: &Env, licensee: Address, caller: Address, revoked: u32) {
+    env.events().publish((symbol_short!("bulk_rvk"), licensee), (caller, revoked));
+}
+
+pub(crate) fn ip_deregistered(env: &Env, ip_id: u64, hard_delete: bool) {
+    env.events().publish((symbol_short!("ip_dereg"), ip_id), hard_delete);
+}
+
+pub(crate) fn ip_restored(env: &Env, ip_id: u64, owner: Address) {
+    env.events().publish((symbol_short!("ip_rstr"), ip_id), owner);
+}
+
+pub(crate) fn signers_set(env: &Env, ip_id: u64, threshold: u32) {
+    env.events().publish((symbol_short!("signers"), ip_id), threshold);
+}
+
+pub(crate) fn prices_set(env: &Env, ip_id: u64, price_exclusive: i128, price_non_exclusive: i128) {
+    env.events().publish((symbol_short!("prices"), ip_id), (price_exclusive, price_non_exclusive));
+}
+
+pub(crate) fn tier_price_set(env: &Env, ip_id: u64, tier: LicenseType, price: Option<i128>) {
+    env.events().publish((symbol_short!("tier_px"), ip_id), (tier, price));
+}
+
+pub(crate) fn price_decay_set(env: &Env, ip_id: u64, step_bps: u32, interval_secs: u64, floor: i128) {
+    env.events().publish((symbol_short!("decay"), ip_id), (step_bps, interval_secs, floor));
+}
+
+pub(crate) fn free_claim_policy_set(env: &Env, ip_id: u64, bond: i128, cooldown_secs: u64) {
+    env.events().publish((symbol_short!("free_pol"), ip_id), (bond, cooldown_secs));
+}
+
+/// A free claim's bond was returned to the licensee, or forfeited to the
+/// owner after a revocation
+pub(crate) fn claim_bond_settled(env: &Env, ip_id: u64, licensee: Address, amount: i128, forfeited: bool) {
+    env.events().publish((symbol_short!("clm_bond"), ip_id, licensee), (amount, forfeited));
+}
+
+pub(crate) fn token_gate_set(env: &Env, ip_id: u64, gate: Option<TokenGate>) {
+    env.events().publish((symbol_short!("gate"), ip_id), gate);
+}
+
+pub(crate) fn attachment_added(env: &Env, ip_id: u64, name: Symbol, uri: String, hash: BytesN<32>) {
+    env.events().publish((symbol_short!("attach"), ip_id), (name, uri, hash));
+}
+
+pub(crate) fn attachment_removed(env: &Env, ip_id: u64, name: Symbol) {
+    env.events().publish((symbol_short!("detach"), ip_id), name);
+}
+
+pub(crate) fn licensing_calendar_set(env: &Env, ip_id: u64, calendar: Option<LicensingCalendar>) {
+    env.events().publish((symbol_short!("calendar"), ip_id), calendar);
+}
+
+pub(crate) fn attestation_gate_set(env: &Env, ip_id: u64, gate: Option<AttestationGate>) {
+    env.events().publish((symbol_short!("kyc_gate"), ip_id), gate);
+}
+
+pub(crate) fn moved_to_commercial(env: &Env, ip_id: u64, moved: u32) {
+    env.events().publish((symbol_short!("tier_move"), ip_id), moved);
+}
+
+pub(crate) fn license_downgraded(env: &Env, ip_id: u64, licensee: Address, refund: i128) {
+    env.events().publish((symbol_short!("downgrd"), ip_id, licensee), refund);
+}
+
+pub(crate) fn proof_set(env: &Env, ip_id: u64, proof_hash: Option<BytesN<32>>) {
+    env.events().publish((symbol_short!("proof"), ip_id), proof_hash);
+}
+
+pub(crate) fn origin_attested(env: &Env, ip_id: u64, attestor: Address, verified: bool) {
+    env.events().publish((symbol_short!("origin"), ip_id), (attestor, verified));
+}
+
+pub(crate) fn bond_posted(env: &Env, ip_id: u64, amount: i128, release_at: u64) {
+    env.events().publish((symbol_short!("bond"), ip_id), (amount, release_at));
+}
+
+pub(crate) fn bond_awarded(env: &Env, ip_id: u64, licensee: Address, amount: i128) {
+    env.events().publish((symbol_short!("bond_awd"), ip_id, licensee), amount);
+}
+
+/// A depositor took back an escrow after the hard timeout
+pub(crate) fn escrow_reclaimed(env: &Env, depositor: Address, escrow: StuckEscrow, amount: i128) {
+    env.events().publish((symbol_short!("esc_rclm"), depositor), (escrow, amount));
+}
+
+pub(crate) fn bond_released(env: &Env, ip_id: u64, depositor: Address, amount: i128) {
+    env.events().publish((symbol_short!("bond_rel"), ip_id), (depositor, amount));
+}
+
+pub(crate) fn offer_made(env: &Env, ip_id: u64, offerer: Address, offer_id: u64, amount: i128, expires_at: u64) {
+    env.events().publish((symbol_short!("offer"), ip_id, offerer), (offer_id, amount, expires_at));
+}
+
+pub(crate) fn offer_accepted(env: &Env, ip_id: u64, offerer: Address, offer_id: u64, license_id: u64) {
+    env.events().publish((symbol_short!("offer_acc"), ip_id, offerer), (offer_id, license_id));
+}
+
+/// The offer was withdrawn by its offerer or released after expiry
+pub(crate) fn offer_closed(env: &Env, ip_id: u64, offerer: Address, offer_id: u64) {
+    env.events().publish((symbol_short!("offer_end"), ip_id, offerer), offer_id);
+}
+
+pub(crate) fn preview_terms_set(env: &Env, ip_id: u64, terms: Option<PreviewTerms>) {
+    env.events().publish((symbol_short!("prv_terms"), ip_id), terms);
+}
+
+pub(crate) fn preview_purchased(env: &Env, ip_id: u64, viewer: Address, fee: i128, expires_at: u64) {
+    env.events().publish((symbol_short!("preview"), ip_id, viewer), (fee, expires_at));
+}
+
+pub(crate) fn option_terms_set(env: &Env, ip_id: u64, terms: Option<OptionTerms>) {
+    env.events().publish((symbol_short!("opt_terms"), ip_id), terms);
+}
+
+pub(crate) fn option_taken(env: &Env, ip_id: u64, holder: Address, fee: i128, expires_at: u64) {
+    env.events().publish((symbol_short!("option"), ip_id, holder), (fee, expires_at));
+}
+
+pub(crate) fn option_exercised(env: &Env, ip_id: u64, holder: Address, license_id: u64) {
+    env.events().publish((symbol_short!("opt_exer"), ip_id, holder), license_id);
+}
+
+pub(crate) fn auction_started(env: &Env, ip_id: u64, auction_id: u64, reserve_price: i128, reveal_ends_at: u64) {
+    env.events().publish((symbol_short!("auc_start"), ip_id), (auction_id, reserve_price, reveal_ends_at));
+}
+
+pub(crate) fn bid_committed(env: &Env, ip_id: u64, bidder: Address, auction_id: u64) {
+    env.events().publish((symbol_short!("auc_bid"), ip_id, bidder), auction_id);
+}
+
+pub(crate) fn bid_revealed(env: &Env, ip_id: u64, bidder: Address, auction_id: u64, amount: i128) {
+    env.events().publish((symbol_short!("auc_rvl"), ip_id, bidder), (auction_id, amount));
+}
+
+pub(crate) fn auction_settled(env: &Env, ip_id: u64, auction_id: u64, winner: Option<Address>, price: i128) {
+    env.events().publish((symbol_short!("auc_end"), ip_id), (auction_id, winner, price));
+}
+
+/// The leader of a settled auction could not be licensed and was refunded
+pub(crate) fn auction_failed(env: &Env, ip_id: u64, auction_id: u64, leader: Address, error: IPError) {
+    env.events().publish((symbol_short!("auc_fail"), ip_id), (auction_id, leader, error as u32));
+}
+
+pub(crate) fn bid_withdrawn(env: &Env, ip_id: u64, bidder: Address, auction_id: u64, amount: i128, forfeited: bool) {
+    env.events().publish((symbol_short!("auc_wdrw"), ip_id, bidder), (auction_id, amount, forfeited));
+}
+
+pub(crate) fn handover_proposed(env: &Env, ip_id: u64, from: Address, to: Address, consideration: i128) {
+    env.events().publish((symbol_short!("handover"), ip_id, from), (to, consideration));
+}
+
+pub(crate) fn handover_funded(env: &Env, ip_id: u64, to: Address, consideration: i128) {
+    env.events().publish((symbol_short!("hndvr_fnd"), ip_id, to), consideration);
+}
+
+pub(crate) fn handover_completed(env: &Env, ip_id: u64, from: Address, to: Address, license_id: u64, fee: i128) {
+    env.events().publish((symbol_short!("hndvr_ok"), ip_id, from, to), (license_id, fee));
+}
+
+/// The handover was cancelled by either party before approval
+pub(crate) fn handover_cancelled(env: &Env, ip_id: u64, from: Address, to: Address) {
+    env.events().publish((symbol_short!("hndvr_end"), ip_id, from), to);
+}
+
+pub(crate) fn dormancy_policy_set(env: &Env, ip_id: u64, threshold_secs: u64, notice_secs: u64) {
+    env.events().publish((symbol_short!("dormancy"), ip_id), (threshold_secs, notice_secs));
+}
+
+pub(crate) fn dormancy_notice_served(env: &Env, ip_id: u64, licensee: Address, reclaimable_at: u64) {
+    env.events().publish((symbol_short!("dorm_note"), ip_id, licensee), reclaimable_at);
+}
+
+pub(crate) fn dormant_exclusive_reclaimed(env: &Env, ip_id: u64, licensee: Address) {
+    env.events().publish((symbol_short!("dorm_rclm"), ip_id, licensee), ());
+}
+
+pub(crate) fn conflict_grouped(env: &Env, ip_id: u64, group_id: u64) {
+    env.events().publish((symbol_short!("conflict"), ip_id), group_id);
+}
+
+pub(crate) fn conflict_left(env: &Env, ip_id: u64, group_id: u64) {
+    env.events().publish((symbol_short!("cnfl_left"), ip_id), group_id);
+}
+
+pub(crate) fn ip_transferred(env: &Env, ip_id: u64, previous_owner: Address, new_owner: Address) {
+    env.events().publish((symbol_short!("ip_xfer"), ip_id), (previous_owner, new_owner));
+}
+
+pub(crate) fn claimed(env: &Env, owner: Address, token: Address, amount: i128) {
+    env.events().publish((symbol_short!("claimed"), owner), (token, amount));
+}
+
+#[cfg(feature = "keepers")]
+pub(crate) fn licenses_expired(env: &Env, ip_id: u64, keeper: Address, expired: u32, reward: i128) {
+    env.events().publish((symbol_short!("expired"), ip_id), (keeper, expired, reward));
+}
+
+#[cfg(feature = "vouchers")]
+pub(crate) fn voucher_created(env: &Env, ip_id: u64, code_hash: BytesN<32>, bps_off: u32, max_uses: u32) {
+    env.events().publish((symbol_short!("voucher"), ip_id), (code_hash, bps_off, max_uses));
+}
+
+#[cfg(feature = "vouchers")]
+pub(crate) fn voucher_redeemed(env: &Env, ip_id: u64, licensee: Address, code_hash: BytesN<32>) {
+    env.events().publish((symbol_short!("vch_used"), ip_id), (licensee, code_hash));
+}
+
+#[cfg(feature = "shares")]
+pub(crate) fn shares_issued(env: &Env, ip_id: u64, owner: Address, supply: i128) {
+    env.events().publish((symbol_short!("shares"), ip_id), (owner, supply));
+}
+
+/// SEP-41 `transfer` event with the share id inserted as topic 1
+#[cfg(feature = "shares")]
+pub(crate) fn share_transfer(env: &Env, ip_id: u64, from: Address, to: Address, amount: i128) {
+    env.events().publish((symbol_short!("transfer"), ip_id, from, to), amount);
+}
+
+/// SEP-41 `approve` event with the share id inserted as topic 1
+#[cfg(feature = "shares")]
+pub(crate) fn share_approve(env: &Env, ip_id: u64, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+    env.events().publish((symbol_short!("approve"), ip_id, from, spender), (amount, expiration_ledger));
+}
+
+#[cfg(feature = "shares")]
+pub(crate) fn share_revenue_claimed(env: &Env, ip_id: u64, holder: Address, amount: i128) {
+    env.events().publish((symbol_short!("sh_claim"), ip_id), (holder, amount));
+}
@@ -0,0 +1,115 @@
+//! Spam protection for zero-price licenses: an owner-set bond escrowed per
+//! claim and a per-address cooldown between free claims
+
+use crate::{
+    events, payments::Funding, storage, AppealStatus, FreeClaimPolicy, IPAsset, IPError, IPLicensingContract,
+    IPLicensingContractArgs, IPLicensingContractClient
+};
+use soroban_sdk::{contractimpl, token, Address, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Require a bond and/or cooldown of licensees who take the IP's licenses
+    /// at no cost, or lift the requirement with `None` (Requires IP owner
+    /// approval). Licenses bought at a positive price are unaffected.
+    pub fn set_free_claim_policy(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        policy: Option<FreeClaimPolicy>,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+        if let Some(policy) = &policy {
+            Self::require_sane_amount(policy.bond, ip.token_decimals)?;
+        }
+        storage::write_free_claim_policy(&env, ip_id, &policy);
+
+        let (bond, cooldown_secs) = policy.map_or((0, 0), |policy| (policy.bond, policy.cooldown_secs));
+        events::free_claim_policy_set(&env, ip_id, bond, cooldown_secs);
+        Ok(())
+    }
+
+    pub fn get_free_claim_policy(env: Env, ip_id: u64) -> Option<FreeClaimPolicy> {
+        storage::read_free_claim_policy(&env, ip_id)
+    }
+
+    /// Token and amount `licensee` has escrowed for a free claim on the IP
+    pub fn get_claim_bond(env: Env, ip_id: u64, licensee: Address) -> Option<(Address, i128)> {
+        storage::read_claim_bond(&env, ip_id, &licensee)
+    }
+
+    /// Settle a free claim's bond once its license has ended: back to the
+    /// licensee, or to the owner's claimable balance if the license was
+    /// revoked and the revocation stands. A bond on an IP deleted since has
+    /// no owner to go to and is refunded. Callable by anyone. Returns whether
+    /// the bond was forfeited.
+    pub fn settle_claim_bond(env: Env, ip_id: u64, licensee: Address) -> Result<bool, IPError> {
+        let (token, amount) = storage::read_claim_bond(&env, ip_id, &licensee).ok_or(IPError::BondNotHeld)?;
+        let license = storage::read_license(&env, ip_id, &licensee)?;
+
+        if license.is_active && !Self::is_expired(&env, &license) {
+            return Err(IPError::BondLocked);
+        }
+        let appeal_open = license.appeal == AppealStatus::NotFiled
+            && license.appeal_deadline.is_some_and(|deadline| env.ledger().timestamp() <= deadline);
+        if !license.is_active && (license.appeal == AppealStatus::Pending || appeal_open) {
+            return Err(IPError::BondLocked);
+        }
+
+        let revoked = !license.is_active && license.revocation_reason.is_some();
+        let owner = if revoked { storage::read_ip(&env, ip_id).ok().map(|ip| ip.owner) } else { None };
+        let forfeited = owner.is_some();
+        storage::write_claim_bond(&env, ip_id, &licensee, &None);
+        if let Some(owner) = owner {
+            let claimable = storage::read_claimable(&env, &owner, &token);
+            storage::write_claimable(&env, &owner, &token, claimable + amount);
+        } else {
+            token::Client::new(&env, &token).transfer(&env.current_contract_address(), &licensee, &amount);
+        }
+
+        events::claim_bond_settled(&env, ip_id, licensee, amount, forfeited);
+        Ok(forfeited)
+    }
+}
+
+impl IPLicensingContract {
+    /// Apply the IP's free claim policy to a zero-price license: enforce the
+    /// claimer's cooldown and escrow the bond through `funding`
+    pub(crate) fn take_free_claim(
+        env: &Env,
+        ip_id: u64,
+        ip: &IPAsset,
+        licensee: &Address,
+        funding: Funding,
+    ) -> Result<(), IPError> {
+        let Some(policy) = storage::read_free_claim_policy(env, ip_id) else {
+            return Ok(());
+        };
+
+        let now = env.ledger().timestamp();
+        if storage::read_last_free_claim(env, licensee)
+            .is_some_and(|claimed_at| now < claimed_at.saturating_add(policy.cooldown_secs))
+        {
+            return Err(IPError::CapReached);
+        }
+        storage::write_last_free_claim(env, licensee, now);
+
+        if policy.bond > 0 {
+            if storage::read_claim_bond(env, ip_id, licensee).is_some() {
+                return Err(IPError::DuplicateOperation);
+            }
+            let token_client = token::Client::new(env, &ip.payment_token);
+            let spender = env.current_contract_address();
+            match funding {
+                Funding::Direct => token_client.transfer(licensee, &spender, &policy.bond),
+                Funding::Allowance => token_client.transfer_from(&spender, licensee, &spender, &policy.bond),
+                Funding::Escrow => return Err(IPError::InsufficientBalance),
+            }
+            storage::write_claim_bond(env, ip_id, licensee, &Some((ip.payment_token.clone(), policy.bond)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+//! Token-gated licensing: IPs sold, or given away, only to holders of an
+//! external token, and exclusives only to counterparties an attestation
+//! registry has verified
+
+use crate::{
+    attestation::AttestationRegistryClient, events, payments::Funding, storage, AttestationGate, IPError,
+    IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient, License, LicenseType, TokenGate,
+    BPS_DENOMINATOR
+};
+use soroban_sdk::{contractimpl, token, Address, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Require licensees of the IP to hold `gate.min_balance` of `gate.token`
+    /// when they buy or claim a license, or lift the requirement with `None`
+    /// (Requires IP owner approval)
+    pub fn set_token_gate(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        gate: Option<TokenGate>,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        if gate.as_ref().is_some_and(|gate| gate.min_balance <= 0) {
+            return Err(IPError::InvalidAmount);
+        }
+        storage::write_token_gate(&env, ip_id, &gate);
+
+        events::token_gate_set(&env, ip_id, gate);
+        Ok(())
+    }
+
+    pub fn get_token_gate(env: Env, ip_id: u64) -> Option<TokenGate> {
+        storage::read_token_gate(&env, ip_id)
+    }
+
+    /// Sell the IP's exclusive license only to addresses attested by
+    /// `gate.registry`, or lift the requirement with `None` (Requires IP
+    /// owner approval). Other license types are unaffected.
+    pub fn set_attestation_gate(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        gate: Option<AttestationGate>,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+        storage::write_attestation_gate(&env, ip_id, &gate);
+
+        events::attestation_gate_set(&env, ip_id, gate);
+        Ok(())
+    }
+
+    pub fn get_attestation_gate(env: Env, ip_id: u64) -> Option<AttestationGate> {
+        storage::read_attestation_gate(&env, ip_id)
+    }
+
+    /// Take a license at no cost as a holder of the IP's gating token, if the
+    /// gate offers free claims. Tier availability and exclusivity apply as for
+    /// a purchase.
+    pub fn claim_license(env: Env, licensee: Address, ip_id: u64, license_type: LicenseType) -> Result<License, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        licensee.require_auth();
+
+        if !storage::read_token_gate(&env, ip_id).is_some_and(|gate| gate.free_claims) {
+            return Err(IPError::Unauthorized);
+        }
+        let discount_bps = BPS_DENOMINATOR as u32;
+        Self::execute_purchase_at_discount(&env, &licensee, ip_id, license_type, None, discount_bps, None, Funding::Direct)
+    }
+}
+
+impl IPLicensingContract {
+    /// Check `licensee` against the IP's token gate, if it has one
+    pub(crate) fn require_gate_holder(env: &Env, ip_id: u64, licensee: &Address) -> Result<(), IPError> {
+        let Some(gate) = storage::read_token_gate(env, ip_id) else {
+            return Ok(());
+        };
+        if token::Client::new(env, &gate.token).balance(licensee) < gate.min_balance {
+            return Err(IPError::GateNotMet);
+        }
+        Ok(())
+    }
+
+    /// Check an exclusive licensee's attestation against the IP's gate, if
+    /// it has one
+    pub(crate) fn require_attested(
+        env: &Env,
+        ip_id: u64,
+        licensee: &Address,
+        license_type: LicenseType,
+    ) -> Result<(), IPError> {
+        if license_type != LicenseType::Exclusive {
+            return Ok(());
+        }
+        let Some(gate) = storage::read_attestation_gate(env, ip_id) else {
+            return Ok(());
+        };
+        let attestation = AttestationRegistryClient::new(env, &gate.registry).attestation(licensee);
+        if !attestation.is_some_and(|attestation| {
+            attestation.level >= gate.min_level && env.ledger().timestamp() < attestation.expires_at
+        }) {
+            return Err(IPError::GateNotMet);
+        }
+        Ok(())
+    }
+}
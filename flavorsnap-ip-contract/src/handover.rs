@@ -0,0 +1,225 @@
+//! Two-phase handover of an exclusive license between licensees: the holder
+//! proposes, the successor escrows the agreed consideration, and the owner's
+//! approval swaps the license atomically
+
+use crate::{
+    events, storage, AppealStatus, ExtError, Handover, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient,
+    License, LicenseType
+};
+use soroban_sdk::{contractimpl, panic_with_error, token, Address, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Propose handing `holder`'s exclusive license to `to` for
+    /// `consideration` of the IP's payment token, `owner_fee` of which goes to
+    /// the IP owner on approval (Only callable by the holder). One handover
+    /// may be pending per IP; a pending one whose holder's license has since
+    /// ended is called off, refunding its successor.
+    pub fn propose_handover(
+        env: Env,
+        holder: Address,
+        ip_id: u64,
+        to: Address,
+        consideration: i128,
+        owner_fee: i128,
+    ) -> Result<(), IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        holder.require_auth();
+
+        let ip = storage::read_ip(&env, ip_id)?;
+        Self::require_not_frozen(&env, ip_id)?;
+        Self::require_handover_source(&env, ip_id, &holder)?;
+        Self::require_sane_amount(consideration, ip.token_decimals)?;
+        if owner_fee < 0 || owner_fee > consideration {
+            return Err(IPError::InvalidAmount);
+        }
+        if to == holder {
+            return Err(IPError::Unauthorized);
+        }
+        Self::require_handover_target(&env, ip_id, &to)?;
+        if let Some(pending) = storage::read_handover(&env, ip_id) {
+            if Self::require_handover_source(&env, ip_id, &pending.from).is_ok() {
+                return Err(IPError::DuplicateOperation);
+            }
+            Self::close_handover(&env, ip_id, pending);
+        }
+
+        let handover = Handover {
+            from: holder.clone(),
+            to: to.clone(),
+            token: ip.payment_token,
+            consideration,
+            owner_fee,
+            funded: false,
+        };
+        storage::write_handover(&env, ip_id, &Some(handover));
+
+        events::handover_proposed(&env, ip_id, holder, to, consideration);
+        Ok(())
+    }
+
+    pub fn get_handover(env: Env, ip_id: u64) -> Result<Handover, IPError> {
+        Ok(Self::require_handover(&env, ip_id))
+    }
+
+    /// Escrow the consideration of the handover pending on the IP with this
+    /// contract (Only callable by its successor)
+    pub fn fund_handover(env: Env, to: Address, ip_id: u64) -> Result<(), IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        to.require_auth();
+
+        let mut handover = Self::require_handover(&env, ip_id);
+        if handover.to != to {
+            return Err(IPError::Unauthorized);
+        }
+        if handover.funded {
+            return Err(IPError::DuplicateOperation);
+        }
+
+        if handover.consideration > 0 {
+            token::Client::new(&env, &handover.token).transfer(&to, &env.current_contract_address(), &handover.consideration);
+        }
+        handover.funded = true;
+        storage::write_handover(&env, ip_id, &Some(handover.clone()));
+
+        events::handover_funded(&env, ip_id, to, handover.consideration);
+        Ok(())
+    }
+
+    /// Complete the funded handover pending on the IP (Requires IP owner
+    /// approval): the holder's license ends and the successor receives a new
+    /// exclusive license with the same expiry, while the escrow pays the owner
+    /// fee and the rest to the former holder. Token and attestation gates
+    /// apply to the successor as for a purchase.
+    pub fn approve_handover(env: Env, approvers: Vec<Address>, ip_id: u64) -> Result<License, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let handover = Self::require_handover(&env, ip_id);
+        if !handover.funded {
+            return Err(IPError::InsufficientBalance);
+        }
+        let mut previous = Self::require_handover_source(&env, ip_id, &handover.from)?;
+        Self::require_handover_target(&env, ip_id, &handover.to)?;
+
+        // Swap the license in place of the slot, which stays taken throughout
+        previous.is_active = false;
+        storage::write_license(&env, &previous);
+
+        let now = env.ledger().timestamp();
+        let license_id = storage::next_license_id(&env);
+        storage::write_license_holder(&env, license_id, ip_id, &handover.to);
+        let license = License {
+            license_id,
+            licensee: handover.to.clone(),
+            ip_id,
+            license_type: LicenseType::Exclusive,
+            is_active: true,
+            issued_at: now,
+            issued_ledger: env.ledger().sequence(),
+            sequence: storage::next_issuance_sequence(&env),
+            last_activity_at: now,
+            expires_at: previous.expires_at,
+            terms_hash: previous.terms_hash,
+            price_paid: handover.consideration,
+            revocation_reason: None,
+            appeal_deadline: None,
+            appeal: AppealStatus::NotFiled,
+        };
+        storage::write_license(&env, &license);
+        storage::add_to_licensee_index(&env, &handover.to, ip_id);
+        storage::write_handover(&env, ip_id, &None);
+        if storage::read_dormancy_notice(&env, ip_id).is_some_and(|(licensee, _)| licensee == handover.from) {
+            storage::write_dormancy_notice(&env, ip_id, &None);
+        }
+
+        let token_client = token::Client::new(&env, &handover.token);
+        let this = env.current_contract_address();
+        if handover.owner_fee > 0 {
+            token_client.transfer(&this, &ip.owner, &handover.owner_fee);
+        }
+        let proceeds = handover.consideration - handover.owner_fee;
+        if proceeds > 0 {
+            token_client.transfer(&this, &handover.from, &proceeds);
+        }
+
+        events::handover_completed(&env, ip_id, handover.from, handover.to, license_id, handover.owner_fee);
+        Ok(license)
+    }
+
+    /// Call off the handover pending on the IP, refunding any escrow to the
+    /// successor (Only callable by the holder or the successor)
+    pub fn cancel_handover(env: Env, caller: Address, ip_id: u64) -> Result<(), IPError> {
+        caller.require_auth();
+        let handover = Self::require_handover(&env, ip_id);
+        if caller != handover.from && caller != handover.to {
+            return Err(IPError::Unauthorized);
+        }
+
+        Self::close_handover(&env, ip_id, handover);
+        Ok(())
+    }
+}
+
+impl IPLicensingContract {
+    /// Call off the handover, refunding any escrow to the successor
+    fn close_handover(env: &Env, ip_id: u64, handover: Handover) {
+        storage::write_handover(env, ip_id, &None);
+        if handover.funded && handover.consideration > 0 {
+            token::Client::new(env, &handover.token).transfer(
+                &env.current_contract_address(),
+                &handover.to,
+                &handover.consideration,
+            );
+        }
+
+        events::handover_cancelled(env, ip_id, handover.from, handover.to);
+    }
+
+    /// Call off the handover `holder` proposed on the IP, if any, once their
+    /// exclusive license ends
+    pub(crate) fn close_handover_from(env: &Env, ip_id: u64, holder: &Address) {
+        if let Some(handover) = storage::read_handover(env, ip_id).filter(|handover| handover.from == *holder) {
+            Self::close_handover(env, ip_id, handover);
+        }
+    }
+
+    /// The handover pending on the IP
+    fn require_handover(env: &Env, ip_id: u64) -> Handover {
+        storage::read_handover(env, ip_id).unwrap_or_else(|| panic_with_error!(env, ExtError::HandoverNotFound))
+    }
+
+    /// The active, unexpired exclusive license `holder` can hand over
+    fn require_handover_source(env: &Env, ip_id: u64, holder: &Address) -> Result<License, IPError> {
+        let license = storage::read_license(env, ip_id, holder)?;
+        if !license.is_active || license.license_type != LicenseType::Exclusive {
+            return Err(IPError::LicenseNotFound);
+        }
+        if Self::is_expired(env, &license) {
+            return Err(IPError::Expired);
+        }
+        Ok(license)
+    }
+
+    /// Checks a successor must pass to take over an exclusive license
+    fn require_handover_target(env: &Env, ip_id: u64, to: &Address) -> Result<(), IPError> {
+        if storage::is_deregistered(env, ip_id) {
+            return Err(IPError::IPDeregistered);
+        }
+        Self::require_gate_holder(env, ip_id, to)?;
+        Self::require_attested(env, ip_id, to, LicenseType::Exclusive)?;
+        if storage::read_license(env, ip_id, to).is_ok_and(|existing| existing.is_active) {
+            return Err(IPError::LicenseAlreadyExists);
+        }
+        Ok(())
+    }
+}
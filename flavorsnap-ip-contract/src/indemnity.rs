@@ -0,0 +1,116 @@
+//! Indemnity bonds: owners escrow tokens that an Arbiter can award to an
+//! exclusive licensee if the IP turns out to be infringing
+
+use crate::{
+    events, storage, BondStatus, IndemnityBond, IPError, IPLicensingContract, IPLicensingContractArgs,
+    IPLicensingContractClient, LicenseType
+};
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Escrow `amount` of the IP's payment token from the owner as indemnity
+    /// for exclusive licensees (Requires IP owner approval). The bond is
+    /// released no earlier than `challenge_period_secs` after posting or after
+    /// the latest exclusive sale. Only one bond may be held at a time.
+    pub fn post_bond(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        amount: i128,
+        challenge_period_secs: u64,
+    ) -> Result<IndemnityBond, IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        Self::require_sane_amount(amount, ip.token_decimals)?;
+        if amount == 0 {
+            return Err(IPError::InvalidAmount);
+        }
+        if storage::read_bond(&env, ip_id).is_some_and(|bond| bond.status == BondStatus::Held) {
+            return Err(IPError::DuplicateOperation);
+        }
+
+        token::Client::new(&env, &ip.payment_token).transfer(&ip.owner, &env.current_contract_address(), &amount);
+
+        let bond = IndemnityBond {
+            depositor: ip.owner,
+            token: ip.payment_token,
+            amount,
+            challenge_period_secs,
+            release_at: env.ledger().timestamp().saturating_add(challenge_period_secs),
+            status: BondStatus::Held,
+        };
+        storage::write_bond(&env, ip_id, &bond);
+
+        events::bond_posted(&env, ip_id, amount, bond.release_at);
+        Ok(bond)
+    }
+
+    /// The IP's current or most recently settled bond
+    pub fn get_bond(env: Env, ip_id: u64) -> Result<IndemnityBond, IPError> {
+        storage::read_bond(&env, ip_id).ok_or(IPError::BondNotHeld)
+    }
+
+    /// Pay the held bond to `licensee`, who must have bought an exclusive
+    /// license on the IP (Only callable by an Admin or Arbiter). Allowed while
+    /// the IP is frozen, as an infringement finding usually comes with a hold.
+    pub fn award_bond(env: Env, caller: Address, ip_id: u64, licensee: Address) -> Result<i128, IPError> {
+        caller.require_auth();
+        Self::require_hold_authority(&env, &caller)?;
+
+        let mut bond = Self::held_bond(&env, ip_id)?;
+        let license = storage::read_license(&env, ip_id, &licensee)?;
+        if license.license_type != LicenseType::Exclusive {
+            return Err(IPError::InvalidTier);
+        }
+
+        bond.status = BondStatus::Awarded;
+        storage::write_bond(&env, ip_id, &bond);
+        token::Client::new(&env, &bond.token).transfer(&env.current_contract_address(), &licensee, &bond.amount);
+
+        Self::audit(&env, symbol_short!("bond_awd"), &caller, (ip_id, licensee.clone(), bond.amount));
+        events::bond_awarded(&env, ip_id, licensee, bond.amount);
+        Ok(bond.amount)
+    }
+
+    /// Return the held bond to its depositor once the challenge period has
+    /// passed. Anyone may call this; a frozen IP keeps its bond in escrow.
+    pub fn release_bond(env: Env, ip_id: u64) -> Result<i128, IPError> {
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let mut bond = Self::held_bond(&env, ip_id)?;
+        if env.ledger().timestamp() < bond.release_at {
+            return Err(IPError::BondLocked);
+        }
+
+        bond.status = BondStatus::Released;
+        storage::write_bond(&env, ip_id, &bond);
+        token::Client::new(&env, &bond.token).transfer(&env.current_contract_address(), &bond.depositor, &bond.amount);
+
+        events::bond_released(&env, ip_id, bond.depositor, bond.amount);
+        Ok(bond.amount)
+    }
+}
+
+impl IPLicensingContract {
+    fn held_bond(env: &Env, ip_id: u64) -> Result<IndemnityBond, IPError> {
+        storage::read_bond(env, ip_id)
+            .filter(|bond| bond.status == BondStatus::Held)
+            .ok_or(IPError::BondNotHeld)
+    }
+
+    /// Keep a held bond in escrow for a full challenge period after an exclusive sale
+    pub(crate) fn extend_bond_for_sale(env: &Env, ip_id: u64) {
+        let Ok(mut bond) = Self::held_bond(env, ip_id) else {
+            return;
+        };
+        let release_at = env.ledger().timestamp().saturating_add(bond.challenge_period_secs);
+        if release_at > bond.release_at {
+            bond.release_at = release_at;
+            storage::write_bond(env, ip_id, &bond);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Keeper-incentivised sweeping of expired licenses (`keepers` feature)
+
+use crate::{
+    config, events, storage, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient,
+    KeeperReward, Role, BPS_DENOMINATOR
+};
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Configure the keeper incentive for `expire_licenses` (Only callable by a FeeManager)
+    pub fn set_keeper_reward(env: Env, fee_manager: Address, reward: KeeperReward) -> Result<(), IPError> {
+        fee_manager.require_auth();
+        Self::require_role(&env, Role::FeeManager, &fee_manager)?;
+
+        if reward.fee_share_bps as i128 > BPS_DENOMINATOR || reward.reward_per_license < 0 {
+            return Err(IPError::InvalidFee);
+        }
+
+        storage::write_keeper_reward(&env, &reward);
+        Self::audit(&env, symbol_short!("keeper"), &fee_manager, (reward.fee_share_bps, reward.reward_per_license));
+        Ok(())
+    }
+
+    pub fn get_keeper_reward(env: Env) -> Option<KeeperReward> {
+        storage::read_keeper_reward(&env)
+    }
+
+    /// Fees retained for keeper rewards in `token`
+    pub fn get_keeper_pool(env: Env, token: Address) -> i128 {
+        storage::read_keeper_pool(&env, &token)
+    }
+
+    /// Deactivate the expired licenses of `licensees` on `ip_id`, at most
+    /// `max_batch_size` per call. Callable by anyone; `keeper` is paid the
+    /// configured reward for each license swept, as far as the keeper pool for
+    /// the IP's token allows.
+    /// Returns the number of licenses expired.
+    pub fn expire_licenses(env: Env, keeper: Address, ip_id: u64, licensees: Vec<Address>) -> Result<u32, IPError> {
+        keeper.require_auth();
+
+        if licensees.len() > config::max_batch_size(&env) {
+            return Err(IPError::CapReached);
+        }
+
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        let mut expired: u32 = 0;
+        for licensee in licensees.iter() {
+            let Ok(license) = storage::read_license(&env, ip_id, &licensee) else {
+                continue;
+            };
+            if license.is_active && Self::is_expired(&env, &license) {
+                Self::deactivate_license(&env, ip_id, &mut ip, &licensee, None)?;
+                expired += 1;
+            }
+        }
+        if expired == 0 {
+            return Ok(0);
+        }
+        storage::write_ip(&env, ip_id, &ip);
+
+        let reward_per_license = storage::read_keeper_reward(&env).map_or(0, |reward| reward.reward_per_license);
+        let pool = storage::read_keeper_pool(&env, &ip.payment_token);
+        let reward = (reward_per_license * expired as i128).min(pool);
+        if reward > 0 {
+            storage::write_keeper_pool(&env, &ip.payment_token, pool - reward);
+            token::Client::new(&env, &ip.payment_token).transfer(
+                &env.current_contract_address(),
+                &keeper,
+                &reward,
+            );
+        }
+
+        events::licenses_expired(&env, ip_id, keeper, expired, reward);
+
+        Ok(expired)
+    }
+}
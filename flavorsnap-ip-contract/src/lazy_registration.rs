@@ -0,0 +1,53 @@
+//! Register-on-first-purchase listings authorized off-chain by the creator
+
+use crate::{
+    payments::Funding, storage, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient,
+    License, LicenseType, RegistrationVoucher
+};
+use soroban_sdk::{contractimpl, Address, BytesN, Env, IntoVal};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Register the IP described by `voucher` on behalf of `creator` and sell
+    /// `licensee` the first license on it. `creator` authorizes exactly this
+    /// voucher through a signed authorization entry that the buyer submits, so
+    /// listing costs the creator nothing until a sale. Returns the license sold;
+    /// later buyers use `purchase_license`.
+    pub fn purchase_with_voucher(
+        env: Env,
+        licensee: Address,
+        creator: Address,
+        voucher: RegistrationVoucher,
+        license_type: LicenseType,
+        max_payment: Option<i128>,
+        deadline: u64,
+        idempotency_key: Option<BytesN<32>>,
+    ) -> Result<License, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        Self::require_before(&env, deadline)?;
+        licensee.require_auth();
+        creator.require_auth_for_args((voucher.clone(),).into_val(&env));
+        Self::claim_idempotency_key(&env, &licensee, idempotency_key)?;
+
+        let ip_id = voucher.ip_id;
+        let mut ip = Self::register(
+            &env,
+            creator,
+            ip_id,
+            voucher.metadata_uri,
+            voucher.metadata_hash,
+            voucher.price_exclusive,
+            voucher.price_non_exclusive,
+            voucher.payment_token,
+            voucher.tags,
+        )?;
+        if voucher.license_duration.is_some() {
+            ip.license_duration = voucher.license_duration;
+            storage::write_ip(&env, ip_id, &ip);
+        }
+
+        Self::execute_purchase(&env, &licensee, ip_id, license_type, None, max_payment, Funding::Direct)
+    }
+}
@@ -1,214 +1,669 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
+
+use soroban_sdk::{contract, contracttype, Address, BytesN, Map, String, Symbol, Vec};
+
+mod admin;
+mod anchors;
+mod assets;
+mod attachments;
+mod attestation;
+mod auctions;
+mod calendar;
+mod config;
+mod conflict_groups;
+mod dashboard;
+mod dormancy;
+mod errors;
+mod escape_hatch;
+mod events;
+mod free_licenses;
+mod gating;
+mod handover;
+mod indemnity;
+#[cfg(feature = "keepers")]
+mod keepers;
+mod lazy_registration;
+mod licensing;
+mod meta;
+mod migration;
+mod offers;
+mod options;
+mod payments;
+mod price_decay;
+mod price_oracle;
+mod previews;
+mod provenance;
+mod quotes;
+mod revenue_vault;
+mod royalty_schedule;
+mod royalty_swaps;
+#[cfg(feature = "search")]
+mod search;
+mod session_keys;
+#[cfg(feature = "shares")]
+mod shares;
+mod statements;
+mod storage;
+mod swap_router;
+mod templates;
+mod terms_library;
+mod tiers;
+#[cfg(feature = "vouchers")]
+mod vouchers;
+
+pub use errors::{ExtError, IPError};
+pub use flavorsnap_common::{AppealStatus, License, LicenseType, MerkleAnchor, Rounding};
+pub use flavorsnap_rbac::{audit::AuditEntry, Role};
+
+pub(crate) use flavorsnap_common::BPS_DENOMINATOR;
 
-use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, token, Address, Env, String
-};
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IPAsset {
+    pub owner: Address,
+    pub metadata_uri: String,
+    pub metadata_hash: Option<BytesN<32>>,
+    pub price_exclusive: i128,
+    pub price_non_exclusive: i128,
+    /// List prices of the usage tiers on offer; tiers without a price are not sold
+    pub tier_prices: Map<LicenseType, i128>,
+    pub payment_token: Address,
+    /// `decimals()` of `payment_token`, read at registration
+    pub token_decimals: u32,
+    pub has_exclusive: bool,
+    pub has_broadcast: bool,
+    pub active_licenses: u32,
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+    pub downgrade_refund_bps: u32,
+    pub license_duration: Option<u64>,
+    pub appeal_window_secs: Option<u64>,
+    pub referral_bps: u32,
+    pub tags: Vec<Symbol>,
+    pub reference_priced: bool,
+    pub push_payouts: bool,
+    /// Receives owner proceeds in place of the owner when set
+    pub revenue_recipient: Option<Address>,
+    /// `revenue_recipient` is a creator vault credited through its `deposit`
+    pub revenue_to_vault: bool,
+    /// Hash of an off-chain ownership proof, such as a message signed by a
+    /// web domain key or by a Stellar account with a matching home_domain
+    pub proof_hash: Option<BytesN<32>>,
+    /// An Attestor checked `proof_hash` against the claimed origin
+    pub verified_origin: bool,
+    /// Id of the terms library template the IP is licensed under
+    pub terms_template: Option<u32>,
+    /// How fee, referral, refund and royalty shares of this IP's payments
+    /// round to whole token units
+    pub rounding: Rounding,
+}
 
-#[contracterror]
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
-#[repr(u32)]
-pub enum IPError {
-    Unauthorized = 1,
-    IPAlreadyRegistered = 2,
-    IPNotFound = 3,
-    ExclusiveAlreadyIssued = 4,
-    ActiveLicensesExist = 5,
-    LicenseNotFound = 6,
-    LicenseAlreadyExists = 7,
+/// How long an exclusive licensee may go without paying royalties before the
+/// owner can serve notice, and how long that notice runs before the slot can
+/// be reclaimed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DormancyPolicy {
+    pub threshold_secs: u64,
+    pub notice_secs: u64,
 }
 
+/// Automatic markdown of an unsold exclusive slot: the exclusive price drops
+/// by `step_bps` every `interval_secs` the slot stays free, down to `floor`
 #[contracttype]
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum LicenseType {
-    Exclusive,
-    NonExclusive,
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceDecay {
+    pub step_bps: u32,
+    pub interval_secs: u64,
+    pub floor: i128,
+    /// Start of the current unsold period, reset by repricing and by the
+    /// exclusive slot being taken or freed
+    pub unsold_since: u64,
 }
 
+/// Restricts licenses of an IP to holders of at least `min_balance` of an
+/// external token, such as a creator's community token or an NFT collection
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct IPAsset {
+pub struct TokenGate {
+    pub token: Address,
+    pub min_balance: i128,
+    /// Holders may `claim_license` without paying
+    pub free_claims: bool,
+}
+
+/// Exclusive licenses of an IP go only to addresses `registry` holds an
+/// unexpired attestation of at least `min_level` for
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AttestationGate {
+    pub registry: Address,
+    pub min_level: u32,
+}
+
+/// A named document attached to an IP after registration, such as a model
+/// release or a high-resolution preview, bound to its content hash
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attachment {
+    pub name: Symbol,
+    pub uri: String,
+    pub hash: BytesN<32>,
+    pub added_at: u64,
+}
+
+/// Half-open span of ledger time `[starts_at, ends_at)`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimeWindow {
+    pub starts_at: u64,
+    pub ends_at: u64,
+}
+
+/// When an IP's licenses are on sale: within one of `availability`, or at any
+/// time if it is empty, and never within one of `blackouts`. Usage of licenses
+/// already issued is reported as usual throughout.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LicensingCalendar {
+    pub availability: Vec<TimeWindow>,
+    pub blackouts: Vec<TimeWindow>,
+}
+
+/// Abuse controls on licenses acquired at no cost: each claim escrows `bond`
+/// of the IP's payment token until the license ends, and an address must wait
+/// `cooldown_secs` after any free claim on this contract before the next one
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FreeClaimPolicy {
+    pub bond: i128,
+    pub cooldown_secs: u64,
+}
+
+/// Outcome of a purchase that would succeed now, from `can_purchase`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PurchaseQuote {
+    pub price: i128,
+    pub payment_token: Address,
+    pub token_decimals: u32,
+    /// Part of `price` taken as the platform fee
+    pub platform_fee: i128,
+    pub balance: i128,
+    /// What the licensee has approved this contract to spend, for
+    /// `purchase_with_allowance`
+    pub allowance: i128,
+}
+
+/// A buyer's escrowed bid for a license, open until accepted, withdrawn or
+/// released after `expires_at`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Offer {
+    pub offer_id: u64,
+    pub ip_id: u64,
+    pub offerer: Address,
+    /// IP owner when the offer was made, whose inbox lists it
     pub owner: Address,
+    pub license_type: LicenseType,
+    pub token: Address,
+    pub amount: i128,
+    pub expires_at: u64,
+}
+
+/// A proposed handover of an exclusive license from its holder to `to`, who
+/// escrows `consideration` of `token` before the owner approves the swap.
+/// `owner_fee` of the consideration goes to the IP owner, the rest to `from`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Handover {
+    pub from: Address,
+    pub to: Address,
+    pub token: Address,
+    pub consideration: i128,
+    pub owner_fee: i128,
+    pub funded: bool,
+}
+
+/// Where an indemnity bond stands
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BondStatus {
+    Held,
+    /// Paid out to an exclusive licensee after the IP was found infringing
+    Awarded,
+    /// Returned to the depositor once the challenge period passed
+    Released,
+    /// Taken back by the depositor after the escrow hard timeout
+    Reclaimed,
+}
+
+/// An escrow whose release waits on someone other than its depositor, named
+/// for `reclaim_stuck_escrow`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StuckEscrow {
+    /// The indemnity bond held on an IP
+    IndemnityBond(u64),
+    /// The caller's free claim bond on an IP
+    ClaimBond(u64),
+    /// The caller's leading bid in a sealed-bid auction that was never settled
+    SealedBid(u64),
+}
+
+/// Tokens an owner escrows against infringement claims by exclusive licensees.
+/// Every exclusive sale pushes `release_at` out to a full challenge period.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IndemnityBond {
+    pub depositor: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub challenge_period_secs: u64,
+    pub release_at: u64,
+    pub status: BondStatus,
+}
+
+/// Contract parameters. `version` is the schema version, bumped whenever
+/// fields are added so a later build can migrate the stored entry.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Config {
+    pub version: u32,
+    pub admin: Address,
+    pub treasury: Address,
+    pub fee_bps: u32,
+    /// Most items per paginated view or batch call
+    pub max_batch_size: u32,
+    /// Ledgers an idempotency key is remembered for
+    pub idempotency_ttl_ledgers: u32,
+    /// How long after deregistration an IP may be restored
+    pub restore_window_secs: u64,
+}
+
+/// An IP asset with its id and hold status, as exported for state migration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IPRecord {
+    pub ip_id: u64,
+    pub asset: IPAsset,
+    pub deregistered_at: Option<u64>,
+    pub freeze_reason: Option<String>,
+}
+
+/// Listing terms a creator authorizes off-chain; the IP is registered with
+/// them when the first buyer calls `purchase_with_voucher`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegistrationVoucher {
+    pub ip_id: u64,
     pub metadata_uri: String,
+    pub metadata_hash: Option<BytesN<32>>,
     pub price_exclusive: i128,
     pub price_non_exclusive: i128,
     pub payment_token: Address,
-    pub has_exclusive: bool,
+    pub tags: Vec<Symbol>,
+    pub license_duration: Option<u64>,
+}
+
+/// SEP-40 price feed converting reference-unit prices into payment tokens
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceOracleConfig {
+    pub oracle: Address,
+    pub max_age_secs: u64,
+}
+
+/// Public profile of a creator. Editing the profile clears `verified`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreatorProfile {
+    pub display_name_hash: BytesN<32>,
+    pub profile_uri: String,
+    pub verified: bool,
+    pub updated_at: u64,
+}
+
+/// An IP asset together with its owner's profile badge and hold status
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IPReport {
+    pub asset: IPAsset,
+    pub creator_profile_uri: Option<String>,
+    pub creator_verified: bool,
+    pub frozen: bool,
+    pub deregistered: bool,
+}
+
+/// Cumulative sales attributed to a referrer across all IPs
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReferrerStats {
+    pub sales: u32,
+    pub earned: i128,
+}
+
+/// Cumulative sales of one IP, kept for dashboards that should not replay events
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IPStats {
+    pub licenses_sold: u32,
+    /// Gross license revenue, before fees, referral shares and refunds
+    pub total_revenue: i128,
+    pub total_royalties: i128,
+    pub last_sale_at: Option<u64>,
+}
+
+/// What an owner charges to hold their IP's exclusive slot for a buyer, and
+/// the price the hold locks in
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OptionTerms {
+    /// Non-refundable, paid to the owner when the option is taken
+    pub fee: i128,
+    /// What the holder pays for the exclusive license on exercise
+    pub strike_price: i128,
+    pub window_secs: u64,
+}
+
+/// A key the licensee lets pay royalties on one license from their token
+/// allowance without signing each payment: at most `max_per_call` a payment
+/// and `max_total` overall, until `expires_at`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionKey {
+    pub key: Address,
+    pub max_per_call: i128,
+    pub max_total: i128,
+    /// Paid through the key so far
+    pub spent: i128,
+    pub expires_at: u64,
+}
+
+/// What an owner charges for a short-lived grant to view an IP's
+/// watermarked previews
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PreviewTerms {
+    /// Paid to the owner like license revenue
+    pub fee: i128,
+    pub duration_secs: u64,
+}
+
+/// A paid hold on an IP's exclusive slot: until `expires_at` only `holder`
+/// can license the IP, and only exclusively
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExclusiveOption {
+    pub holder: Address,
+    pub strike_price: i128,
+    pub fee_paid: i128,
+    pub expires_at: u64,
+}
+
+/// A sealed-bid auction of an IP's exclusive license. Bidders commit to a
+/// hashed bid with `deposit` until `commit_ends_at`, reveal it until
+/// `reveal_ends_at`, and the highest revealed bid at or above
+/// `reserve_price` wins, paying the higher of the runner-up bid and the
+/// reserve.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SealedAuction {
+    pub auction_id: u64,
+    pub ip_id: u64,
+    pub seller: Address,
+    pub token: Address,
+    pub reserve_price: i128,
+    pub deposit: i128,
+    pub commit_ends_at: u64,
+    pub reveal_ends_at: u64,
+    /// Highest valid bidder revealed so far
+    pub leader: Option<Address>,
+    pub highest_bid: i128,
+    pub second_bid: i128,
+    pub settled: bool,
+}
+
+/// A bidder's committed bid and the funds escrowed behind it: the deposit,
+/// topped up to the bid itself on reveal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SealedBid {
+    pub commitment: BytesN<32>,
+    pub escrowed: i128,
+    pub revealed: bool,
+}
+
+/// An owner's home screen in one call, aggregated over the IPs they own
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnerDashboard {
+    pub ip_count: u32,
     pub active_licenses: u32,
+    /// Claimable balance in each payment token the owner's IPs sell in
+    pub unclaimed: Map<Address, i128>,
+    /// Unexpired offers awaiting the owner
+    pub open_offers: u32,
+    /// Revocation appeals pending and royalty reports the owner has flagged
+    pub open_disputes: u32,
 }
 
+/// Whether the IP owner has disputed a royalty report
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ReportStatus {
+    Filed,
+    Disputed,
+}
+
+/// One usage royalty payment, with the hash of the licensee's detailed
+/// off-chain usage report as evidence for later underreporting claims
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct License {
+pub struct RoyaltyReport {
+    pub report_id: u32,
     pub licensee: Address,
+    pub amount: i128,
+    pub report_hash: BytesN<32>,
+    pub paid_at: u64,
+    pub status: ReportStatus,
+    pub dispute_reason: Option<Symbol>,
+}
+
+/// One rate of a royalty schedule, applying from `threshold` onwards
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoyaltyStep {
+    pub threshold: i128,
+    pub bps: u32,
+}
+
+/// Royalty rates that step up over a license's life. With `by_volume` the
+/// thresholds are cumulative reported units and each report pays per bracket
+/// ("5% of the first 10k units, 7% after"); otherwise they are seconds since
+/// the license was issued and a report pays the rate current at reporting.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoyaltySchedule {
+    pub by_volume: bool,
+    pub steps: Vec<RoyaltyStep>,
+}
+
+/// Royalties an IP received over a period, per paying licensee
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoyaltyStatement {
     pub ip_id: u64,
-    pub license_type: LicenseType,
-    pub is_active: bool,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub total: i128,
+    pub by_licensee: Map<Address, i128>,
+}
+
+/// Promotional discount redeemable by presenting the preimage of its code hash
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Voucher {
+    pub bps_off: u32,
+    pub uses_left: u32,
+    pub expires_at: u64,
+}
+
+/// Fractional revenue shares of an IP. Once issued, the owner's proceeds from
+/// the IP accrue to share holders pro rata instead of being paid to the owner.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShareToken {
+    pub payment_token: Address,
+    pub total_supply: i128,
+    /// Cumulative revenue per share, scaled by 10^12
+    pub revenue_per_share: i128,
+}
+
+/// A holder's share balance and revenue not yet claimed
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ShareHolding {
+    pub balance: i128,
+    pub revenue_snapshot: i128,
+    pub unclaimed: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShareAllowance {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+/// Incentive for sweeping expired licenses. `fee_share_bps` of every platform
+/// fee is retained in a per-token pool that pays `reward_per_license` to keepers.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperReward {
+    pub fee_share_bps: u32,
+    pub reward_per_license: i128,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
+    Config,
+    Paused,
     IP(u64),
     License(u64, Address),
+    Tombstone(u64),
+    Frozen(u64),
+    /// Ids of the IPs currently under legal hold
+    FrozenIps,
+    Idempotency(Address, BytesN<32>),
+    PurchaseAgent(Address, Address),
+    LicenseCount,
+    LicenseById(u64),
+    /// Chunked list of the IPs a licensee has ever held a license on
+    LicenseeIndex(Address),
+    /// Set once an IP is in its licensee's `LicenseeIndex`
+    LicenseeIndexed(Address, u64),
+    KeeperReward,
+    KeeperPool(Address),
+    ReferrerStats(Address),
+    IPStats(u64),
+    RoyaltyReportCount(u64),
+    RoyaltyReport(u64, u32),
+    /// Royalties of (ip_id, day number) per licensee, for statements
+    RoyaltyDay(u64, u64),
+    RoyaltySchedule(u64, Address),
+    UsageUnits(u64, Address),
+    Bond(u64),
+    ConflictGroupCount,
+    /// Member IP ids of a conflict group
+    ConflictGroup(u64),
+    /// Conflict group an IP belongs to
+    ConflictGroupOf(u64),
+    OfferCount,
+    Offer(u64),
+    /// Ids of the open offers in an owner's inbox
+    OwnerOffers(Address),
+    PriceDecay(u64),
+    /// Exclusive license handover pending on an IP
+    Handover(u64),
+    Dormancy(u64),
+    /// (licensee, served_at) of the dormancy notice open on an IP
+    DormancyNotice(u64),
+    TokenGate(u64),
+    FreeClaimPolicy(u64),
+    /// (token, amount) escrowed by a licensee's free claim on an IP
+    ClaimBond(u64, Address),
+    /// Time of an address's latest free claim on any IP
+    LastFreeClaim(Address),
+    Voucher(u64, BytesN<32>),
+    /// Chunked list of the ids of IPs registered with a tag
+    TagIndex(Symbol),
+    CreatorProfile(Address),
+    /// Chunked list of IP ids in first-registration order
+    IPIndex,
+    IPIndexed(u64),
+    ImportFinalized,
+    ShareToken(u64),
+    ShareHolding(u64, Address),
+    ShareAllowance(u64, Address, Address),
+    PriceOracle,
+    TermsLibrary,
+    Claimable(Address, Address),
+    Operator(Address, Address),
+}
+
+/// Storage keys beyond the 50 cases a contract enum can hold
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExtKey {
+    /// Ids of the IPs an address currently owns
+    OwnerIps(Address),
+    /// Pending appeals plus flagged royalty reports of an IP
+    OpenDisputes(u64),
+    /// Sequence number of the latest license issued
+    IssuanceSequence,
+    OptionTerms(u64),
+    /// Latest option taken on an IP's exclusive slot
+    ExclusiveOption(u64),
+    AttestationGate(u64),
+    AuctionCount,
+    Auction(u64),
+    /// Id of the auction open on an IP's exclusive slot
+    OpenAuction(u64),
+    SealedBid(u64, Address),
+    LicensingCalendar(u64),
+    SwapRouter,
+    /// Attachments of an IP in the order they were added
+    Attachments(u64),
+    PreviewTerms(u64),
+    /// Expiry of a viewer's preview grant, kept in temporary storage
+    PreviewGrant(u64, Address),
+    /// Session key of a licensee's license on an IP
+    SessionKey(u64, Address),
+    /// Position of a frozen IP in the frozen index
+    FrozenPosition(u64),
+    /// Position of an IP in its owner's `OwnerIps`
+    OwnerIpPosition(u64),
+    /// Number of open offers escrowed against an IP
+    OpenOffers(u64),
+    /// Set once an IP has state keyed by a party or code (vouchers, preview
+    /// grants, shares, imported licenses) that deleting it could not clear
+    Undeletable(u64),
 }
 
+/// Owners, signers and licensees are plain addresses checked with
+/// `require_auth`, so contract accounts such as multisig wallets and DAOs can
+/// hold any role. Every payment a party makes is one transfer of the quoted
+/// amount into this contract, which is all a contract account has to
+/// pre-authorize when it invokes an entrypoint itself.
 #[contract]
 pub struct IPLicensingContract;
 
-#[contractimpl]
-impl IPLicensingContract {
-    /// Register a new IP asset
-    pub fn register_ip(
-        env: Env,
-        owner: Address,
-        ip_id: u64,
-        metadata_uri: String,
-        price_exclusive: i128,
-        price_non_exclusive: i128,
-        payment_token: Address,
-    ) -> Result<(), IPError> {
-        owner.require_auth();
-
-        let key = DataKey::IP(ip_id);
-        if env.storage().persistent().has(&key) {
-            return Err(IPError::IPAlreadyRegistered);
-        }
-
-        let asset = IPAsset {
-            owner,
-            metadata_uri,
-            price_exclusive,
-            price_non_exclusive,
-            payment_token,
-            has_exclusive: false,
-            active_licenses: 0,
-        };
-
-        env.storage().persistent().set(&key, &asset);
-        Ok(())
-    }
-
-    /// Purchase a license
-    pub fn purchase_license(
-        env: Env,
-        licensee: Address,
-        ip_id: u64,
-        license_type: LicenseType,
-    ) -> Result<(), IPError> {
-        licensee.require_auth();
-
-        let ip_key = DataKey::IP(ip_id);
-        let mut ip: IPAsset = env.storage().persistent().get(&ip_key).ok_or(IPError::IPNotFound)?;
-
-        // Validation: Exclusive logic
-        if ip.has_exclusive {
-            return Err(IPError::ExclusiveAlreadyIssued);
-        }
-        if license_type == LicenseType::Exclusive && ip.active_licenses > 0 {
-            return Err(IPError::ActiveLicensesExist);
-        }
-
-        let license_key = DataKey::License(ip_id, licensee.clone());
-        if env.storage().persistent().has(&license_key) {
-            let existing_license: License = env.storage().persistent().get(&license_key).unwrap();
-            if existing_license.is_active {
-                return Err(IPError::LicenseAlreadyExists);
-            }
-        }
-
-        // Determine price
-        let price = match license_type {
-            LicenseType::Exclusive => ip.price_exclusive,
-            LicenseType::NonExclusive => ip.price_non_exclusive,
-        };
-
-        // Execute Payment
-        let token_client = token::Client::new(&env, &ip.payment_token);
-        token_client.transfer(&licensee, &ip.owner, &price);
-
-        // Update State
-        if license_type == LicenseType::Exclusive {
-            ip.has_exclusive = true;
-        } else {
-            ip.active_licenses += 1;
-        }
-        
-        env.storage().persistent().set(&ip_key, &ip);
-
-        let new_license = License {
-            licensee: licensee.clone(),
-            ip_id,
-            license_type,
-            is_active: true,
-        };
-        env.storage().persistent().set(&license_key, &new_license);
-
-        Ok(())
-    }
-
-    /// Pay usage-based royalties
-    pub fn pay_usage_royalty(
-        env: Env,
-        licensee: Address,
-        ip_id: u64,
-        amount: i128,
-    ) -> Result<(), IPError> {
-        licensee.require_auth();
-        
-        let ip_key = DataKey::IP(ip_id);
-        let ip: IPAsset = env.storage().persistent().get(&ip_key).ok_or(IPError::IPNotFound)?;
-
-        let license_key = DataKey::License(ip_id, licensee.clone());
-        let license: License = env.storage().persistent().get(&license_key).ok_or(IPError::LicenseNotFound)?;
-
-        if !license.is_active {
-            return Err(IPError::LicenseNotFound);
-        }
-
-        let token_client = token::Client::new(&env, &ip.payment_token);
-        token_client.transfer(&licensee, &ip.owner, &amount);
-
-        Ok(())
-    }
-
-    /// Revoke an active license (Only callable by IP owner)
-    pub fn revoke_license(
-        env: Env,
-        owner: Address,
-        licensee: Address,
-        ip_id: u64,
-    ) -> Result<(), IPError> {
-        owner.require_auth();
-
-        let ip_key = DataKey::IP(ip_id);
-        let mut ip: IPAsset = env.storage().persistent().get(&ip_key).ok_or(IPError::IPNotFound)?;
-
-        if ip.owner != owner {
-            return Err(IPError::Unauthorized);
-        }
-
-        let license_key = DataKey::License(ip_id, licensee.clone());
-        let mut license: License = env.storage().persistent().get(&license_key).ok_or(IPError::LicenseNotFound)?;
-
-        if !license.is_active {
-            return Err(IPError::LicenseNotFound);
-        }
-
-        // Revoke license
-        license.is_active = false;
-        env.storage().persistent().set(&license_key, &license);
-
-        // Update IP tracking
-        if license.license_type == LicenseType::Exclusive {
-            ip.has_exclusive = false;
-        } else {
-            ip.active_licenses -= 1;
-        }
-        env.storage().persistent().set(&ip_key, &ip);
-
-        Ok(())
-    }
-}
-
 #[cfg(test)]
-mod test;
\ No newline at end of file
+mod scenarios;
+#[cfg(test)]
+mod test;
+#[cfg(test)]
+mod test_budget;
@@ -0,0 +1,565 @@
+//! License purchase, lookup, revocation, appeals and downgrade
+
+use crate::{
+    config, events, storage, payments::Funding, AppealStatus, IPAsset, IPError, IPLicensingContract,
+    IPLicensingContractArgs, IPLicensingContractClient, License, LicenseType, Role
+};
+use flavorsnap_common::{bps_of, PageCursor, Rounding};
+use flavorsnap_rbac as rbac;
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Bytes, BytesN, Env, String, Symbol, Vec};
+
+const RECEIPT_DOMAIN: &[u8] = b"flavorsnap-license-receipt-v1";
+
+/// What a purchase is charged
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Pricing {
+    /// The list price less a discount in bps
+    Discounted(u32),
+    /// A price agreed and escrowed beforehand, which leaves the list price
+    /// and its oracle out of the sale
+    Fixed(i128),
+}
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Purchase a license, crediting `referrer` with the IP's referral share if set,
+    /// and return it.
+    /// Fails with `SlippageExceeded` if the price, converted at execution time for
+    /// reference-priced IPs, exceeds `max_payment`, and with `DeadlineExceeded`
+    /// once the ledger time passes `deadline`. A retried submission carrying
+    /// the same `idempotency_key` is rejected with `DuplicateOperation`.
+    /// A `memo`, such as an invoice or PO number, is published with the
+    /// purchase event but not stored.
+    pub fn purchase_license(
+        env: Env,
+        licensee: Address,
+        ip_id: u64,
+        license_type: LicenseType,
+        referrer: Option<Address>,
+        max_payment: Option<i128>,
+        deadline: u64,
+        idempotency_key: Option<BytesN<32>>,
+        memo: Option<String>,
+    ) -> Result<License, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        Self::require_before(&env, deadline)?;
+        Self::require_valid_memo(&memo)?;
+        licensee.require_auth();
+        Self::claim_idempotency_key(&env, &licensee, idempotency_key)?;
+
+        let license = Self::execute_purchase(&env, &licensee, ip_id, license_type, referrer, max_payment, Funding::Direct)?;
+        Self::publish_memo(&env, ip_id, &licensee, symbol_short!("purchase"), license.license_id, memo);
+        Ok(license)
+    }
+
+    /// Approve or remove an agent allowed to buy licenses for `licensee` out
+    /// of the token allowance the licensee granted to this contract
+    pub fn set_purchase_agent(env: Env, licensee: Address, agent: Address, approved: bool) {
+        licensee.require_auth();
+
+        storage::set_purchase_agent(&env, &licensee, &agent, approved);
+        events::purchase_agent_set(&env, licensee, agent, approved);
+    }
+
+    pub fn is_purchase_agent(env: Env, licensee: Address, agent: Address) -> bool {
+        storage::is_purchase_agent(&env, &licensee, &agent)
+    }
+
+    /// Authorize `operator` to pay usage royalties on the licensee's behalf
+    pub fn add_operator(env: Env, licensee: Address, operator: Address) {
+        licensee.require_auth();
+
+        storage::set_operator(&env, &licensee, &operator, true);
+        events::operator_set(&env, licensee, operator, true);
+    }
+
+    pub fn remove_operator(env: Env, licensee: Address, operator: Address) {
+        licensee.require_auth();
+
+        storage::set_operator(&env, &licensee, &operator, false);
+        events::operator_set(&env, licensee, operator, false);
+    }
+
+    pub fn is_operator(env: Env, licensee: Address, operator: Address) -> bool {
+        storage::is_operator(&env, &licensee, &operator)
+    }
+
+    /// Purchase a license for `licensee`, submitted by one of their purchase agents.
+    /// Payment is pulled with `transfer_from` against the allowance the licensee
+    /// approved for this contract, so the licensee does not sign this invocation.
+    pub fn purchase_with_allowance(
+        env: Env,
+        agent: Address,
+        licensee: Address,
+        ip_id: u64,
+        license_type: LicenseType,
+        referrer: Option<Address>,
+        deadline: u64,
+        idempotency_key: Option<BytesN<32>>,
+        memo: Option<String>,
+    ) -> Result<License, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        Self::require_before(&env, deadline)?;
+        Self::require_valid_memo(&memo)?;
+        agent.require_auth();
+        if !storage::is_purchase_agent(&env, &licensee, &agent) {
+            return Err(IPError::Unauthorized);
+        }
+        Self::claim_idempotency_key(&env, &licensee, idempotency_key)?;
+
+        let license = Self::execute_purchase(&env, &licensee, ip_id, license_type, referrer, None, Funding::Allowance)?;
+        Self::publish_memo(&env, ip_id, &licensee, symbol_short!("purchase"), license.license_id, memo);
+        Ok(license)
+    }
+
+    pub fn get_license(env: Env, ip_id: u64, licensee: Address) -> Result<License, IPError> {
+        storage::read_license(&env, ip_id, &licensee)
+    }
+
+    /// `get_license` as an `Option`, for cross-contract license checks
+    pub fn lookup_license(env: Env, ip_id: u64, licensee: Address) -> Option<License> {
+        storage::read_license(&env, ip_id, &licensee).ok()
+    }
+
+    /// Look up a license by its certificate number. Certificates superseded by
+    /// a later purchase of the same (ip_id, licensee) pair are not found.
+    pub fn get_license_by_id(env: Env, license_id: u64) -> Result<License, IPError> {
+        let (ip_id, licensee) = storage::read_license_holder(&env, license_id).ok_or(IPError::LicenseNotFound)?;
+
+        let license = storage::read_license(&env, ip_id, &licensee)?;
+        if license.license_id != license_id {
+            return Err(IPError::LicenseNotFound);
+        }
+        Ok(license)
+    }
+
+    pub fn lookup_license_by_id(env: Env, license_id: u64) -> Option<License> {
+        Self::get_license_by_id(env, license_id).ok()
+    }
+
+    /// Deterministic receipt hash of a license, recomputable off-chain as
+    ///
+    /// ```text
+    /// sha256(
+    ///     "flavorsnap-license-receipt-v1"
+    ///     || license_id            u64, big-endian
+    ///     || license_type          u8: 0 = Exclusive, 1 = NonExclusive, 2 = Personal,
+    ///                                  3 = Editorial, 4 = Commercial, 5 = Broadcast
+    ///     || issued_at             u64, big-endian
+    ///     || expires_at            u8 0 if perpetual, else u8 1 || u64 big-endian
+    ///     || terms_hash            u8 0 if unbound, else u8 1 || 32 bytes
+    /// )
+    /// ```
+    pub fn license_receipt(env: Env, ip_id: u64, licensee: Address) -> Result<BytesN<32>, IPError> {
+        let license = storage::read_license(&env, ip_id, &licensee)?;
+
+        let mut preimage = Bytes::from_slice(&env, RECEIPT_DOMAIN);
+        preimage.extend_from_array(&license.license_id.to_be_bytes());
+        preimage.push_back(match license.license_type {
+            LicenseType::Exclusive => 0,
+            LicenseType::NonExclusive => 1,
+            LicenseType::Personal => 2,
+            LicenseType::Editorial => 3,
+            LicenseType::Commercial => 4,
+            LicenseType::Broadcast => 5,
+        });
+        preimage.extend_from_array(&license.issued_at.to_be_bytes());
+        match license.expires_at {
+            Some(expires_at) => {
+                preimage.push_back(1);
+                preimage.extend_from_array(&expires_at.to_be_bytes());
+            }
+            None => preimage.push_back(0),
+        }
+        match license.terms_hash {
+            Some(terms_hash) => {
+                preimage.push_back(1);
+                preimage.extend_from_array(&terms_hash.to_array());
+            }
+            None => preimage.push_back(0),
+        }
+
+        Ok(env.crypto().sha256(&preimage).into())
+    }
+
+    /// Revoke an active license, recording `reason` on it. The licensee may
+    /// `appeal_revocation` within the IP's appeal window, if it has one
+    /// (Requires IP owner approval)
+    pub fn revoke_license(
+        env: Env,
+        approvers: Vec<Address>,
+        licensee: Address,
+        ip_id: u64,
+        reason: Symbol,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+
+        Self::deactivate_license(&env, ip_id, &mut ip, &licensee, Some(reason.clone()))?;
+        storage::write_ip(&env, ip_id, &ip);
+
+        events::license_revoked(&env, ip_id, licensee, reason);
+        Ok(())
+    }
+
+    /// Contest a revocation before its appeal deadline. Appeals are settled by
+    /// an Arbiter or Admin through `resolve_appeal`.
+    pub fn appeal_revocation(env: Env, licensee: Address, ip_id: u64) -> Result<(), IPError> {
+        licensee.require_auth();
+
+        let mut license = storage::read_license(&env, ip_id, &licensee)?;
+        let open = license.appeal == AppealStatus::NotFiled
+            && license.appeal_deadline.is_some_and(|deadline| env.ledger().timestamp() <= deadline);
+        if license.is_active || !open {
+            return Err(IPError::AppealClosed);
+        }
+
+        license.appeal = AppealStatus::Pending;
+        storage::write_license(&env, &license);
+        storage::write_open_disputes(&env, ip_id, storage::read_open_disputes(&env, ip_id) + 1);
+
+        events::revocation_appealed(&env, ip_id, licensee);
+        Ok(())
+    }
+
+    /// Settle a pending appeal, reinstating the license if `reinstate` and its
+    /// slot is still free (Only callable by an Admin or Arbiter)
+    pub fn resolve_appeal(
+        env: Env,
+        caller: Address,
+        ip_id: u64,
+        licensee: Address,
+        reinstate: bool,
+    ) -> Result<(), IPError> {
+        caller.require_auth();
+        Self::require_hold_authority(&env, &caller)?;
+
+        let mut license = storage::read_license(&env, ip_id, &licensee)?;
+        if license.appeal != AppealStatus::Pending {
+            return Err(IPError::NoPendingAppeal);
+        }
+
+        if reinstate {
+            let mut ip = storage::read_ip(&env, ip_id)?;
+            Self::take_license_slot(&mut ip, license.license_type)?;
+            if license.license_type == LicenseType::Exclusive {
+                Self::require_no_conflicting_exclusive(&env, ip_id)?;
+            }
+            storage::write_ip(&env, ip_id, &ip);
+            license.is_active = true;
+            license.appeal = AppealStatus::Upheld;
+        } else {
+            license.appeal = AppealStatus::Rejected;
+        }
+        storage::write_license(&env, &license);
+        storage::write_open_disputes(&env, ip_id, storage::read_open_disputes(&env, ip_id).saturating_sub(1));
+
+        Self::audit(&env, symbol_short!("appeal_rs"), &caller, (ip_id, licensee.clone(), reinstate));
+        events::appeal_resolved(&env, ip_id, licensee, reinstate);
+        Ok(())
+    }
+
+    /// Revoke every active license held by `licensee`, e.g. after a key leak,
+    /// recording `reason` on each. Admins revoke platform-wide; anyone else
+    /// only on IPs they solely control.
+    /// Processes up to `limit` (at most `max_batch_size`) of the licensee's IPs
+    /// starting at `cursor` and returns the cursor to continue from, or `None`
+    /// when done.
+    pub fn revoke_all_licenses_of(
+        env: Env,
+        caller: Address,
+        licensee: Address,
+        reason: Symbol,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<Option<u32>, IPError> {
+        caller.require_auth();
+        let is_admin = rbac::has_role(&env, Role::Admin, &caller);
+
+        let index = storage::licensee_index(&licensee);
+        let cursor = PageCursor::new(cursor, limit, config::max_batch_size(&env), index.len(&env));
+
+        let mut revoked: u32 = 0;
+        for ip_id in index.page(&env, &cursor).iter() {
+            let Ok(mut ip) = storage::read_ip(&env, ip_id) else {
+                continue;
+            };
+            if !is_admin && !Self::is_sole_approver(&ip, &caller) {
+                continue;
+            }
+            if Self::deactivate_license(&env, ip_id, &mut ip, &licensee, Some(reason.clone())).is_ok() {
+                storage::write_ip(&env, ip_id, &ip);
+                events::license_revoked(&env, ip_id, licensee.clone(), reason.clone());
+                revoked += 1;
+            }
+        }
+
+        events::bulk_revoked(&env, licensee, caller, revoked);
+
+        Ok(cursor.next_start())
+    }
+
+    /// Voluntarily convert an exclusive license into a non-exclusive one,
+    /// re-opening exclusive availability. Returns the refund paid.
+    /// The refund comes out of the owner's claimable balance first and any
+    /// remainder out of the allowance they granted this contract; fails with
+    /// `InsufficientBalance` if the two together fall short.
+    pub fn downgrade_license(env: Env, licensee: Address, ip_id: u64) -> Result<i128, IPError> {
+        licensee.require_auth();
+
+        let mut ip = storage::read_ip(&env, ip_id)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        let mut license = storage::read_license(&env, ip_id, &licensee)?;
+
+        if !license.is_active || license.license_type != LicenseType::Exclusive {
+            return Err(IPError::LicenseNotFound);
+        }
+
+        let price_difference = (ip.price_exclusive - ip.price_non_exclusive).max(0);
+        let refund = bps_of(price_difference, ip.downgrade_refund_bps, ip.rounding);
+        if refund > 0 {
+            Self::pay_downgrade_refund(&env, &ip, &licensee, refund)?;
+        }
+
+        license.license_type = LicenseType::NonExclusive;
+        storage::write_license(&env, &license);
+
+        ip.has_exclusive = false;
+        ip.active_licenses += 1;
+        storage::write_ip(&env, ip_id, &ip);
+        Self::restart_price_decay(&env, ip_id);
+        Self::close_handover_from(&env, ip_id, &licensee);
+
+        events::license_downgraded(&env, ip_id, licensee, refund);
+
+        Ok(refund)
+    }
+}
+
+impl IPLicensingContract {
+    fn pay_downgrade_refund(env: &Env, ip: &IPAsset, licensee: &Address, refund: i128) -> Result<(), IPError> {
+        let token = token::Client::new(env, &ip.payment_token);
+        let contract = env.current_contract_address();
+        let claimable = storage::read_claimable(env, &ip.owner, &ip.payment_token);
+        let from_claimable = claimable.min(refund);
+        let from_allowance = refund - from_claimable;
+        if from_allowance > 0
+            && (token.allowance(&ip.owner, &contract) < from_allowance || token.balance(&ip.owner) < from_allowance)
+        {
+            return Err(IPError::InsufficientBalance);
+        }
+
+        if from_claimable > 0 {
+            storage::write_claimable(env, &ip.owner, &ip.payment_token, claimable - from_claimable);
+            token.transfer(&contract, licensee, &from_claimable);
+        }
+        if from_allowance > 0 {
+            token.transfer_from(&contract, &ip.owner, licensee, &from_allowance);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn execute_purchase(
+        env: &Env,
+        licensee: &Address,
+        ip_id: u64,
+        license_type: LicenseType,
+        referrer: Option<Address>,
+        max_payment: Option<i128>,
+        funding: Funding,
+    ) -> Result<License, IPError> {
+        Self::execute_purchase_at_discount(env, licensee, ip_id, license_type, referrer, 0, max_payment, funding)
+    }
+
+    pub(crate) fn execute_purchase_at_discount(
+        env: &Env,
+        licensee: &Address,
+        ip_id: u64,
+        license_type: LicenseType,
+        referrer: Option<Address>,
+        discount_bps: u32,
+        max_payment: Option<i128>,
+        funding: Funding,
+    ) -> Result<License, IPError> {
+        let pricing = Pricing::Discounted(discount_bps);
+        Self::execute_purchase_priced(env, licensee, ip_id, license_type, referrer, pricing, max_payment, funding)
+    }
+
+    /// Issue a license at the price `pricing` sets
+    pub(crate) fn execute_purchase_priced(
+        env: &Env,
+        licensee: &Address,
+        ip_id: u64,
+        license_type: LicenseType,
+        referrer: Option<Address>,
+        pricing: Pricing,
+        max_payment: Option<i128>,
+        funding: Funding,
+    ) -> Result<License, IPError> {
+        let mut ip = storage::read_ip(env, ip_id)?;
+        Self::validate_purchase(env, licensee, ip_id, &mut ip, license_type)?;
+
+        // Determine price
+        let price = match pricing {
+            Pricing::Discounted(discount_bps) => {
+                let list_price = Self::list_price(env, ip_id, &ip, license_type)?;
+                list_price - bps_of(list_price, discount_bps, Rounding::Floor)
+            }
+            Pricing::Fixed(price) => {
+                let is_tier = !matches!(license_type, LicenseType::Exclusive | LicenseType::NonExclusive);
+                if is_tier && !ip.tier_prices.contains_key(license_type) {
+                    return Err(IPError::InvalidTier);
+                }
+                price
+            }
+        };
+        if max_payment.is_some_and(|max_payment| price > max_payment) {
+            return Err(IPError::SlippageExceeded);
+        }
+
+        // Referrals only pay out on IPs that opted in, and never to the buyer
+        let referral = match referrer {
+            Some(referrer) if ip.referral_bps > 0 => {
+                if referrer == *licensee {
+                    return Err(IPError::Unauthorized);
+                }
+                let share = bps_of(price, ip.referral_bps, ip.rounding);
+                Some((referrer, share.min(price - Self::platform_fee(env, &ip, price))))
+            }
+            _ => None,
+        };
+
+        // Execute Payment
+        if license_type == LicenseType::Exclusive {
+            storage::write_exclusive_option(env, ip_id, &None);
+        }
+        if price > 0 {
+            Self::collect_payment(env, ip_id, &ip, licensee, price, referral.clone(), funding);
+        } else {
+            Self::take_free_claim(env, ip_id, &ip, licensee, funding)?;
+        }
+
+        if let Some((referrer, amount)) = referral {
+            Self::record_referral(env, ip_id, referrer, amount);
+        }
+        Self::record_sale(env, ip_id, price);
+
+        // Update State
+        storage::write_ip(env, ip_id, &ip);
+
+        let license_id = storage::next_license_id(env);
+        storage::write_license_holder(env, license_id, ip_id, licensee);
+
+        let new_license = License {
+            license_id,
+            licensee: licensee.clone(),
+            ip_id,
+            license_type,
+            is_active: true,
+            issued_at: env.ledger().timestamp(),
+            issued_ledger: env.ledger().sequence(),
+            sequence: storage::next_issuance_sequence(env),
+            last_activity_at: env.ledger().timestamp(),
+            expires_at: ip.license_duration.map(|duration| env.ledger().timestamp().saturating_add(duration)),
+            terms_hash: ip.metadata_hash.clone(),
+            price_paid: price,
+            revocation_reason: None,
+            appeal_deadline: None,
+            appeal: AppealStatus::NotFiled,
+        };
+        storage::write_license(env, &new_license);
+        storage::add_to_licensee_index(env, licensee, ip_id);
+        if license_type == LicenseType::Exclusive {
+            Self::extend_bond_for_sale(env, ip_id);
+            Self::restart_price_decay(env, ip_id);
+        }
+
+        events::license_purchased(env, ip_id, licensee.clone(), license_id, price, ip.terms_template);
+
+        Ok(new_license)
+    }
+
+    /// Checks every purchase path applies before pricing, taking the license's
+    /// slot on the in-memory asset
+    pub(crate) fn validate_purchase(
+        env: &Env,
+        licensee: &Address,
+        ip_id: u64,
+        ip: &mut IPAsset,
+        license_type: LicenseType,
+    ) -> Result<(), IPError> {
+        if storage::is_deregistered(env, ip_id) {
+            return Err(IPError::IPDeregistered);
+        }
+        Self::require_not_frozen(env, ip_id)?;
+        Self::require_licensing_open(env, ip_id);
+        Self::require_gate_holder(env, ip_id, licensee)?;
+        Self::require_option_holder(env, ip_id, licensee, license_type)?;
+        Self::require_no_open_auction(env, ip_id)?;
+        Self::require_attested(env, ip_id, licensee, license_type)?;
+
+        // Validation: tier exclusivity, applied to the in-memory asset
+        Self::take_license_slot(ip, license_type)?;
+        if license_type == LicenseType::Exclusive {
+            Self::require_no_conflicting_exclusive(env, ip_id)?;
+        }
+
+        if storage::read_license(env, ip_id, licensee).is_ok_and(|existing| existing.is_active) {
+            return Err(IPError::LicenseAlreadyExists);
+        }
+        Ok(())
+    }
+
+    /// Mark `licensee`'s license on `ip` inactive and release its slot on the
+    /// in-memory asset; the caller persists `ip`. A revocation records its
+    /// reason and opens the IP's appeal window.
+    pub(crate) fn deactivate_license(
+        env: &Env,
+        ip_id: u64,
+        ip: &mut IPAsset,
+        licensee: &Address,
+        revocation_reason: Option<Symbol>,
+    ) -> Result<(), IPError> {
+        let mut license = storage::read_license(env, ip_id, licensee)?;
+
+        if !license.is_active {
+            return Err(IPError::LicenseNotFound);
+        }
+
+        // Revoke license
+        license.is_active = false;
+        if revocation_reason.is_some() {
+            license.revocation_reason = revocation_reason;
+            license.appeal_deadline = ip.appeal_window_secs
+                .map(|window| env.ledger().timestamp().saturating_add(window));
+            license.appeal = AppealStatus::NotFiled;
+        }
+        storage::write_license(env, &license);
+
+        // Update IP tracking
+        Self::release_license_slot(ip, license.license_type);
+        if license.license_type == LicenseType::Exclusive {
+            Self::restart_price_decay(env, ip_id);
+            Self::close_handover_from(env, ip_id, licensee);
+        }
+        Ok(())
+    }
+
+    /// Reject calls executing after the caller's `deadline`
+    pub(crate) fn require_before(env: &Env, deadline: u64) -> Result<(), IPError> {
+        if env.ledger().timestamp() > deadline {
+            return Err(IPError::DeadlineExceeded);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_expired(env: &Env, license: &License) -> bool {
+        license.expires_at.is_some_and(|deadline| env.ledger().timestamp() >= deadline)
+    }
+}
@@ -0,0 +1,77 @@
+//! Self-description for tooling and indexers probing a deployment before use
+
+use crate::{IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient};
+use soroban_sdk::{contractimpl, vec, Env, Symbol, Vec};
+
+/// Storage schema version of this build. Bump it whenever a stored type such as
+/// `IPAsset`, `License` or `Config`, or a storage key, changes shape, since
+/// records written under the old layout no longer decode and `export_ips` /
+/// `import_ips` consumers must convert them. Reset minor and patch when it moves.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Minor and patch parts of `version`: minor for interface additions, patch for
+/// fixes, both leaving the storage schema alone
+const VERSION_MINOR: u32 = 0;
+const VERSION_PATCH: u32 = 0;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// `(major, minor, patch)`, where `major` is the storage schema version
+    /// that migrations export and import against
+    pub fn version() -> (u32, u32, u32) {
+        (SCHEMA_VERSION, VERSION_MINOR, VERSION_PATCH)
+    }
+
+    pub fn name(env: Env) -> Symbol {
+        Symbol::new(&env, "ip_licensing")
+    }
+
+    /// Capabilities of this build, including the optional ones it was compiled with
+    pub fn supported_features(env: Env) -> Vec<Symbol> {
+        let mut features = vec![
+            &env,
+            Symbol::new(&env, "anchors"),
+            Symbol::new(&env, "appeals"),
+            Symbol::new(&env, "attachments"),
+            Symbol::new(&env, "auctions"),
+            Symbol::new(&env, "calendar"),
+            Symbol::new(&env, "conflict_groups"),
+            Symbol::new(&env, "dashboard"),
+            Symbol::new(&env, "decay"),
+            Symbol::new(&env, "dormancy"),
+            Symbol::new(&env, "escape_hatch"),
+            Symbol::new(&env, "free_claims"),
+            Symbol::new(&env, "gating"),
+            Symbol::new(&env, "handover"),
+            Symbol::new(&env, "indemnity"),
+            Symbol::new(&env, "kyc_gate"),
+            Symbol::new(&env, "lookups"),
+            Symbol::new(&env, "offers"),
+            Symbol::new(&env, "options"),
+            Symbol::new(&env, "previews"),
+            Symbol::new(&env, "provenance"),
+            Symbol::new(&env, "reports"),
+            Symbol::new(&env, "rounding"),
+            Symbol::new(&env, "schedules"),
+            Symbol::new(&env, "session_keys"),
+            Symbol::new(&env, "statements"),
+            Symbol::new(&env, "swaps"),
+            Symbol::new(&env, "templates"),
+            Symbol::new(&env, "tiers"),
+            Symbol::new(&env, "vault"),
+        ];
+        if cfg!(feature = "keepers") {
+            features.push_back(Symbol::new(&env, "keepers"));
+        }
+        if cfg!(feature = "search") {
+            features.push_back(Symbol::new(&env, "search"));
+        }
+        if cfg!(feature = "shares") {
+            features.push_back(Symbol::new(&env, "shares"));
+        }
+        if cfg!(feature = "vouchers") {
+            features.push_back(Symbol::new(&env, "vouchers"));
+        }
+        features
+    }
+}
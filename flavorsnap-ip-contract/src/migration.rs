@@ -0,0 +1,144 @@
+//! Raw state export and import for migrating between contract deployments
+//! or taking recovery snapshots
+
+use crate::{
+    config, storage, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient, IPRecord,
+    License, Role
+};
+use flavorsnap_common::PageCursor;
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Export registered IPs in first-registration order, at most
+    /// `max_batch_size` per call, with the cursor to continue from or `None`
+    /// when done. Deleted IPs are skipped, so a page may be shorter than `limit`.
+    pub fn export_ips(env: Env, cursor: u32, limit: u32) -> (Vec<IPRecord>, Option<u32>) {
+        let ips = storage::ip_index();
+        let cursor = PageCursor::new(cursor, limit, config::max_batch_size(&env), ips.len(&env));
+
+        let mut page = Vec::new(&env);
+        for ip_id in ips.page(&env, &cursor).iter() {
+            let Ok(asset) = storage::read_ip(&env, ip_id) else {
+                continue;
+            };
+            page.push_back(IPRecord {
+                ip_id,
+                asset,
+                deregistered_at: storage::read_tombstone(&env, ip_id),
+                freeze_reason: storage::read_freeze_reason(&env, ip_id),
+            });
+        }
+
+        (page, cursor.next_start())
+    }
+
+    /// Export licenses in certificate order, at most `max_batch_size` per call,
+    /// with the cursor to continue from or `None` when done. Superseded
+    /// certificates are skipped, so a page may be shorter than `limit`.
+    pub fn export_licenses(env: Env, cursor: u32, limit: u32) -> (Vec<License>, Option<u32>) {
+        let count = storage::read_license_count(&env).min(u32::MAX as u64) as u32;
+        let cursor = PageCursor::new(cursor, limit, config::max_batch_size(&env), count);
+
+        let mut page = Vec::new(&env);
+        for index in cursor.range() {
+            let license_id = index as u64 + 1;
+            let Some((ip_id, licensee)) = storage::read_license_holder(&env, license_id) else {
+                continue;
+            };
+            match storage::read_license(&env, ip_id, &licensee) {
+                Ok(license) if license.license_id == license_id => page.push_back(license),
+                _ => continue,
+            }
+        }
+
+        (page, cursor.next_start())
+    }
+
+    /// Seed IPs exported from a previous deployment, at most `max_batch_size`
+    /// per call, keeping their ids, signers and hold status (Only callable by
+    /// an Admin before `finalize_import`)
+    pub fn import_ips(env: Env, admin: Address, records: Vec<IPRecord>) -> Result<(), IPError> {
+        Self::require_import_open(&env, &admin)?;
+
+        if records.len() > config::max_batch_size(&env) {
+            return Err(IPError::CapReached);
+        }
+
+        for record in records.iter() {
+            if storage::has_ip(&env, record.ip_id) {
+                return Err(IPError::IPAlreadyRegistered);
+            }
+            storage::write_ip(&env, record.ip_id, &record.asset);
+            storage::index_ip(&env, record.ip_id);
+            storage::add_owner_ip(&env, &record.asset.owner, record.ip_id);
+            #[cfg(feature = "search")]
+            for tag in record.asset.tags.iter() {
+                storage::tag_index(&tag).push(&env, record.ip_id);
+            }
+            if let Some(deregistered_at) = record.deregistered_at {
+                storage::write_tombstone(&env, record.ip_id, deregistered_at);
+            }
+            if let Some(reason) = record.freeze_reason {
+                storage::write_freeze_reason(&env, record.ip_id, &reason);
+            }
+        }
+        Self::audit(&env, symbol_short!("imp_ips"), &admin, (records.len(),));
+        Ok(())
+    }
+
+    /// Seed licenses exported from a previous deployment, at most
+    /// `max_batch_size` per call, keeping their certificate ids and issuance
+    /// sequence numbers. Their IPs must be imported first and already account
+    /// for them in `has_exclusive`, `has_broadcast` and `active_licenses`
+    /// (Only callable by an Admin before `finalize_import`)
+    pub fn import_licenses(env: Env, admin: Address, licenses: Vec<License>) -> Result<(), IPError> {
+        Self::require_import_open(&env, &admin)?;
+
+        if licenses.len() > config::max_batch_size(&env) {
+            return Err(IPError::CapReached);
+        }
+
+        for license in licenses.iter() {
+            if !storage::has_ip(&env, license.ip_id) {
+                return Err(IPError::IPNotFound);
+            }
+            if storage::read_license_holder(&env, license.license_id).is_some() {
+                return Err(IPError::LicenseAlreadyExists);
+            }
+            storage::reserve_license_id(&env, license.license_id);
+            storage::reserve_issuance_sequence(&env, license.sequence);
+            storage::write_license_holder(&env, license.license_id, license.ip_id, &license.licensee);
+            storage::add_to_licensee_index(&env, &license.licensee, license.ip_id);
+            storage::write_license(&env, &license);
+            storage::mark_undeletable(&env, license.ip_id);
+        }
+        Self::audit(&env, symbol_short!("imp_lic"), &admin, (licenses.len(),));
+        Ok(())
+    }
+
+    /// Permanently close `import_ips` and `import_licenses` (Only callable by an Admin)
+    pub fn finalize_import(env: Env, admin: Address) -> Result<(), IPError> {
+        Self::require_import_open(&env, &admin)?;
+
+        storage::finalize_import(&env);
+        Self::audit(&env, symbol_short!("imp_fin"), &admin, ());
+        Ok(())
+    }
+
+    pub fn is_import_finalized(env: Env) -> bool {
+        storage::is_import_finalized(&env)
+    }
+}
+
+impl IPLicensingContract {
+    fn require_import_open(env: &Env, admin: &Address) -> Result<(), IPError> {
+        admin.require_auth();
+        Self::require_role(env, Role::Admin, admin)?;
+
+        if storage::is_import_finalized(env) {
+            return Err(IPError::ImportFinalized);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+//! Escrowed license offers below or beside list price, with a per-owner
+//! inbox of open offers and permissionless refunds once they expire. Offers
+//! run for at most `MAX_OFFER_DURATION_SECS` and owners can reject any of
+//! them, so a full inbox frees up without waiting on the offerers.
+
+use crate::{
+    config, events, licensing::Pricing, payments::Funding, storage, ExtError, IPError, IPLicensingContract,
+    IPLicensingContractArgs, IPLicensingContractClient, License, LicenseType, Offer
+};
+use flavorsnap_common::{IdCursor, PageCursor};
+use soroban_sdk::{contractimpl, panic_with_error, token, Address, Env, Vec};
+
+/// Most open offers one owner's inbox holds
+const MAX_OPEN_OFFERS: u32 = 50;
+
+/// Longest an offer may stay open, 30 days
+const MAX_OFFER_DURATION_SECS: u64 = 30 * 24 * 60 * 60;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Offer `amount` of the IP's payment token for a license, escrowed with
+    /// this contract until the owner accepts, the offerer withdraws, or anyone
+    /// releases it back after `expires_at`, at most `MAX_OFFER_DURATION_SECS`
+    /// away. Returns the offer id.
+    pub fn make_offer(
+        env: Env,
+        offerer: Address,
+        ip_id: u64,
+        license_type: LicenseType,
+        amount: i128,
+        expires_at: u64,
+    ) -> Result<u64, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        offerer.require_auth();
+
+        let ip = storage::read_ip(&env, ip_id)?;
+        if storage::is_deregistered(&env, ip_id) {
+            return Err(IPError::IPDeregistered);
+        }
+        Self::require_not_frozen(&env, ip_id)?;
+        Self::require_sane_amount(amount, ip.token_decimals)?;
+        if amount == 0 {
+            return Err(IPError::InvalidAmount);
+        }
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            return Err(IPError::Expired);
+        }
+        if expires_at - now > MAX_OFFER_DURATION_SECS {
+            panic_with_error!(&env, ExtError::OfferTooLong);
+        }
+
+        let mut inbox = storage::read_owner_offers(&env, &ip.owner);
+        if inbox.len() >= MAX_OPEN_OFFERS {
+            return Err(IPError::CapReached);
+        }
+
+        token::Client::new(&env, &ip.payment_token).transfer(&offerer, &env.current_contract_address(), &amount);
+
+        let offer = Offer {
+            offer_id: storage::next_offer_id(&env),
+            ip_id,
+            offerer: offerer.clone(),
+            owner: ip.owner.clone(),
+            license_type,
+            token: ip.payment_token,
+            amount,
+            expires_at,
+        };
+        storage::write_offer(&env, &offer);
+        storage::write_open_offers(&env, ip_id, storage::read_open_offers(&env, ip_id) + 1);
+        inbox.push_back(offer.offer_id);
+        storage::write_owner_offers(&env, &ip.owner, &inbox);
+
+        events::offer_made(&env, ip_id, offerer, offer.offer_id, amount, expires_at);
+        Ok(offer.offer_id)
+    }
+
+    pub fn get_offer(env: Env, offer_id: u64) -> Result<Offer, IPError> {
+        storage::read_offer(&env, offer_id)
+    }
+
+    /// Unexpired open offers in `owner`'s inbox, oldest first, scanning at
+    /// most `max_batch_size` offers after `cursor` (0 for the first page),
+    /// with the cursor to continue from or `None` when done. Offers closed
+    /// between pages do not shift the cursor; expired ones are skipped, so a
+    /// page may be shorter than `limit`.
+    pub fn get_offers_for_owner(env: Env, owner: Address, cursor: IdCursor, limit: u32) -> (Vec<Offer>, Option<IdCursor>) {
+        let inbox = storage::read_owner_offers(&env, &owner);
+        let page_cursor = PageCursor::after(&inbox, cursor, limit, config::max_batch_size(&env));
+        let now = env.ledger().timestamp();
+
+        let mut page = Vec::new(&env);
+        for index in page_cursor.range() {
+            if let Ok(offer) = storage::read_offer(&env, inbox.get_unchecked(index)) {
+                if offer.expires_at > now {
+                    page.push_back(offer);
+                }
+            }
+        }
+        (page, page_cursor.next_after(&inbox))
+    }
+
+    /// Accept an unexpired offer, issuing the license to the offerer at the
+    /// offered amount out of escrow (Requires IP owner approval). Tier
+    /// availability, exclusivity and token gates apply as for a purchase.
+    pub fn accept_offer(env: Env, approvers: Vec<Address>, offer_id: u64) -> Result<License, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        let offer = storage::read_offer(&env, offer_id)?;
+        let ip = storage::read_ip(&env, offer.ip_id)?;
+        Self::require_owner_approval(&ip, &approvers)?;
+        if env.ledger().timestamp() >= offer.expires_at {
+            return Err(IPError::Expired);
+        }
+
+        Self::close_offer(&env, &offer);
+        let amount = offer.amount;
+        let license = Self::execute_purchase_priced(
+            &env,
+            &offer.offerer,
+            offer.ip_id,
+            offer.license_type,
+            None,
+            Pricing::Fixed(amount),
+            None,
+            Funding::Escrow,
+        )?;
+
+        events::offer_accepted(&env, offer.ip_id, offer.offerer, offer_id, license.license_id);
+        Ok(license)
+    }
+
+    /// Withdraw an open offer and refund its escrow (Only callable by the offerer)
+    pub fn withdraw_offer(env: Env, offerer: Address, offer_id: u64) -> Result<(), IPError> {
+        offerer.require_auth();
+        let offer = storage::read_offer(&env, offer_id)?;
+        if offer.offerer != offerer {
+            return Err(IPError::Unauthorized);
+        }
+
+        Self::refund_offer(&env, &offer);
+        Ok(())
+    }
+
+    /// Turn down an open offer and refund its escrow to the offerer (Requires
+    /// IP owner approval)
+    pub fn reject_offer(env: Env, approvers: Vec<Address>, offer_id: u64) -> Result<(), IPError> {
+        let offer = storage::read_offer(&env, offer_id)?;
+        let ip = storage::read_ip(&env, offer.ip_id)?;
+        Self::require_owner_approval(&ip, &approvers)?;
+
+        Self::refund_offer(&env, &offer);
+        Ok(())
+    }
+
+    /// Refund an expired offer's escrow to its offerer. Callable by anyone.
+    pub fn release_expired_offer(env: Env, offer_id: u64) -> Result<(), IPError> {
+        let offer = storage::read_offer(&env, offer_id)?;
+        if env.ledger().timestamp() < offer.expires_at {
+            return Err(IPError::OfferNotExpired);
+        }
+
+        Self::refund_offer(&env, &offer);
+        Ok(())
+    }
+}
+
+impl IPLicensingContract {
+    /// Remove the offer and its inbox entry
+    fn close_offer(env: &Env, offer: &Offer) {
+        storage::remove_offer(env, offer.offer_id);
+        storage::write_open_offers(env, offer.ip_id, storage::read_open_offers(env, offer.ip_id).saturating_sub(1));
+        let mut inbox = storage::read_owner_offers(env, &offer.owner);
+        if let Some(index) = inbox.first_index_of(offer.offer_id) {
+            inbox.remove(index);
+            storage::write_owner_offers(env, &offer.owner, &inbox);
+        }
+    }
+
+    fn refund_offer(env: &Env, offer: &Offer) {
+        Self::close_offer(env, offer);
+        token::Client::new(env, &offer.token).transfer(&env.current_contract_address(), &offer.offerer, &offer.amount);
+        events::offer_closed(env, offer.ip_id, offer.offerer.clone(), offer.offer_id);
+    }
+}
@@ -0,0 +1,153 @@
+//! Paid options on an IP's exclusive slot: a buyer in due diligence holds the
+//! slot for a fixed window and may take the exclusive license at an agreed
+//! price before it lapses
+
+use crate::{
+    events, licensing::Pricing, payments::Funding, storage, ExclusiveOption, ExtError, IPError, IPLicensingContract,
+    IPLicensingContractArgs, IPLicensingContractClient, License, LicenseType, OptionTerms
+};
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Offer options on the IP's exclusive slot on `terms`, or stop offering
+    /// them with `None` (Requires IP owner approval). An option already taken
+    /// keeps the terms it was taken on.
+    pub fn set_option_terms(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        terms: Option<OptionTerms>,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+        if let Some(terms) = &terms {
+            Self::require_sane_amount(terms.fee, ip.token_decimals)?;
+            Self::require_sane_amount(terms.strike_price, ip.token_decimals)?;
+            if terms.window_secs == 0 {
+                return Err(IPError::InvalidPeriod);
+            }
+        }
+        storage::write_option_terms(&env, ip_id, &terms);
+
+        events::option_terms_set(&env, ip_id, terms);
+        Ok(())
+    }
+
+    pub fn get_option_terms(env: Env, ip_id: u64) -> Option<OptionTerms> {
+        storage::read_option_terms(&env, ip_id)
+    }
+
+    /// The unexpired option on the IP's exclusive slot, if any
+    pub fn get_exclusive_option(env: Env, ip_id: u64) -> Option<ExclusiveOption> {
+        Self::live_option(&env, ip_id)
+    }
+
+    /// Pay the option fee to hold the IP's free exclusive slot for the terms'
+    /// window, failing with `SlippageExceeded` unless the owner's current
+    /// terms are `terms`. The option also holds the exclusive slot of the IP's
+    /// conflict group, so siblings cannot sell exclusives while it is live.
+    /// The fee is paid out like license revenue and is not refunded, whether
+    /// or not the option is exercised.
+    pub fn take_exclusive_option(
+        env: Env,
+        holder: Address,
+        ip_id: u64,
+        terms: OptionTerms,
+    ) -> Result<ExclusiveOption, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        holder.require_auth();
+
+        let ip = storage::read_ip(&env, ip_id)?;
+        if storage::read_option_terms(&env, ip_id) != Some(terms.clone()) {
+            return Err(IPError::SlippageExceeded);
+        }
+        if storage::is_deregistered(&env, ip_id) {
+            return Err(IPError::IPDeregistered);
+        }
+        Self::require_not_frozen(&env, ip_id)?;
+        Self::require_gate_holder(&env, ip_id, &holder)?;
+        Self::require_attested(&env, ip_id, &holder, LicenseType::Exclusive)?;
+        Self::require_no_open_auction(&env, ip_id)?;
+        if ip.has_exclusive || Self::live_option(&env, ip_id).is_some() {
+            return Err(IPError::ExclusiveAlreadyIssued);
+        }
+        if ip.active_licenses > 0 {
+            return Err(IPError::ActiveLicensesExist);
+        }
+        Self::require_no_conflicting_exclusive(&env, ip_id)?;
+
+        if terms.fee > 0 {
+            Self::collect_payment(&env, ip_id, &ip, &holder, terms.fee, None, Funding::Direct);
+        }
+        let option = ExclusiveOption {
+            holder: holder.clone(),
+            strike_price: terms.strike_price,
+            fee_paid: terms.fee,
+            expires_at: env.ledger().timestamp().saturating_add(terms.window_secs),
+        };
+        storage::write_exclusive_option(&env, ip_id, &Some(option.clone()));
+
+        events::option_taken(&env, ip_id, holder, terms.fee, option.expires_at);
+        Ok(option)
+    }
+
+    /// Take the exclusive license at the option's strike price before the
+    /// option lapses (Only callable by the holder)
+    pub fn exercise_exclusive_option(env: Env, holder: Address, ip_id: u64) -> Result<License, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        holder.require_auth();
+
+        let option = storage::read_exclusive_option(&env, ip_id)
+            .unwrap_or_else(|| panic_with_error!(&env, ExtError::OptionNotFound));
+        if option.holder != holder {
+            return Err(IPError::Unauthorized);
+        }
+        if env.ledger().timestamp() >= option.expires_at {
+            return Err(IPError::Expired);
+        }
+
+        let strike_price = option.strike_price;
+        let license = Self::execute_purchase_priced(
+            &env,
+            &holder,
+            ip_id,
+            LicenseType::Exclusive,
+            None,
+            Pricing::Fixed(strike_price),
+            None,
+            Funding::Direct,
+        )?;
+
+        events::option_exercised(&env, ip_id, holder, license.license_id);
+        Ok(license)
+    }
+}
+
+impl IPLicensingContract {
+    pub(crate) fn live_option(env: &Env, ip_id: u64) -> Option<ExclusiveOption> {
+        storage::read_exclusive_option(env, ip_id).filter(|option| env.ledger().timestamp() < option.expires_at)
+    }
+
+    /// Keep the IP's slot for an unexpired option's holder: any license but
+    /// the holder's exclusive one is refused until the option lapses
+    pub(crate) fn require_option_holder(
+        env: &Env,
+        ip_id: u64,
+        licensee: &Address,
+        license_type: LicenseType,
+    ) -> Result<(), IPError> {
+        match Self::live_option(env, ip_id) {
+            Some(option) if option.holder != *licensee || license_type != LicenseType::Exclusive => {
+                Err(IPError::ExclusiveAlreadyIssued)
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,378 @@
+//! Royalty payments, fee routing, referrals, sales stats and idempotency
+
+use crate::{
+    config, events, revenue_vault::RevenueVaultClient, storage, IPAsset, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient,
+    IPStats, ReferrerStats, ReportStatus, RoyaltyReport
+};
+use flavorsnap_common::{bps_of, split_amounts, PageCursor, MAX_BPS};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contractimpl, symbol_short, token, vec, Address, BytesN, Env, IntoVal, String, Symbol, Vec,
+};
+
+/// Longest memo, in bytes, accepted alongside a payment
+pub(crate) const MAX_MEMO_LEN: u32 = 64;
+
+/// Where a payment is drawn from
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Funding {
+    /// Licensee-signed `transfer`
+    Direct,
+    /// `transfer_from` against an allowance granted to this contract
+    Allowance,
+    /// Tokens the licensee already escrowed with this contract
+    Escrow,
+}
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Pay usage-based royalties on `licensee`'s license. `caller` is the licensee,
+    /// paying directly, or one of their operators, paying from the allowance the
+    /// licensee granted this contract. `report_hash` commits to the detailed
+    /// off-chain usage report the payment is based on and is kept in the IP's
+    /// royalty history. A retried submission carrying the same `idempotency_key`
+    /// is rejected with `DuplicateOperation`. A `memo` is published with the
+    /// royalty event but not stored. Returns the report id.
+    pub fn pay_usage_royalty(
+        env: Env,
+        caller: Address,
+        licensee: Address,
+        ip_id: u64,
+        amount: i128,
+        report_hash: BytesN<32>,
+        idempotency_key: Option<BytesN<32>>,
+        memo: Option<String>,
+    ) -> Result<u32, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        Self::require_valid_memo(&memo)?;
+        caller.require_auth();
+        Self::settle_royalty(&env, caller, licensee, ip_id, amount, report_hash, idempotency_key, memo)
+    }
+
+    pub fn get_royalty_report(env: Env, ip_id: u64, report_id: u32) -> Result<RoyaltyReport, IPError> {
+        storage::read_royalty_report(&env, ip_id, report_id)
+    }
+
+    /// The IP's royalty history in payment order, at most `max_batch_size` from
+    /// report id `start`
+    pub fn get_royalty_reports(env: Env, ip_id: u64, start: u32, limit: u32) -> Vec<RoyaltyReport> {
+        let count = storage::read_royalty_report_count(&env, ip_id);
+        let cursor = PageCursor::new(start, limit, config::max_batch_size(&env), count);
+
+        let mut reports = Vec::new(&env);
+        for report_id in cursor.range() {
+            if let Ok(report) = storage::read_royalty_report(&env, ip_id, report_id) {
+                reports.push_back(report);
+            }
+        }
+        reports
+    }
+
+    /// Dispute a royalty report as underreported, pointing at the report hash
+    /// the licensee committed to (Requires IP owner approval). A report is
+    /// disputed at most once; repeats fail with `DuplicateOperation`.
+    pub fn flag_report(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        report_id: u32,
+        reason: Symbol,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+        Self::require_owner_approval(&ip, &approvers)?;
+
+        let mut report = storage::read_royalty_report(&env, ip_id, report_id)?;
+        if report.status == ReportStatus::Disputed {
+            return Err(IPError::DuplicateOperation);
+        }
+        report.status = ReportStatus::Disputed;
+        report.dispute_reason = Some(reason.clone());
+        storage::write_royalty_report(&env, ip_id, &report);
+        storage::write_open_disputes(&env, ip_id, storage::read_open_disputes(&env, ip_id) + 1);
+
+        events::report_flagged(&env, ip_id, report.licensee, report_id, report.report_hash, reason);
+        Ok(())
+    }
+
+    pub fn get_referrer_stats(env: Env, referrer: Address) -> ReferrerStats {
+        storage::read_referrer_stats(&env, &referrer)
+    }
+
+    /// Licenses sold, revenue and royalties of an IP since registration
+    pub fn get_ip_stats(env: Env, ip_id: u64) -> Result<IPStats, IPError> {
+        if !storage::has_ip(&env, ip_id) {
+            return Err(IPError::IPNotFound);
+        }
+        Ok(storage::read_ip_stats(&env, ip_id))
+    }
+
+    /// Pay owner proceeds straight to the owner on every sale instead of
+    /// accruing them for `claim` (Requires IP owner approval). A push to an
+    /// owner whose token account cannot receive fails the whole purchase.
+    pub fn set_push_payouts(env: Env, approvers: Vec<Address>, ip_id: u64, enabled: bool) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        ip.push_payouts = enabled;
+        storage::write_ip(&env, ip_id, &ip);
+        Ok(())
+    }
+
+    /// Route owner proceeds to `recipient` instead of the owner (Requires IP
+    /// owner approval); `None` restores payouts to the owner. With `vault` set,
+    /// `recipient` is a creator vault contract and proceeds are paid in through
+    /// its `deposit`, attributed to `ip_id`; otherwise they are transferred to
+    /// it directly. Revenue shares, once issued, still take precedence.
+    pub fn set_revenue_recipient(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        recipient: Option<Address>,
+        vault: bool,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        ip.revenue_to_vault = vault && recipient.is_some();
+        ip.revenue_recipient = recipient;
+        storage::write_ip(&env, ip_id, &ip);
+        Ok(())
+    }
+
+    /// License fees and royalties accrued to `owner` in `token`
+    pub fn get_claimable(env: Env, owner: Address, token: Address) -> i128 {
+        storage::read_claimable(&env, &owner, &token)
+    }
+
+    /// Withdraw everything accrued to `owner` in `token`. Returns the amount paid.
+    pub fn claim(env: Env, owner: Address, token: Address) -> i128 {
+        owner.require_auth();
+
+        let amount = storage::read_claimable(&env, &owner, &token);
+        if amount > 0 {
+            storage::write_claimable(&env, &owner, &token, 0);
+            token::Client::new(&env, &token).transfer(&env.current_contract_address(), &owner, &amount);
+        }
+
+        events::claimed(&env, owner, token, amount);
+        amount
+    }
+}
+
+impl IPLicensingContract {
+    /// Take `amount` from `from` in the IP's payment token, routing the
+    /// platform fee to the treasury and any referral share to its referrer.
+    /// The payer makes a single transfer of the whole amount into this
+    /// contract, which pays the shares out of it, so a contract account can
+    /// pre-authorize the one transfer it is quoted. The remainder accrues to
+    /// the share holders of `ip_id` once it has issued revenue shares,
+    /// otherwise goes to the IP's revenue recipient if it has one, and else to
+    /// the owner's claimable balance, or is pushed straight to the owner if
+    /// the IP opted into push payouts.
+    #[cfg_attr(not(feature = "shares"), allow(unused_variables))]
+    pub(crate) fn collect_payment(
+        env: &Env,
+        ip_id: u64,
+        ip: &IPAsset,
+        from: &Address,
+        amount: i128,
+        referral: Option<(Address, i128)>,
+        funding: Funding,
+    ) {
+        let config = storage::read_config(env);
+        let fee = Self::platform_fee(env, ip, amount);
+
+        let token = &ip.payment_token;
+        let token_client = token::Client::new(env, token);
+        let spender = env.current_contract_address();
+        match funding {
+            Funding::Direct => token_client.transfer(from, &spender, &amount),
+            Funding::Allowance => token_client.transfer_from(&spender, from, &spender, &amount),
+            Funding::Escrow => {}
+        }
+        let pay = |recipient: &Address, value: i128| {
+            if *recipient != spender {
+                token_client.transfer(&spender, recipient, &value);
+            }
+        };
+
+        // The keeper pool's cut of the fee stays here, the treasury takes the rest
+        let keeper_bps = storage::read_keeper_reward(env).map_or(0, |reward| reward.fee_share_bps);
+        let fee_shares = vec![env, (spender.clone(), keeper_bps), (config.treasury.clone(), MAX_BPS - keeper_bps)];
+        let fee_parts = split_amounts(fee, &fee_shares, ip.rounding);
+        let (keeper_share, treasury_share) = (fee_parts.get_unchecked(0), fee_parts.get_unchecked(1));
+        if keeper_share > 0 {
+            let pool = storage::read_keeper_pool(env, token);
+            storage::write_keeper_pool(env, token, pool + keeper_share);
+        }
+        if treasury_share > 0 {
+            pay(&config.treasury, treasury_share);
+        }
+        let mut remainder = amount - fee;
+        if let Some((referrer, share)) = referral {
+            if share > 0 {
+                pay(&referrer, share);
+                remainder -= share;
+            }
+        }
+        #[cfg(feature = "shares")]
+        if let Some(share_token) = storage::read_share_token(env, ip_id) {
+            pay(&spender, remainder);
+            Self::accrue_share_revenue(env, ip_id, share_token, remainder);
+            return;
+        }
+        if let Some(recipient) = &ip.revenue_recipient {
+            if ip.revenue_to_vault {
+                pay(&spender, remainder);
+                Self::deposit_to_vault(env, recipient, token, ip_id, remainder);
+            } else {
+                pay(recipient, remainder);
+            }
+        } else if ip.push_payouts {
+            pay(&ip.owner, remainder);
+        } else {
+            pay(&spender, remainder);
+            let claimable = storage::read_claimable(env, &ip.owner, token);
+            storage::write_claimable(env, &ip.owner, token, claimable + remainder);
+        }
+    }
+
+    /// Pay `amount` held by this contract into `vault` as revenue of `ip_id`,
+    /// authorizing the token transfer the vault makes on our behalf
+    fn deposit_to_vault(env: &Env, vault: &Address, token: &Address, ip_id: u64, amount: i128) {
+        let this = env.current_contract_address();
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token.clone(),
+                    fn_name: symbol_short!("transfer"),
+                    args: (this.clone(), vault.clone(), amount).into_val(env),
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+        RevenueVaultClient::new(env, vault).deposit(&this, token, &ip_id, &amount);
+    }
+
+    /// Count a license sale at `price` towards the IP's stats
+    pub(crate) fn record_sale(env: &Env, ip_id: u64, price: i128) {
+        let mut stats = storage::read_ip_stats(env, ip_id);
+        stats.licenses_sold += 1;
+        stats.total_revenue += price;
+        stats.last_sale_at = Some(env.ledger().timestamp());
+        storage::write_ip_stats(env, ip_id, &stats);
+    }
+
+    /// The platform's share of a payment of `amount` for the IP
+    pub(crate) fn platform_fee(env: &Env, ip: &IPAsset, amount: i128) -> i128 {
+        bps_of(amount, storage::read_config(env).fee_bps, ip.rounding)
+    }
+
+    /// Credit a paid referral to the referrer's cumulative stats
+    pub(crate) fn record_referral(env: &Env, ip_id: u64, referrer: Address, amount: i128) {
+        let mut stats = storage::read_referrer_stats(env, &referrer);
+        stats.sales += 1;
+        stats.earned += amount;
+        storage::write_referrer_stats(env, &referrer, &stats);
+
+        events::referral_paid(env, ip_id, referrer, amount);
+    }
+
+    /// Charge and record one royalty payment for `pay_usage_royalty`,
+    /// `report_usage` and `pay_royalty_with_swap`, whose callers have already
+    /// required `caller`'s authorization
+    pub(crate) fn settle_royalty(
+        env: &Env,
+        caller: Address,
+        licensee: Address,
+        ip_id: u64,
+        amount: i128,
+        report_hash: BytesN<32>,
+        idempotency_key: Option<BytesN<32>>,
+        memo: Option<String>,
+    ) -> Result<u32, IPError> {
+        let funding = if caller == licensee {
+            Funding::Direct
+        } else if storage::is_operator(env, &licensee, &caller) || Self::spend_session_key(env, ip_id, &licensee, &caller, amount)? {
+            Funding::Allowance
+        } else {
+            return Err(IPError::Unauthorized);
+        };
+        Self::claim_idempotency_key(env, &licensee, idempotency_key)?;
+
+        if amount <= 0 {
+            return Err(IPError::InvalidAmount);
+        }
+
+        let ip = storage::read_ip(env, ip_id)?;
+        Self::require_sane_amount(amount, ip.token_decimals)?;
+        Self::require_not_frozen(env, ip_id)?;
+
+        let mut license = storage::read_license(env, ip_id, &licensee)?;
+
+        if !license.is_active {
+            return Err(IPError::LicenseNotFound);
+        }
+        if Self::is_expired(env, &license) {
+            return Err(IPError::Expired);
+        }
+
+        Self::collect_payment(env, ip_id, &ip, &licensee, amount, None, funding);
+        license.last_activity_at = env.ledger().timestamp();
+        storage::write_license(env, &license);
+
+        let mut stats = storage::read_ip_stats(env, ip_id);
+        stats.total_royalties += amount;
+        storage::write_ip_stats(env, ip_id, &stats);
+
+        let report = RoyaltyReport {
+            report_id: storage::read_royalty_report_count(env, ip_id),
+            licensee: licensee.clone(),
+            amount,
+            report_hash,
+            paid_at: env.ledger().timestamp(),
+            status: ReportStatus::Filed,
+            dispute_reason: None,
+        };
+        storage::append_royalty_report(env, ip_id, &report);
+        Self::record_royalty_day(env, ip_id, &licensee, amount);
+
+        events::royalty_paid(env, ip_id, licensee.clone(), amount);
+        Self::publish_memo(env, ip_id, &licensee, symbol_short!("royalty"), report.report_id as u64, memo);
+        Ok(report.report_id)
+    }
+
+    pub(crate) fn require_valid_memo(memo: &Option<String>) -> Result<(), IPError> {
+        if memo.as_ref().is_some_and(|memo| memo.len() > MAX_MEMO_LEN) {
+            return Err(IPError::MemoTooLong);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn publish_memo(env: &Env, ip_id: u64, licensee: &Address, kind: Symbol, reference: u64, memo: Option<String>) {
+        if let Some(memo) = memo {
+            events::payment_memo(env, ip_id, licensee.clone(), kind, reference, memo);
+        }
+    }
+
+    /// Reject a key already used by `caller` within its TTL, otherwise remember it.
+    /// Failed calls roll back, so only landed operations consume their key.
+    pub(crate) fn claim_idempotency_key(env: &Env, caller: &Address, key: Option<BytesN<32>>) -> Result<(), IPError> {
+        let Some(key) = key else {
+            return Ok(());
+        };
+
+        if storage::has_idempotency_key(env, caller, &key) {
+            return Err(IPError::DuplicateOperation);
+        }
+        storage::write_idempotency_key(env, caller, &key);
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+//! Paid preview access: viewers buy a short-lived grant that the CDN serving
+//! an IP's watermarked previews checks on chain before serving them
+
+use crate::{
+    events, payments::Funding, storage, ExtError, IPError, IPLicensingContract, IPLicensingContractArgs,
+    IPLicensingContractClient, PreviewTerms
+};
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env, Vec};
+
+/// Longest preview grant an owner can sell
+const MAX_PREVIEW_SECS: u64 = 7 * 24 * 60 * 60;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Sell preview grants of the IP on `terms`, or stop selling them with
+    /// `None` (Requires IP owner approval). Grants already bought run out as
+    /// bought.
+    pub fn set_preview_terms(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        terms: Option<PreviewTerms>,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+        if let Some(terms) = &terms {
+            Self::require_sane_amount(terms.fee, ip.token_decimals)?;
+            if terms.duration_secs == 0 || terms.duration_secs > MAX_PREVIEW_SECS {
+                return Err(IPError::InvalidPeriod);
+            }
+        }
+        storage::write_preview_terms(&env, ip_id, &terms);
+
+        events::preview_terms_set(&env, ip_id, terms);
+        Ok(())
+    }
+
+    pub fn get_preview_terms(env: Env, ip_id: u64) -> Option<PreviewTerms> {
+        storage::read_preview_terms(&env, ip_id)
+    }
+
+    /// Pay the preview fee for access to the IP's previews for the terms'
+    /// duration, failing with `SlippageExceeded` unless the owner's current
+    /// terms are `terms`. Buying again while a grant is live restarts it.
+    /// Returns when the grant expires.
+    pub fn purchase_preview(env: Env, viewer: Address, ip_id: u64, terms: PreviewTerms) -> Result<u64, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        viewer.require_auth();
+
+        let ip = storage::read_ip(&env, ip_id)?;
+        match storage::read_preview_terms(&env, ip_id) {
+            None => panic_with_error!(&env, ExtError::PreviewTermsNotFound),
+            Some(current) if current != terms => return Err(IPError::SlippageExceeded),
+            Some(_) => {}
+        }
+        if storage::is_deregistered(&env, ip_id) {
+            return Err(IPError::IPDeregistered);
+        }
+        Self::require_not_frozen(&env, ip_id)?;
+
+        if terms.fee > 0 {
+            Self::collect_payment(&env, ip_id, &ip, &viewer, terms.fee, None, Funding::Direct);
+        }
+        let expires_at = env.ledger().timestamp().saturating_add(terms.duration_secs);
+        storage::write_preview_grant(&env, ip_id, &viewer, expires_at);
+        storage::mark_undeletable(&env, ip_id);
+
+        events::preview_purchased(&env, ip_id, viewer, terms.fee, expires_at);
+        Ok(expires_at)
+    }
+
+    /// Whether `viewer` holds an unexpired preview grant for the IP
+    pub fn has_preview_access(env: Env, ip_id: u64, viewer: Address) -> bool {
+        storage::read_preview_grant(&env, ip_id, &viewer).is_some_and(|expires_at| env.ledger().timestamp() < expires_at)
+    }
+}
@@ -0,0 +1,77 @@
+//! Automatic markdown of exclusive slots that stay unsold, evaluated lazily
+//! whenever the exclusive price is quoted or paid
+
+use crate::{
+    events, storage, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient, PriceDecay,
+    BPS_DENOMINATOR
+};
+use flavorsnap_common::{bps_of, Rounding};
+use soroban_sdk::{contractimpl, Address, Env, Vec};
+
+/// Most decay steps applied to a price, bounding the cost of a quote
+const MAX_DECAY_STEPS: u64 = 100;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Lower the exclusive price by `step_bps` for every `interval_secs` the
+    /// exclusive slot stays unsold, never below `floor`; `step_bps` of 0 turns
+    /// decay off (Requires IP owner approval). The unsold period starts now.
+    pub fn set_price_decay(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        step_bps: u32,
+        interval_secs: u64,
+        floor: i128,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        if step_bps == 0 {
+            storage::remove_price_decay(&env, ip_id);
+        } else {
+            Self::require_sane_amount(floor, ip.token_decimals)?;
+            if step_bps as i128 > BPS_DENOMINATOR || interval_secs == 0 {
+                return Err(IPError::InvalidAmount);
+            }
+            let decay = PriceDecay { step_bps, interval_secs, floor, unsold_since: env.ledger().timestamp() };
+            storage::write_price_decay(&env, ip_id, &decay);
+        }
+
+        events::price_decay_set(&env, ip_id, step_bps, interval_secs, floor);
+        Ok(())
+    }
+
+    pub fn get_price_decay(env: Env, ip_id: u64) -> Option<PriceDecay> {
+        storage::read_price_decay(&env, ip_id)
+    }
+}
+
+impl IPLicensingContract {
+    /// `price` after the decay steps elapsed in the current unsold period
+    pub(crate) fn decayed_exclusive_price(env: &Env, ip_id: u64, price: i128) -> i128 {
+        let Some(decay) = storage::read_price_decay(env, ip_id) else {
+            return price;
+        };
+        let steps = env.ledger().timestamp().saturating_sub(decay.unsold_since) / decay.interval_secs;
+
+        let mut decayed = price;
+        for _ in 0..steps.min(MAX_DECAY_STEPS) {
+            if decayed <= decay.floor {
+                break;
+            }
+            decayed -= bps_of(decayed, decay.step_bps, Rounding::Floor);
+        }
+        decayed.max(decay.floor).min(price)
+    }
+
+    /// Start a new unsold period after the exclusive slot was taken, freed or repriced
+    pub(crate) fn restart_price_decay(env: &Env, ip_id: u64) {
+        if let Some(mut decay) = storage::read_price_decay(env, ip_id) {
+            decay.unsold_since = env.ledger().timestamp();
+            storage::write_price_decay(env, ip_id, &decay);
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env, Symbol};
+
+/// Mirror of the SEP-40 price feed `Asset`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// Mirror of the SEP-40 `PriceData`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// The subset of a SEP-40 price feed used to quote reference prices
+#[allow(dead_code)]
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    fn decimals(env: Env) -> u32;
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
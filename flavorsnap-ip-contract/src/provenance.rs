@@ -0,0 +1,56 @@
+//! Claim-based ownership verification: owners reference an off-chain proof
+//! that they control the work's origin, and an Attestor vouches for it
+
+use crate::{events, storage, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient, Role};
+use soroban_sdk::{contractimpl, symbol_short, Address, BytesN, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Reference an external ownership proof for the IP, or drop it with
+    /// `None` (Requires IP owner approval). Usually sent together with
+    /// `register_ip`. Any change clears `verified_origin` until re-attested.
+    pub fn set_ownership_proof(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        proof_hash: Option<BytesN<32>>,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        ip.proof_hash = proof_hash.clone();
+        ip.verified_origin = false;
+        storage::write_ip(&env, ip_id, &ip);
+
+        events::proof_set(&env, ip_id, proof_hash);
+        Ok(())
+    }
+
+    /// Record whether the proof `proof_hash` establishes the owner as the
+    /// work's origin (Only callable by an Attestor). The hash must be the one
+    /// on file, so a proof swapped after review is never marked verified.
+    pub fn attest_origin(
+        env: Env,
+        attestor: Address,
+        ip_id: u64,
+        proof_hash: BytesN<32>,
+        verified: bool,
+    ) -> Result<(), IPError> {
+        attestor.require_auth();
+        Self::require_role(&env, Role::Attestor, &attestor)?;
+
+        let mut ip = storage::read_ip(&env, ip_id)?;
+        if ip.proof_hash.as_ref() != Some(&proof_hash) {
+            return Err(IPError::ProofMismatch);
+        }
+
+        ip.verified_origin = verified;
+        storage::write_ip(&env, ip_id, &ip);
+
+        Self::audit(&env, symbol_short!("origin"), &attestor, (ip_id, verified));
+        events::origin_attested(&env, ip_id, attestor, verified);
+        Ok(())
+    }
+}
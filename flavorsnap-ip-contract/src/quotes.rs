@@ -0,0 +1,139 @@
+//! Prices denominated in a reference unit and converted through a price oracle
+
+use crate::{
+    price_oracle::{Asset, PriceOracleClient}, storage, IPAsset, IPError, IPLicensingContract, IPLicensingContractArgs,
+    IPLicensingContractClient, LicenseType, PriceOracleConfig, PurchaseQuote, Role
+};
+use soroban_sdk::{contractimpl, symbol_short, token, Address, Env, Vec};
+
+/// Most whole tokens a price or royalty may amount to, so an amount scaled by
+/// the wrong power of ten is rejected instead of charged
+const MAX_WHOLE_TOKENS: i128 = 1_000_000_000_000;
+
+/// Most `decimals()` a payment token may report
+const MAX_TOKEN_DECIMALS: u32 = 18;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Set the SEP-40 price feed quoting payment tokens in the reference unit,
+    /// and the oldest price it may serve (Only callable by an Admin)
+    pub fn set_price_oracle(env: Env, admin: Address, oracle: Address, max_age_secs: u64) -> Result<(), IPError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        storage::write_price_oracle(&env, &PriceOracleConfig { oracle: oracle.clone(), max_age_secs });
+        Self::audit(&env, symbol_short!("oracle"), &admin, (oracle, max_age_secs));
+        Ok(())
+    }
+
+    pub fn get_price_oracle(env: Env) -> Option<PriceOracleConfig> {
+        storage::read_price_oracle(&env)
+    }
+
+    /// Denominate the IP's prices in the oracle's reference unit (e.g. USD
+    /// cents) instead of its payment token (Requires IP owner approval)
+    pub fn set_reference_pricing(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        enabled: bool,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        ip.reference_priced = enabled;
+        storage::write_ip(&env, ip_id, &ip);
+        Ok(())
+    }
+
+    /// Current list price of a license in the IP's payment token
+    pub fn quote_license(env: Env, ip_id: u64, license_type: LicenseType) -> Result<i128, IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+        Self::list_price(&env, ip_id, &ip, license_type)
+    }
+
+    /// Current list price together with the token it is paid in and that
+    /// token's decimals, so integrators can scale it for display
+    pub fn quote_price(env: Env, ip_id: u64, license_type: LicenseType) -> Result<(i128, Address, u32), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+        let price = Self::list_price(&env, ip_id, &ip, license_type)?;
+        Ok((price, ip.payment_token, ip.token_decimals))
+    }
+
+    /// Validate and price a purchase of `license_type` by `licensee` without
+    /// executing it, failing with the error the purchase would fail with,
+    /// `ExtError`s such as `LicensingClosed` included.
+    /// Auth, slippage, deadline and idempotency are left to the real call.
+    pub fn can_purchase(env: Env, licensee: Address, ip_id: u64, license_type: LicenseType) -> Result<PurchaseQuote, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        let mut ip = storage::read_ip(&env, ip_id)?;
+        Self::validate_purchase(&env, &licensee, ip_id, &mut ip, license_type)?;
+        let price = Self::list_price(&env, ip_id, &ip, license_type)?;
+
+        let token_client = token::Client::new(&env, &ip.payment_token);
+        let balance = token_client.balance(&licensee);
+        if balance < price {
+            return Err(IPError::InsufficientBalance);
+        }
+
+        Ok(PurchaseQuote {
+            price,
+            platform_fee: Self::platform_fee(&env, &ip, price),
+            balance,
+            allowance: token_client.allowance(&licensee, &env.current_contract_address()),
+            payment_token: ip.payment_token,
+            token_decimals: ip.token_decimals,
+        })
+    }
+}
+
+impl IPLicensingContract {
+    pub(crate) fn list_price(env: &Env, ip_id: u64, ip: &IPAsset, license_type: LicenseType) -> Result<i128, IPError> {
+        let price = match license_type {
+            LicenseType::Exclusive => Self::decayed_exclusive_price(env, ip_id, ip.price_exclusive),
+            LicenseType::NonExclusive => ip.price_non_exclusive,
+            tier => ip.tier_prices.get(tier).ok_or(IPError::InvalidTier)?,
+        };
+        if !ip.reference_priced {
+            return Ok(price);
+        }
+        Self::convert_reference_price(env, ip, price)
+    }
+
+    /// `decimals()` of a prospective payment token
+    pub(crate) fn token_decimals(env: &Env, token: &Address) -> Result<u32, IPError> {
+        let decimals = token::Client::new(env, token).decimals();
+        if decimals > MAX_TOKEN_DECIMALS {
+            return Err(IPError::UnsupportedToken);
+        }
+        Ok(decimals)
+    }
+
+    /// Reject negative amounts and those above `MAX_WHOLE_TOKENS` at `decimals`
+    pub(crate) fn require_sane_amount(amount: i128, decimals: u32) -> Result<(), IPError> {
+        if amount < 0 || amount > MAX_WHOLE_TOKENS * 10i128.pow(decimals) {
+            return Err(IPError::InvalidAmount);
+        }
+        Ok(())
+    }
+
+    /// Convert `amount` reference units into base units of the IP's payment
+    /// token at the oracle's latest price for one whole token
+    fn convert_reference_price(env: &Env, ip: &IPAsset, amount: i128) -> Result<i128, IPError> {
+        let config = storage::read_price_oracle(env).ok_or(IPError::PriceUnavailable)?;
+        let oracle = PriceOracleClient::new(env, &config.oracle);
+
+        let quote = oracle.lastprice(&Asset::Stellar(ip.payment_token.clone())).ok_or(IPError::PriceUnavailable)?;
+        if quote.price <= 0 || quote.timestamp.saturating_add(config.max_age_secs) < env.ledger().timestamp() {
+            return Err(IPError::PriceUnavailable);
+        }
+
+        let oracle_scale = 10i128.pow(oracle.decimals());
+        let token_scale = 10i128.pow(ip.token_decimals);
+        Ok(amount * oracle_scale * token_scale / quote.price)
+    }
+}
@@ -0,0 +1,8 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The subset of the creator vault contract owner proceeds are routed into
+#[allow(dead_code)]
+#[contractclient(name = "RevenueVaultClient")]
+pub trait RevenueVault {
+    fn deposit(env: Env, from: Address, token: Address, ip_id: u64, amount: i128);
+}
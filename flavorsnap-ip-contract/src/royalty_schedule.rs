@@ -0,0 +1,135 @@
+//! Step-up royalty schedules agreed per license, applied to reported usage
+
+use crate::{
+    events, storage, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient,
+    RoyaltySchedule, BPS_DENOMINATOR
+};
+use flavorsnap_common::{bps_of, mul_div, Rounding};
+use soroban_sdk::{contractimpl, Address, BytesN, Env, String, Vec};
+
+/// Most steps in one royalty schedule
+const MAX_ROYALTY_STEPS: u32 = 8;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Agree the royalty schedule `report_usage` applies to `licensee`'s
+    /// license, or remove it with `None` (Requires IP owner approval and the
+    /// licensee's signature). Steps must start at threshold 0 with strictly
+    /// ascending thresholds.
+    pub fn set_royalty_schedule(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        licensee: Address,
+        schedule: Option<RoyaltySchedule>,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+        licensee.require_auth();
+        storage::read_license(&env, ip_id, &licensee)?;
+
+        if let Some(schedule) = &schedule {
+            Self::require_valid_schedule(schedule)?;
+        }
+        storage::write_royalty_schedule(&env, ip_id, &licensee, &schedule);
+
+        events::royalty_schedule_set(&env, ip_id, licensee, schedule.map_or(0, |schedule| schedule.steps.len()));
+        Ok(())
+    }
+
+    pub fn get_royalty_schedule(env: Env, ip_id: u64, licensee: Address) -> Option<RoyaltySchedule> {
+        storage::read_royalty_schedule(&env, ip_id, &licensee)
+    }
+
+    /// Cumulative units reported through `report_usage`
+    pub fn get_reported_units(env: Env, ip_id: u64, licensee: Address) -> i128 {
+        storage::read_usage_units(&env, ip_id, &licensee)
+    }
+
+    /// Report `units` of usage that earned `revenue`, paying the royalty the
+    /// license's schedule puts on it. Authorization, idempotency, memo and
+    /// royalty history work as in `pay_usage_royalty`. Returns the report id.
+    pub fn report_usage(
+        env: Env,
+        caller: Address,
+        licensee: Address,
+        ip_id: u64,
+        units: i128,
+        revenue: i128,
+        report_hash: BytesN<32>,
+        idempotency_key: Option<BytesN<32>>,
+        memo: Option<String>,
+    ) -> Result<u32, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        Self::require_valid_memo(&memo)?;
+        caller.require_auth();
+        if units <= 0 || revenue <= 0 {
+            return Err(IPError::InvalidAmount);
+        }
+
+        let schedule = storage::read_royalty_schedule(&env, ip_id, &licensee).ok_or(IPError::NoRoyaltySchedule)?;
+        let reported = storage::read_usage_units(&env, ip_id, &licensee);
+        let rounding = storage::read_ip(&env, ip_id)?.rounding;
+        let amount = if schedule.by_volume {
+            Self::volume_royalty(&schedule, reported, units, revenue, rounding)?
+        } else {
+            let license = storage::read_license(&env, ip_id, &licensee)?;
+            let age = env.ledger().timestamp().saturating_sub(license.issued_at) as i128;
+            let bps = schedule.steps.iter()
+                .take_while(|step| step.threshold <= age)
+                .last()
+                .map_or(0, |step| step.bps);
+            bps_of(revenue, bps, rounding)
+        };
+        storage::write_usage_units(&env, ip_id, &licensee, reported + units);
+
+        Self::settle_royalty(&env, caller, licensee, ip_id, amount, report_hash, idempotency_key, memo)
+    }
+}
+
+impl IPLicensingContract {
+    fn require_valid_schedule(schedule: &RoyaltySchedule) -> Result<(), IPError> {
+        let steps = &schedule.steps;
+        if steps.is_empty() || steps.len() > MAX_ROYALTY_STEPS || steps.first().unwrap().threshold != 0 {
+            return Err(IPError::InvalidSchedule);
+        }
+        let mut previous = -1;
+        for step in steps.iter() {
+            if step.threshold <= previous || step.bps as i128 > BPS_DENOMINATOR {
+                return Err(IPError::InvalidSchedule);
+            }
+            previous = step.threshold;
+        }
+        Ok(())
+    }
+
+    /// Royalty on units `reported..reported + units`, each bracket of the
+    /// schedule charging its rate on its share of `revenue`. Shares are cut
+    /// at rounded cumulative offsets so together they are exactly `revenue`.
+    fn volume_royalty(
+        schedule: &RoyaltySchedule,
+        reported: i128,
+        units: i128,
+        revenue: i128,
+        rounding: Rounding,
+    ) -> Result<i128, IPError> {
+        revenue.checked_mul(units).ok_or(IPError::InvalidAmount)?;
+        let revenue_through = |unit: i128| mul_div(revenue, unit - reported, units, rounding);
+
+        let end = reported + units;
+        let mut royalty = 0;
+        for (index, step) in schedule.steps.iter().enumerate() {
+            let bracket_end = schedule.steps.get(index as u32 + 1).map_or(i128::MAX, |next| next.threshold);
+            let (from, to) = (reported.max(step.threshold), end.min(bracket_end));
+            if to <= from {
+                continue;
+            }
+            royalty += bps_of(revenue_through(to) - revenue_through(from), step.bps, rounding);
+        }
+        Ok(royalty)
+    }
+}
@@ -0,0 +1,76 @@
+//! Royalties paid from another asset, swapped into the IP's payment token
+//! through the configured AMM router within the payment call
+
+use crate::{
+    events, storage, swap_router::SwapRouterClient, ExtError, IPError, IPLicensingContract, IPLicensingContractArgs,
+    IPLicensingContractClient, Role
+};
+use soroban_sdk::{contractimpl, panic_with_error, symbol_short, vec, Address, BytesN, Env, String};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Set or clear the AMM router royalties may be swapped through (Only
+    /// callable by an Admin)
+    pub fn set_swap_router(env: Env, admin: Address, router: Option<Address>) -> Result<(), IPError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        storage::write_swap_router(&env, &router);
+        Self::audit(&env, symbol_short!("router"), &admin, (router,));
+        Ok(())
+    }
+
+    pub fn get_swap_router(env: Env) -> Option<Address> {
+        storage::read_swap_router(&env)
+    }
+
+    /// Pay a royalty of `amount` in the IP's payment token as the licensee,
+    /// funding it by swapping at most `max_amount_in` of `token_in` through
+    /// the swap router before `deadline`. Otherwise works as
+    /// `pay_usage_royalty`; a failed payment undoes the swap. Returns the
+    /// report id.
+    pub fn pay_royalty_with_swap(
+        env: Env,
+        licensee: Address,
+        ip_id: u64,
+        amount: i128,
+        token_in: Address,
+        max_amount_in: i128,
+        deadline: u64,
+        report_hash: BytesN<32>,
+        idempotency_key: Option<BytesN<32>>,
+        memo: Option<String>,
+    ) -> Result<u32, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        Self::require_valid_memo(&memo)?;
+        licensee.require_auth();
+
+        let router = storage::read_swap_router(&env)
+            .unwrap_or_else(|| panic_with_error!(&env, ExtError::SwapRouterNotSet));
+        if env.ledger().timestamp() > deadline {
+            return Err(IPError::DeadlineExceeded);
+        }
+        if amount <= 0 || max_amount_in <= 0 {
+            return Err(IPError::InvalidAmount);
+        }
+        let payment_token = storage::read_ip(&env, ip_id)?.payment_token;
+        if token_in == payment_token {
+            return Err(IPError::UnsupportedToken);
+        }
+
+        // The router pulls `token_in` from the licensee and pays them the
+        // royalty, which is then collected as usual
+        let path = vec![&env, token_in.clone(), payment_token];
+        let amounts = SwapRouterClient::new(&env, &router)
+            .swap_tokens_for_exact_tokens(&amount, &max_amount_in, &path, &licensee, &deadline);
+        let amount_in = amounts.first().unwrap_or(i128::MAX);
+        if amount_in > max_amount_in || amounts.last() != Some(amount) {
+            return Err(IPError::SlippageExceeded);
+        }
+
+        events::royalty_swapped(&env, ip_id, licensee.clone(), token_in, amount_in, amount);
+        Self::settle_royalty(&env, licensee.clone(), licensee, ip_id, amount, report_hash, idempotency_key, memo)
+    }
+}
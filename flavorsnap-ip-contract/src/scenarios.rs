@@ -0,0 +1,264 @@
+#![cfg(test)]
+
+//! Deterministic test setups built from named parties, and golden assertions
+//! over the contract's full storage.
+//!
+//! Goldens live in `test_goldens/<name>.txt`, one storage entry per line in a
+//! stable order. Run the tests with `UPDATE_GOLDENS=1` to rewrite them after an
+//! intended storage change and review the diff like any other code.
+
+extern crate std;
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token,
+    xdr::{ContractDataEntry, LedgerEntryData, ScAddress, ScVal},
+    vec, Env, String,
+};
+use std::{format, string::String as StdString, vec::Vec as StdVec};
+
+/// Ledger timestamp every scenario starts at
+pub(crate) const START_TIME: u64 = 1_000;
+
+pub(crate) struct ScenarioBuilder {
+    fee_bps: u32,
+    ips: StdVec<(u64, &'static str, i128, i128)>,
+    licenses: StdVec<(u64, &'static str, LicenseType)>,
+    funds: StdVec<(&'static str, i128)>,
+}
+
+impl ScenarioBuilder {
+    pub(crate) fn new() -> Self {
+        ScenarioBuilder { fee_bps: 0, ips: StdVec::new(), licenses: StdVec::new(), funds: StdVec::new() }
+    }
+
+    /// Platform fee taken from every payment
+    pub(crate) fn with_fee_bps(mut self, fee_bps: u32) -> Self {
+        self.fee_bps = fee_bps;
+        self
+    }
+
+    /// An IP registered by `owner` with the given exclusive and non-exclusive prices
+    pub(crate) fn with_ip(mut self, ip_id: u64, owner: &'static str, price_exclusive: i128, price_non_exclusive: i128) -> Self {
+        self.ips.push((ip_id, owner, price_exclusive, price_non_exclusive));
+        self
+    }
+
+    /// A license bought by `licensee` at list price, minted to them just before
+    pub(crate) fn with_license(mut self, ip_id: u64, licensee: &'static str, license_type: LicenseType) -> Self {
+        self.licenses.push((ip_id, licensee, license_type));
+        self
+    }
+
+    /// Tokens minted to `party` once every license has been bought
+    pub(crate) fn with_funds(mut self, party: &'static str, amount: i128) -> Self {
+        self.funds.push((party, amount));
+        self
+    }
+
+    pub(crate) fn build(self) -> Scenario {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = START_TIME);
+
+        let mut parties = StdVec::new();
+        let names = ["admin", "treasury"].into_iter()
+            .chain(self.ips.iter().map(|ip| ip.1))
+            .chain(self.licenses.iter().map(|license| license.1))
+            .chain(self.funds.iter().map(|funds| funds.0));
+        for name in names {
+            if !parties.iter().any(|(known, _)| *known == name) {
+                parties.push((name, Address::generate(&env)));
+            }
+        }
+
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+        let mut scenario = Scenario { env, contract: token.clone(), token, parties };
+        scenario.contract = scenario.env.register(
+            IPLicensingContract,
+            (scenario.party("admin"), scenario.party("treasury"), self.fee_bps),
+        );
+
+        let client = scenario.client();
+        let minter = token::StellarAssetClient::new(&scenario.env, &scenario.token);
+        let metadata = String::from_str(&scenario.env, "ipfs://metadata");
+        for (ip_id, owner, price_exclusive, price_non_exclusive) in self.ips {
+            let owner = scenario.party(owner);
+            let tags = vec![&scenario.env];
+            client.register_ip(&owner, &ip_id, &metadata, &None, &price_exclusive, &price_non_exclusive, &scenario.token, &tags);
+        }
+        for (ip_id, licensee, license_type) in self.licenses {
+            let licensee = scenario.party(licensee);
+            let price = client.quote_price(&ip_id, &license_type).0;
+            minter.mint(&licensee, &price);
+            client.purchase_license(&licensee, &ip_id, &license_type, &None, &None, &u64::MAX, &None, &None);
+        }
+        for (party, amount) in self.funds {
+            minter.mint(&scenario.party(party), &amount);
+        }
+        scenario
+    }
+}
+
+pub(crate) struct Scenario {
+    pub(crate) env: Env,
+    pub(crate) contract: Address,
+    pub(crate) token: Address,
+    parties: StdVec<(&'static str, Address)>,
+}
+
+impl Scenario {
+    pub(crate) fn client(&self) -> IPLicensingContractClient<'_> {
+        IPLicensingContractClient::new(&self.env, &self.contract)
+    }
+
+    pub(crate) fn token_client(&self) -> token::Client<'_> {
+        token::Client::new(&self.env, &self.token)
+    }
+
+    /// The address behind a name used while building
+    pub(crate) fn party(&self, name: &str) -> Address {
+        self.parties.iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, address)| address.clone())
+            .unwrap_or_else(|| panic!("unknown party {name}"))
+    }
+
+    /// Token balance of a named party
+    pub(crate) fn balance(&self, name: &str) -> i128 {
+        self.token_client().balance(&self.party(name))
+    }
+
+    pub(crate) fn advance_time(&self, secs: u64) {
+        self.env.ledger().with_mut(|li| li.timestamp += secs);
+    }
+
+    /// Every storage entry of the contract, one per line, sorted. Instance
+    /// storage is expanded into one line per key.
+    pub(crate) fn storage_dump(&self) -> StdString {
+        let contract = ScAddress::from(&self.contract);
+        let mut lines = StdVec::new();
+        for (_, (entry, _)) in self.env.to_ledger_snapshot().ledger_entries {
+            let LedgerEntryData::ContractData(ContractDataEntry { contract: owner, key, durability, val, .. }) = entry.data
+            else {
+                continue;
+            };
+            if owner != contract {
+                continue;
+            }
+            match (key, val) {
+                (ScVal::LedgerKeyContractInstance, ScVal::ContractInstance(instance)) => {
+                    for item in instance.storage.iter().flat_map(|map| map.iter()) {
+                        lines.push(format!("instance {:?} = {:?}", item.key, item.val));
+                    }
+                }
+                (key, val) => lines.push(format!("{durability:?} {key:?} = {val:?}")),
+            }
+        }
+        lines.sort();
+        lines.join("\n") + "\n"
+    }
+
+    /// Compare `storage_dump` against `test_goldens/<name>.txt`
+    pub(crate) fn assert_golden(&self, name: &str) {
+        let path = format!("{}/test_goldens/{name}.txt", env!("CARGO_MANIFEST_DIR"));
+        let actual = self.storage_dump();
+        if std::env::var_os("UPDATE_GOLDENS").is_some() {
+            std::fs::create_dir_all(format!("{}/test_goldens", env!("CARGO_MANIFEST_DIR"))).unwrap();
+            std::fs::write(&path, &actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("missing golden {path}; run with UPDATE_GOLDENS=1 to create it"));
+        assert!(actual == expected, "storage differs from golden {path}; run with UPDATE_GOLDENS=1 and review the diff");
+    }
+}
+
+#[test]
+fn test_scenario_purchase_matches_golden() {
+    let s = ScenarioBuilder::new()
+        .with_fee_bps(250)
+        .with_ip(1, "chef", 500, 100)
+        .with_license(1, "bistro", LicenseType::NonExclusive)
+        .build();
+
+    assert_eq!(s.balance("bistro"), 0);
+    assert_eq!(s.balance("treasury"), 2);
+    assert_eq!(s.client().get_claimable(&s.party("chef"), &s.token), 98);
+    s.assert_golden("purchase");
+}
+
+#[test]
+fn test_scenario_revocation_and_appeal() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 500, 100)
+        .with_license(1, "bistro", LicenseType::NonExclusive)
+        .with_license(1, "cafe", LicenseType::NonExclusive)
+        .build();
+    let client = s.client();
+    let owner = vec![&s.env, s.party("chef")];
+    let reason = Symbol::new(&s.env, "breach");
+
+    client.set_appeal_window(&owner, &1, &Some(100));
+    client.revoke_license(&owner, &s.party("bistro"), &1, &reason);
+    client.revoke_license(&owner, &s.party("cafe"), &1, &reason);
+    assert_eq!(client.get_ip(&1).active_licenses, 0);
+
+    client.appeal_revocation(&s.party("bistro"), &1);
+    s.advance_time(101);
+    let res = client.try_appeal_revocation(&s.party("cafe"), &1);
+    assert_eq!(res, Err(Ok(IPError::AppealClosed)));
+    client.resolve_appeal(&s.party("admin"), &1, &s.party("bistro"), &true);
+    assert!(client.get_license(&1, &s.party("bistro")).is_active);
+    s.assert_golden("revocation_appeal");
+}
+
+#[test]
+fn test_scenario_royalties_accrue_and_claim() {
+    let s = ScenarioBuilder::new()
+        .with_fee_bps(1_000)
+        .with_ip(1, "chef", 500, 100)
+        .with_ip(2, "chef", 800, 200)
+        .with_license(1, "bistro", LicenseType::NonExclusive)
+        .with_license(2, "bistro", LicenseType::Exclusive)
+        .with_funds("bistro", 300)
+        .build();
+    let client = s.client();
+    let bistro = s.party("bistro");
+
+    client.pay_usage_royalty(&bistro, &bistro, &1, &100, &BytesN::from_array(&s.env, &[1; 32]), &None, &None);
+    client.pay_usage_royalty(&bistro, &bistro, &2, &200, &BytesN::from_array(&s.env, &[1; 32]), &None, &None);
+    assert_eq!(client.get_ip_stats(&2).total_royalties, 200);
+    assert_eq!(s.balance("treasury"), 10 + 80 + 10 + 20);
+    assert_eq!(client.claim(&s.party("chef"), &s.token), 90 + 720 + 90 + 180);
+    assert_eq!(s.balance("bistro"), 0);
+    s.assert_golden("royalties");
+}
+
+#[test]
+fn test_scenario_error_paths_leave_storage_untouched() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 500, 100)
+        .with_license(1, "bistro", LicenseType::Exclusive)
+        .with_funds("cafe", 1_000)
+        .build();
+    let client = s.client();
+    let before = s.storage_dump();
+    let cafe = s.party("cafe");
+
+    let res = client.try_purchase_license(&cafe, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::ExclusiveAlreadyIssued)));
+    let res = client.try_purchase_license(&cafe, &2, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::IPNotFound)));
+    let res = client.try_pay_usage_royalty(&cafe, &cafe, &1, &10, &BytesN::from_array(&s.env, &[1; 32]), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::LicenseNotFound)));
+    let res = client.try_pay_usage_royalty(&s.party("bistro"), &s.party("bistro"), &1, &0, &BytesN::from_array(&s.env, &[1; 32]), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::InvalidAmount)));
+    let res = client.try_revoke_license(&vec![&s.env, cafe.clone()], &s.party("bistro"), &1, &Symbol::new(&s.env, "breach"));
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+
+    assert_eq!(s.storage_dump(), before);
+    assert_eq!(s.balance("cafe"), 1_000);
+    s.assert_golden("error_paths");
+}
@@ -0,0 +1,27 @@
+//! On-chain tag index over registered IPs (`search` feature)
+
+use crate::{
+    config, storage, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient
+};
+use flavorsnap_common::PageCursor;
+use soroban_sdk::{contractimpl, Env, Symbol, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// List ids of IPs registered with `tag`, in registration order, at most
+    /// `max_batch_size` per call. Deleted IPs are skipped, so a page may be
+    /// shorter than `limit`.
+    pub fn search_by_tag(env: Env, tag: Symbol, start: u32, limit: u32) -> Vec<u64> {
+        let index = storage::tag_index(&tag);
+        let cursor = PageCursor::new(start, limit, config::max_batch_size(&env), index.len(&env));
+
+        let mut page = Vec::new(&env);
+        for ip_id in index.page(&env, &cursor).iter() {
+            // Skip deleted IPs and ids re-registered since without this tag
+            if storage::read_ip(&env, ip_id).is_ok_and(|ip| ip.tags.contains(&tag)) {
+                page.push_back(ip_id);
+            }
+        }
+        page
+    }
+}
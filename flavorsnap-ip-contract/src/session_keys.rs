@@ -0,0 +1,88 @@
+//! Session keys: bounded, expiring keys that pay a license's royalties from
+//! the licensee's token allowance, so frequent micro-royalties do not need
+//! the licensee's main key on every payment
+
+use crate::{
+    events, storage, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient, SessionKey
+};
+use soroban_sdk::{contractimpl, Address, Env};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Let `key` pay royalties on the licensee's license of the IP, like an
+    /// operator but only up to `max_per_call` a payment and `max_total`
+    /// overall, until `expires_at`. Replaces any session key the license
+    /// had, restarting the spend count.
+    pub fn set_session_key(
+        env: Env,
+        licensee: Address,
+        ip_id: u64,
+        key: Address,
+        max_per_call: i128,
+        max_total: i128,
+        expires_at: u64,
+    ) -> Result<(), IPError> {
+        licensee.require_auth();
+
+        let license = storage::read_license(&env, ip_id, &licensee)?;
+        if !license.is_active {
+            return Err(IPError::LicenseNotFound);
+        }
+        if max_per_call <= 0 || max_total < max_per_call {
+            return Err(IPError::InvalidAmount);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            return Err(IPError::InvalidPeriod);
+        }
+
+        let session = SessionKey { key: key.clone(), max_per_call, max_total, spent: 0, expires_at };
+        storage::write_session_key(&env, ip_id, &licensee, &Some(session));
+
+        events::session_key_set(&env, ip_id, licensee, key, max_total, expires_at);
+        Ok(())
+    }
+
+    /// Withdraw the license's session key. Returns whether it had one.
+    pub fn revoke_session_key(env: Env, licensee: Address, ip_id: u64) -> bool {
+        licensee.require_auth();
+
+        let Some(session) = storage::read_session_key(&env, ip_id, &licensee) else {
+            return false;
+        };
+        storage::write_session_key(&env, ip_id, &licensee, &None);
+
+        events::session_key_revoked(&env, ip_id, licensee, session.key);
+        true
+    }
+
+    pub fn get_session_key(env: Env, ip_id: u64, licensee: Address) -> Option<SessionKey> {
+        storage::read_session_key(&env, ip_id, &licensee)
+    }
+}
+
+impl IPLicensingContract {
+    /// Charge a royalty of `amount` against the license's session key if
+    /// `caller` is that key. `Ok(false)` when it is not, so other
+    /// authorizations can be tried.
+    pub(crate) fn spend_session_key(
+        env: &Env,
+        ip_id: u64,
+        licensee: &Address,
+        caller: &Address,
+        amount: i128,
+    ) -> Result<bool, IPError> {
+        let Some(mut session) = storage::read_session_key(env, ip_id, licensee).filter(|session| session.key == *caller) else {
+            return Ok(false);
+        };
+        if env.ledger().timestamp() >= session.expires_at {
+            return Err(IPError::Expired);
+        }
+        if amount > session.max_per_call || session.spent + amount > session.max_total {
+            return Err(IPError::CapReached);
+        }
+
+        session.spent += amount;
+        storage::write_session_key(env, ip_id, licensee, &Some(session));
+        Ok(true)
+    }
+}
@@ -0,0 +1,195 @@
+//! Fractional IP revenue shares (`shares` feature)
+//!
+//! Each IP's shares form a token addressed by the IP id. The entrypoints mirror
+//! the SEP-41 token interface with the share id as the leading argument, so
+//! wallets and exchanges can adapt a standard token integration per IP.
+
+use crate::{
+    events, storage, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient,
+    ShareAllowance, ShareHolding, ShareToken
+};
+use soroban_sdk::{contractimpl, token, Address, Env, String, Vec};
+
+const SHARE_DECIMALS: u32 = 7;
+const REVENUE_SCALE: i128 = 1_000_000_000_000;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Split the IP's future proceeds into `supply` shares minted to its owner
+    /// (Requires IP owner approval)
+    pub fn issue_shares(env: Env, approvers: Vec<Address>, ip_id: u64, supply: i128) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        if supply <= 0 {
+            return Err(IPError::InvalidAmount);
+        }
+        if storage::read_share_token(&env, ip_id).is_some() {
+            return Err(IPError::SharesAlreadyIssued);
+        }
+
+        let share_token = ShareToken {
+            payment_token: ip.payment_token,
+            total_supply: supply,
+            revenue_per_share: 0,
+        };
+        storage::write_share_token(&env, ip_id, &share_token);
+        storage::mark_undeletable(&env, ip_id);
+        storage::write_share_holding(&env, ip_id, &ip.owner, &ShareHolding {
+            balance: supply,
+            ..Default::default()
+        });
+
+        events::shares_issued(&env, ip_id, ip.owner, supply);
+        Ok(())
+    }
+
+    pub fn share_total_supply(env: Env, ip_id: u64) -> Result<i128, IPError> {
+        Ok(Self::read_share_token(&env, ip_id)?.total_supply)
+    }
+
+    pub fn share_balance(env: Env, ip_id: u64, id: Address) -> i128 {
+        storage::read_share_holding(&env, ip_id, &id).balance
+    }
+
+    pub fn share_decimals(_env: Env, _ip_id: u64) -> u32 {
+        SHARE_DECIMALS
+    }
+
+    pub fn share_name(env: Env, _ip_id: u64) -> String {
+        String::from_str(&env, "FlavorSnap IP Revenue Share")
+    }
+
+    pub fn share_symbol(env: Env, _ip_id: u64) -> String {
+        String::from_str(&env, "FSIPS")
+    }
+
+    pub fn share_allowance(env: Env, ip_id: u64, from: Address, spender: Address) -> i128 {
+        storage::read_share_allowance(&env, ip_id, &from, &spender).amount
+    }
+
+    /// Let `spender` move up to `amount` of `from`'s shares until `expiration_ledger`
+    pub fn share_approve(
+        env: Env,
+        ip_id: u64,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) -> Result<(), IPError> {
+        from.require_auth();
+        Self::read_share_token(&env, ip_id)?;
+
+        if amount < 0 || (amount > 0 && expiration_ledger < env.ledger().sequence()) {
+            return Err(IPError::InvalidAmount);
+        }
+
+        let allowance = ShareAllowance { amount, expiration_ledger };
+        storage::write_share_allowance(&env, ip_id, &from, &spender, &allowance);
+
+        events::share_approve(&env, ip_id, from, spender, amount, expiration_ledger);
+        Ok(())
+    }
+
+    pub fn share_transfer(env: Env, ip_id: u64, from: Address, to: Address, amount: i128) -> Result<(), IPError> {
+        from.require_auth();
+
+        Self::move_shares(&env, ip_id, &from, &to, amount)
+    }
+
+    pub fn share_transfer_from(
+        env: Env,
+        ip_id: u64,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), IPError> {
+        spender.require_auth();
+
+        let mut allowance = storage::read_share_allowance(&env, ip_id, &from, &spender);
+        if amount < 0 || allowance.amount < amount {
+            return Err(IPError::InsufficientShares);
+        }
+        allowance.amount -= amount;
+        storage::write_share_allowance(&env, ip_id, &from, &spender, &allowance);
+
+        Self::move_shares(&env, ip_id, &from, &to, amount)
+    }
+
+    /// Revenue accrued to `holder`'s shares and not yet claimed
+    pub fn claimable_share_revenue(env: Env, ip_id: u64, holder: Address) -> Result<i128, IPError> {
+        let share_token = Self::read_share_token(&env, ip_id)?;
+        let holding = Self::settled_holding(&env, ip_id, &share_token, &holder);
+        Ok(holding.unclaimed)
+    }
+
+    /// Pay out `holder`'s accrued revenue in the IP's payment token
+    pub fn claim_share_revenue(env: Env, ip_id: u64, holder: Address) -> Result<i128, IPError> {
+        holder.require_auth();
+
+        let share_token = Self::read_share_token(&env, ip_id)?;
+        let mut holding = Self::settled_holding(&env, ip_id, &share_token, &holder);
+        let amount = holding.unclaimed;
+        holding.unclaimed = 0;
+        storage::write_share_holding(&env, ip_id, &holder, &holding);
+
+        if amount > 0 {
+            token::Client::new(&env, &share_token.payment_token).transfer(
+                &env.current_contract_address(),
+                &holder,
+                &amount,
+            );
+        }
+
+        events::share_revenue_claimed(&env, ip_id, holder, amount);
+        Ok(amount)
+    }
+}
+
+impl IPLicensingContract {
+    /// Credit `amount`, already held by this contract, to the IP's share holders
+    pub(crate) fn accrue_share_revenue(env: &Env, ip_id: u64, mut share_token: ShareToken, amount: i128) {
+        if amount <= 0 {
+            return;
+        }
+        share_token.revenue_per_share += amount * REVENUE_SCALE / share_token.total_supply;
+        storage::write_share_token(env, ip_id, &share_token);
+    }
+
+    fn read_share_token(env: &Env, ip_id: u64) -> Result<ShareToken, IPError> {
+        storage::read_share_token(env, ip_id).ok_or(IPError::SharesNotIssued)
+    }
+
+    /// `holder`'s holding with revenue accrued since its last update moved into `unclaimed`
+    fn settled_holding(env: &Env, ip_id: u64, share_token: &ShareToken, holder: &Address) -> ShareHolding {
+        let mut holding = storage::read_share_holding(env, ip_id, holder);
+        let accrued = share_token.revenue_per_share - holding.revenue_snapshot;
+        holding.unclaimed += holding.balance * accrued / REVENUE_SCALE;
+        holding.revenue_snapshot = share_token.revenue_per_share;
+        holding
+    }
+
+    fn move_shares(env: &Env, ip_id: u64, from: &Address, to: &Address, amount: i128) -> Result<(), IPError> {
+        let share_token = Self::read_share_token(env, ip_id)?;
+        if amount < 0 {
+            return Err(IPError::InvalidAmount);
+        }
+
+        let mut sender = Self::settled_holding(env, ip_id, &share_token, from);
+        if sender.balance < amount {
+            return Err(IPError::InsufficientShares);
+        }
+        sender.balance -= amount;
+        storage::write_share_holding(env, ip_id, from, &sender);
+
+        let mut recipient = Self::settled_holding(env, ip_id, &share_token, to);
+        recipient.balance += amount;
+        storage::write_share_holding(env, ip_id, to, &recipient);
+
+        events::share_transfer(env, ip_id, from.clone(), to.clone(), amount);
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+//! Royalty statements over whole-day periods, aggregated from per-day
+//! royalty totals kept alongside the royalty history
+
+use crate::{storage, IPError, IPLicensingContract, IPLicensingContractArgs, IPLicensingContractClient, RoyaltyStatement};
+use soroban_sdk::{contractimpl, Address, Env, Map};
+
+/// Granularity of royalty aggregation; statement periods start and end on
+/// multiples of it
+pub(crate) const STATEMENT_DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Longest statement period, in days, so a statement reads a bounded number
+/// of entries
+const MAX_STATEMENT_DAYS: u64 = 31;
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Royalties paid on the IP in `[period_start, period_end)`, in total and
+    /// per licensee. Both bounds must fall on UTC day boundaries and span at
+    /// most 31 days, so a month is one call. Payments made before statements
+    /// were tracked are not included.
+    pub fn get_statement(env: Env, ip_id: u64, period_start: u64, period_end: u64) -> Result<RoyaltyStatement, IPError> {
+        storage::read_ip(&env, ip_id)?;
+
+        let aligned = period_start.is_multiple_of(STATEMENT_DAY_SECS) && period_end.is_multiple_of(STATEMENT_DAY_SECS);
+        if !aligned || period_end <= period_start || period_end - period_start > MAX_STATEMENT_DAYS * STATEMENT_DAY_SECS {
+            return Err(IPError::InvalidPeriod);
+        }
+
+        let mut total = 0;
+        let mut by_licensee = Map::new(&env);
+        for day in period_start / STATEMENT_DAY_SECS..period_end / STATEMENT_DAY_SECS {
+            let Some(totals) = storage::read_royalty_day(&env, ip_id, day) else {
+                continue;
+            };
+            for (licensee, amount) in totals.iter() {
+                by_licensee.set(licensee.clone(), by_licensee.get(licensee).unwrap_or(0) + amount);
+                total += amount;
+            }
+        }
+
+        Ok(RoyaltyStatement { ip_id, period_start, period_end, total, by_licensee })
+    }
+}
+
+impl IPLicensingContract {
+    /// Add a royalty payment to today's totals of the IP
+    pub(crate) fn record_royalty_day(env: &Env, ip_id: u64, licensee: &Address, amount: i128) {
+        let day = env.ledger().timestamp() / STATEMENT_DAY_SECS;
+        let mut totals = storage::read_royalty_day(env, ip_id, day).unwrap_or_else(|| Map::new(env));
+        totals.set(licensee.clone(), totals.get(licensee.clone()).unwrap_or(0) + amount);
+        storage::write_royalty_day(env, ip_id, day, &totals);
+    }
+}
@@ -0,0 +1,778 @@
+//! Typed accessors over the contract's storage keys
+
+use crate::{
+    Attachment, AttestationGate, Config, CreatorProfile, DataKey, DormancyPolicy, ExclusiveOption, ExtKey, FreeClaimPolicy, Handover, IndemnityBond, IPAsset, IPError, IPStats, KeeperReward, License, LicensingCalendar, Offer, OptionTerms, PreviewTerms, PriceDecay, PriceOracleConfig, ReferrerStats,
+    RoyaltyReport, RoyaltySchedule, SealedAuction, SealedBid, SessionKey, TokenGate
+};
+#[cfg(feature = "vouchers")]
+use crate::Voucher;
+#[cfg(feature = "shares")]
+use crate::{ShareAllowance, ShareHolding, ShareToken};
+#[cfg(feature = "search")]
+use soroban_sdk::Symbol;
+use flavorsnap_common::ChunkedVec;
+use soroban_sdk::{Address, BytesN, Env, Map, String, Vec};
+
+/// Nominal ledger close time, for sizing temporary entry TTLs
+const LEDGER_CLOSE_SECS: u64 = 5;
+
+pub(crate) fn read_config(env: &Env) -> Config {
+    env.storage().instance().get(&DataKey::Config).unwrap()
+}
+
+pub(crate) fn write_config(env: &Env, config: &Config) {
+    env.storage().instance().set(&DataKey::Config, config);
+}
+
+pub(crate) fn is_paused(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::Paused)
+}
+
+pub(crate) fn set_paused(env: &Env, paused: bool) {
+    if paused {
+        env.storage().instance().set(&DataKey::Paused, &true);
+    } else {
+        env.storage().instance().remove(&DataKey::Paused);
+    }
+}
+
+pub(crate) fn is_import_finalized(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::ImportFinalized)
+}
+
+pub(crate) fn finalize_import(env: &Env) {
+    env.storage().instance().set(&DataKey::ImportFinalized, &true);
+}
+
+pub(crate) fn read_terms_library(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::TermsLibrary)
+}
+
+pub(crate) fn write_terms_library(env: &Env, library: &Address) {
+    env.storage().instance().set(&DataKey::TermsLibrary, library);
+}
+
+pub(crate) fn read_swap_router(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&ExtKey::SwapRouter)
+}
+
+pub(crate) fn write_swap_router(env: &Env, router: &Option<Address>) {
+    match router {
+        Some(router) => env.storage().instance().set(&ExtKey::SwapRouter, router),
+        None => env.storage().instance().remove(&ExtKey::SwapRouter),
+    }
+}
+
+pub(crate) fn read_price_oracle(env: &Env) -> Option<PriceOracleConfig> {
+    env.storage().instance().get(&DataKey::PriceOracle)
+}
+
+pub(crate) fn write_price_oracle(env: &Env, config: &PriceOracleConfig) {
+    env.storage().instance().set(&DataKey::PriceOracle, config);
+}
+
+pub(crate) fn has_ip(env: &Env, ip_id: u64) -> bool {
+    env.storage().persistent().has(&DataKey::IP(ip_id))
+}
+
+pub(crate) fn read_ip(env: &Env, ip_id: u64) -> Result<IPAsset, IPError> {
+    env.storage().persistent().get(&DataKey::IP(ip_id)).ok_or(IPError::IPNotFound)
+}
+
+pub(crate) fn write_ip(env: &Env, ip_id: u64, ip: &IPAsset) {
+    env.storage().persistent().set(&DataKey::IP(ip_id), ip);
+}
+
+/// Append `ip_id` to the registration index the first time it is registered
+pub(crate) fn index_ip(env: &Env, ip_id: u64) {
+    if env.storage().persistent().has(&DataKey::IPIndexed(ip_id)) {
+        return;
+    }
+    ip_index().push(env, ip_id);
+    env.storage().persistent().set(&DataKey::IPIndexed(ip_id), &true);
+}
+
+/// Ids of registered IPs in first-registration order
+pub(crate) fn ip_index() -> ChunkedVec<DataKey, u64> {
+    ChunkedVec::new(DataKey::IPIndex)
+}
+
+pub(crate) fn remove_ip(env: &Env, ip_id: u64) {
+    env.storage().persistent().remove(&DataKey::IP(ip_id));
+}
+
+pub(crate) fn read_license(env: &Env, ip_id: u64, licensee: &Address) -> Result<License, IPError> {
+    env.storage().persistent()
+        .get(&DataKey::License(ip_id, licensee.clone()))
+        .ok_or(IPError::LicenseNotFound)
+}
+
+pub(crate) fn write_license(env: &Env, license: &License) {
+    let key = DataKey::License(license.ip_id, license.licensee.clone());
+    env.storage().persistent().set(&key, license);
+}
+
+/// Allocate the next sequential license certificate id
+pub(crate) fn next_license_id(env: &Env) -> u64 {
+    let license_id = read_license_count(env) + 1;
+    env.storage().instance().set(&DataKey::LicenseCount, &license_id);
+    license_id
+}
+
+/// Number of license certificates issued so far; ids run from 1 to this count
+pub(crate) fn read_license_count(env: &Env) -> u64 {
+    env.storage().instance().get(&DataKey::LicenseCount).unwrap_or(0)
+}
+
+/// Raise the certificate counter so imported ids are never re-issued
+pub(crate) fn reserve_license_id(env: &Env, license_id: u64) {
+    if license_id > read_license_count(env) {
+        env.storage().instance().set(&DataKey::LicenseCount, &license_id);
+    }
+}
+
+pub(crate) fn next_issuance_sequence(env: &Env) -> u64 {
+    let sequence = read_issuance_sequence(env) + 1;
+    env.storage().instance().set(&ExtKey::IssuanceSequence, &sequence);
+    sequence
+}
+
+/// Sequence number of the latest license issued, 0 before the first
+pub(crate) fn read_issuance_sequence(env: &Env) -> u64 {
+    env.storage().instance().get(&ExtKey::IssuanceSequence).unwrap_or(0)
+}
+
+/// Raise the issuance sequence so licenses issued after an import order after it
+pub(crate) fn reserve_issuance_sequence(env: &Env, sequence: u64) {
+    if sequence > read_issuance_sequence(env) {
+        env.storage().instance().set(&ExtKey::IssuanceSequence, &sequence);
+    }
+}
+
+pub(crate) fn read_license_holder(env: &Env, license_id: u64) -> Option<(u64, Address)> {
+    env.storage().persistent().get(&DataKey::LicenseById(license_id))
+}
+
+pub(crate) fn write_license_holder(env: &Env, license_id: u64, ip_id: u64, licensee: &Address) {
+    env.storage().persistent().set(&DataKey::LicenseById(license_id), &(ip_id, licensee.clone()));
+}
+
+/// IPs `licensee` has ever held a license on, in first-purchase order
+pub(crate) fn licensee_index(licensee: &Address) -> ChunkedVec<DataKey, u64> {
+    ChunkedVec::new(DataKey::LicenseeIndex(licensee.clone()))
+}
+
+pub(crate) fn add_to_licensee_index(env: &Env, licensee: &Address, ip_id: u64) {
+    let indexed_key = DataKey::LicenseeIndexed(licensee.clone(), ip_id);
+    if !env.storage().persistent().has(&indexed_key) {
+        licensee_index(licensee).push(env, ip_id);
+        env.storage().persistent().set(&indexed_key, &true);
+    }
+}
+
+/// Deregistration time of a tombstoned IP
+pub(crate) fn read_tombstone(env: &Env, ip_id: u64) -> Option<u64> {
+    env.storage().persistent().get(&DataKey::Tombstone(ip_id))
+}
+
+pub(crate) fn write_tombstone(env: &Env, ip_id: u64, deregistered_at: u64) {
+    env.storage().persistent().set(&DataKey::Tombstone(ip_id), &deregistered_at);
+}
+
+pub(crate) fn remove_tombstone(env: &Env, ip_id: u64) {
+    env.storage().persistent().remove(&DataKey::Tombstone(ip_id));
+}
+
+pub(crate) fn is_deregistered(env: &Env, ip_id: u64) -> bool {
+    env.storage().persistent().has(&DataKey::Tombstone(ip_id))
+}
+
+pub(crate) fn read_freeze_reason(env: &Env, ip_id: u64) -> Option<String> {
+    env.storage().persistent().get(&DataKey::Frozen(ip_id))
+}
+
+pub(crate) fn write_freeze_reason(env: &Env, ip_id: u64, reason: &String) {
+    if !is_frozen(env, ip_id) {
+        let position = frozen_index().push(env, ip_id);
+        env.storage().persistent().set(&ExtKey::FrozenPosition(ip_id), &position);
+    }
+    env.storage().persistent().set(&DataKey::Frozen(ip_id), reason);
+}
+
+pub(crate) fn remove_freeze_reason(env: &Env, ip_id: u64) {
+    let index = frozen_index();
+    if let Some(position) = env.storage().persistent().get::<_, u32>(&ExtKey::FrozenPosition(ip_id)) {
+        index.swap_remove(env, position);
+        if let Some(moved) = index.get(env, position) {
+            env.storage().persistent().set(&ExtKey::FrozenPosition(moved), &position);
+        }
+        env.storage().persistent().remove(&ExtKey::FrozenPosition(ip_id));
+    }
+    env.storage().persistent().remove(&DataKey::Frozen(ip_id));
+}
+
+/// Ids of IPs under legal hold; lifting a hold moves the latest one into its place
+pub(crate) fn frozen_index() -> ChunkedVec<DataKey, u64> {
+    ChunkedVec::new(DataKey::FrozenIps)
+}
+
+pub(crate) fn is_frozen(env: &Env, ip_id: u64) -> bool {
+    env.storage().persistent().has(&DataKey::Frozen(ip_id))
+}
+
+pub(crate) fn read_creator_profile(env: &Env, creator: &Address) -> Option<CreatorProfile> {
+    env.storage().persistent().get(&DataKey::CreatorProfile(creator.clone()))
+}
+
+pub(crate) fn write_creator_profile(env: &Env, creator: &Address, profile: &CreatorProfile) {
+    env.storage().persistent().set(&DataKey::CreatorProfile(creator.clone()), profile);
+}
+
+pub(crate) fn read_referrer_stats(env: &Env, referrer: &Address) -> ReferrerStats {
+    env.storage().persistent()
+        .get(&DataKey::ReferrerStats(referrer.clone()))
+        .unwrap_or_default()
+}
+
+pub(crate) fn write_referrer_stats(env: &Env, referrer: &Address, stats: &ReferrerStats) {
+    env.storage().persistent().set(&DataKey::ReferrerStats(referrer.clone()), stats);
+}
+
+pub(crate) fn read_ip_stats(env: &Env, ip_id: u64) -> IPStats {
+    env.storage().persistent()
+        .get(&DataKey::IPStats(ip_id))
+        .unwrap_or_default()
+}
+
+pub(crate) fn write_ip_stats(env: &Env, ip_id: u64, stats: &IPStats) {
+    env.storage().persistent().set(&DataKey::IPStats(ip_id), stats);
+}
+
+pub(crate) fn remove_ip_stats(env: &Env, ip_id: u64) {
+    env.storage().persistent().remove(&DataKey::IPStats(ip_id));
+}
+
+pub(crate) fn read_royalty_report_count(env: &Env, ip_id: u64) -> u32 {
+    env.storage().persistent().get(&DataKey::RoyaltyReportCount(ip_id)).unwrap_or(0)
+}
+
+/// Store `report` under the next id of `ip_id`'s royalty history
+pub(crate) fn append_royalty_report(env: &Env, ip_id: u64, report: &RoyaltyReport) {
+    env.storage().persistent().set(&DataKey::RoyaltyReport(ip_id, report.report_id), report);
+    env.storage().persistent().set(&DataKey::RoyaltyReportCount(ip_id), &(report.report_id + 1));
+}
+
+pub(crate) fn read_royalty_report(env: &Env, ip_id: u64, report_id: u32) -> Result<RoyaltyReport, IPError> {
+    env.storage().persistent()
+        .get(&DataKey::RoyaltyReport(ip_id, report_id))
+        .ok_or(IPError::ReportNotFound)
+}
+
+pub(crate) fn write_royalty_report(env: &Env, ip_id: u64, report: &RoyaltyReport) {
+    env.storage().persistent().set(&DataKey::RoyaltyReport(ip_id, report.report_id), report);
+}
+
+pub(crate) fn read_bond(env: &Env, ip_id: u64) -> Option<IndemnityBond> {
+    env.storage().persistent().get(&DataKey::Bond(ip_id))
+}
+
+pub(crate) fn write_bond(env: &Env, ip_id: u64, bond: &IndemnityBond) {
+    env.storage().persistent().set(&DataKey::Bond(ip_id), bond);
+}
+
+pub(crate) fn remove_bond(env: &Env, ip_id: u64) {
+    env.storage().persistent().remove(&DataKey::Bond(ip_id));
+}
+
+pub(crate) fn read_price_decay(env: &Env, ip_id: u64) -> Option<PriceDecay> {
+    env.storage().persistent().get(&DataKey::PriceDecay(ip_id))
+}
+
+pub(crate) fn write_price_decay(env: &Env, ip_id: u64, decay: &PriceDecay) {
+    env.storage().persistent().set(&DataKey::PriceDecay(ip_id), decay);
+}
+
+pub(crate) fn remove_price_decay(env: &Env, ip_id: u64) {
+    env.storage().persistent().remove(&DataKey::PriceDecay(ip_id));
+}
+
+pub(crate) fn read_token_gate(env: &Env, ip_id: u64) -> Option<TokenGate> {
+    env.storage().persistent().get(&DataKey::TokenGate(ip_id))
+}
+
+pub(crate) fn write_token_gate(env: &Env, ip_id: u64, gate: &Option<TokenGate>) {
+    match gate {
+        Some(gate) => env.storage().persistent().set(&DataKey::TokenGate(ip_id), gate),
+        None => env.storage().persistent().remove(&DataKey::TokenGate(ip_id)),
+    }
+}
+
+pub(crate) fn read_attestation_gate(env: &Env, ip_id: u64) -> Option<AttestationGate> {
+    env.storage().persistent().get(&ExtKey::AttestationGate(ip_id))
+}
+
+pub(crate) fn write_attestation_gate(env: &Env, ip_id: u64, gate: &Option<AttestationGate>) {
+    match gate {
+        Some(gate) => env.storage().persistent().set(&ExtKey::AttestationGate(ip_id), gate),
+        None => env.storage().persistent().remove(&ExtKey::AttestationGate(ip_id)),
+    }
+}
+
+pub(crate) fn read_attachments(env: &Env, ip_id: u64) -> Vec<Attachment> {
+    env.storage().persistent().get(&ExtKey::Attachments(ip_id)).unwrap_or_else(|| Vec::new(env))
+}
+
+pub(crate) fn write_attachments(env: &Env, ip_id: u64, attachments: &Vec<Attachment>) {
+    if attachments.is_empty() {
+        env.storage().persistent().remove(&ExtKey::Attachments(ip_id));
+    } else {
+        env.storage().persistent().set(&ExtKey::Attachments(ip_id), attachments);
+    }
+}
+
+pub(crate) fn read_licensing_calendar(env: &Env, ip_id: u64) -> Option<LicensingCalendar> {
+    env.storage().persistent().get(&ExtKey::LicensingCalendar(ip_id))
+}
+
+pub(crate) fn write_licensing_calendar(env: &Env, ip_id: u64, calendar: &Option<LicensingCalendar>) {
+    match calendar {
+        Some(calendar) => env.storage().persistent().set(&ExtKey::LicensingCalendar(ip_id), calendar),
+        None => env.storage().persistent().remove(&ExtKey::LicensingCalendar(ip_id)),
+    }
+}
+
+pub(crate) fn read_free_claim_policy(env: &Env, ip_id: u64) -> Option<FreeClaimPolicy> {
+    env.storage().persistent().get(&DataKey::FreeClaimPolicy(ip_id))
+}
+
+pub(crate) fn write_free_claim_policy(env: &Env, ip_id: u64, policy: &Option<FreeClaimPolicy>) {
+    match policy {
+        Some(policy) => env.storage().persistent().set(&DataKey::FreeClaimPolicy(ip_id), policy),
+        None => env.storage().persistent().remove(&DataKey::FreeClaimPolicy(ip_id)),
+    }
+}
+
+pub(crate) fn read_claim_bond(env: &Env, ip_id: u64, licensee: &Address) -> Option<(Address, i128)> {
+    env.storage().persistent().get(&DataKey::ClaimBond(ip_id, licensee.clone()))
+}
+
+pub(crate) fn write_claim_bond(env: &Env, ip_id: u64, licensee: &Address, bond: &Option<(Address, i128)>) {
+    let key = DataKey::ClaimBond(ip_id, licensee.clone());
+    match bond {
+        Some(bond) => env.storage().persistent().set(&key, bond),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+pub(crate) fn read_last_free_claim(env: &Env, claimer: &Address) -> Option<u64> {
+    env.storage().persistent().get(&DataKey::LastFreeClaim(claimer.clone()))
+}
+
+pub(crate) fn write_last_free_claim(env: &Env, claimer: &Address, claimed_at: u64) {
+    env.storage().persistent().set(&DataKey::LastFreeClaim(claimer.clone()), &claimed_at);
+}
+
+pub(crate) fn read_royalty_day(env: &Env, ip_id: u64, day: u64) -> Option<Map<Address, i128>> {
+    env.storage().persistent().get(&DataKey::RoyaltyDay(ip_id, day))
+}
+
+pub(crate) fn write_royalty_day(env: &Env, ip_id: u64, day: u64, totals: &Map<Address, i128>) {
+    env.storage().persistent().set(&DataKey::RoyaltyDay(ip_id, day), totals);
+}
+
+pub(crate) fn read_royalty_schedule(env: &Env, ip_id: u64, licensee: &Address) -> Option<RoyaltySchedule> {
+    env.storage().persistent().get(&DataKey::RoyaltySchedule(ip_id, licensee.clone()))
+}
+
+pub(crate) fn write_royalty_schedule(env: &Env, ip_id: u64, licensee: &Address, schedule: &Option<RoyaltySchedule>) {
+    let key = DataKey::RoyaltySchedule(ip_id, licensee.clone());
+    match schedule {
+        Some(schedule) => env.storage().persistent().set(&key, schedule),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+pub(crate) fn read_usage_units(env: &Env, ip_id: u64, licensee: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::UsageUnits(ip_id, licensee.clone())).unwrap_or(0)
+}
+
+pub(crate) fn write_usage_units(env: &Env, ip_id: u64, licensee: &Address, units: i128) {
+    env.storage().persistent().set(&DataKey::UsageUnits(ip_id, licensee.clone()), &units);
+}
+
+pub(crate) fn read_dormancy_policy(env: &Env, ip_id: u64) -> Option<DormancyPolicy> {
+    env.storage().persistent().get(&DataKey::Dormancy(ip_id))
+}
+
+pub(crate) fn write_dormancy_policy(env: &Env, ip_id: u64, policy: &Option<DormancyPolicy>) {
+    match policy {
+        Some(policy) => env.storage().persistent().set(&DataKey::Dormancy(ip_id), policy),
+        None => env.storage().persistent().remove(&DataKey::Dormancy(ip_id)),
+    }
+}
+
+pub(crate) fn read_dormancy_notice(env: &Env, ip_id: u64) -> Option<(Address, u64)> {
+    env.storage().persistent().get(&DataKey::DormancyNotice(ip_id))
+}
+
+pub(crate) fn write_dormancy_notice(env: &Env, ip_id: u64, notice: &Option<(Address, u64)>) {
+    match notice {
+        Some(notice) => env.storage().persistent().set(&DataKey::DormancyNotice(ip_id), notice),
+        None => env.storage().persistent().remove(&DataKey::DormancyNotice(ip_id)),
+    }
+}
+
+/// Next conflict group id, starting at 1
+pub(crate) fn next_conflict_group_id(env: &Env) -> u64 {
+    let group_id = env.storage().instance().get(&DataKey::ConflictGroupCount).unwrap_or(0u64) + 1;
+    env.storage().instance().set(&DataKey::ConflictGroupCount, &group_id);
+    group_id
+}
+
+pub(crate) fn read_conflict_group(env: &Env, group_id: u64) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&DataKey::ConflictGroup(group_id))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub(crate) fn write_conflict_group(env: &Env, group_id: u64, ip_ids: &Vec<u64>) {
+    if ip_ids.is_empty() {
+        env.storage().persistent().remove(&DataKey::ConflictGroup(group_id));
+    } else {
+        env.storage().persistent().set(&DataKey::ConflictGroup(group_id), ip_ids);
+    }
+}
+
+pub(crate) fn read_conflict_group_of(env: &Env, ip_id: u64) -> Option<u64> {
+    env.storage().persistent().get(&DataKey::ConflictGroupOf(ip_id))
+}
+
+pub(crate) fn write_conflict_group_of(env: &Env, ip_id: u64, group_id: Option<u64>) {
+    match group_id {
+        Some(group_id) => env.storage().persistent().set(&DataKey::ConflictGroupOf(ip_id), &group_id),
+        None => env.storage().persistent().remove(&DataKey::ConflictGroupOf(ip_id)),
+    }
+}
+
+/// Next offer id, starting at 1
+pub(crate) fn next_offer_id(env: &Env) -> u64 {
+    let offer_id = env.storage().instance().get(&DataKey::OfferCount).unwrap_or(0u64) + 1;
+    env.storage().instance().set(&DataKey::OfferCount, &offer_id);
+    offer_id
+}
+
+pub(crate) fn read_offer(env: &Env, offer_id: u64) -> Result<Offer, IPError> {
+    env.storage().persistent().get(&DataKey::Offer(offer_id)).ok_or(IPError::OfferNotFound)
+}
+
+pub(crate) fn write_offer(env: &Env, offer: &Offer) {
+    env.storage().persistent().set(&DataKey::Offer(offer.offer_id), offer);
+}
+
+pub(crate) fn remove_offer(env: &Env, offer_id: u64) {
+    env.storage().persistent().remove(&DataKey::Offer(offer_id));
+}
+
+pub(crate) fn read_open_offers(env: &Env, ip_id: u64) -> u32 {
+    env.storage().persistent().get(&ExtKey::OpenOffers(ip_id)).unwrap_or(0)
+}
+
+pub(crate) fn write_open_offers(env: &Env, ip_id: u64, count: u32) {
+    if count == 0 {
+        env.storage().persistent().remove(&ExtKey::OpenOffers(ip_id));
+    } else {
+        env.storage().persistent().set(&ExtKey::OpenOffers(ip_id), &count);
+    }
+}
+
+pub(crate) fn mark_undeletable(env: &Env, ip_id: u64) {
+    env.storage().persistent().set(&ExtKey::Undeletable(ip_id), &true);
+}
+
+pub(crate) fn is_undeletable(env: &Env, ip_id: u64) -> bool {
+    env.storage().persistent().has(&ExtKey::Undeletable(ip_id))
+}
+
+/// Ids of the IPs `owner` holds; losing one moves their latest into its place
+pub(crate) fn owner_ips(owner: &Address) -> ChunkedVec<ExtKey, u64> {
+    ChunkedVec::new(ExtKey::OwnerIps(owner.clone()))
+}
+
+pub(crate) fn add_owner_ip(env: &Env, owner: &Address, ip_id: u64) {
+    if !env.storage().persistent().has(&ExtKey::OwnerIpPosition(ip_id)) {
+        let position = owner_ips(owner).push(env, ip_id);
+        env.storage().persistent().set(&ExtKey::OwnerIpPosition(ip_id), &position);
+    }
+}
+
+pub(crate) fn remove_owner_ip(env: &Env, owner: &Address, ip_id: u64) {
+    let ip_ids = owner_ips(owner);
+    if let Some(position) = env.storage().persistent().get::<_, u32>(&ExtKey::OwnerIpPosition(ip_id)) {
+        ip_ids.swap_remove(env, position);
+        if let Some(moved) = ip_ids.get(env, position) {
+            env.storage().persistent().set(&ExtKey::OwnerIpPosition(moved), &position);
+        }
+        env.storage().persistent().remove(&ExtKey::OwnerIpPosition(ip_id));
+    }
+}
+
+pub(crate) fn read_open_disputes(env: &Env, ip_id: u64) -> u32 {
+    env.storage().persistent().get(&ExtKey::OpenDisputes(ip_id)).unwrap_or(0)
+}
+
+pub(crate) fn write_open_disputes(env: &Env, ip_id: u64, count: u32) {
+    if count == 0 {
+        env.storage().persistent().remove(&ExtKey::OpenDisputes(ip_id));
+    } else {
+        env.storage().persistent().set(&ExtKey::OpenDisputes(ip_id), &count);
+    }
+}
+
+pub(crate) fn read_option_terms(env: &Env, ip_id: u64) -> Option<OptionTerms> {
+    env.storage().persistent().get(&ExtKey::OptionTerms(ip_id))
+}
+
+pub(crate) fn write_option_terms(env: &Env, ip_id: u64, terms: &Option<OptionTerms>) {
+    match terms {
+        Some(terms) => env.storage().persistent().set(&ExtKey::OptionTerms(ip_id), terms),
+        None => env.storage().persistent().remove(&ExtKey::OptionTerms(ip_id)),
+    }
+}
+
+pub(crate) fn read_exclusive_option(env: &Env, ip_id: u64) -> Option<ExclusiveOption> {
+    env.storage().persistent().get(&ExtKey::ExclusiveOption(ip_id))
+}
+
+pub(crate) fn write_exclusive_option(env: &Env, ip_id: u64, option: &Option<ExclusiveOption>) {
+    match option {
+        Some(option) => env.storage().persistent().set(&ExtKey::ExclusiveOption(ip_id), option),
+        None => env.storage().persistent().remove(&ExtKey::ExclusiveOption(ip_id)),
+    }
+}
+
+pub(crate) fn next_auction_id(env: &Env) -> u64 {
+    let auction_id = env.storage().instance().get(&ExtKey::AuctionCount).unwrap_or(0u64) + 1;
+    env.storage().instance().set(&ExtKey::AuctionCount, &auction_id);
+    auction_id
+}
+
+pub(crate) fn read_auction(env: &Env, auction_id: u64) -> Option<SealedAuction> {
+    env.storage().persistent().get(&ExtKey::Auction(auction_id))
+}
+
+pub(crate) fn write_auction(env: &Env, auction: &SealedAuction) {
+    env.storage().persistent().set(&ExtKey::Auction(auction.auction_id), auction);
+}
+
+pub(crate) fn read_open_auction(env: &Env, ip_id: u64) -> Option<u64> {
+    env.storage().persistent().get(&ExtKey::OpenAuction(ip_id))
+}
+
+pub(crate) fn write_open_auction(env: &Env, ip_id: u64, auction_id: Option<u64>) {
+    match auction_id {
+        Some(auction_id) => env.storage().persistent().set(&ExtKey::OpenAuction(ip_id), &auction_id),
+        None => env.storage().persistent().remove(&ExtKey::OpenAuction(ip_id)),
+    }
+}
+
+pub(crate) fn read_sealed_bid(env: &Env, auction_id: u64, bidder: &Address) -> Option<SealedBid> {
+    env.storage().persistent().get(&ExtKey::SealedBid(auction_id, bidder.clone()))
+}
+
+pub(crate) fn write_sealed_bid(env: &Env, auction_id: u64, bidder: &Address, bid: &Option<SealedBid>) {
+    match bid {
+        Some(bid) => env.storage().persistent().set(&ExtKey::SealedBid(auction_id, bidder.clone()), bid),
+        None => env.storage().persistent().remove(&ExtKey::SealedBid(auction_id, bidder.clone())),
+    }
+}
+
+pub(crate) fn read_owner_offers(env: &Env, owner: &Address) -> Vec<u64> {
+    env.storage().persistent()
+        .get(&DataKey::OwnerOffers(owner.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub(crate) fn write_owner_offers(env: &Env, owner: &Address, offer_ids: &Vec<u64>) {
+    if offer_ids.is_empty() {
+        env.storage().persistent().remove(&DataKey::OwnerOffers(owner.clone()));
+    } else {
+        env.storage().persistent().set(&DataKey::OwnerOffers(owner.clone()), offer_ids);
+    }
+}
+
+pub(crate) fn read_handover(env: &Env, ip_id: u64) -> Option<Handover> {
+    env.storage().persistent().get(&DataKey::Handover(ip_id))
+}
+
+pub(crate) fn write_handover(env: &Env, ip_id: u64, handover: &Option<Handover>) {
+    match handover {
+        Some(handover) => env.storage().persistent().set(&DataKey::Handover(ip_id), handover),
+        None => env.storage().persistent().remove(&DataKey::Handover(ip_id)),
+    }
+}
+
+pub(crate) fn is_purchase_agent(env: &Env, licensee: &Address, agent: &Address) -> bool {
+    env.storage().persistent().has(&DataKey::PurchaseAgent(licensee.clone(), agent.clone()))
+}
+
+pub(crate) fn set_purchase_agent(env: &Env, licensee: &Address, agent: &Address, approved: bool) {
+    let key = DataKey::PurchaseAgent(licensee.clone(), agent.clone());
+    if approved {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub(crate) fn is_operator(env: &Env, licensee: &Address, operator: &Address) -> bool {
+    env.storage().persistent().has(&DataKey::Operator(licensee.clone(), operator.clone()))
+}
+
+pub(crate) fn set_operator(env: &Env, licensee: &Address, operator: &Address, authorized: bool) {
+    let key = DataKey::Operator(licensee.clone(), operator.clone());
+    if authorized {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub(crate) fn read_session_key(env: &Env, ip_id: u64, licensee: &Address) -> Option<SessionKey> {
+    env.storage().persistent().get(&ExtKey::SessionKey(ip_id, licensee.clone()))
+}
+
+pub(crate) fn write_session_key(env: &Env, ip_id: u64, licensee: &Address, session: &Option<SessionKey>) {
+    let key = ExtKey::SessionKey(ip_id, licensee.clone());
+    match session {
+        Some(session) => env.storage().persistent().set(&key, session),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+/// Owner proceeds held by the contract until claimed, per payment token
+pub(crate) fn read_claimable(env: &Env, owner: &Address, token: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::Claimable(owner.clone(), token.clone())).unwrap_or(0)
+}
+
+pub(crate) fn write_claimable(env: &Env, owner: &Address, token: &Address, amount: i128) {
+    let key = DataKey::Claimable(owner.clone(), token.clone());
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &amount);
+    }
+}
+
+/// Fees retained for keeper rewards, per payment token
+pub(crate) fn read_keeper_pool(env: &Env, token: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::KeeperPool(token.clone())).unwrap_or(0)
+}
+
+pub(crate) fn write_keeper_pool(env: &Env, token: &Address, amount: i128) {
+    env.storage().persistent().set(&DataKey::KeeperPool(token.clone()), &amount);
+}
+
+pub(crate) fn read_keeper_reward(env: &Env) -> Option<KeeperReward> {
+    env.storage().instance().get(&DataKey::KeeperReward)
+}
+
+#[cfg(feature = "keepers")]
+pub(crate) fn write_keeper_reward(env: &Env, reward: &KeeperReward) {
+    env.storage().instance().set(&DataKey::KeeperReward, reward);
+}
+
+#[cfg(feature = "vouchers")]
+pub(crate) fn read_voucher(env: &Env, ip_id: u64, code_hash: &BytesN<32>) -> Option<Voucher> {
+    env.storage().persistent().get(&DataKey::Voucher(ip_id, code_hash.clone()))
+}
+
+#[cfg(feature = "vouchers")]
+pub(crate) fn write_voucher(env: &Env, ip_id: u64, code_hash: &BytesN<32>, voucher: &Voucher) {
+    env.storage().persistent().set(&DataKey::Voucher(ip_id, code_hash.clone()), voucher);
+}
+
+/// Ids of IPs registered with `tag`, in registration order
+#[cfg(feature = "search")]
+pub(crate) fn tag_index(tag: &Symbol) -> ChunkedVec<DataKey, u64> {
+    ChunkedVec::new(DataKey::TagIndex(tag.clone()))
+}
+
+#[cfg(feature = "shares")]
+pub(crate) fn read_share_token(env: &Env, ip_id: u64) -> Option<ShareToken> {
+    env.storage().persistent().get(&DataKey::ShareToken(ip_id))
+}
+
+#[cfg(feature = "shares")]
+pub(crate) fn write_share_token(env: &Env, ip_id: u64, share_token: &ShareToken) {
+    env.storage().persistent().set(&DataKey::ShareToken(ip_id), share_token);
+}
+
+#[cfg(feature = "shares")]
+pub(crate) fn read_share_holding(env: &Env, ip_id: u64, holder: &Address) -> ShareHolding {
+    env.storage().persistent()
+        .get(&DataKey::ShareHolding(ip_id, holder.clone()))
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "shares")]
+pub(crate) fn write_share_holding(env: &Env, ip_id: u64, holder: &Address, holding: &ShareHolding) {
+    env.storage().persistent().set(&DataKey::ShareHolding(ip_id, holder.clone()), holding);
+}
+
+/// Allowance of `spender` over `from`'s shares; lapsed approvals read as zero
+#[cfg(feature = "shares")]
+pub(crate) fn read_share_allowance(env: &Env, ip_id: u64, from: &Address, spender: &Address) -> ShareAllowance {
+    let key = DataKey::ShareAllowance(ip_id, from.clone(), spender.clone());
+    match env.storage().persistent().get::<_, ShareAllowance>(&key) {
+        Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+        _ => ShareAllowance { amount: 0, expiration_ledger: 0 },
+    }
+}
+
+#[cfg(feature = "shares")]
+pub(crate) fn write_share_allowance(env: &Env, ip_id: u64, from: &Address, spender: &Address, allowance: &ShareAllowance) {
+    let key = DataKey::ShareAllowance(ip_id, from.clone(), spender.clone());
+    if allowance.amount == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, allowance);
+    }
+}
+
+pub(crate) fn read_preview_terms(env: &Env, ip_id: u64) -> Option<PreviewTerms> {
+    env.storage().persistent().get(&ExtKey::PreviewTerms(ip_id))
+}
+
+pub(crate) fn write_preview_terms(env: &Env, ip_id: u64, terms: &Option<PreviewTerms>) {
+    match terms {
+        Some(terms) => env.storage().persistent().set(&ExtKey::PreviewTerms(ip_id), terms),
+        None => env.storage().persistent().remove(&ExtKey::PreviewTerms(ip_id)),
+    }
+}
+
+pub(crate) fn read_preview_grant(env: &Env, ip_id: u64, viewer: &Address) -> Option<u64> {
+    env.storage().temporary().get(&ExtKey::PreviewGrant(ip_id, viewer.clone()))
+}
+
+/// Record a preview grant in temporary storage, living at least until
+/// `expires_at` at the nominal ledger close time, or as long as the network
+/// allows
+pub(crate) fn write_preview_grant(env: &Env, ip_id: u64, viewer: &Address, expires_at: u64) {
+    let key = ExtKey::PreviewGrant(ip_id, viewer.clone());
+    let ledgers = expires_at.saturating_sub(env.ledger().timestamp()) / LEDGER_CLOSE_SECS + 1;
+    let ttl = ledgers.min(env.storage().max_ttl() as u64) as u32;
+    env.storage().temporary().set(&key, &expires_at);
+    env.storage().temporary().extend_ttl(&key, ttl, ttl);
+}
+
+pub(crate) fn has_idempotency_key(env: &Env, caller: &Address, key: &BytesN<32>) -> bool {
+    env.storage().temporary().has(&DataKey::Idempotency(caller.clone(), key.clone()))
+}
+
+/// Remember an idempotency key in temporary storage for the configured TTL
+pub(crate) fn write_idempotency_key(env: &Env, caller: &Address, key: &BytesN<32>) {
+    let storage_key = DataKey::Idempotency(caller.clone(), key.clone());
+    let ttl = read_config(env).idempotency_ttl_ledgers;
+    env.storage().temporary().set(&storage_key, &true);
+    env.storage().temporary().extend_ttl(&storage_key, ttl, ttl);
+}
@@ -0,0 +1,19 @@
+use soroban_sdk::{contractclient, Address, Env, Vec};
+
+/// The subset of a Soroswap-style AMM router used to pay royalties in an
+/// asset other than the IP's payment token
+#[allow(dead_code)]
+#[contractclient(name = "SwapRouterClient")]
+pub trait SwapRouter {
+    /// Swap at most `amount_in_max` of `path[0]`, taken from `to`, into exactly
+    /// `amount_out` of the last asset of `path`, paid to `to`. Returns the
+    /// amount at each hop of `path`.
+    fn swap_tokens_for_exact_tokens(
+        env: Env,
+        amount_out: i128,
+        amount_in_max: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128>;
+}
@@ -0,0 +1,85 @@
+//! IPs licensed under a standard terms template from a shared terms library
+
+use crate::{
+    events, storage, terms_library::TermsLibraryClient, IPAsset, IPError, IPLicensingContract,
+    IPLicensingContractArgs, IPLicensingContractClient, Role
+};
+use soroban_sdk::{contractimpl, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Set the terms library IPs reference templates from (Only callable by an Admin)
+    pub fn set_terms_library(env: Env, admin: Address, library: Address) -> Result<(), IPError> {
+        admin.require_auth();
+        Self::require_role(&env, Role::Admin, &admin)?;
+
+        storage::write_terms_library(&env, &library);
+        Self::audit(&env, symbol_short!("terms_lib"), &admin, (library,));
+        Ok(())
+    }
+
+    pub fn get_terms_library(env: Env) -> Option<Address> {
+        storage::read_terms_library(&env)
+    }
+
+    /// Register a new IP asset licensed under the library's template
+    /// `template_id`, otherwise as `register_ip`. Purchases of the IP carry
+    /// the template id in their event.
+    pub fn register_ip_from_template(
+        env: Env,
+        owner: Address,
+        ip_id: u64,
+        template_id: u32,
+        metadata_uri: String,
+        metadata_hash: Option<BytesN<32>>,
+        price_exclusive: i128,
+        price_non_exclusive: i128,
+        payment_token: Address,
+        tags: Vec<Symbol>,
+    ) -> Result<IPAsset, IPError> {
+        owner.require_auth();
+        Self::require_template(&env, template_id)?;
+
+        let mut asset = Self::register(&env, owner, ip_id, metadata_uri, metadata_hash, price_exclusive, price_non_exclusive, payment_token, tags)?;
+        asset.terms_template = Some(template_id);
+        storage::write_ip(&env, ip_id, &asset);
+
+        events::terms_template_set(&env, ip_id, asset.terms_template);
+        Ok(asset)
+    }
+
+    /// Move the IP onto another library template, or back to bespoke terms
+    /// with `None` (Requires IP owner approval). Licenses already issued keep
+    /// the terms they were bought under.
+    pub fn set_terms_template(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        template_id: Option<u32>,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+        if let Some(template_id) = template_id {
+            Self::require_template(&env, template_id)?;
+        }
+
+        ip.terms_template = template_id;
+        storage::write_ip(&env, ip_id, &ip);
+
+        events::terms_template_set(&env, ip_id, template_id);
+        Ok(())
+    }
+}
+
+impl IPLicensingContract {
+    /// Check the configured terms library publishes `template_id`
+    fn require_template(env: &Env, template_id: u32) -> Result<(), IPError> {
+        let library = storage::read_terms_library(env).ok_or(IPError::InvalidConfig)?;
+        if TermsLibraryClient::new(env, &library).lookup_template(&template_id).is_none() {
+            return Err(IPError::InvalidConfig);
+        }
+        Ok(())
+    }
+}
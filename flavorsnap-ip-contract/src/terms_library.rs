@@ -0,0 +1,20 @@
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env, String};
+
+/// Mirror of the terms library's `TermsTemplate`; field names and types must
+/// match the library's definition for templates to decode.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TermsTemplate {
+    pub template_id: u32,
+    pub terms_hash: BytesN<32>,
+    pub uri: String,
+    pub creator: Address,
+    pub published_at: u64,
+}
+
+/// The subset of `TermsLibraryContract` used to check template references
+#[allow(dead_code)]
+#[contractclient(name = "TermsLibraryClient")]
+pub trait TermsLibrary {
+    fn lookup_template(env: Env, template_id: u32) -> Option<TermsTemplate>;
+}
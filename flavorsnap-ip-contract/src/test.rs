@@ -1,60 +1,3449 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::{Address as _, Ledger}, token, Env, String};
+use crate::{scenarios::{self, ScenarioBuilder}, statements};
+use flavorsnap_terms_library::{TermsLibraryContract, TermsLibraryContractClient};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation}, contract, contractimpl, symbol_short, testutils::{Address as _, AuthorizedFunction, Events, Ledger}, token, vec, Bytes, BytesN, Env, InvokeError,
+    xdr::ToXdr, FromVal, IntoVal, String, Symbol
+};
+
+/// Hash of an off-chain usage report, as licensees submit with royalties
+fn usage_report(env: &Env) -> BytesN<32> {
+    BytesN::from_array(env, &[0xab; 32])
+}
 
 #[test]
 fn test_license_purchase_flow() {
+    let s = ScenarioBuilder::new()
+        .with_ip(101, "owner", 500, 100)
+        .with_license(101, "buyer", LicenseType::NonExclusive)
+        .with_funds("buyer", 900)
+        .build();
+    let client = s.client();
+
+    assert_eq!(s.balance("buyer"), 900);
+    assert_eq!(client.get_claimable(&s.party("owner"), &s.token), 100);
+
+    // A non-exclusive license already exists, so the exclusive one cannot be sold
+    let res = client.try_purchase_license(&s.party("buyer"), &101, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_roles_and_pause() {
     let env = Env::default();
-    
-    env.ledger().with_mut(|li| {
-        li.timestamp = 12345;
-    });
+    env.mock_all_auths();
 
-    // 1. Register Contract
-    let contract_address = env.register(IPLicensingContract, ());
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), treasury, 0u32));
     let client = IPLicensingContractClient::new(&env, &contract_address);
 
-    // 2. Setup Token & Admin
     let token_admin = Address::generate(&env);
     let sac = env.register_stellar_asset_contract_v2(token_admin.clone());
     let token_address = sac.address();
-    
-    let token_admin_client = token::StellarAssetClient::new(&env, &token_address);
-    let token_client = token::Client::new(&env, &token_address);
 
+    let pauser = Address::generate(&env);
     let owner = Address::generate(&env);
     let buyer = Address::generate(&env);
 
-    // 3. Mint tokens (Must mock auth!)
-    token_admin_client.mock_all_auths().mint(&buyer, &1000);
-    assert_eq!(token_client.balance(&buyer), 1000);
+    // Pauser role is required to pause
+    assert_eq!(client.try_pause(&pauser), Err(Ok(IPError::Unauthorized)));
+    client.grant_role(&admin, &Role::Pauser, &pauser);
+    assert!(client.has_role(&Role::Pauser, &pauser));
 
-    // 4. Register IP (Must mock auth for owner!)
-    client.mock_all_auths().register_ip(
+    client.register_ip(
         &owner,
-        &101, 
+        &1,
         &String::from_str(&env, "ipfs://metadata"),
+        &None,
         &500,
         &100,
-        &token_address
+        &token_address,
+        &vec![&env]
+    );
+
+    client.pause(&pauser);
+    assert!(client.is_paused());
+    let res = client.try_purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::Paused)));
+
+    client.unpause(&pauser);
+    assert!(!client.is_paused());
+
+    client.revoke_role(&admin, &Role::Pauser, &pauser);
+    assert_eq!(client.try_pause(&pauser), Err(Ok(IPError::Unauthorized)));
+
+    // Admins can hand over, but never leave the contract without one
+    let res = client.try_revoke_role(&admin, &Role::Admin, &admin);
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::LastAdmin as u32))));
+    client.grant_role(&admin, &Role::Admin, &pauser);
+    client.revoke_role(&pauser, &Role::Admin, &admin);
+    assert!(!client.has_role(&Role::Admin, &admin));
+}
+
+#[test]
+fn test_platform_fee_routed_to_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), treasury.clone(), 250u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let config = client.get_config();
+    assert_eq!(config.admin, admin);
+    assert_eq!(config.treasury, treasury);
+    assert_eq!(config.fee_bps, 250);
+
+    let token_admin = Address::generate(&env);
+    let token_address = env.register_stellar_asset_contract_v2(token_admin).address();
+    let token_client = token::Client::new(&env, &token_address);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+
+    client.register_ip(
+        &owner,
+        &1,
+        &String::from_str(&env, "ipfs://metadata"),
+        &None,
+        &500,
+        &400,
+        &token_address,
+        &vec![&env]
+    );
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    assert_eq!(token_client.balance(&treasury), 10);
+    assert_eq!(client.get_claimable(&owner, &token_address), 390);
+
+    // Only a FeeManager may change the fee
+    let fee_manager = Address::generate(&env);
+    assert_eq!(client.try_set_fee(&fee_manager, &treasury, &100), Err(Ok(IPError::Unauthorized)));
+    client.grant_role(&admin, &Role::FeeManager, &fee_manager);
+    assert_eq!(client.try_set_fee(&fee_manager, &treasury, &10_001), Err(Ok(IPError::InvalidFee)));
+    client.set_fee(&fee_manager, &treasury, &100);
+    assert_eq!(client.get_config().fee_bps, 100);
+}
+
+#[test]
+fn test_deregister_and_restore_ip() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_admin = Address::generate(&env);
+    let token_address = env.register_stellar_asset_contract_v2(token_admin).address();
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let metadata = String::from_str(&env, "ipfs://metadata");
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+
+    // Unlicensed asset is hard-deleted and its id can be reused
+    client.register_ip(&owner, &1, &metadata, &None, &500, &100, &token_address, &vec![&env]);
+    client.deregister_ip(&vec![&env, owner.clone()], &1);
+    assert_eq!(client.try_restore_ip(&vec![&env, owner.clone()], &1), Err(Ok(IPError::IPNotFound)));
+    client.register_ip(&owner, &1, &metadata, &None, &500, &100, &token_address, &vec![&env]);
+
+    // Licensed asset is tombstoned: no new sales, restorable within the window
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(client.try_deregister_ip(&vec![&env, buyer.clone()], &1), Err(Ok(IPError::Unauthorized)));
+    client.deregister_ip(&vec![&env, owner.clone()], &1);
+    let second_buyer = Address::generate(&env);
+    let res = client.try_purchase_license(&second_buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::IPDeregistered)));
+
+    client.restore_ip(&vec![&env, owner.clone()], &1);
+    assert_eq!(client.try_restore_ip(&vec![&env, owner.clone()], &1), Err(Ok(IPError::NotDeregistered)));
+
+    client.deregister_ip(&vec![&env, owner.clone()], &1);
+    env.ledger().with_mut(|li| {
+        li.timestamp += 31 * 24 * 60 * 60;
+    });
+    assert_eq!(client.try_restore_ip(&vec![&env, owner.clone()], &1), Err(Ok(IPError::RestoreWindowExpired)));
+}
+
+#[test]
+fn test_hard_deleted_ip_re_registers_clean() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "chef", 1_000, 100)
+        .with_funds("chef", 500)
+        .with_funds("studio", 1_000)
+        .build();
+    let client = s.client();
+    let chef = s.party("chef");
+    let owners = vec![&s.env, chef.clone()];
+    let terms = OptionTerms { fee: 50, strike_price: 800, window_secs: 100 };
+    client.set_option_terms(&owners, &1, &Some(terms));
+    client.set_preview_terms(&owners, &1, &Some(PreviewTerms { fee: 5, duration_secs: 60 }));
+    client.set_free_claim_policy(&owners, &1, &Some(FreeClaimPolicy { bond: 10, cooldown_secs: 0 }));
+    client.set_price_decay(&owners, &1, &1_000, &100, &700);
+    client.create_conflict_group(&owners, &vec![&s.env, 1, 2]);
+    client.post_bond(&owners, &1, &500, &100);
+
+    // Nothing a counterparty holds may be deleted out from under them
+    assert_eq!(client.try_deregister_ip(&owners, &1), Err(Ok(IPError::DuplicateOperation)));
+    s.advance_time(100);
+    client.release_bond(&1);
+    let offer_id = client.make_offer(&s.party("studio"), &1, &LicenseType::NonExclusive, &80, &(scenarios::START_TIME + 200));
+    assert_eq!(client.try_deregister_ip(&owners, &1), Err(Ok(IPError::DuplicateOperation)));
+    client.reject_offer(&owners, &offer_id);
+
+    client.deregister_ip(&owners, &1);
+    let metadata = String::from_str(&s.env, "ipfs://other");
+    client.register_ip(&s.party("studio"), &1, &metadata, &None, &500, &100, &s.token, &vec![&s.env]);
+    assert_eq!(client.get_option_terms(&1), None);
+    assert_eq!(client.get_preview_terms(&1), None);
+    assert_eq!(client.get_free_claim_policy(&1), None);
+    assert_eq!(client.get_price_decay(&1), None);
+    assert_eq!(client.try_get_bond(&1), Err(Ok(IPError::BondNotHeld)));
+    assert_eq!(client.get_conflict_group(&1), None);
+    assert_eq!(client.get_conflict_group(&2), None);
+    assert_eq!(client.get_ip_stats(&1), IPStats::default());
+}
+
+#[test]
+fn test_deregister_tombstones_ip_with_party_state() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "chef", 1_000, 100)
+        .with_funds("studio", 1_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let studio = s.party("studio");
+
+    // Revoked licenses and preview grants stay keyed to the id, so it is kept
+    client.purchase_license(&studio, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.revoke_license(&owners, &studio, &1, &symbol_short!("breach"));
+    client.deregister_ip(&owners, &1);
+    assert!(client.get_ip_report(&1).deregistered);
+
+    let terms = PreviewTerms { fee: 5, duration_secs: 60 };
+    client.set_preview_terms(&owners, &2, &Some(terms.clone()));
+    client.purchase_preview(&studio, &2, &terms);
+    client.set_preview_terms(&owners, &2, &None);
+    client.deregister_ip(&owners, &2);
+    assert!(client.get_ip_report(&2).deregistered);
+    let res = client.try_register_ip(&studio, &2, &String::from_str(&s.env, "ipfs://other"), &None, &500, &100, &s.token, &vec![&s.env]);
+    assert_eq!(res, Err(Ok(IPError::IPAlreadyRegistered)));
+}
+
+#[test]
+fn test_verify_metadata_hash() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let owner = Address::generate(&env);
+    let metadata = String::from_str(&env, "ipfs://metadata");
+    let metadata_hash = BytesN::from_array(&env, &[7; 32]);
+
+    client.register_ip(&owner, &1, &metadata, &Some(metadata_hash.clone()), &500, &100, &token_address, &vec![&env]);
+    client.register_ip(&owner, &2, &metadata, &None, &500, &100, &token_address, &vec![&env]);
+
+    assert!(client.verify_metadata(&1, &metadata_hash));
+    assert!(!client.verify_metadata(&1, &BytesN::from_array(&env, &[8; 32])));
+    assert!(!client.verify_metadata(&2, &metadata_hash));
+    assert_eq!(client.try_verify_metadata(&3, &metadata_hash), Err(Ok(IPError::IPNotFound)));
+}
+
+#[test]
+fn test_error_codes_documented() {
+    let env = Env::default();
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    for code in 1..=50u32 {
+        let error = IPError::from_code(code).unwrap();
+        assert_eq!(error as u32, code);
+        assert_eq!(client.error_message(&code), String::from_str(&env, error.message()));
+    }
+    assert_eq!(IPError::from_code(0), None);
+    assert_eq!(IPError::from_code(51), None);
+    for code in 51..=63u32 {
+        let error = ExtError::from_code(code).unwrap();
+        assert_eq!(error as u32, code);
+        assert_eq!(client.error_message(&code), String::from_str(&env, error.message()));
+    }
+    assert_eq!(ExtError::from_code(50), None);
+    assert_eq!(ExtError::from_code(64), None);
+    assert_eq!(client.error_message(&64), String::from_str(&env, "unknown error"));
+}
+
+#[test]
+fn test_contract_metadata_views() {
+    let env = Env::default();
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    assert_eq!(client.version(), (meta::SCHEMA_VERSION, 0, 0));
+    assert_eq!(client.name(), Symbol::new(&env, "ip_licensing"));
+    let features = client.supported_features();
+    assert!(features.contains(Symbol::new(&env, "tiers")));
+    assert_eq!(features.contains(Symbol::new(&env, "shares")), cfg!(feature = "shares"));
+}
+
+#[test]
+fn test_entrypoint_failure_modes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_address = env.register(
+        IPLicensingContract,
+        (admin.clone(), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let other = Address::generate(&env);
+    let metadata = String::from_str(&env, "ipfs://metadata");
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+    token::StellarAssetClient::new(&env, &token_address).mint(&other, &1000);
+
+    // register_ip
+    let res = client.try_register_ip(&owner, &1, &metadata, &None, &-1, &100, &token_address, &vec![&env]);
+    assert_eq!(res, Err(Ok(IPError::InvalidAmount)));
+    client.register_ip(&owner, &1, &metadata, &None, &500, &100, &token_address, &vec![&env]);
+    let res = client.try_register_ip(&owner, &1, &metadata, &None, &500, &100, &token_address, &vec![&env]);
+    assert_eq!(res, Err(Ok(IPError::IPAlreadyRegistered)));
+
+    // purchase_license
+    let res = client.try_purchase_license(&buyer, &2, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::IPNotFound)));
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    let res = client.try_purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::LicenseAlreadyExists)));
+    let res = client.try_purchase_license(&other, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::ActiveLicensesExist)));
+
+    // pay_usage_royalty
+    let res = client.try_pay_usage_royalty(&buyer, &buyer, &1, &0, &usage_report(&env), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::InvalidAmount)));
+    let res = client.try_pay_usage_royalty(&other, &other, &1, &10, &usage_report(&env), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::LicenseNotFound)));
+    let res = client.try_pay_usage_royalty(&buyer, &buyer, &2, &10, &usage_report(&env), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::IPNotFound)));
+
+    // revoke_license
+    let res = client.try_revoke_license(&vec![&env, other.clone()], &buyer, &1, &symbol_short!("breach"));
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    let res = client.try_revoke_license(&vec![&env, owner.clone()], &other, &1, &symbol_short!("breach"));
+    assert_eq!(res, Err(Ok(IPError::LicenseNotFound)));
+    client.revoke_license(&vec![&env, owner.clone()], &buyer, &1, &symbol_short!("breach"));
+    let res = client.try_revoke_license(&vec![&env, owner.clone()], &buyer, &1, &symbol_short!("breach"));
+    assert_eq!(res, Err(Ok(IPError::LicenseNotFound)));
+
+    // Exclusive blocks every further purchase
+    client.purchase_license(&other, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    let res = client.try_purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::ExclusiveAlreadyIssued)));
+}
+
+#[test]
+fn test_idempotency_key_prevents_double_charge() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
     );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+
+    let purchase_key = Some(BytesN::from_array(&env, &[1; 32]));
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &purchase_key, &None);
+
+    let royalty_key = Some(BytesN::from_array(&env, &[2; 32]));
+    client.pay_usage_royalty(&buyer, &buyer, &1, &50, &usage_report(&env), &royalty_key, &None);
+    let res = client.try_pay_usage_royalty(&buyer, &buyer, &1, &50, &usage_report(&env), &royalty_key, &None);
+    assert_eq!(res, Err(Ok(IPError::DuplicateOperation)));
+
+    // Keys are scoped per caller and unkeyed calls are never deduplicated
+    client.pay_usage_royalty(&buyer, &buyer, &1, &50, &usage_report(&env), &None, &None);
+    client.pay_usage_royalty(&buyer, &buyer, &1, &50, &usage_report(&env), &None, &None);
+    assert_eq!(token_client.balance(&buyer), 750);
+    assert_eq!(client.get_claimable(&owner, &token_address), 250);
+}
+
+#[test]
+fn test_purchase_with_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
 
-    // 5. Purchase License (Must mock auth for buyer!)
-    client.mock_all_auths().purchase_license(
-        &buyer, 
-        &101, 
-        &LicenseType::NonExclusive
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
     );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let backend = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+
+    let metadata = String::from_str(&env, "ipfs://metadata");
+    client.register_ip(&owner, &1, &metadata, &None, &500, &100, &token_address, &vec![&env]);
+    client.register_ip(&owner, &2, &metadata, &None, &500, &150, &token_address, &vec![&env]);
+
+    // The buyer pre-approves the contract and designates the backend
+    token_client.approve(&buyer, &contract_address, &200, &1000);
+    let res = client.try_purchase_with_allowance(&backend, &buyer, &1, &LicenseType::NonExclusive, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    client.set_purchase_agent(&buyer, &backend, &true);
+    assert!(client.is_purchase_agent(&buyer, &backend));
 
-    // 6. Verify Balances
+    client.purchase_with_allowance(&backend, &buyer, &1, &LicenseType::NonExclusive, &None, &u64::MAX, &None, &None);
     assert_eq!(token_client.balance(&buyer), 900);
-    assert_eq!(token_client.balance(&owner), 100);
-
-    // 7. Verify Exclusive Restriction
-    // We expect this to fail, so we dont necessarily need mock_auths here if it fails early,
-    // but good practice to include it if the failure is logic-based.
-    // However, try_purchase_license captures the error.
-    let res = client.mock_all_auths().try_purchase_license(&buyer, &101, &LicenseType::Exclusive);
-    assert!(res.is_err()); 
+    assert_eq!(client.get_claimable(&owner, &token_address), 100);
+    assert_eq!(token_client.allowance(&buyer, &contract_address), 100);
+
+    // Remaining allowance cannot cover the second license
+    let res = client.try_purchase_with_allowance(&backend, &buyer, &2, &LicenseType::NonExclusive, &None, &u64::MAX, &None, &None);
+    assert!(res.is_err());
+    assert_eq!(token_client.balance(&buyer), 900);
+
+    client.set_purchase_agent(&buyer, &backend, &false);
+    token_client.approve(&buyer, &contract_address, &500, &1000);
+    let res = client.try_purchase_with_allowance(&backend, &buyer, &2, &LicenseType::NonExclusive, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+}
+
+#[test]
+fn test_multisig_owner_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let owner = Address::generate(&env);
+    let cosigner_a = Address::generate(&env);
+    let cosigner_b = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+
+    // Threshold must be reachable and signers unique
+    let signers = vec![&env, owner.clone(), cosigner_a.clone(), cosigner_b.clone()];
+    let res = client.try_set_signers(&vec![&env, owner.clone()], &1, &signers, &4);
+    assert_eq!(res, Err(Ok(IPError::InvalidSigners)));
+    let duplicated = vec![&env, owner.clone(), owner.clone()];
+    let res = client.try_set_signers(&vec![&env, owner.clone()], &1, &duplicated, &1);
+    assert_eq!(res, Err(Ok(IPError::InvalidSigners)));
+    client.set_signers(&vec![&env, owner.clone()], &1, &signers, &2);
+
+    // One signature is no longer enough, nor are repeated or foreign ones
+    let res = client.try_set_prices(&vec![&env, owner.clone()], &1, &800, &200);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    let res = client.try_set_prices(&vec![&env, owner.clone(), owner.clone()], &1, &800, &200);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    let res = client.try_set_prices(&vec![&env, owner.clone(), outsider.clone()], &1, &800, &200);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+
+    client.set_prices(&vec![&env, cosigner_a.clone(), cosigner_b.clone()], &1, &800, &200);
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(client.get_claimable(&owner, &token_address), 200);
+
+    let res = client.try_revoke_license(&vec![&env, owner.clone()], &buyer, &1, &symbol_short!("breach"));
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    client.revoke_license(&vec![&env, owner.clone(), cosigner_a.clone()], &buyer, &1, &symbol_short!("breach"));
+
+    // Transfer hands the asset to a single new owner
+    let new_owner = Address::generate(&env);
+    client.transfer_ip(&vec![&env, owner.clone(), cosigner_b.clone()], &1, &new_owner);
+    let res = client.try_set_prices(&vec![&env, owner.clone(), cosigner_a.clone()], &1, &1, &1);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    client.set_prices(&vec![&env, new_owner.clone()], &1, &1, &1);
+}
+
+#[test]
+fn test_license_certificate_ids() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let owner = Address::generate(&env);
+    let buyer_a = Address::generate(&env);
+    let buyer_b = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer_a, &1000);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer_b, &1000);
+
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    client.purchase_license(&buyer_a, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.purchase_license(&buyer_b, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    let first = client.get_license_by_id(&1);
+    assert_eq!(first.licensee, buyer_a);
+    assert_eq!(first.ip_id, 1);
+    assert_eq!(client.get_license(&1, &buyer_b).license_id, 2);
+
+    // A repurchase after revocation issues a new certificate and retires the old one
+    client.revoke_license(&vec![&env, owner.clone()], &buyer_a, &1, &symbol_short!("breach"));
+    client.purchase_license(&buyer_a, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(client.get_license(&1, &buyer_a).license_id, 3);
+    assert_eq!(client.try_get_license_by_id(&1), Err(Ok(IPError::LicenseNotFound)));
+    assert_eq!(client.get_license_by_id(&3).licensee, buyer_a);
+    assert_eq!(client.try_get_license_by_id(&4), Err(Ok(IPError::LicenseNotFound)));
+}
+
+#[test]
+fn test_revoke_all_licenses_of_with_cursor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_address = env.register(
+        IPLicensingContract,
+        (admin.clone(), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let owner = Address::generate(&env);
+    let other_owner = Address::generate(&env);
+    let leaked = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&leaked, &10_000);
+
+    let metadata = String::from_str(&env, "ipfs://metadata");
+    for ip_id in 1..=3u64 {
+        client.register_ip(&owner, &ip_id, &metadata, &None, &500, &100, &token_address, &vec![&env]);
+        client.purchase_license(&leaked, &ip_id, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    }
+    client.register_ip(&other_owner, &4, &metadata, &None, &500, &100, &token_address, &vec![&env]);
+    client.purchase_license(&leaked, &4, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    // The owner pages through the index and only touches their own IPs
+    assert_eq!(client.revoke_all_licenses_of(&owner, &leaked, &symbol_short!("key_leak"), &0, &2), Some(2));
+    assert_eq!(client.revoke_all_licenses_of(&owner, &leaked, &symbol_short!("key_leak"), &2, &2), None);
+    for ip_id in 1..=3u64 {
+        assert!(!client.get_license(&ip_id, &leaked).is_active);
+    }
+    assert!(client.get_license(&4, &leaked).is_active);
+
+    // The admin can clean up platform-wide
+    assert_eq!(client.revoke_all_licenses_of(&admin, &leaked, &symbol_short!("key_leak"), &0, &50), None);
+    assert!(!client.get_license(&4, &leaked).is_active);
+}
+
+#[test]
+fn test_licensee_index_pages_across_chunks() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 500, 100)
+        .with_license(1, "studio", LicenseType::NonExclusive)
+        .build();
+    let client = s.client();
+    let studio = s.party("studio");
+
+    // Hundreds more IPs after the licensed one, spread over several pages
+    let members = 3 * flavorsnap_common::CHUNK_SIZE as u64;
+    s.env.as_contract(&s.contract, || {
+        for ip_id in 2..=members {
+            storage::add_to_licensee_index(&s.env, &studio, ip_id);
+        }
+        storage::add_to_licensee_index(&s.env, &studio, 1);
+        let index = storage::licensee_index(&studio);
+        assert_eq!(index.len(&s.env), members as u32);
+        assert_eq!(index.get(&s.env, flavorsnap_common::CHUNK_SIZE), Some(flavorsnap_common::CHUNK_SIZE as u64 + 1));
+    });
+
+    // Sweeps resume across page boundaries; only the first page holds a license
+    let start = flavorsnap_common::CHUNK_SIZE - 2;
+    assert_eq!(client.revoke_all_licenses_of(&s.party("admin"), &studio, &symbol_short!("key_leak"), &start, &4), Some(start + 4));
+    assert!(client.get_license(&1, &studio).is_active);
+    assert_eq!(client.revoke_all_licenses_of(&s.party("admin"), &studio, &symbol_short!("key_leak"), &0, &2), Some(2));
+    assert!(!client.get_license(&1, &studio).is_active);
+}
+
+#[test]
+fn test_downgrade_exclusive_with_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+    let owner = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let newcomer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&holder, &1000);
+    token::StellarAssetClient::new(&env, &token_address).mint(&newcomer, &1000);
+
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    client.set_push_payouts(&vec![&env, owner.clone()], &1, &true);
+    client.purchase_license(&holder, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+
+    // Only the exclusive holder can downgrade
+    assert_eq!(client.try_downgrade_license(&newcomer, &1), Err(Ok(IPError::LicenseNotFound)));
+
+    // Owner refunds half of the price difference out of their allowance
+    let res = client.try_set_downgrade_refund(&vec![&env, owner.clone()], &1, &10_001);
+    assert_eq!(res, Err(Ok(IPError::InvalidAmount)));
+    client.set_downgrade_refund(&vec![&env, owner.clone()], &1, &5_000);
+    token_client.approve(&owner, &contract_address, &200, &1000);
+
+    assert_eq!(client.downgrade_license(&holder, &1), 200);
+    assert_eq!(token_client.balance(&holder), 700);
+    assert_eq!(token_client.balance(&owner), 300);
+
+    let license = client.get_license(&1, &holder);
+    assert!(license.is_active);
+    assert_eq!(license.license_type, LicenseType::NonExclusive);
+    assert_eq!(client.try_downgrade_license(&holder, &1), Err(Ok(IPError::LicenseNotFound)));
+
+    // The asset is open to non-exclusive buyers again
+    client.purchase_license(&newcomer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    let res = client.try_purchase_license(&Address::generate(&env), &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::ActiveLicensesExist)));
+}
+
+#[test]
+fn test_downgrade_refund_from_claimable_then_allowance() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 500, 100)
+        .with_ip(2, "chef", 500, 100)
+        .with_license(1, "studio", LicenseType::Exclusive)
+        .with_license(2, "diner", LicenseType::Exclusive)
+        .build();
+    let client = s.client();
+    let chef = s.party("chef");
+    let studio = s.party("studio");
+    let diner = s.party("diner");
+    let owners = vec![&s.env, chef.clone()];
+    client.set_downgrade_refund(&owners, &1, &5_000);
+    client.set_downgrade_refund(&owners, &2, &5_000);
+
+    // Both sales accrued to the owner's claimable balance, which funds refunds
+    // without any allowance
+    let claimable = client.get_claimable(&chef, &s.token);
+    let studio_before = s.balance("studio");
+    assert_eq!(client.downgrade_license(&studio, &1), 200);
+    assert_eq!(s.balance("studio"), studio_before + 200);
+    assert_eq!(client.get_claimable(&chef, &s.token), claimable - 200);
+
+    // Once claimed, the owner's allowance must cover the refund
+    client.claim(&chef, &s.token);
+    assert_eq!(client.try_downgrade_license(&diner, &2), Err(Ok(IPError::InsufficientBalance)));
+    assert_eq!(client.get_license(&2, &diner).license_type, LicenseType::Exclusive);
+
+    token::Client::new(&s.env, &s.token).approve(&chef, &s.contract, &200, &1000);
+    let diner_before = s.balance("diner");
+    assert_eq!(client.downgrade_license(&diner, &2), 200);
+    assert_eq!(s.balance("diner"), diner_before + 200);
+}
+
+#[test]
+fn test_freeze_ip_legal_hold() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_id = env.register(IPLicensingContract, (admin.clone(), Address::generate(&env), 0u32));
+    let client = IPLicensingContractClient::new(&env, &contract_id);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let owner = Address::generate(&env);
+    let licensee = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&licensee, &1000);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    client.register_ip(&owner, &2, &String::from_str(&env, "ipfs://other"), &None, &500, &100, &token_address, &vec![&env]);
+    client.purchase_license(&licensee, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    let arbiter = Address::generate(&env);
+    let reason = String::from_str(&env, "case #42");
+    let res = client.try_freeze_ip(&arbiter, &1, &reason);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+
+    client.grant_role(&admin, &Role::Arbiter, &arbiter);
+    client.freeze_ip(&arbiter, &1, &reason);
+    assert_eq!(client.get_freeze_reason(&1), Some(reason));
+
+    let owners = vec![&env, owner.clone()];
+    let res = client.try_purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::IPFrozen)));
+    assert_eq!(client.try_pay_usage_royalty(&licensee, &licensee, &1, &10, &usage_report(&env), &None, &None), Err(Ok(IPError::IPFrozen)));
+    assert_eq!(client.try_transfer_ip(&owners, &1, &buyer), Err(Ok(IPError::IPFrozen)));
+    assert_eq!(client.try_set_prices(&owners, &1, &1, &1), Err(Ok(IPError::IPFrozen)));
+    assert_eq!(client.try_deregister_ip(&owners, &1), Err(Ok(IPError::IPFrozen)));
+
+    // Other assets are unaffected
+    client.purchase_license(&buyer, &2, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    client.unfreeze_ip(&admin, &1);
+    assert_eq!(client.get_freeze_reason(&1), None);
+    assert_eq!(client.try_unfreeze_ip(&admin, &1), Err(Err(InvokeError::Contract(ExtError::NotFrozen as u32))));
+    client.pay_usage_royalty(&licensee, &licensee, &1, &10, &usage_report(&env), &None, &None);
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+}
+
+#[cfg(feature = "keepers")]
+#[test]
+fn test_expire_licenses_pays_keeper() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), treasury.clone(), 1_000u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+
+    let fee_manager = Address::generate(&env);
+    client.grant_role(&admin, &Role::FeeManager, &fee_manager);
+    let reward = KeeperReward { fee_share_bps: 5_000, reward_per_license: 3 };
+    client.set_keeper_reward(&fee_manager, &reward);
+
+    let owner = Address::generate(&env);
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    client.set_license_duration(&vec![&env, owner.clone()], &1, &Some(100));
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    for buyer in [&alice, &bob] {
+        token::StellarAssetClient::new(&env, &token_address).mint(buyer, &100);
+        client.purchase_license(buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    }
+
+    // 10% fee of 100, half retained for keepers, on each sale
+    assert_eq!(token_client.balance(&treasury), 10);
+    assert_eq!(client.get_keeper_pool(&token_address), 10);
+    assert_eq!(client.get_license(&1, &alice).expires_at, Some(1_100));
+
+    let keeper = Address::generate(&env);
+    let licensees = vec![&env, alice.clone(), bob.clone()];
+    assert_eq!(client.expire_licenses(&keeper, &1, &licensees), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 1_100);
+    let res = client.try_pay_usage_royalty(&alice, &alice, &1, &10, &usage_report(&env), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::Expired)));
+
+    assert_eq!(client.expire_licenses(&keeper, &1, &licensees), 2);
+    assert_eq!(token_client.balance(&keeper), 6);
+    assert_eq!(client.get_keeper_pool(&token_address), 4);
+    assert!(!client.get_license(&1, &alice).is_active);
+
+    // Counters were released, so an exclusive license can now be sold
+    let carol = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&carol, &500);
+    client.purchase_license(&carol, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+
+    // Already swept licenses earn nothing
+    assert_eq!(client.expire_licenses(&keeper, &1, &licensees), 0);
+    assert_eq!(token_client.balance(&keeper), 6);
+}
+
+#[test]
+fn test_referral_share_and_stats() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin, treasury.clone(), 1_000u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+
+    let owner = Address::generate(&env);
+    let referrer = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    client.register_ip(&owner, &2, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+
+    // Without an opt-in the referrer earns nothing
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &Some(referrer.clone()), &None, &u64::MAX, &None, &None);
+    assert_eq!(token_client.balance(&referrer), 0);
+    assert_eq!(client.get_referrer_stats(&referrer), ReferrerStats::default());
+
+    let approvers = vec![&env, owner.clone()];
+    let res = client.try_set_referral_bps(&approvers, &2, &9_001);
+    assert_eq!(res, Err(Ok(IPError::InvalidFee)));
+    assert_eq!(client.try_set_referral_bps(&approvers, &2, &u32::MAX), Err(Ok(IPError::InvalidFee)));
+    client.set_referral_bps(&approvers, &2, &2_000);
+
+    let res = client.try_purchase_license(&buyer, &2, &LicenseType::NonExclusive, &Some(buyer.clone()), &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+
+    client.purchase_license(&buyer, &2, &LicenseType::NonExclusive, &Some(referrer.clone()), &None, &u64::MAX, &None, &None);
+    assert_eq!(token_client.balance(&treasury), 20);
+    assert_eq!(token_client.balance(&referrer), 20);
+    assert_eq!(client.get_claimable(&owner, &token_address), 90 + 70);
+    assert_eq!(client.get_referrer_stats(&referrer), ReferrerStats { sales: 1, earned: 20 });
+}
+
+#[cfg(feature = "vouchers")]
+#[test]
+fn test_voucher_discount_and_uses() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+
+    let owner = Address::generate(&env);
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+
+    let code = Bytes::from_slice(&env, b"LAUNCH25");
+    let code_hash: BytesN<32> = env.crypto().sha256(&code).into();
+    let approvers = vec![&env, owner.clone()];
+    let res = client.try_create_voucher(&approvers, &1, &code_hash, &0, &1, &2_000);
+    assert_eq!(res, Err(Ok(IPError::InvalidAmount)));
+    client.create_voucher(&approvers, &1, &code_hash, &2_500, &1, &2_000);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&alice, &1_000);
+    token::StellarAssetClient::new(&env, &token_address).mint(&bob, &1_000);
+
+    let wrong_code = Bytes::from_slice(&env, b"LAUNCH50");
+    let res = client.try_purchase_license_with_voucher(&alice, &1, &LicenseType::NonExclusive, &wrong_code, &None, &u64::MAX, &None);
+    assert_eq!(res, Err(Ok(IPError::VoucherNotFound)));
+
+    client.purchase_license_with_voucher(&alice, &1, &LicenseType::NonExclusive, &code, &None, &u64::MAX, &None);
+    assert_eq!(token_client.balance(&alice), 925);
+    assert_eq!(client.get_claimable(&owner, &token_address), 75);
+    assert_eq!(client.get_voucher(&1, &code_hash).unwrap().uses_left, 0);
+
+    let res = client.try_purchase_license_with_voucher(&bob, &1, &LicenseType::NonExclusive, &code, &None, &u64::MAX, &None);
+    assert_eq!(res, Err(Ok(IPError::CapReached)));
+
+    client.create_voucher(&approvers, &1, &code_hash, &2_500, &5, &2_000);
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    let res = client.try_purchase_license_with_voucher(&bob, &1, &LicenseType::NonExclusive, &code, &None, &u64::MAX, &None);
+    assert_eq!(res, Err(Ok(IPError::Expired)));
+}
+
+#[cfg(feature = "search")]
+#[test]
+fn test_search_by_tag() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let metadata = String::from_str(&env, "ipfs://metadata");
+    let cuisine = Symbol::new(&env, "cuisine");
+    let photo = Symbol::new(&env, "photo");
+    let logo = Symbol::new(&env, "logo");
+
+    client.register_ip(&owner, &1, &metadata, &None, &500, &100, &token_address, &vec![&env, cuisine.clone(), photo.clone()]);
+    client.register_ip(&owner, &2, &metadata, &None, &500, &100, &token_address, &vec![&env, logo.clone()]);
+    client.register_ip(&owner, &3, &metadata, &None, &500, &100, &token_address, &vec![&env, photo.clone(), photo.clone()]);
+
+    let too_many = vec![
+        &env,
+        Symbol::new(&env, "a"),
+        Symbol::new(&env, "b"),
+        Symbol::new(&env, "c"),
+        Symbol::new(&env, "d"),
+        Symbol::new(&env, "e"),
+        Symbol::new(&env, "f"),
+    ];
+    let res = client.try_register_ip(&owner, &4, &metadata, &None, &500, &100, &token_address, &too_many);
+    assert_eq!(res, Err(Ok(IPError::CapReached)));
+
+    assert_eq!(client.search_by_tag(&photo, &0, &10), vec![&env, 1u64, 3]);
+    assert_eq!(client.search_by_tag(&photo, &1, &10), vec![&env, 3u64]);
+    assert_eq!(client.search_by_tag(&cuisine, &0, &10), vec![&env, 1u64]);
+    assert_eq!(client.get_ip(&3).tags, vec![&env, photo.clone()]);
+
+    // Deleted IPs drop out of the index
+    client.deregister_ip(&vec![&env, owner.clone()], &1);
+    assert_eq!(client.search_by_tag(&photo, &0, &10), vec![&env, 3u64]);
+}
+
+#[test]
+fn test_creator_profile_verification() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), Address::generate(&env), 0u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let creator = Address::generate(&env);
+    client.register_ip(&creator, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    assert_eq!(client.get_ip_report(&1).creator_profile_uri, None);
+
+    let res = client.try_set_creator_verified(&admin, &creator, &true);
+    assert_eq!(res, Err(Ok(IPError::ProfileNotFound)));
+
+    let name_hash = BytesN::from_array(&env, &[4; 32]);
+    let profile_uri = String::from_str(&env, "ipfs://profile");
+    client.set_creator_profile(&creator, &name_hash, &profile_uri);
+
+    let res = client.try_set_creator_verified(&creator, &creator, &true);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    client.set_creator_verified(&admin, &creator, &true);
+
+    let report = client.get_ip_report(&1);
+    assert!(report.creator_verified);
+    assert_eq!(report.creator_profile_uri, Some(profile_uri.clone()));
+    assert!(!report.frozen && !report.deregistered);
+    assert_eq!(client.get_creator_profile(&creator).unwrap().display_name_hash, name_hash);
+
+    // Editing the profile drops the badge
+    client.set_creator_profile(&creator, &BytesN::from_array(&env, &[5; 32]), &profile_uri);
+    assert!(!client.get_creator_profile(&creator).unwrap().verified);
+}
+
+#[test]
+fn test_ownership_proof_attestation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), Address::generate(&env), 0u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let creator = Address::generate(&env);
+    let attestor = Address::generate(&env);
+    let owner = vec![&env, creator.clone()];
+    let proof = BytesN::from_array(&env, &[6; 32]);
+    client.register_ip(&creator, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    client.set_ownership_proof(&owner, &1, &Some(proof.clone()));
+    assert_eq!(client.get_ip(&1).proof_hash, Some(proof.clone()));
+
+    let res = client.try_attest_origin(&attestor, &1, &proof, &true);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    client.grant_role(&admin, &Role::Attestor, &attestor);
+    let res = client.try_attest_origin(&attestor, &1, &BytesN::from_array(&env, &[7; 32]), &true);
+    assert_eq!(res, Err(Ok(IPError::ProofMismatch)));
+
+    client.attest_origin(&attestor, &1, &proof, &true);
+    let event = env.events().all().last().unwrap();
+    assert_eq!(event.1, (symbol_short!("origin"), 1u64).into_val(&env));
+    assert_eq!(<(Address, bool)>::from_val(&env, &event.2), (attestor.clone(), true));
+    assert!(client.get_ip(&1).verified_origin);
+
+    // Replacing the proof needs a fresh attestation
+    client.set_ownership_proof(&owner, &1, &Some(BytesN::from_array(&env, &[8; 32])));
+    assert!(!client.get_ip(&1).verified_origin);
+
+    // The proof spoke for the previous owner only
+    client.set_ownership_proof(&owner, &1, &Some(proof.clone()));
+    client.attest_origin(&attestor, &1, &proof, &true);
+    client.transfer_ip(&owner, &1, &Address::generate(&env));
+    let ip = client.get_ip(&1);
+    assert_eq!(ip.proof_hash, None);
+    assert!(!ip.verified_origin);
+}
+
+#[test]
+fn test_license_receipt_hash() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    let terms_hash = BytesN::from_array(&env, &[7; 32]);
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &Some(terms_hash.clone()), &500, &100, &token_address, &vec![&env]);
+    client.register_ip(&owner, &2, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    client.set_license_duration(&vec![&env, owner.clone()], &1, &Some(60));
+
+    assert_eq!(client.try_license_receipt(&1, &buyer), Err(Ok(IPError::LicenseNotFound)));
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.purchase_license(&buyer, &2, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+
+    let mut preimage = Bytes::from_slice(&env, b"flavorsnap-license-receipt-v1");
+    preimage.extend_from_array(&1u64.to_be_bytes());
+    preimage.push_back(1);
+    preimage.extend_from_array(&1_000u64.to_be_bytes());
+    preimage.push_back(1);
+    preimage.extend_from_array(&1_060u64.to_be_bytes());
+    preimage.push_back(1);
+    preimage.extend_from_array(&terms_hash.to_array());
+    let expected: BytesN<32> = env.crypto().sha256(&preimage).into();
+    assert_eq!(client.license_receipt(&1, &buyer), expected);
+
+    let mut preimage = Bytes::from_slice(&env, b"flavorsnap-license-receipt-v1");
+    preimage.extend_from_array(&2u64.to_be_bytes());
+    preimage.push_back(0);
+    preimage.extend_from_array(&1_000u64.to_be_bytes());
+    preimage.push_back(0);
+    preimage.push_back(0);
+    let expected: BytesN<32> = env.crypto().sha256(&preimage).into();
+    assert_eq!(client.license_receipt(&2, &buyer), expected);
+}
+
+#[test]
+fn test_export_ips_and_licenses() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), Address::generate(&env), 0u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    for ip_id in [5u64, 3, 9] {
+        client.register_ip(&owner, &ip_id, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    }
+    client.purchase_license(&buyer, &3, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.purchase_license(&buyer, &9, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.revoke_license(&vec![&env, owner.clone()], &buyer, &3, &symbol_short!("breach"));
+    client.purchase_license(&buyer, &3, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.deregister_ip(&vec![&env, owner.clone()], &5);
+    client.freeze_ip(&admin, &9, &String::from_str(&env, "court order"));
+
+    let (page, cursor) = client.export_ips(&0, &2);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get_unchecked(0).ip_id, 3);
+    assert_eq!(cursor, Some(2));
+    let (page, cursor) = client.export_ips(&2, &2);
+    assert_eq!(page.get_unchecked(0).ip_id, 9);
+    assert_eq!(page.get_unchecked(0).freeze_reason, Some(String::from_str(&env, "court order")));
+    assert_eq!(cursor, None);
+
+    // Certificate 1 was superseded by the re-purchase of IP 3
+    let (page, cursor) = client.export_licenses(&0, &50);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get_unchecked(0).license_id, 2);
+    assert_eq!(page.get_unchecked(1).license_id, 3);
+    assert_eq!(cursor, None);
+}
+
+#[test]
+fn test_import_from_export_then_finalize() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let source = IPLicensingContractClient::new(
+        &env,
+        &env.register(IPLicensingContract, (admin.clone(), Address::generate(&env), 0u32)),
+    );
+    let target = IPLicensingContractClient::new(
+        &env,
+        &env.register(IPLicensingContract, (admin.clone(), Address::generate(&env), 0u32)),
+    );
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    source.register_ip(&owner, &7, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    source.purchase_license(&buyer, &7, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    let (ips, _) = source.export_ips(&0, &50);
+    let (licenses, _) = source.export_licenses(&0, &50);
+    assert_eq!(target.try_import_ips(&buyer, &ips), Err(Ok(IPError::Unauthorized)));
+    target.import_ips(&admin, &ips);
+    target.import_licenses(&admin, &licenses);
+    assert_eq!(target.try_import_ips(&admin, &ips), Err(Ok(IPError::IPAlreadyRegistered)));
+
+    assert_eq!(target.get_ip(&7), source.get_ip(&7));
+    assert_eq!(target.get_license_by_id(&1), source.get_license(&7, &buyer));
+
+    target.finalize_import(&admin);
+    assert!(target.is_import_finalized());
+    assert_eq!(target.try_import_licenses(&admin, &licenses), Err(Ok(IPError::ImportFinalized)));
+
+    // Certificate ids continue after the imported ones
+    let second_buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&second_buyer, &1_000);
+    target.purchase_license(&second_buyer, &7, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(target.get_license(&7, &second_buyer).license_id, 2);
+    assert_eq!(target.get_license(&7, &second_buyer).sequence, 2);
+}
+
+#[cfg(feature = "shares")]
+#[test]
+fn test_revenue_shares_accrue_and_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+
+    let owner = Address::generate(&env);
+    let investor = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+
+    assert_eq!(client.share_balance(&1, &owner), 0);
+    client.issue_shares(&vec![&env, owner.clone()], &1, &1_000);
+    assert_eq!(client.try_issue_shares(&vec![&env, owner.clone()], &1, &1_000), Err(Ok(IPError::SharesAlreadyIssued)));
+    assert_eq!(client.share_total_supply(&1), 1_000);
+    assert_eq!(client.share_decimals(&1), 7);
+
+    client.share_transfer(&1, &owner, &investor, &250);
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    // The sale is held by the contract and split pro rata
+    assert_eq!(token_client.balance(&owner), 0);
+    assert_eq!(client.claimable_share_revenue(&1, &owner), 75);
+    assert_eq!(client.claimable_share_revenue(&1, &investor), 25);
+
+    // Revenue accrued before a transfer stays with the sender
+    client.share_approve(&1, &investor, &spender, &250, &1_000);
+    assert_eq!(client.try_share_transfer_from(&1, &spender, &investor, &spender, &300), Err(Ok(IPError::InsufficientShares)));
+    client.share_transfer_from(&1, &spender, &investor, &spender, &250);
+    assert_eq!(client.share_allowance(&1, &investor, &spender), 0);
+    assert_eq!(client.share_balance(&1, &spender), 250);
+
+    client.pay_usage_royalty(&buyer, &buyer, &1, &400, &usage_report(&env), &None, &None);
+    assert_eq!(client.claim_share_revenue(&1, &investor), 25);
+    assert_eq!(client.claim_share_revenue(&1, &spender), 100);
+    assert_eq!(client.claim_share_revenue(&1, &owner), 375);
+    assert_eq!(token_client.balance(&owner), 375);
+    assert_eq!(token_client.balance(&contract_address), 0);
+}
+
+mod mock_price_oracle {
+    use crate::price_oracle::{Asset, PriceData};
+    use soroban_sdk::{contract, contractimpl, symbol_short, Env};
+
+    #[contract]
+    pub struct MockPriceOracle;
+
+    #[contractimpl]
+    impl MockPriceOracle {
+        pub fn set_price(env: Env, price: i128, timestamp: u64) {
+            env.storage().instance().set(&symbol_short!("price"), &PriceData { price, timestamp });
+        }
+
+        pub fn decimals(_env: Env) -> u32 {
+            2
+        }
+
+        pub fn lastprice(env: Env, _asset: Asset) -> Option<PriceData> {
+            env.storage().instance().get(&symbol_short!("price"))
+        }
+    }
+}
+
+#[test]
+fn test_reference_priced_purchase_with_slippage_bound() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+
+    let admin = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), Address::generate(&env), 0u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let oracle_address = env.register(mock_price_oracle::MockPriceOracle, ());
+    let oracle = mock_price_oracle::MockPriceOracleClient::new(&env, &oracle_address);
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &100_000_000);
+    // Priced at 100 reference units (cents)
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    client.set_reference_pricing(&vec![&env, owner.clone()], &1, &true);
+
+    assert_eq!(client.try_quote_license(&1, &LicenseType::NonExclusive), Err(Ok(IPError::PriceUnavailable)));
+    client.set_price_oracle(&admin, &oracle_address, &300);
+
+    // One token (7 decimals) trades at 50.00 cents
+    oracle.set_price(&5_000, &9_000);
+    assert_eq!(client.try_quote_license(&1, &LicenseType::NonExclusive), Err(Ok(IPError::PriceUnavailable)));
+    oracle.set_price(&5_000, &10_000);
+    assert_eq!(client.quote_license(&1, &LicenseType::NonExclusive), 20_000_000);
+
+    let res = client.try_purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &Some(19_999_999), &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::SlippageExceeded)));
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &Some(20_000_000), &u64::MAX, &None, &None);
+    assert_eq!(client.get_claimable(&owner, &token_address), 20_000_000);
+}
+
+#[test]
+fn test_agreed_prices_settle_without_the_oracle() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "chef", 1_000, 100)
+        .with_ip(3, "chef", 1_000, 100)
+        .with_funds("studio", 2_000)
+        .with_funds("bistro", 2_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (studio, bistro) = (s.party("studio"), s.party("bistro"));
+    let salt = BytesN::from_array(&s.env, &[3; 32]);
+    let terms = OptionTerms { fee: 50, strike_price: 800, window_secs: 100 };
+    for ip_id in 1..=3 {
+        client.set_reference_pricing(&owners, &ip_id, &true);
+    }
+    client.set_option_terms(&owners, &3, &Some(terms.clone()));
+
+    // No oracle is configured, so list-priced sales fail...
+    let res = client.try_purchase_license(&studio, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::PriceUnavailable)));
+
+    // ...but offers, auctions and options settle at the price agreed
+    let offer_id = client.make_offer(&studio, &1, &LicenseType::NonExclusive, &80, &(scenarios::START_TIME + 100));
+    assert_eq!(client.accept_offer(&owners, &offer_id).price_paid, 80);
+
+    let auction_id = client.start_sealed_auction(&owners, &2, &300, &100, &100, &100);
+    client.commit_bid(&bistro, &auction_id, &client.bid_commitment(&400, &salt));
+    s.advance_time(100);
+    client.reveal_bid(&bistro, &auction_id, &400, &salt);
+    s.advance_time(100);
+    assert_eq!(client.settle_sealed_auction(&auction_id).unwrap().price_paid, 300);
+
+    client.take_exclusive_option(&studio, &3, &terms);
+    assert_eq!(client.exercise_exclusive_option(&studio, &3).price_paid, 800);
+}
+
+#[test]
+fn test_usage_tiers_prices_and_exclusivity() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "owner", 5_000, 100)
+        .with_license(1, "legacy", LicenseType::NonExclusive)
+        .with_funds("blog", 1_000)
+        .with_funds("network", 1_000)
+        .with_funds("rival", 1_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("owner")];
+    let (blog, network, rival) = (s.party("blog"), s.party("network"), s.party("rival"));
+
+    // Tiers are only sold once priced, and legacy types keep their own setter
+    let res = client.try_purchase_license(&blog, &1, &LicenseType::Editorial, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::InvalidTier)));
+    let res = client.try_set_tier_price(&owners, &1, &LicenseType::Exclusive, &Some(10));
+    assert_eq!(res, Err(Ok(IPError::InvalidTier)));
+    client.set_tier_price(&owners, &1, &LicenseType::Editorial, &Some(40));
+    client.set_tier_price(&owners, &1, &LicenseType::Broadcast, &Some(600));
+    assert_eq!(client.quote_license(&1, &LicenseType::Editorial), 40);
+
+    client.purchase_license(&blog, &1, &LicenseType::Editorial, &None, &None, &u64::MAX, &None, &None);
+    client.purchase_license(&network, &1, &LicenseType::Broadcast, &None, &None, &u64::MAX, &None, &None);
+    let res = client.try_purchase_license(&rival, &1, &LicenseType::Broadcast, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::BroadcastAlreadyIssued)));
+    let res = client.try_purchase_license(&rival, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::ActiveLicensesExist)));
+    assert_eq!(client.get_ip(&1).active_licenses, 3);
+    assert_eq!(s.balance("network"), 400);
+
+    // Revoking the broadcast license frees the tier
+    client.revoke_license(&owners, &network, &1, &symbol_short!("breach"));
+    client.purchase_license(&rival, &1, &LicenseType::Broadcast, &None, &None, &u64::MAX, &None, &None);
+    assert!(client.get_ip(&1).has_broadcast);
+
+    // Non-exclusive licenses move onto Commercial, carrying the non-exclusive
+    // price over, but not while the IP is under legal hold
+    let licensees = vec![&s.env, s.party("legacy"), blog.clone()];
+    client.freeze_ip(&s.party("admin"), &1, &String::from_str(&s.env, "court order"));
+    let res = client.try_move_to_commercial_tier(&owners, &1, &licensees);
+    assert_eq!(res, Err(Ok(IPError::IPFrozen)));
+    client.unfreeze_ip(&s.party("admin"), &1);
+
+    let moved = client.move_to_commercial_tier(&owners, &1, &licensees);
+    assert_eq!(moved, 1);
+    assert_eq!(client.get_license(&1, &s.party("legacy")).license_type, LicenseType::Commercial);
+    assert_eq!(client.get_license(&1, &blog).license_type, LicenseType::Editorial);
+    assert_eq!(client.get_tier_prices(&1).get(LicenseType::Commercial), Some(100));
+
+    client.set_tier_price(&owners, &1, &LicenseType::Editorial, &None);
+    assert_eq!(client.try_quote_license(&1, &LicenseType::Editorial), Err(Ok(IPError::InvalidTier)));
+}
+
+#[test]
+fn test_royalty_reports_kept_and_flagged() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "owner", 500, 100)
+        .with_license(1, "studio", LicenseType::NonExclusive)
+        .with_funds("studio", 100)
+        .build();
+    let client = s.client();
+    let studio = s.party("studio");
+    let owners = vec![&s.env, s.party("owner")];
+    let march = BytesN::from_array(&s.env, &[3; 32]);
+
+    assert_eq!(client.pay_usage_royalty(&studio, &studio, &1, &30, &usage_report(&s.env), &None, &None), 0);
+    s.advance_time(60);
+    assert_eq!(client.pay_usage_royalty(&studio, &studio, &1, &20, &march, &None, &None), 1);
+
+    let reports = client.get_royalty_reports(&1, &0, &10);
+    assert_eq!(reports.len(), 2);
+    let report = reports.get(1).unwrap();
+    assert_eq!((report.licensee.clone(), report.amount, report.report_hash.clone()), (studio.clone(), 20, march.clone()));
+    assert_eq!(report.paid_at, scenarios::START_TIME + 60);
+    assert_eq!(report.status, ReportStatus::Filed);
+
+    let res = client.try_flag_report(&vec![&s.env, studio.clone()], &1, &1, &symbol_short!("underrep"));
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    assert_eq!(client.try_flag_report(&owners, &1, &2, &symbol_short!("underrep")), Err(Ok(IPError::ReportNotFound)));
+    client.flag_report(&owners, &1, &1, &symbol_short!("underrep"));
+    let event = s.env.events().all().last().unwrap();
+    assert_eq!(event.1, (symbol_short!("rpt_flag"), 1u64, studio.clone()).into_val(&s.env));
+    assert_eq!(<(u32, BytesN<32>, Symbol)>::from_val(&s.env, &event.2), (1, march, symbol_short!("underrep")));
+
+    let report = client.get_royalty_report(&1, &1);
+    assert_eq!(report.status, ReportStatus::Disputed);
+    assert_eq!(report.dispute_reason, Some(symbol_short!("underrep")));
+    let res = client.try_flag_report(&owners, &1, &1, &symbol_short!("underrep"));
+    assert_eq!(res, Err(Ok(IPError::DuplicateOperation)));
+}
+
+#[test]
+fn test_claimable_proceeds_and_push_payouts() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "owner", 500, 100)
+        .with_ip(2, "owner", 500, 100)
+        .with_license(1, "buyer", LicenseType::NonExclusive)
+        .with_license(2, "buyer", LicenseType::NonExclusive)
+        .with_funds("buyer", 90)
+        .build();
+    let client = s.client();
+    let (owner, buyer) = (s.party("owner"), s.party("buyer"));
+
+    // Proceeds from both IPs accrue to one balance per token
+    client.pay_usage_royalty(&buyer, &buyer, &1, &50, &usage_report(&s.env), &None, &None);
+    assert_eq!(s.balance("owner"), 0);
+    assert_eq!(client.get_claimable(&owner, &s.token), 250);
+
+    assert_eq!(client.claim(&owner, &s.token), 250);
+    assert_eq!(client.claim(&owner, &s.token), 0);
+    assert_eq!(s.balance("owner"), 250);
+
+    client.set_push_payouts(&vec![&s.env, owner.clone()], &1, &true);
+    client.pay_usage_royalty(&buyer, &buyer, &1, &40, &usage_report(&s.env), &None, &None);
+    assert_eq!(s.balance("owner"), 290);
+    assert_eq!(client.get_claimable(&owner, &s.token), 0);
+}
+
+#[test]
+fn test_revenue_routed_to_recipient_and_creator_vault() {
+    use flavorsnap_creator_vault::{CreatorVaultContract, CreatorVaultContractClient, Split};
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+
+    let owner = Address::generate(&env);
+    let partner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+
+    let vault_id = env.register(CreatorVaultContract, (owner.clone(),));
+    let vault = CreatorVaultContractClient::new(&env, &vault_id);
+    let splits = vec![
+        &env,
+        Split { member: owner.clone(), bps: 7_000 },
+        Split { member: partner.clone(), bps: 3_000 },
+    ];
+    vault.set_splits(&owner, &splits);
+
+    let res = client.try_set_revenue_recipient(&vec![&env, buyer.clone()], &1, &Some(vault_id.clone()), &true);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    client.set_revenue_recipient(&vec![&env, owner.clone()], &1, &Some(vault_id.clone()), &true);
+
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.pay_usage_royalty(&buyer, &buyer, &1, &50, &usage_report(&env), &None, &None);
+    assert_eq!(client.get_claimable(&owner, &token_address), 0);
+    assert_eq!(token_client.balance(&vault_id), 150);
+    assert_eq!(vault.ip_revenue(&contract_address, &1, &token_address), 150);
+    assert_eq!(vault.balance(&owner, &token_address), 105);
+    assert_eq!(vault.balance(&partner, &token_address), 45);
+
+    // A plain account recipient is paid directly
+    client.set_revenue_recipient(&vec![&env, owner.clone()], &1, &Some(partner.clone()), &false);
+    client.pay_usage_royalty(&buyer, &buyer, &1, &40, &usage_report(&env), &None, &None);
+    assert_eq!(token_client.balance(&partner), 40);
+
+    client.set_revenue_recipient(&vec![&env, owner.clone()], &1, &None, &true);
+    assert!(!client.get_ip(&1).revenue_to_vault);
+    client.pay_usage_royalty(&buyer, &buyer, &1, &10, &usage_report(&env), &None, &None);
+    assert_eq!(client.get_claimable(&owner, &token_address), 10);
+}
+
+#[test]
+fn test_operator_pays_usage_royalty() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token_client = token::Client::new(&env, &token_address);
+
+    let owner = Address::generate(&env);
+    let licensee = Address::generate(&env);
+    let render_farm = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&licensee, &1_000);
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    client.purchase_license(&licensee, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    let res = client.try_pay_usage_royalty(&render_farm, &licensee, &1, &30, &usage_report(&env), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+
+    client.add_operator(&licensee, &render_farm);
+    assert!(client.is_operator(&licensee, &render_farm));
+    token_client.approve(&licensee, &contract_address, &30, &1_000);
+    client.pay_usage_royalty(&render_farm, &licensee, &1, &30, &usage_report(&env), &None, &None);
+    assert_eq!(token_client.balance(&licensee), 870);
+    assert_eq!(client.get_claimable(&owner, &token_address), 130);
+
+    client.remove_operator(&licensee, &render_farm);
+    let res = client.try_pay_usage_royalty(&render_farm, &licensee, &1, &30, &usage_report(&env), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+}
+
+#[test]
+fn test_ip_events_carry_ip_id_topic() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+
+    // The last event published by this contract in the latest invocation
+    let last_event = || {
+        env.events().all().iter().filter(|event| event.0 == contract_address).last().unwrap()
+    };
+
+    client.register_ip(&owner, &7, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    assert_eq!(last_event().1, (symbol_short!("ip_reg"), 7u64).into_val(&env));
+
+    client.purchase_license(&buyer, &7, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    let event = last_event();
+    assert_eq!(event.1, (symbol_short!("purchase"), 7u64, buyer.clone()).into_val(&env));
+    assert_eq!(<(u64, i128, Option<u32>)>::from_val(&env, &event.2), (1, 100, None));
+
+    client.pay_usage_royalty(&buyer, &buyer, &7, &25, &usage_report(&env), &None, &None);
+    assert_eq!(last_event().1, (symbol_short!("royalty"), 7u64, buyer.clone()).into_val(&env));
+
+    client.revoke_license(&vec![&env, owner.clone()], &buyer, &7, &symbol_short!("breach"));
+    assert_eq!(last_event().1, (symbol_short!("revoke"), 7u64, buyer.clone()).into_val(&env));
+
+    client.set_prices(&vec![&env, owner.clone()], &7, &600, &120);
+    assert_eq!(last_event().1, (symbol_short!("prices"), 7u64).into_val(&env));
+}
+
+#[test]
+fn test_owner_of_and_prove_ownership() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    assert_eq!(client.try_owner_of(&1), Err(Ok(IPError::IPNotFound)));
+    assert!(!client.prove_ownership(&1, &owner));
+
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    assert_eq!(client.owner_of(&1), owner);
+    assert!(client.prove_ownership(&1, &owner));
+    assert!(!client.prove_ownership(&1, &new_owner));
+
+    client.transfer_ip(&vec![&env, owner.clone()], &1, &new_owner);
+    assert_eq!(client.owner_of(&1), new_owner);
+    assert!(!client.prove_ownership(&1, &owner));
+
+    // A tombstoned IP still has an owner but proves nothing
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.deregister_ip(&vec![&env, new_owner.clone()], &1);
+    assert_eq!(client.owner_of(&1), new_owner);
+    assert!(!client.prove_ownership(&1, &new_owner));
+}
+
+#[test]
+fn test_purchase_with_voucher_registers_on_first_sale() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let creator = Address::generate(&env);
+    let first_buyer = Address::generate(&env);
+    let second_buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&first_buyer, &1_000);
+    token::StellarAssetClient::new(&env, &token_address).mint(&second_buyer, &1_000);
+
+    let voucher = RegistrationVoucher {
+        ip_id: 42,
+        metadata_uri: String::from_str(&env, "ipfs://catalog/42"),
+        metadata_hash: Some(BytesN::from_array(&env, &[3; 32])),
+        price_exclusive: 500,
+        price_non_exclusive: 100,
+        payment_token: token_address.clone(),
+        tags: vec![&env],
+        license_duration: Some(3_600),
+    };
+    assert_eq!(client.try_get_ip(&42), Err(Ok(IPError::IPNotFound)));
+
+    client.purchase_with_voucher(&first_buyer, &creator, &voucher, &LicenseType::NonExclusive, &None, &u64::MAX, &None);
+
+    // The creator signed for this exact voucher
+    let creator_auth = env.auths().into_iter().find(|(address, _)| *address == creator).unwrap();
+    assert_eq!(
+        creator_auth.1.function,
+        AuthorizedFunction::Contract((
+            contract_address.clone(),
+            Symbol::new(&env, "purchase_with_voucher"),
+            (voucher.clone(),).into_val(&env),
+        ))
+    );
+
+    let ip = client.get_ip(&42);
+    assert_eq!(ip.owner, creator);
+    assert_eq!(ip.license_duration, Some(3_600));
+    assert_eq!(client.get_claimable(&creator, &token_address), 100);
+
+    // The voucher only lists the IP once; later buyers purchase normally
+    let res = client.try_purchase_with_voucher(&second_buyer, &creator, &voucher, &LicenseType::NonExclusive, &None, &u64::MAX, &None);
+    assert_eq!(res, Err(Ok(IPError::IPAlreadyRegistered)));
+    client.purchase_license(&second_buyer, &42, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+}
+
+#[test]
+fn test_purchase_rejected_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 5_000);
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+
+    let res = client.try_purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &4_999, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::DeadlineExceeded)));
+    assert_eq!(token::Client::new(&env, &token_address).balance(&buyer), 1_000);
+
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &5_000, &None, &None);
+}
+
+#[test]
+fn test_mutating_entrypoints_return_results() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| {
+        li.timestamp = 7_000;
+        li.sequence_number = 420;
+    });
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+
+    let asset = client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    assert_eq!(asset, client.get_ip(&1));
+    assert_eq!(asset.owner, owner);
+
+    let license = client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(license, client.get_license(&1, &buyer));
+    assert_eq!(license.license_id, 1);
+    assert_eq!(license.issued_at, 7_000);
+    assert_eq!((license.issued_ledger, license.sequence), (420, 1));
+    assert_eq!(license.price_paid, 100);
+
+    // Issuance in the same ledger is still ordered
+    let second_buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&second_buyer, &1_000);
+    let second = client.purchase_license(&second_buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!((second.issued_at, second.issued_ledger, second.sequence), (7_000, 420, 2));
+}
+
+#[test]
+fn test_privileged_actions_audit_logged() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), treasury.clone(), 0u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    assert_eq!(client.get_audit_log_len(), 0);
+
+    client.grant_role(&admin, &Role::FeeManager, &admin);
+    client.set_fee(&admin, &treasury, &300);
+    client.freeze_ip(&admin, &1, &String::from_str(&env, "court order"));
+    client.unfreeze_ip(&admin, &1);
+
+    let log = client.get_audit_log(&admin, &0, &10);
+    assert_eq!(log.len(), 4);
+    for (seq, entry) in log.iter().enumerate() {
+        assert_eq!(entry.seq, seq as u32);
+        assert_eq!(entry.actor, admin);
+    }
+    let fee = log.get(1).unwrap();
+    assert_eq!(fee.action, symbol_short!("fee_set"));
+    assert_eq!(u32::from_val(&env, &fee.details.get(1).unwrap()), 300);
+    assert_eq!(log.get(2).unwrap().action, symbol_short!("ip_frz"));
+    assert_eq!(log.get(3).unwrap().action, symbol_short!("ip_unfrz"));
+    assert_eq!(client.get_audit_log(&admin, &4, &10).len(), 0);
+}
+
+#[test]
+fn test_auditor_reads_restricted_views_only() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let auditor = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), Address::generate(&env), 0u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let metadata = String::from_str(&env, "ipfs://metadata");
+    for ip_id in 1..=3u64 {
+        client.register_ip(&owner, &ip_id, &metadata, &None, &500, &100, &token_address, &vec![&env]);
+    }
+    let order = String::from_str(&env, "court order");
+    client.freeze_ip(&admin, &1, &order);
+    client.freeze_ip(&admin, &3, &order);
+    client.freeze_ip(&admin, &3, &String::from_str(&env, "appeal"));
+
+    assert_eq!(client.try_list_frozen_ips(&auditor, &0, &10), Err(Ok(IPError::Unauthorized)));
+    assert_eq!(client.try_get_audit_log(&auditor, &0, &10), Err(Ok(IPError::Unauthorized)));
+    client.grant_role(&admin, &Role::Auditor, &auditor);
+
+    let holds = client.list_frozen_ips(&auditor, &0, &10);
+    assert_eq!(holds, vec![&env, (1u64, order.clone()), (3u64, String::from_str(&env, "appeal"))]);
+    client.unfreeze_ip(&admin, &1);
+    assert_eq!(client.list_frozen_ips(&auditor, &0, &10).len(), 1);
+    assert_eq!(client.list_frozen_ips(&auditor, &1, &10).len(), 0);
+    assert_eq!(client.get_audit_log(&auditor, &0, &10).len(), 5);
+
+    // No mutation rights come with the role
+    let res = client.try_freeze_ip(&auditor, &2, &order);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    let res = client.try_grant_role(&auditor, &Role::Auditor, &owner);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+}
+
+#[test]
+fn test_frozen_index_pages_across_chunks() {
+    let s = ScenarioBuilder::new().with_ip(1, "chef", 500, 100).build();
+    let client = s.client();
+    let admin = s.party("admin");
+    let order = String::from_str(&s.env, "court order");
+
+    let holds = 2 * flavorsnap_common::CHUNK_SIZE as u64 + 2;
+    s.env.as_contract(&s.contract, || {
+        for ip_id in 1..=holds {
+            storage::write_freeze_reason(&s.env, ip_id, &order);
+        }
+        assert_eq!(storage::frozen_index().len(&s.env), holds as u32);
+    });
+
+    // Lifting an early hold moves the latest one into its slot
+    client.unfreeze_ip(&admin, &2);
+    let page = client.list_frozen_ips(&admin, &0, &3);
+    assert_eq!(page, vec![&s.env, (1u64, order.clone()), (holds, order.clone()), (3u64, order.clone())]);
+
+    // Pages straddle chunk boundaries and end at the shortened length
+    let start = flavorsnap_common::CHUNK_SIZE - 1;
+    let page = client.list_frozen_ips(&admin, &start, &2);
+    assert_eq!(page.get_unchecked(0).0, start as u64 + 1);
+    assert_eq!(page.get_unchecked(1).0, start as u64 + 2);
+    assert_eq!(client.list_frozen_ips(&admin, &(holds as u32 - 2), &10).len(), 1);
+
+    // Lifting the hold that moved keeps its new position in step
+    client.unfreeze_ip(&admin, &holds);
+    assert_eq!(client.list_frozen_ips(&admin, &1, &1), vec![&s.env, (holds - 1, order.clone())]);
+    assert_eq!(client.get_freeze_reason(&holds), None);
+}
+
+#[test]
+fn test_versioned_config_setter() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), treasury.clone(), 100u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let config = client.get_config();
+    assert_eq!(config.version, 1);
+    assert_eq!(config.max_batch_size, 50);
+    assert_eq!(config.restore_window_secs, 30 * 24 * 60 * 60);
+
+    let mut update = config.clone();
+    update.max_batch_size = 2;
+    update.restore_window_secs = 60;
+    assert_eq!(client.try_set_config(&treasury, &update), Err(Ok(IPError::Unauthorized)));
+
+    let mut bad = update.clone();
+    bad.version = 2;
+    assert_eq!(client.try_set_config(&admin, &bad), Err(Ok(IPError::InvalidConfig)));
+    bad = update.clone();
+    bad.admin = treasury.clone();
+    assert_eq!(client.try_set_config(&admin, &bad), Err(Ok(IPError::InvalidConfig)));
+    bad = update.clone();
+    bad.max_batch_size = 0;
+    assert_eq!(client.try_set_config(&admin, &bad), Err(Ok(IPError::InvalidConfig)));
+
+    client.set_config(&admin, &update);
+    assert_eq!(client.get_config(), update);
+    assert_eq!(client.get_audit_log(&admin, &0, &10).get(0).unwrap().action, symbol_short!("config"));
+
+    // The batch cap and restore window follow the stored config
+    let owner = Address::generate(&env);
+    let metadata = String::from_str(&env, "ipfs://metadata");
+    for ip_id in 1..=3u64 {
+        client.register_ip(&owner, &ip_id, &metadata, &None, &500, &100, &token_address, &vec![&env]);
+    }
+    let (page, next) = client.export_ips(&0, &10);
+    assert_eq!(page.len(), 2);
+    assert_eq!(next, Some(2));
+
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.deregister_ip(&vec![&env, owner.clone()], &1);
+    env.ledger().with_mut(|li| li.timestamp += 61);
+    assert_eq!(client.try_restore_ip(&vec![&env, owner.clone()], &1), Err(Ok(IPError::RestoreWindowExpired)));
+}
+
+#[test]
+fn test_amounts_validated_against_token_decimals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let owners = vec![&env, owner.clone()];
+    let metadata = String::from_str(&env, "ipfs://metadata");
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+
+    // A trillion whole tokens at the SAC's 7 decimals is the ceiling
+    let ceiling = 1_000_000_000_000i128 * 10_000_000;
+    let res = client.try_register_ip(&owner, &1, &metadata, &None, &(ceiling + 1), &100, &token_address, &vec![&env]);
+    assert_eq!(res, Err(Ok(IPError::InvalidAmount)));
+    let asset = client.register_ip(&owner, &1, &metadata, &None, &ceiling, &100, &token_address, &vec![&env]);
+    assert_eq!(asset.token_decimals, 7);
+
+    assert_eq!(client.quote_price(&1, &LicenseType::NonExclusive), (100, token_address.clone(), 7));
+    assert_eq!(client.try_set_prices(&owners, &1, &500, &(ceiling + 1)), Err(Ok(IPError::InvalidAmount)));
+
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    let res = client.try_pay_usage_royalty(&buyer, &buyer, &1, &(ceiling + 1), &usage_report(&env), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::InvalidAmount)));
+}
+
+#[test]
+fn test_ip_stats_track_sales_and_royalties() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 0u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&first, &1_000);
+    token::StellarAssetClient::new(&env, &token_address).mint(&second, &1_000);
+
+    assert_eq!(client.try_get_ip_stats(&1), Err(Ok(IPError::IPNotFound)));
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    assert_eq!(client.get_ip_stats(&1), IPStats::default());
+
+    client.purchase_license(&first, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    env.ledger().with_mut(|li| li.timestamp = 3_000);
+    client.purchase_license(&second, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.pay_usage_royalty(&first, &first, &1, &40, &usage_report(&env), &None, &None);
+
+    assert_eq!(
+        client.get_ip_stats(&1),
+        IPStats { licenses_sold: 2, total_revenue: 200, total_royalties: 40, last_sale_at: Some(3_000) }
+    );
+}
+
+#[test]
+fn test_revocation_reason_and_appeal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let admin = Address::generate(&env);
+    let contract_address = env.register(IPLicensingContract, (admin.clone(), Address::generate(&env), 0u32));
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    let owner = Address::generate(&env);
+    let owners = vec![&env, owner.clone()];
+    let buyer = Address::generate(&env);
+    let late_buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1_000);
+    token::StellarAssetClient::new(&env, &token_address).mint(&late_buyer, &1_000);
+    client.register_ip(&owner, &1, &String::from_str(&env, "ipfs://metadata"), &None, &500, &100, &token_address, &vec![&env]);
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.purchase_license(&late_buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    // Without an appeal window the revocation is final
+    client.revoke_license(&owners, &late_buyer, &1, &symbol_short!("fraud"));
+    assert_eq!(<Symbol>::from_val(&env, &env.events().all().last().unwrap().2), symbol_short!("fraud"));
+    let license = client.get_license(&1, &late_buyer);
+    assert_eq!(license.revocation_reason, Some(symbol_short!("fraud")));
+    assert_eq!(license.appeal_deadline, None);
+    assert_eq!(client.try_appeal_revocation(&late_buyer, &1), Err(Ok(IPError::AppealClosed)));
+
+    client.set_appeal_window(&owners, &1, &Some(100));
+    client.revoke_license(&owners, &buyer, &1, &symbol_short!("breach"));
+    assert_eq!(client.get_license(&1, &buyer).appeal_deadline, Some(1_100));
+    assert_eq!(client.try_appeal_revocation(&late_buyer, &1), Err(Ok(IPError::AppealClosed)));
+    assert_eq!(client.try_resolve_appeal(&admin, &1, &buyer, &true), Err(Ok(IPError::NoPendingAppeal)));
+
+    client.appeal_revocation(&buyer, &1);
+    assert_eq!(client.get_license(&1, &buyer).appeal, AppealStatus::Pending);
+    assert_eq!(client.try_appeal_revocation(&buyer, &1), Err(Ok(IPError::AppealClosed)));
+
+    // Only an Admin or Arbiter settles appeals
+    assert_eq!(client.try_resolve_appeal(&owner, &1, &buyer, &true), Err(Ok(IPError::Unauthorized)));
+    client.resolve_appeal(&admin, &1, &buyer, &true);
+    let license = client.get_license(&1, &buyer);
+    assert!(license.is_active);
+    assert_eq!(license.appeal, AppealStatus::Upheld);
+    assert_eq!(license.revocation_reason, Some(symbol_short!("breach")));
+    assert_eq!(client.get_ip(&1).active_licenses, 1);
+
+    // Appeals past the deadline are refused
+    client.revoke_license(&owners, &buyer, &1, &symbol_short!("breach"));
+    env.ledger().with_mut(|li| li.timestamp = 1_101);
+    assert_eq!(client.try_appeal_revocation(&buyer, &1), Err(Ok(IPError::AppealClosed)));
+}
+
+#[test]
+fn test_indemnity_bond_awarded_or_released() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 500, 100)
+        .with_ip(2, "chef", 500, 100)
+        .with_funds("chef", 1_000)
+        .with_funds("studio", 500)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let studio = s.party("studio");
+    let arbiter = Address::generate(&s.env);
+    client.grant_role(&s.party("admin"), &Role::Arbiter, &arbiter);
+
+    let bond = client.post_bond(&owners, &1, &300, &100);
+    assert_eq!((bond.release_at, bond.status), (scenarios::START_TIME + 100, BondStatus::Held));
+    assert_eq!(client.try_post_bond(&owners, &1, &300, &100), Err(Ok(IPError::DuplicateOperation)));
+    client.post_bond(&owners, &2, &200, &100);
+    assert_eq!(s.balance("chef"), 500);
+
+    // An exclusive sale restarts the challenge period
+    s.advance_time(50);
+    client.purchase_license(&studio, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    s.advance_time(50);
+    assert_eq!(client.try_release_bond(&1), Err(Ok(IPError::BondLocked)));
+    assert_eq!(client.get_bond(&1).release_at, scenarios::START_TIME + 150);
+
+    let res = client.try_award_bond(&studio, &1, &studio);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    assert_eq!(client.try_award_bond(&arbiter, &2, &studio), Err(Ok(IPError::LicenseNotFound)));
+    assert_eq!(client.award_bond(&arbiter, &1, &studio), 300);
+    assert_eq!(s.balance("studio"), 300);
+    assert_eq!(client.get_bond(&1).status, BondStatus::Awarded);
+    assert_eq!(client.try_release_bond(&1), Err(Ok(IPError::BondNotHeld)));
+
+    // Unchallenged bonds return to the depositor
+    client.release_bond(&2);
+    assert_eq!(s.balance("chef"), 700);
+    assert_eq!(client.get_bond(&2).status, BondStatus::Released);
+    assert_eq!(client.try_get_bond(&3), Err(Ok(IPError::BondNotHeld)));
+}
+
+#[test]
+fn test_unsold_exclusive_price_decays_to_floor() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("studio", 1_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let studio = s.party("studio");
+    let month = 30 * 24 * 60 * 60;
+
+    let res = client.try_set_price_decay(&owners, &1, &1_000, &0, &700);
+    assert_eq!(res, Err(Ok(IPError::InvalidAmount)));
+    client.set_price_decay(&owners, &1, &1_000, &month, &700);
+    assert_eq!(client.quote_price(&1, &LicenseType::Exclusive).0, 1_000);
+
+    s.advance_time(month);
+    assert_eq!(client.quote_price(&1, &LicenseType::Exclusive).0, 900);
+    s.advance_time(2 * month);
+    assert_eq!(client.quote_price(&1, &LicenseType::Exclusive).0, 729);
+    s.advance_time(month);
+    assert_eq!(client.quote_price(&1, &LicenseType::Exclusive).0, 700);
+    assert_eq!(client.quote_price(&1, &LicenseType::NonExclusive).0, 100);
+
+    // A sale pays the decayed price, and freeing the slot starts a new period
+    let license = client.purchase_license(&studio, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(license.price_paid, 700);
+    s.advance_time(month);
+    client.revoke_license(&owners, &studio, &1, &symbol_short!("breach"));
+    assert_eq!(client.quote_price(&1, &LicenseType::Exclusive).0, 1_000);
+
+    // Repricing restarts the period too, and a zero step turns decay off
+    s.advance_time(month);
+    client.set_prices(&owners, &1, &2_000, &100);
+    assert_eq!(client.quote_price(&1, &LicenseType::Exclusive).0, 2_000);
+    client.set_price_decay(&owners, &1, &0, &0, &0);
+    s.advance_time(month);
+    assert_eq!(client.quote_price(&1, &LicenseType::Exclusive).0, 2_000);
+    assert_eq!(client.get_price_decay(&1), None);
+}
+
+#[test]
+fn test_token_gated_purchase_and_free_claim() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("fan", 100)
+        .with_funds("outsider", 100)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (fan, outsider) = (s.party("fan"), s.party("outsider"));
+    let community = s.env.register_stellar_asset_contract_v2(Address::generate(&s.env)).address();
+    token::StellarAssetClient::new(&s.env, &community).mint(&fan, &5);
+
+    let mut gate = TokenGate { token: community.clone(), min_balance: 0, free_claims: false };
+    assert_eq!(client.try_set_token_gate(&owners, &1, &Some(gate.clone())), Err(Ok(IPError::InvalidAmount)));
+    gate.min_balance = 5;
+    client.set_token_gate(&owners, &1, &Some(gate.clone()));
+    assert_eq!(client.get_token_gate(&1), Some(gate.clone()));
+
+    let res = client.try_purchase_license(&outsider, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::GateNotMet)));
+    client.purchase_license(&fan, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(s.balance("fan"), 0);
+
+    // Free claims are opt-in and still gated
+    let res = client.try_claim_license(&outsider, &1, &LicenseType::NonExclusive);
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    gate.free_claims = true;
+    client.set_token_gate(&owners, &1, &Some(gate));
+    let res = client.try_claim_license(&outsider, &1, &LicenseType::NonExclusive);
+    assert_eq!(res, Err(Ok(IPError::GateNotMet)));
+    token::StellarAssetClient::new(&s.env, &community).mint(&outsider, &5);
+    let license = client.claim_license(&outsider, &1, &LicenseType::NonExclusive);
+    assert_eq!(license.price_paid, 0);
+    assert_eq!(s.balance("outsider"), 100);
+
+    client.set_token_gate(&owners, &1, &None);
+    assert_eq!(client.try_claim_license(&outsider, &1, &LicenseType::Personal), Err(Ok(IPError::Unauthorized)));
+}
+
+mod mock_attestation_registry {
+    use crate::attestation::Attestation;
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    #[contract]
+    pub struct MockAttestationRegistry;
+
+    #[contractimpl]
+    impl MockAttestationRegistry {
+        pub fn attest(env: Env, subject: Address, level: u32, expires_at: u64) {
+            env.storage().persistent().set(&subject, &Attestation { level, expires_at });
+        }
+
+        pub fn attestation(env: Env, subject: Address) -> Option<Attestation> {
+            env.storage().persistent().get(&subject)
+        }
+    }
+}
+
+#[test]
+fn test_exclusive_purchases_require_attestation() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("studio", 2_000)
+        .with_funds("anon", 2_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (studio, anon) = (s.party("studio"), s.party("anon"));
+    let registry_address = s.env.register(mock_attestation_registry::MockAttestationRegistry, ());
+    let registry = mock_attestation_registry::MockAttestationRegistryClient::new(&s.env, &registry_address);
+
+    let gate = AttestationGate { registry: registry_address.clone(), min_level: 2 };
+    client.set_attestation_gate(&owners, &1, &Some(gate.clone()));
+    assert_eq!(client.get_attestation_gate(&1), Some(gate));
+
+    // Unattested, under-level and lapsed attestations are all refused
+    let res = client.try_purchase_license(&anon, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::GateNotMet)));
+    registry.attest(&studio, &1, &(scenarios::START_TIME + 100));
+    let res = client.try_purchase_license(&studio, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::GateNotMet)));
+    registry.attest(&studio, &2, &scenarios::START_TIME);
+    let res = client.try_purchase_license(&studio, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::GateNotMet)));
+
+    // Options on the exclusive slot are gated too
+    let terms = OptionTerms { fee: 10, strike_price: 900, window_secs: 50 };
+    client.set_option_terms(&owners, &1, &Some(terms.clone()));
+    assert_eq!(client.try_take_exclusive_option(&anon, &1, &terms), Err(Ok(IPError::GateNotMet)));
+
+    registry.attest(&studio, &2, &(scenarios::START_TIME + 100));
+    let license = client.purchase_license(&studio, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(license.license_type, LicenseType::Exclusive);
+
+    // A handover cannot route the exclusive to an unattested successor
+    assert_eq!(client.try_propose_handover(&studio, &1, &anon, &100, &0), Err(Ok(IPError::GateNotMet)));
+    registry.attest(&anon, &2, &(scenarios::START_TIME + 10));
+    client.propose_handover(&studio, &1, &anon, &100, &0);
+    client.fund_handover(&anon, &1);
+    s.advance_time(10);
+    assert_eq!(client.try_approve_handover(&owners, &1), Err(Ok(IPError::GateNotMet)));
+    client.cancel_handover(&anon, &1);
+
+    // Only exclusives are gated
+    client.revoke_license(&owners, &studio, &1, &symbol_short!("test"));
+    client.purchase_license(&anon, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.set_attestation_gate(&owners, &1, &None);
+    assert_eq!(client.get_attestation_gate(&1), None);
+}
+
+#[test]
+fn test_payment_memos_published_not_stored() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 500, 100)
+        .with_funds("studio", 1_000)
+        .build();
+    let client = s.client();
+    let studio = s.party("studio");
+    let invoice = String::from_str(&s.env, "INV-2026-0042");
+
+    let long_memo = String::from_str(&s.env, &"x".repeat(65));
+    let res = client.try_purchase_license(&studio, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &Some(long_memo));
+    assert_eq!(res, Err(Ok(IPError::MemoTooLong)));
+
+    let license = client.purchase_license(&studio, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &Some(invoice.clone()));
+    let event = s.env.events().all().last().unwrap();
+    assert_eq!(event.1, (symbol_short!("memo"), 1u64, studio.clone()).into_val(&s.env));
+    assert_eq!(<(Symbol, u64, String)>::from_val(&s.env, &event.2), (symbol_short!("purchase"), license.license_id, invoice));
+
+    let po = String::from_str(&s.env, "PO-77");
+    let report_id = client.pay_usage_royalty(&studio, &studio, &1, &50, &usage_report(&s.env), &None, &Some(po.clone()));
+    let event = s.env.events().all().last().unwrap();
+    assert_eq!(<(Symbol, u64, String)>::from_val(&s.env, &event.2), (symbol_short!("royalty"), report_id as u64, po));
+
+    // Without a memo only the payment event is published
+    client.pay_usage_royalty(&studio, &studio, &1, &50, &usage_report(&s.env), &None, &None);
+    assert_eq!(s.env.events().all().last().unwrap().1, (symbol_short!("royalty"), 1u64, studio).into_val(&s.env));
+}
+
+#[test]
+fn test_royalty_statement_per_period() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 500, 100)
+        .with_license(1, "bistro", LicenseType::NonExclusive)
+        .with_license(1, "cafe", LicenseType::NonExclusive)
+        .with_funds("bistro", 1_000)
+        .with_funds("cafe", 1_000)
+        .build();
+    let client = s.client();
+    let (bistro, cafe) = (s.party("bistro"), s.party("cafe"));
+    let day = statements::STATEMENT_DAY_SECS;
+
+    client.pay_usage_royalty(&bistro, &bistro, &1, &100, &usage_report(&s.env), &None, &None);
+    client.pay_usage_royalty(&cafe, &cafe, &1, &50, &usage_report(&s.env), &None, &None);
+    s.advance_time(day);
+    client.pay_usage_royalty(&bistro, &bistro, &1, &30, &usage_report(&s.env), &None, &None);
+
+    let first_day = client.get_statement(&1, &0, &day);
+    assert_eq!(first_day.total, 150);
+    assert_eq!(first_day.by_licensee.get(cafe.clone()), Some(50));
+    let both_days = client.get_statement(&1, &0, &(2 * day));
+    assert_eq!(both_days.total, 180);
+    assert_eq!(both_days.by_licensee.get(bistro), Some(130));
+    assert_eq!(client.get_statement(&1, &(2 * day), &(3 * day)).total, 0);
+
+    assert_eq!(client.try_get_statement(&1, &1, &day), Err(Ok(IPError::InvalidPeriod)));
+    assert_eq!(client.try_get_statement(&1, &day, &day), Err(Ok(IPError::InvalidPeriod)));
+    assert_eq!(client.try_get_statement(&1, &0, &(32 * day)), Err(Ok(IPError::InvalidPeriod)));
+    assert_eq!(client.try_get_statement(&2, &0, &day), Err(Ok(IPError::IPNotFound)));
+}
+
+#[test]
+fn test_step_up_royalty_schedules() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 500, 100)
+        .with_license(1, "bistro", LicenseType::NonExclusive)
+        .with_license(1, "cafe", LicenseType::NonExclusive)
+        .with_funds("bistro", 1_000)
+        .with_funds("cafe", 1_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (bistro, cafe) = (s.party("bistro"), s.party("cafe"));
+    let step = |threshold: i128, bps: u32| RoyaltyStep { threshold, bps };
+
+    let res = client.try_report_usage(&bistro, &bistro, &1, &10, &100, &usage_report(&s.env), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::NoRoyaltySchedule)));
+    let bad = RoyaltySchedule { by_volume: true, steps: vec![&s.env, step(10, 500)] };
+    assert_eq!(client.try_set_royalty_schedule(&owners, &1, &bistro, &Some(bad)), Err(Ok(IPError::InvalidSchedule)));
+
+    // 5% for the first 10k units, 7% after, charged per bracket
+    let volume = RoyaltySchedule { by_volume: true, steps: vec![&s.env, step(0, 500), step(10_000, 700)] };
+    client.set_royalty_schedule(&owners, &1, &bistro, &Some(volume.clone()));
+    assert_eq!(client.get_royalty_schedule(&1, &bistro), Some(volume));
+    let report_id = client.report_usage(&bistro, &bistro, &1, &8_000, &8_000, &usage_report(&s.env), &None, &None);
+    assert_eq!(client.get_royalty_report(&1, &report_id).amount, 400);
+    let report_id = client.report_usage(&bistro, &bistro, &1, &4_000, &4_000, &usage_report(&s.env), &None, &None);
+    assert_eq!(client.get_royalty_report(&1, &report_id).amount, 100 + 140);
+    assert_eq!(client.get_reported_units(&1, &bistro), 12_000);
+
+    // 5% for the first 30 days of the license, 7% after
+    let month = 30 * 24 * 60 * 60;
+    let timed = RoyaltySchedule { by_volume: false, steps: vec![&s.env, step(0, 500), step(month, 700)] };
+    client.set_royalty_schedule(&owners, &1, &cafe, &Some(timed));
+    let report_id = client.report_usage(&cafe, &cafe, &1, &1, &1_000, &usage_report(&s.env), &None, &None);
+    assert_eq!(client.get_royalty_report(&1, &report_id).amount, 50);
+    s.advance_time(month as u64);
+    let report_id = client.report_usage(&cafe, &cafe, &1, &1, &1_000, &usage_report(&s.env), &None, &None);
+    assert_eq!(client.get_royalty_report(&1, &report_id).amount, 70);
+    assert_eq!(s.balance("cafe"), 1_000 - 120);
+}
+
+#[test]
+fn test_offers_inbox_accept_withdraw_and_expire() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "chef", 1_000, 100)
+        .with_funds("studio", 1_000)
+        .with_funds("bistro", 1_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (studio, bistro) = (s.party("studio"), s.party("bistro"));
+    let expires_at = scenarios::START_TIME + 100;
+
+    let res = client.try_make_offer(&studio, &1, &LicenseType::Exclusive, &600, &scenarios::START_TIME);
+    assert_eq!(res, Err(Ok(IPError::Expired)));
+    let exclusive = client.make_offer(&studio, &1, &LicenseType::Exclusive, &600, &expires_at);
+    let shared = client.make_offer(&bistro, &2, &LicenseType::NonExclusive, &80, &expires_at);
+    let stale = client.make_offer(&bistro, &1, &LicenseType::NonExclusive, &50, &(scenarios::START_TIME + 10));
+    assert_eq!(s.balance("studio"), 400);
+    assert_eq!(client.get_offers_for_owner(&s.party("chef"), &0, &10).0.len(), 3);
+
+    // Owners accept out of escrow at the offered amount
+    let license = client.accept_offer(&owners, &exclusive);
+    assert_eq!((license.licensee, license.price_paid), (studio.clone(), 600));
+    assert_eq!(client.get_claimable(&s.party("chef"), &s.token), 600);
+    assert_eq!(client.try_get_offer(&exclusive), Err(Ok(IPError::OfferNotFound)));
+
+    // Offerers withdraw their own offers only
+    assert_eq!(client.try_withdraw_offer(&studio, &shared), Err(Ok(IPError::Unauthorized)));
+    client.withdraw_offer(&bistro, &shared);
+
+    // Expired offers drop out of the inbox and anyone can refund them
+    assert_eq!(client.try_release_expired_offer(&stale), Err(Ok(IPError::OfferNotExpired)));
+    s.advance_time(10);
+    assert_eq!(client.get_offers_for_owner(&s.party("chef"), &0, &10).0.len(), 0);
+    assert_eq!(client.try_accept_offer(&owners, &stale), Err(Ok(IPError::Expired)));
+    client.release_expired_offer(&stale);
+    assert_eq!(s.balance("bistro"), 1_000);
+    assert_eq!(s.token_client().balance(&s.contract), 600);
+}
+
+#[test]
+fn test_full_offer_inbox_frees_up_on_reject() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("spammer", 1_000)
+        .with_funds("studio", 1_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (spammer, studio) = (s.party("spammer"), s.party("studio"));
+    let expires_at = scenarios::START_TIME + 100;
+
+    // Offers cannot squat on the inbox indefinitely
+    let too_long = scenarios::START_TIME + 30 * 24 * 60 * 60 + 1;
+    let res = client.try_make_offer(&spammer, &1, &LicenseType::NonExclusive, &1, &too_long);
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::OfferTooLong as u32))));
+
+    let mut spam = Vec::new(&s.env);
+    for _ in 0..50 {
+        spam.push_back(client.make_offer(&spammer, &1, &LicenseType::NonExclusive, &1, &expires_at));
+    }
+    let res = client.try_make_offer(&studio, &1, &LicenseType::Exclusive, &600, &expires_at);
+    assert_eq!(res, Err(Ok(IPError::CapReached)));
+
+    // Only the owner rejects, and each rejection refunds the offerer
+    let first = spam.get_unchecked(0);
+    assert_eq!(client.try_reject_offer(&vec![&s.env, studio.clone()], &first), Err(Ok(IPError::Unauthorized)));
+    client.reject_offer(&owners, &first);
+    assert_eq!(s.balance("spammer"), 951);
+    assert_eq!(client.try_get_offer(&first), Err(Ok(IPError::OfferNotFound)));
+
+    let offer_id = client.make_offer(&studio, &1, &LicenseType::Exclusive, &600, &expires_at);
+    assert_eq!(client.accept_offer(&owners, &offer_id).licensee, studio);
+}
+
+#[test]
+fn test_conflict_group_blocks_sibling_exclusives() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "chef", 1_000, 100)
+        .with_ip(3, "chef", 1_000, 100)
+        .with_ip(4, "chef", 1_000, 100)
+        .with_ip(5, "rival", 1_000, 100)
+        .with_funds("studio", 5_000)
+        .with_funds("bistro", 5_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (studio, bistro) = (s.party("studio"), s.party("bistro"));
+    let buy_exclusive = |licensee: &Address, ip_id: u64| {
+        client.try_purchase_license(licensee, &ip_id, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None)
+    };
+
+    assert_eq!(client.try_create_conflict_group(&owners, &vec![&s.env, 1]), Err(Ok(IPError::InvalidAmount)));
+    assert_eq!(client.try_create_conflict_group(&owners, &vec![&s.env, 1, 5]), Err(Ok(IPError::Unauthorized)));
+    assert_eq!(client.try_create_conflict_group(&owners, &vec![&s.env, 1, 1]), Err(Ok(IPError::AlreadyGrouped)));
+    let group_id = client.create_conflict_group(&owners, &vec![&s.env, 1, 2, 3, 4]);
+    assert_eq!(client.get_conflict_group(&2), Some((group_id, vec![&s.env, 1, 2, 3, 4])));
+    assert_eq!(client.try_create_conflict_group(&owners, &vec![&s.env, 3, 2]), Err(Ok(IPError::AlreadyGrouped)));
+
+    // One exclusive in the group blocks exclusives on its siblings, not non-exclusives
+    assert!(buy_exclusive(&studio, 1).is_ok());
+    assert_eq!(buy_exclusive(&bistro, 2), Err(Ok(IPError::ExclusiveConflict)));
+    client.purchase_license(&bistro, &2, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    // Downgrading frees the group's exclusive slot
+    client.downgrade_license(&studio, &1);
+    assert!(buy_exclusive(&bistro, 3).is_ok());
+    assert_eq!(buy_exclusive(&studio, 4), Err(Ok(IPError::ExclusiveConflict)));
+
+    // Leaving the group lifts the constraint; a lone remaining member is released too
+    client.leave_conflict_group(&owners, &3);
+    assert!(buy_exclusive(&studio, 4).is_ok());
+    client.leave_conflict_group(&owners, &4);
+    client.leave_conflict_group(&owners, &2);
+    assert_eq!(client.get_conflict_group(&1), None);
+    assert_eq!(client.try_leave_conflict_group(&owners, &1), Err(Ok(IPError::NotGrouped)));
+}
+
+#[test]
+fn test_conflict_group_counts_options_and_auctions_as_slot_holders() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "chef", 1_000, 100)
+        .with_ip(3, "chef", 1_000, 100)
+        .with_ip(4, "chef", 1_000, 100)
+        .with_funds("studio", 2_000)
+        .with_funds("bistro", 2_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let terms = OptionTerms { fee: 50, strike_price: 800, window_secs: 100 };
+    client.purchase_license(&s.party("studio"), &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    client.set_option_terms(&owners, &2, &Some(terms.clone()));
+    client.take_exclusive_option(&s.party("bistro"), &2, &terms);
+    client.start_sealed_auction(&owners, &3, &300, &100, &100, &100);
+
+    // An exclusive, a live option and an open auction each take the slot
+    for ip_ids in [vec![&s.env, 1, 2], vec![&s.env, 1, 3], vec![&s.env, 2, 3]] {
+        assert_eq!(client.try_create_conflict_group(&owners, &ip_ids), Err(Ok(IPError::ExclusiveConflict)));
+    }
+    client.create_conflict_group(&owners, &vec![&s.env, 2, 4]);
+}
+
+#[test]
+fn test_can_purchase_dry_run() {
+    let s = ScenarioBuilder::new()
+        .with_fee_bps(500)
+        .with_ip(1, "chef", 1_000, 100)
+        .with_license(1, "bistro", LicenseType::NonExclusive)
+        .with_funds("studio", 1_000)
+        .with_funds("fan", 50)
+        .build();
+    let client = s.client();
+    let studio = s.party("studio");
+    s.token_client().approve(&studio, &s.contract, &300, &1_000);
+
+    let quote = client.can_purchase(&studio, &1, &LicenseType::NonExclusive);
+    assert_eq!(quote, PurchaseQuote {
+        price: 100,
+        payment_token: s.token.clone(),
+        token_decimals: 7,
+        platform_fee: 5,
+        balance: 1_000,
+        allowance: 300,
+    });
+
+    // Failures match the ones the real purchase would hit
+    let dry_run = |licensee: &str, license_type| client.try_can_purchase(&s.party(licensee), &1, &license_type);
+    assert_eq!(dry_run("fan", LicenseType::NonExclusive), Err(Ok(IPError::InsufficientBalance)));
+    assert_eq!(dry_run("bistro", LicenseType::NonExclusive), Err(Ok(IPError::LicenseAlreadyExists)));
+    assert_eq!(dry_run("studio", LicenseType::Exclusive), Err(Ok(IPError::ActiveLicensesExist)));
+    assert_eq!(dry_run("studio", LicenseType::Commercial), Err(Ok(IPError::InvalidTier)));
+
+    // Nothing was taken or recorded by the dry runs
+    client.purchase_license(&studio, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(client.get_ip(&1).active_licenses, 2);
+    assert_eq!(s.balance("studio"), 900);
+}
+
+#[test]
+fn test_exclusive_option_holds_slot_until_exercised_or_lapsed() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "chef", 1_000, 100)
+        .with_funds("studio", 2_000)
+        .with_funds("bistro", 2_000)
+        .build();
+    let client = s.client();
+    let chef = s.party("chef");
+    let owners = vec![&s.env, chef.clone()];
+    let (studio, bistro) = (s.party("studio"), s.party("bistro"));
+    let terms = OptionTerms { fee: 50, strike_price: 800, window_secs: 100 };
+
+    assert_eq!(client.try_take_exclusive_option(&studio, &1, &terms), Err(Ok(IPError::SlippageExceeded)));
+    let invalid = OptionTerms { window_secs: 0, ..terms.clone() };
+    assert_eq!(client.try_set_option_terms(&owners, &1, &Some(invalid)), Err(Ok(IPError::InvalidPeriod)));
+    client.set_option_terms(&owners, &1, &Some(terms.clone()));
+    client.set_option_terms(&owners, &2, &Some(terms.clone()));
+
+    // The fee is paid to the owner up front
+    let option = client.take_exclusive_option(&studio, &1, &terms);
+    assert_eq!(option.expires_at, scenarios::START_TIME + 100);
+    assert_eq!(client.get_exclusive_option(&1), Some(option));
+    assert_eq!(s.balance("studio"), 1_950);
+    assert_eq!(client.get_claimable(&chef, &s.token), 50);
+
+    // Nobody else may license or option the slot while it is held
+    let res = client.try_take_exclusive_option(&bistro, &1, &terms);
+    assert_eq!(res, Err(Ok(IPError::ExclusiveAlreadyIssued)));
+    for license_type in [LicenseType::Exclusive, LicenseType::NonExclusive] {
+        let res = client.try_purchase_license(&bistro, &1, &license_type, &None, &None, &u64::MAX, &None, &None);
+        assert_eq!(res, Err(Ok(IPError::ExclusiveAlreadyIssued)));
+    }
+    let res = client.try_purchase_license(&studio, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::ExclusiveAlreadyIssued)));
+
+    // The holder exercises at the strike price
+    assert_eq!(client.try_exercise_exclusive_option(&bistro, &1), Err(Ok(IPError::Unauthorized)));
+    let license = client.exercise_exclusive_option(&studio, &1);
+    assert_eq!((license.license_type, license.price_paid), (LicenseType::Exclusive, 800));
+    assert_eq!(s.balance("studio"), 1_150);
+    assert_eq!(client.get_exclusive_option(&1), None);
+    let res = client.try_exercise_exclusive_option(&studio, &1);
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::OptionNotFound as u32))));
+
+    // A lapsed option frees the slot and keeps the fee with the owner
+    client.take_exclusive_option(&studio, &2, &terms);
+    s.advance_time(100);
+    assert_eq!(client.get_exclusive_option(&2), None);
+    assert_eq!(client.try_exercise_exclusive_option(&studio, &2), Err(Ok(IPError::Expired)));
+    client.purchase_license(&bistro, &2, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(client.get_claimable(&chef, &s.token), 1_900);
+}
+
+#[test]
+fn test_option_holds_conflict_group_exclusive_slot() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "chef", 1_000, 100)
+        .with_funds("studio", 2_000)
+        .with_funds("bistro", 2_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (studio, bistro) = (s.party("studio"), s.party("bistro"));
+    let terms = OptionTerms { fee: 50, strike_price: 800, window_secs: 100 };
+    client.create_conflict_group(&owners, &vec![&s.env, 1, 2]);
+    client.set_option_terms(&owners, &1, &Some(terms.clone()));
+    client.set_option_terms(&owners, &2, &Some(terms.clone()));
+    client.take_exclusive_option(&studio, &1, &terms);
+
+    // Siblings can neither sell nor option their exclusive while it is live
+    let res = client.try_purchase_license(&bistro, &2, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::ExclusiveConflict)));
+    assert_eq!(client.try_take_exclusive_option(&bistro, &2, &terms), Err(Ok(IPError::ExclusiveConflict)));
+    client.purchase_license(&bistro, &2, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    // Once it lapses the group's slot is free again
+    s.advance_time(100);
+    client.revoke_license(&owners, &bistro, &2, &symbol_short!("breach"));
+    client.purchase_license(&bistro, &2, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+}
+
+#[test]
+fn test_sealed_auction_sells_exclusive_at_second_price() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("studio", 2_000)
+        .with_funds("bistro", 2_000)
+        .with_funds("diner", 2_000)
+        .build();
+    let client = s.client();
+    let chef = s.party("chef");
+    let owners = vec![&s.env, chef.clone()];
+    let (studio, bistro, diner) = (s.party("studio"), s.party("bistro"), s.party("diner"));
+    let salt = BytesN::from_array(&s.env, &[3; 32]);
+
+    assert_eq!(client.try_start_sealed_auction(&owners, &1, &300, &100, &0, &100), Err(Ok(IPError::InvalidPeriod)));
+    let auction_id = client.start_sealed_auction(&owners, &1, &300, &100, &100, &100);
+    assert_eq!(client.get_open_auction(&1), Some(auction_id));
+
+    // Commits escrow the deposit; the slot stays closed to buyers meanwhile
+    let commit = |bidder: &Address, amount: i128| client.commit_bid(bidder, &auction_id, &client.bid_commitment(&amount, &salt));
+    commit(&studio, 900);
+    commit(&bistro, 600);
+    commit(&diner, 700);
+    assert_eq!(s.balance("studio"), 1_900);
+    let res = client.try_commit_bid(&chef, &auction_id, &client.bid_commitment(&1_000, &salt));
+    assert_eq!(res, Err(Ok(IPError::Unauthorized)));
+    assert_eq!(client.try_commit_bid(&studio, &auction_id, &client.bid_commitment(&1, &salt)), Err(Ok(IPError::DuplicateOperation)));
+    let res = client.try_purchase_license(&bistro, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::ExclusiveAlreadyIssued)));
+    assert_eq!(client.try_reveal_bid(&studio, &auction_id, &900, &salt), Err(Err(InvokeError::Contract(ExtError::AuctionPhase as u32))));
+
+    // Reveals must match the commitment and top the escrow up to the bid
+    s.advance_time(100);
+    assert_eq!(client.try_commit_bid(&s.party("admin"), &auction_id, &client.bid_commitment(&1, &salt)), Err(Ok(IPError::DeadlineExceeded)));
+    let res = client.try_reveal_bid(&studio, &auction_id, &950, &salt);
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::BidCommitmentMismatch as u32))));
+    client.reveal_bid(&studio, &auction_id, &900, &salt);
+    client.reveal_bid(&bistro, &auction_id, &600, &salt);
+    assert_eq!(s.balance("studio"), 1_100);
+    let auction = client.get_auction(&auction_id);
+    assert_eq!((auction.leader, auction.highest_bid, auction.second_bid), (Some(studio.clone()), 900, 600));
+    assert_eq!(client.try_settle_sealed_auction(&auction_id), Err(Err(InvokeError::Contract(ExtError::AuctionPhase as u32))));
+
+    // The winner pays the second price; losers withdraw, silent bidders forfeit
+    s.advance_time(100);
+    assert_eq!(client.try_reveal_bid(&diner, &auction_id, &700, &salt), Err(Ok(IPError::DeadlineExceeded)));
+    assert_eq!(client.try_withdraw_bid(&studio, &auction_id), Err(Err(InvokeError::Contract(ExtError::AuctionPhase as u32))));
+    let license = client.settle_sealed_auction(&auction_id).unwrap();
+    assert_eq!((license.licensee, license.license_type, license.price_paid), (studio, LicenseType::Exclusive, 600));
+    assert_eq!(s.balance("studio"), 1_400);
+    assert_eq!(client.get_open_auction(&1), None);
+    assert_eq!(client.try_settle_sealed_auction(&auction_id), Err(Ok(IPError::DuplicateOperation)));
+
+    assert!(!client.withdraw_bid(&bistro, &auction_id));
+    assert!(client.withdraw_bid(&diner, &auction_id));
+    assert_eq!((s.balance("bistro"), s.balance("diner")), (2_000, 1_900));
+    assert_eq!(client.get_claimable(&chef, &s.token), 700);
+    assert_eq!(client.try_withdraw_bid(&bistro, &auction_id), Err(Err(InvokeError::Contract(ExtError::BidNotFound as u32))));
+}
+
+#[test]
+fn test_sealed_auction_refunds_leader_when_license_cannot_issue() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("studio", 2_000)
+        .with_funds("bistro", 2_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (studio, bistro) = (s.party("studio"), s.party("bistro"));
+    let salt = BytesN::from_array(&s.env, &[3; 32]);
+    let auction_id = client.start_sealed_auction(&owners, &1, &300, &100, &100, &100);
+    client.commit_bid(&studio, &auction_id, &client.bid_commitment(&900, &salt));
+    client.commit_bid(&bistro, &auction_id, &client.bid_commitment(&600, &salt));
+    s.advance_time(100);
+    client.reveal_bid(&studio, &auction_id, &900, &salt);
+    client.reveal_bid(&bistro, &auction_id, &600, &salt);
+
+    // A freeze during reveals blocks the sale, but the auction still closes
+    client.freeze_ip(&s.party("admin"), &1, &String::from_str(&s.env, "court order"));
+    s.advance_time(100);
+    assert_eq!(client.settle_sealed_auction(&auction_id), None);
+    assert!(client.get_auction(&auction_id).settled);
+    assert_eq!(client.get_open_auction(&1), None);
+    assert!(!client.get_ip(&1).has_exclusive);
+    assert_eq!(s.balance("studio"), 2_000);
+    let res = client.try_get_sealed_bid(&auction_id, &studio);
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::BidNotFound as u32))));
+
+    assert!(!client.withdraw_bid(&bistro, &auction_id));
+    assert_eq!(s.balance("bistro"), 2_000);
+    assert_eq!(client.try_settle_sealed_auction(&auction_id), Err(Ok(IPError::DuplicateOperation)));
+    let res = client.try_get_auction(&(auction_id + 1));
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::AuctionNotFound as u32))));
+}
+
+#[test]
+fn test_open_auction_holds_conflict_group_exclusive_slot() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "chef", 1_000, 100)
+        .with_funds("studio", 2_000)
+        .with_funds("bistro", 2_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (studio, bistro) = (s.party("studio"), s.party("bistro"));
+    let salt = BytesN::from_array(&s.env, &[3; 32]);
+    let terms = OptionTerms { fee: 50, strike_price: 800, window_secs: 100 };
+    client.create_conflict_group(&owners, &vec![&s.env, 1, 2]);
+    client.set_option_terms(&owners, &2, &Some(terms.clone()));
+    let auction_id = client.start_sealed_auction(&owners, &1, &300, &100, &100, &100);
+
+    // Until it settles, siblings can neither sell, option nor auction theirs
+    let res = client.try_purchase_license(&bistro, &2, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(res, Err(Ok(IPError::ExclusiveConflict)));
+    assert_eq!(client.try_take_exclusive_option(&bistro, &2, &terms), Err(Ok(IPError::ExclusiveConflict)));
+    assert_eq!(client.try_start_sealed_auction(&owners, &2, &300, &100, &100, &100), Err(Ok(IPError::ExclusiveConflict)));
+
+    // An auction that closes unsold frees the group's slot
+    client.commit_bid(&studio, &auction_id, &client.bid_commitment(&200, &salt));
+    s.advance_time(100);
+    client.reveal_bid(&studio, &auction_id, &200, &salt);
+    s.advance_time(100);
+    assert_eq!(client.settle_sealed_auction(&auction_id), None);
+    client.purchase_license(&bistro, &2, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+}
+
+#[test]
+fn test_licensing_calendar_blocks_sales_outside_availability() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("studio", 1_000)
+        .with_funds("bistro", 1_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (studio, bistro) = (s.party("studio"), s.party("bistro"));
+    let t = scenarios::START_TIME;
+    let window = |starts_at: u64, ends_at: u64| TimeWindow { starts_at: t + starts_at, ends_at: t + ends_at };
+    let purchase = |licensee: &Address| {
+        client.try_purchase_license(licensee, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None)
+    };
+
+    let invalid = LicensingCalendar { availability: vec![&s.env, window(100, 100)], blackouts: vec![&s.env] };
+    assert_eq!(client.try_set_licensing_calendar(&owners, &1, &Some(invalid)), Err(Ok(IPError::InvalidPeriod)));
+    let calendar = LicensingCalendar {
+        availability: vec![&s.env, window(100, 500)],
+        blackouts: vec![&s.env, window(200, 300)],
+    };
+    client.set_licensing_calendar(&owners, &1, &Some(calendar.clone()));
+    assert_eq!(client.get_licensing_calendar(&1), Some(calendar));
+
+    // Closed until the season opens
+    assert!(!client.is_licensing_open(&1));
+    assert_eq!(client.next_licensing_window(&1), Some(window(100, 200)));
+    let closed = Err(Err(InvokeError::Contract(ExtError::LicensingClosed as u32)));
+    assert_eq!(purchase(&studio), closed);
+    let dry_run = client.try_can_purchase(&studio, &1, &LicenseType::NonExclusive);
+    assert_eq!(dry_run, Err(Err(InvokeError::Contract(ExtError::LicensingClosed as u32))));
+
+    s.advance_time(150);
+    assert!(client.is_licensing_open(&1));
+    assert_eq!(client.next_licensing_window(&1), Some(window(150, 200)));
+    purchase(&studio).unwrap().unwrap();
+
+    // A blackout stops sales but not usage reporting
+    s.advance_time(100);
+    assert_eq!(client.next_licensing_window(&1), Some(window(300, 500)));
+    assert_eq!(purchase(&bistro), closed);
+    client.pay_usage_royalty(&studio, &studio, &1, &10, &usage_report(&s.env), &None, &None);
+
+    s.advance_time(100);
+    purchase(&bistro).unwrap().unwrap();
+    s.advance_time(200);
+    assert_eq!(client.next_licensing_window(&1), None);
+
+    client.set_licensing_calendar(&owners, &1, &None);
+    assert_eq!(client.next_licensing_window(&1), Some(TimeWindow { starts_at: t + 550, ends_at: u64::MAX }));
+}
+
+mod mock_swap_router {
+    use soroban_sdk::{contract, contractimpl, symbol_short, token, vec, Address, Env, Vec};
+
+    #[contract]
+    pub struct MockSwapRouter;
+
+    #[contractimpl]
+    impl MockSwapRouter {
+        /// Quote `rate` units of the input asset per unit of output
+        pub fn set_rate(env: Env, rate: i128) {
+            env.storage().instance().set(&symbol_short!("rate"), &rate);
+        }
+
+        pub fn swap_tokens_for_exact_tokens(
+            env: Env,
+            amount_out: i128,
+            amount_in_max: i128,
+            path: Vec<Address>,
+            to: Address,
+            _deadline: u64,
+        ) -> Vec<i128> {
+            to.require_auth();
+            let rate: i128 = env.storage().instance().get(&symbol_short!("rate")).unwrap();
+            let amount_in = amount_out * rate;
+            assert!(amount_in <= amount_in_max, "excessive input amount");
+
+            let pool = env.current_contract_address();
+            token::Client::new(&env, &path.first().unwrap()).transfer(&to, &pool, &amount_in);
+            token::Client::new(&env, &path.last().unwrap()).transfer(&pool, &to, &amount_out);
+            vec![&env, amount_in, amount_out]
+        }
+    }
+}
+
+#[test]
+fn test_royalty_paid_through_swap_router() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_license(1, "bistro", LicenseType::NonExclusive)
+        .build();
+    let client = s.client();
+    let (admin, bistro) = (s.party("admin"), s.party("bistro"));
+    let other_token = s.env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let other = token::Client::new(&s.env, &other_token);
+    token::StellarAssetClient::new(&s.env, &other_token).mint(&bistro, &1_000);
+    let router_address = s.env.register(mock_swap_router::MockSwapRouter, ());
+    mock_swap_router::MockSwapRouterClient::new(&s.env, &router_address).set_rate(&3);
+    token::StellarAssetClient::new(&s.env, &s.token).mint(&router_address, &1_000);
+    let deadline = scenarios::START_TIME + 60;
+    let pay = |token_in: &Address, max_amount_in: i128, deadline: u64| {
+        client.try_pay_royalty_with_swap(&bistro, &1, &50, token_in, &max_amount_in, &deadline, &usage_report(&s.env), &None, &None)
+    };
+
+    let res = pay(&other_token, 150, deadline);
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::SwapRouterNotSet as u32))));
+    client.set_swap_router(&admin, &Some(router_address.clone()));
+    assert_eq!(client.get_swap_router(), Some(router_address));
+
+    assert_eq!(pay(&s.token, 150, deadline), Err(Ok(IPError::UnsupportedToken)));
+    assert_eq!(pay(&other_token, 150, scenarios::START_TIME - 1), Err(Ok(IPError::DeadlineExceeded)));
+    assert!(pay(&other_token, 149, deadline).is_err());
+    assert_eq!(other.balance(&bistro), 1_000);
+
+    // 150 of the other asset buys the 50 token royalty, collected as usual
+    assert_eq!(pay(&other_token, 150, deadline), Ok(Ok(0)));
+    assert_eq!(other.balance(&bistro), 850);
+    assert_eq!(s.balance("bistro"), 0);
+    assert_eq!(client.get_royalty_report(&1, &0).amount, 50);
+    assert_eq!(client.get_claimable(&s.party("chef"), &s.token), 150);
+}
+
+#[test]
+fn test_attachments_form_capped_data_room() {
+    let s = ScenarioBuilder::new().with_ip(1, "chef", 1_000, 100).with_funds("bistro", 0).build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let uri = String::from_str(&s.env, "ipfs://release");
+    let hash = BytesN::from_array(&s.env, &[5; 32]);
+    let release = symbol_short!("release");
+
+    assert_eq!(client.try_add_attachment(&vec![&s.env, s.party("bistro")], &1, &release, &uri, &hash), Err(Ok(IPError::Unauthorized)));
+    client.add_attachment(&owners, &1, &release, &uri, &hash);
+    let attachment = Attachment { name: release.clone(), uri: uri.clone(), hash: hash.clone(), added_at: scenarios::START_TIME };
+    assert_eq!(client.get_attachment(&1, &release), Some(attachment.clone()));
+    assert_eq!(client.try_add_attachment(&owners, &1, &release, &uri, &hash), Err(Ok(IPError::DuplicateOperation)));
+
+    // Names are unique and the room holds at most 16 documents
+    for index in 1..16u8 {
+        let name = [b'd', b'o', b'c', b'a' + index];
+        client.add_attachment(&owners, &1, &Symbol::new(&s.env, core::str::from_utf8(&name).unwrap()), &uri, &hash);
+    }
+    assert_eq!(client.get_attachments(&1).len(), 16);
+    assert_eq!(client.try_add_attachment(&owners, &1, &symbol_short!("preview"), &uri, &hash), Err(Ok(IPError::CapReached)));
+
+    assert!(client.remove_attachment(&owners, &1, &release));
+    assert!(!client.remove_attachment(&owners, &1, &release));
+    client.add_attachment(&owners, &1, &symbol_short!("preview"), &uri, &hash);
+    assert_eq!(client.get_attachments(&1).last().unwrap().name, symbol_short!("preview"));
+
+    // Deleting the IP empties its data room
+    client.deregister_ip(&owners, &1);
+    assert_eq!(client.get_attachments(&1).len(), 0);
+}
+
+#[test]
+fn test_preview_grants_are_paid_and_short_lived() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("studio", 100)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let studio = s.party("studio");
+    let terms = PreviewTerms { fee: 5, duration_secs: 3_600 };
+
+    let res = client.try_purchase_preview(&studio, &1, &terms);
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::PreviewTermsNotFound as u32))));
+    let too_long = PreviewTerms { duration_secs: 8 * 24 * 60 * 60, ..terms.clone() };
+    assert_eq!(client.try_set_preview_terms(&owners, &1, &Some(too_long)), Err(Ok(IPError::InvalidPeriod)));
+    client.set_preview_terms(&owners, &1, &Some(terms.clone()));
+    let stale = PreviewTerms { fee: 4, ..terms.clone() };
+    assert_eq!(client.try_purchase_preview(&studio, &1, &stale), Err(Ok(IPError::SlippageExceeded)));
+
+    // The fee is paid out like license revenue
+    assert!(!client.has_preview_access(&1, &studio));
+    assert_eq!(client.purchase_preview(&studio, &1, &terms), scenarios::START_TIME + 3_600);
+    assert!(client.has_preview_access(&1, &studio));
+    assert!(!client.has_preview_access(&1, &s.party("chef")));
+    assert_eq!(s.balance("studio"), 95);
+    assert_eq!(client.get_claimable(&s.party("chef"), &s.token), 5);
+
+    // Buying again restarts the grant, which then lapses
+    s.advance_time(3_000);
+    assert_eq!(client.purchase_preview(&studio, &1, &terms), scenarios::START_TIME + 6_600);
+    s.advance_time(3_599);
+    assert!(client.has_preview_access(&1, &studio));
+    s.advance_time(1);
+    assert!(!client.has_preview_access(&1, &studio));
+}
+
+#[test]
+fn test_session_key_pays_bounded_royalties() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_license(1, "bistro", LicenseType::NonExclusive)
+        .with_funds("bistro", 1_000)
+        .with_funds("till", 0)
+        .build();
+    let client = s.client();
+    let (bistro, till) = (s.party("bistro"), s.party("till"));
+    s.token_client().approve(&bistro, &s.contract, &1_000, &1_000);
+    let pay = |amount: i128| client.try_pay_usage_royalty(&till, &bistro, &1, &amount, &usage_report(&s.env), &None, &None);
+    let expires_at = scenarios::START_TIME + 3_600;
+
+    assert_eq!(pay(10), Err(Ok(IPError::Unauthorized)));
+    assert_eq!(client.try_set_session_key(&bistro, &1, &till, &50, &40, &expires_at), Err(Ok(IPError::InvalidAmount)));
+    assert_eq!(client.try_set_session_key(&bistro, &2, &till, &50, &100, &expires_at), Err(Ok(IPError::LicenseNotFound)));
+    client.set_session_key(&bistro, &1, &till, &50, &100, &expires_at);
+
+    // The key pays from the allowance, within its per-call and total caps
+    assert_eq!(pay(40), Ok(Ok(0)));
+    assert_eq!(pay(51), Err(Ok(IPError::CapReached)));
+    assert_eq!(pay(50), Ok(Ok(1)));
+    assert_eq!(pay(11), Err(Ok(IPError::CapReached)));
+    assert_eq!(client.get_session_key(&1, &bistro).unwrap().spent, 90);
+    assert_eq!(s.balance("bistro"), 910);
+
+    // It lapses at expiry and can be revoked outright
+    client.set_session_key(&bistro, &1, &till, &50, &100, &expires_at);
+    s.advance_time(3_600);
+    assert_eq!(pay(10), Err(Ok(IPError::Expired)));
+    assert!(client.revoke_session_key(&bistro, &1));
+    assert!(!client.revoke_session_key(&bistro, &1));
+    assert_eq!(pay(10), Err(Ok(IPError::Unauthorized)));
+}
+
+#[test]
+fn test_anchor_commits_to_batch_of_ip_records() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "baker", 500, 50)
+        .with_license(2, "bistro", LicenseType::NonExclusive)
+        .build();
+    let client = s.client();
+
+    // Leaves are recomputable from the public IP record
+    let ip = client.get_ip(&1);
+    let mut preimage = Bytes::from_slice(&s.env, b"flavorsnap-ip-anchor-v1");
+    preimage.extend_from_array(&1u64.to_be_bytes());
+    preimage.append(&ip.owner.clone().to_xdr(&s.env));
+    preimage.append(&ip.metadata_uri.clone().to_xdr(&s.env));
+    match &ip.metadata_hash {
+        Some(metadata_hash) => {
+            preimage.push_back(1);
+            preimage.extend_from_array(&metadata_hash.to_array());
+        }
+        None => preimage.push_back(0),
+    }
+    preimage.push_back(0);
+    let leaf: BytesN<32> = s.env.crypto().sha256(&preimage).into();
+    assert_eq!(client.ip_anchor_leaf(&1), leaf);
+
+    let anchor = client.anchor_ips(&vec![&s.env, 1, 2]);
+    let event = s.env.events().all().last().unwrap();
+    assert_eq!(event.1, (symbol_short!("anchor"),).into_val(&s.env));
+    assert_eq!(MerkleAnchor::from_val(&s.env, &event.2), anchor);
+    let leaves = vec![&s.env, leaf.clone(), client.ip_anchor_leaf(&2)];
+    assert_eq!(anchor.root, flavorsnap_common::merkle_root(&s.env, &leaves));
+    assert_eq!((anchor.leaf_count, anchor.timestamp), (2, scenarios::START_TIME));
+
+    // A change of owner is a new record
+    client.transfer_ip(&vec![&s.env, s.party("chef")], &1, &s.party("bistro"));
+    assert_ne!(client.ip_anchor_leaf(&1), leaf);
+    assert_ne!(client.anchor_ips(&vec![&s.env, 1, 2]).root, anchor.root);
+
+    let res = client.try_anchor_ips(&Vec::new(&s.env));
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::EmptyBatch as u32))));
+    assert_eq!(client.try_anchor_ips(&vec![&s.env, 1, 3]), Err(Ok(IPError::IPNotFound)));
+    let mut oversized = Vec::new(&s.env);
+    for _ in 0..=client.get_config().max_batch_size {
+        oversized.push_back(1);
+    }
+    assert_eq!(client.try_anchor_ips(&oversized), Err(Ok(IPError::CapReached)));
+    // A licensed IP is tombstoned on deregistration, and no longer anchored
+    client.deregister_ip(&vec![&s.env, s.party("baker")], &2);
+    assert_eq!(client.try_anchor_ips(&vec![&s.env, 1, 2]), Err(Ok(IPError::IPDeregistered)));
+}
+
+#[test]
+fn test_owner_dashboard_aggregates_owned_ips() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_ip(2, "chef", 1_000, 100)
+        .with_ip(3, "baker", 1_000, 100)
+        .with_funds("studio", 1_000)
+        .with_funds("bistro", 1_000)
+        .build();
+    let client = s.client();
+    let (chef, baker) = (s.party("chef"), s.party("baker"));
+    let owners = vec![&s.env, chef.clone()];
+    let (studio, bistro) = (s.party("studio"), s.party("bistro"));
+
+    let (empty, _) = client.get_owner_dashboard(&studio, &0);
+    assert_eq!((empty.ip_count, empty.active_licenses, empty.unclaimed.len()), (0, 0, 0));
+    assert_eq!(client.get_owner_ips(&chef, &0, &10), vec![&s.env, 1, 2]);
+
+    client.purchase_license(&studio, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.purchase_license(&bistro, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.purchase_license(&studio, &2, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.purchase_license(&bistro, &3, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    client.make_offer(&bistro, &2, &LicenseType::Exclusive, &500, &(scenarios::START_TIME + 100));
+    client.make_offer(&studio, &2, &LicenseType::Exclusive, &400, &(scenarios::START_TIME + 10));
+
+    client.set_appeal_window(&owners, &1, &Some(100));
+    client.revoke_license(&owners, &bistro, &1, &symbol_short!("breach"));
+    client.appeal_revocation(&bistro, &1);
+
+    let (dashboard, next) = client.get_owner_dashboard(&chef, &0);
+    assert_eq!(next, None);
+    assert_eq!(dashboard.ip_count, 2);
+    assert_eq!(dashboard.active_licenses, 2);
+    assert_eq!(dashboard.unclaimed.get(s.token.clone()), Some(300));
+    assert_eq!(dashboard.open_offers, 2);
+    assert_eq!(dashboard.open_disputes, 1);
+
+    // Expired offers and settled appeals drop out
+    s.advance_time(50);
+    client.resolve_appeal(&s.party("admin"), &1, &bistro, &false);
+    let (dashboard, _) = client.get_owner_dashboard(&chef, &0);
+    assert_eq!((dashboard.open_offers, dashboard.open_disputes), (1, 0));
+
+    // Transfers move the IP between dashboards
+    client.transfer_ip(&owners, &2, &baker);
+    assert_eq!(client.get_owner_ips(&chef, &0, &10), vec![&s.env, 1]);
+    assert_eq!(client.get_owner_ips(&baker, &0, &10), vec![&s.env, 3, 2]);
+    let (dashboard, _) = client.get_owner_dashboard(&baker, &0);
+    assert_eq!((dashboard.ip_count, dashboard.active_licenses, dashboard.open_offers), (2, 2, 0));
+    assert_eq!(client.get_owner_dashboard(&chef, &0).0.active_licenses, 1);
+}
+
+#[test]
+fn test_owner_ips_page_across_chunks() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_license(1, "studio", LicenseType::NonExclusive)
+        .build();
+    let client = s.client();
+    let chef = s.party("chef");
+
+    let owned = 2 * flavorsnap_common::CHUNK_SIZE as u64 + 2;
+    s.env.as_contract(&s.contract, || {
+        for ip_id in 2..=owned {
+            storage::add_owner_ip(&s.env, &chef, ip_id);
+        }
+        // Re-adding an IP already listed is a no-op
+        storage::add_owner_ip(&s.env, &chef, 1);
+        assert_eq!(storage::owner_ips(&chef).len(&s.env), owned as u32);
+    });
+
+    // Pages straddle chunk boundaries; losing an IP moves the latest into its slot
+    let start = flavorsnap_common::CHUNK_SIZE - 1;
+    assert_eq!(client.get_owner_ips(&chef, &start, &2), vec![&s.env, start as u64 + 1, start as u64 + 2]);
+    s.env.as_contract(&s.contract, || storage::remove_owner_ip(&s.env, &chef, 2));
+    assert_eq!(client.get_owner_ips(&chef, &0, &2), vec![&s.env, 1, owned]);
+    assert_eq!(client.get_owner_ips(&chef, &(owned as u32 - 2), &10), vec![&s.env, owned - 1]);
+
+    // The dashboard walks the IPs a page at a time
+    let (dashboard, next) = client.get_owner_dashboard(&chef, &0);
+    assert_eq!((dashboard.ip_count, dashboard.active_licenses), (owned as u32 - 1, 1));
+    assert_eq!(next, Some(50));
+    let (dashboard, next) = client.get_owner_dashboard(&chef, &100);
+    assert_eq!((dashboard.ip_count, dashboard.active_licenses), (owned as u32 - 1, 0));
+    assert_eq!(next, None);
+}
+
+#[test]
+fn test_offer_inbox_pages_survive_withdrawals() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("studio", 1_000)
+        .build();
+    let client = s.client();
+    let (chef, studio) = (s.party("chef"), s.party("studio"));
+    let mut offers = Vec::new(&s.env);
+    for _ in 0..6 {
+        offers.push_back(client.make_offer(&studio, &1, &LicenseType::NonExclusive, &10, &(scenarios::START_TIME + 100)));
+    }
+    let ids = |page: &Vec<Offer>| {
+        let mut ids = Vec::new(&s.env);
+        page.iter().for_each(|offer| ids.push_back(offer.offer_id));
+        ids
+    };
+
+    let (first, cursor) = client.get_offers_for_owner(&chef, &0, &2);
+    assert_eq!((ids(&first), cursor), (offers.slice(0..2), Some(offers.get_unchecked(1))));
+
+    // Closing offers already returned, and the next one, neither skips nor repeats any
+    client.withdraw_offer(&studio, &offers.get_unchecked(0));
+    client.withdraw_offer(&studio, &offers.get_unchecked(2));
+    let (second, cursor) = client.get_offers_for_owner(&chef, &cursor.unwrap(), &2);
+    assert_eq!((ids(&second), cursor), (offers.slice(3..5), Some(offers.get_unchecked(4))));
+    let (last, cursor) = client.get_offers_for_owner(&chef, &cursor.unwrap(), &2);
+    assert_eq!((ids(&last), cursor), (offers.slice(5..), None));
+}
+
+#[test]
+fn test_dormant_exclusive_reclaimed_after_notice() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_license(1, "studio", LicenseType::Exclusive)
+        .with_funds("studio", 100)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let studio = s.party("studio");
+    let day = 86_400;
+
+    assert_eq!(client.get_license(&1, &studio).last_activity_at, scenarios::START_TIME);
+    assert_eq!(client.try_serve_dormancy_notice(&owners, &1, &studio), Err(Ok(IPError::NotDormant)));
+    client.set_dormancy_policy(&owners, &1, &Some(DormancyPolicy { threshold_secs: 30 * day, notice_secs: 7 * day }));
+
+    // Royalty payments count as activity
+    s.advance_time(20 * day);
+    client.pay_usage_royalty(&studio, &studio, &1, &10, &usage_report(&s.env), &None, &None);
+    s.advance_time(20 * day);
+    assert_eq!(client.try_serve_dormancy_notice(&owners, &1, &studio), Err(Ok(IPError::NotDormant)));
+
+    // Answering a notice with a royalty payment voids it
+    s.advance_time(10 * day);
+    client.serve_dormancy_notice(&owners, &1, &studio);
+    s.advance_time(day);
+    client.pay_usage_royalty(&studio, &studio, &1, &10, &usage_report(&s.env), &None, &None);
+    s.advance_time(7 * day);
+    assert_eq!(client.try_reclaim_dormant_exclusive(&owners, &1), Err(Ok(IPError::NotDormant)));
+    assert_eq!(client.get_dormancy_notice(&1), None);
+
+    // A notice left unanswered frees the slot once it runs out
+    s.advance_time(30 * day);
+    let reclaimable_at = client.serve_dormancy_notice(&owners, &1, &studio);
+    assert_eq!(client.try_reclaim_dormant_exclusive(&owners, &1), Err(Ok(IPError::NotDormant)));
+    s.advance_time(7 * day);
+    assert_eq!(s.env.ledger().timestamp(), reclaimable_at);
+    client.reclaim_dormant_exclusive(&owners, &1);
+
+    let license = client.get_license(&1, &studio);
+    assert!(!license.is_active);
+    assert_eq!(license.revocation_reason, Some(symbol_short!("dormant")));
+    assert!(!client.get_ip(&1).has_exclusive);
+}
+
+#[test]
+fn test_exclusive_handover_escrows_and_swaps_license() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_license(1, "studio", LicenseType::Exclusive)
+        .with_funds("agency", 800)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (studio, agency) = (s.party("studio"), s.party("agency"));
+
+    assert_eq!(client.try_propose_handover(&agency, &1, &studio, &800, &0), Err(Ok(IPError::LicenseNotFound)));
+    assert_eq!(client.try_propose_handover(&studio, &1, &agency, &800, &900), Err(Ok(IPError::InvalidAmount)));
+    client.propose_handover(&studio, &1, &agency, &800, &50);
+    assert_eq!(client.try_propose_handover(&studio, &1, &agency, &800, &50), Err(Ok(IPError::DuplicateOperation)));
+
+    // The owner can only approve once the successor has escrowed the consideration
+    assert_eq!(client.try_approve_handover(&owners, &1), Err(Ok(IPError::InsufficientBalance)));
+    assert_eq!(client.try_fund_handover(&studio, &1), Err(Ok(IPError::Unauthorized)));
+    let held = s.token_client().balance(&s.contract);
+    client.fund_handover(&agency, &1);
+    assert_eq!(s.balance("agency"), 0);
+    assert_eq!(s.token_client().balance(&s.contract), held + 800);
+
+    // Either party may call it off, refunding the escrow
+    client.cancel_handover(&studio, &1);
+    assert_eq!(s.balance("agency"), 800);
+    assert_eq!(client.try_get_handover(&1), Err(Err(InvokeError::Contract(ExtError::HandoverNotFound as u32))));
+
+    client.propose_handover(&studio, &1, &agency, &800, &50);
+    client.fund_handover(&agency, &1);
+    let license = client.approve_handover(&owners, &1);
+
+    assert_eq!(license.licensee, agency);
+    assert_eq!(license.license_type, LicenseType::Exclusive);
+    assert_eq!(license.price_paid, 800);
+    assert_eq!(client.get_license_by_id(&license.license_id), license);
+    assert!(!client.get_license(&1, &studio).is_active);
+    assert!(client.get_ip(&1).has_exclusive);
+    assert_eq!(s.balance("chef"), 50);
+    assert_eq!(s.balance("studio"), 750);
+    assert_eq!(client.try_get_handover(&1), Err(Err(InvokeError::Contract(ExtError::HandoverNotFound as u32))));
+}
+
+#[test]
+fn test_handover_ends_with_its_holders_exclusive() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_license(1, "studio", LicenseType::Exclusive)
+        .with_funds("agency", 800)
+        .with_funds("bistro", 1_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (studio, agency, bistro) = (s.party("studio"), s.party("agency"), s.party("bistro"));
+
+    // A revoked holder's handover is called off and its escrow refunded
+    client.propose_handover(&studio, &1, &agency, &800, &0);
+    client.fund_handover(&agency, &1);
+    client.revoke_license(&owners, &studio, &1, &symbol_short!("breach"));
+    assert_eq!(s.balance("agency"), 800);
+    assert_eq!(client.try_get_handover(&1), Err(Err(InvokeError::Contract(ExtError::HandoverNotFound as u32))));
+
+    // So is a downgraded one's, so it cannot block the next exclusive holder
+    client.purchase_license(&bistro, &1, &LicenseType::Exclusive, &None, &None, &u64::MAX, &None, &None);
+    client.propose_handover(&bistro, &1, &agency, &800, &0);
+    client.downgrade_license(&bistro, &1);
+    assert_eq!(client.try_get_handover(&1), Err(Err(InvokeError::Contract(ExtError::HandoverNotFound as u32))));
+}
+
+#[test]
+fn test_free_claims_escrow_bond_and_enforce_cooldown() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 0)
+        .with_ip(2, "chef", 1_000, 0)
+        .with_funds("fan", 20)
+        .with_funds("troll", 20)
+        .with_funds("guest", 0)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (fan, troll, guest) = (s.party("fan"), s.party("troll"), s.party("guest"));
+    let policy = FreeClaimPolicy { bond: 10, cooldown_secs: 3_600 };
+    client.set_free_claim_policy(&owners, &1, &Some(policy.clone()));
+    client.set_free_claim_policy(&owners, &2, &Some(policy.clone()));
+    client.set_license_duration(&owners, &1, &Some(1_000));
+    assert_eq!(client.get_free_claim_policy(&1), Some(policy));
+
+    let license = client.purchase_license(&fan, &1, &LicenseType::NonExclusive, &None, &Some(0), &u64::MAX, &None, &None);
+    assert_eq!(license.price_paid, 0);
+    assert_eq!(s.balance("fan"), 10);
+    assert_eq!(client.get_claim_bond(&1, &fan), Some((s.token_client().address.clone(), 10)));
+    assert_eq!(client.try_settle_claim_bond(&1, &fan), Err(Ok(IPError::BondLocked)));
+
+    // One free claim per cooldown, across every IP of the contract
+    let claim_2 = client.try_purchase_license(&fan, &2, &LicenseType::NonExclusive, &None, &Some(0), &u64::MAX, &None, &None);
+    assert_eq!(claim_2, Err(Ok(IPError::CapReached)));
+
+    // A revoked claimer's bond goes to the owner
+    client.purchase_license(&troll, &1, &LicenseType::NonExclusive, &None, &Some(0), &u64::MAX, &None, &None);
+    client.revoke_license(&owners, &troll, &1, &symbol_short!("spam"));
+    assert!(client.settle_claim_bond(&1, &troll));
+    assert_eq!(s.balance("troll"), 10);
+    assert_eq!(client.get_claimable(&s.party("chef"), &s.token_client().address), 10);
+
+    s.advance_time(3_600);
+    client.purchase_license(&fan, &2, &LicenseType::NonExclusive, &None, &Some(0), &u64::MAX, &None, &None);
+    assert_eq!(s.balance("fan"), 0);
+
+    // The expired license's bond comes back to the claimer
+    assert!(!client.settle_claim_bond(&1, &fan));
+    assert_eq!(s.balance("fan"), 10);
+    assert_eq!(client.try_settle_claim_bond(&1, &fan), Err(Ok(IPError::BondNotHeld)));
+
+    client.set_free_claim_policy(&owners, &1, &None);
+    client.purchase_license(&guest, &1, &LicenseType::NonExclusive, &None, &Some(0), &u64::MAX, &None, &None);
+    assert_eq!(client.get_claim_bond(&1, &guest), None);
+}
+
+#[test]
+fn test_terms_template_travels_with_purchases() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("studio", 100)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (admin, chef, studio) = (s.party("admin"), s.party("chef"), s.party("studio"));
+    let library_id = s.env.register(TermsLibraryContract, ());
+    let library = TermsLibraryContractClient::new(&s.env, &library_id);
+    let cc_by = library.publish_template(&admin, &BytesN::from_array(&s.env, &[1; 32]), &String::from_str(&s.env, "ipfs://terms/cc-by"));
+
+    let uri = String::from_str(&s.env, "ipfs://recipe");
+    let register = |ip_id: u64, template_id: u32| {
+        client.try_register_ip_from_template(&chef, &ip_id, &template_id, &uri, &None, &500, &100, &s.token_client().address, &vec![&s.env])
+    };
+    assert_eq!(register(2, cc_by), Err(Ok(IPError::InvalidConfig)));
+    client.set_terms_library(&admin, &library_id);
+    assert_eq!(register(2, 9), Err(Ok(IPError::InvalidConfig)));
+    assert_eq!(register(2, cc_by).unwrap().unwrap().terms_template, Some(cc_by));
+
+    assert_eq!(client.try_set_terms_template(&owners, &1, &Some(9)), Err(Ok(IPError::InvalidConfig)));
+    client.set_terms_template(&owners, &1, &Some(cc_by));
+    assert_eq!(client.get_ip(&1).terms_template, Some(cc_by));
+
+    client.purchase_license(&studio, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    let event = s.env.events().all().iter().filter(|event| event.0 == s.contract).last().unwrap();
+    assert_eq!(event.1, (symbol_short!("purchase"), 1u64, studio.clone()).into_val(&s.env));
+    assert_eq!(<(u64, i128, Option<u32>)>::from_val(&s.env, &event.2).2, Some(cc_by));
+}
+
+#[test]
+fn test_rounding_policy_applies_to_fees_and_conserves_payments() {
+    let s = ScenarioBuilder::new()
+        .with_fee_bps(5_000)
+        .with_ip(1, "chef", 1_000, 3)
+        .with_funds("diner", 9)
+        .with_funds("critic", 3)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (diner, critic, chef) = (s.party("diner"), s.party("critic"), s.party("chef"));
+    let token = s.token_client().address;
+    let buy = |buyer: &Address, referrer: Option<Address>| {
+        client.purchase_license(buyer, &1, &LicenseType::NonExclusive, &referrer, &None, &u64::MAX, &None, &None);
+        client.revoke_license(&owners, buyer, &1, &symbol_short!("resale"));
+    };
+
+    // Half of 3 is 1.5 atoms: floor by default, then up, then to the even 2
+    let mut treasury = 0;
+    let mut proceeds = 0;
+    for (rounding, fee) in [(Rounding::Floor, 1), (Rounding::Ceil, 2), (Rounding::HalfEven, 2)] {
+        client.set_rounding(&owners, &1, &rounding);
+        buy(&diner, None);
+        treasury += fee;
+        proceeds += 3 - fee;
+        assert_eq!(s.balance("treasury"), treasury, "{rounding:?}");
+        assert_eq!(client.get_claimable(&chef, &token), proceeds, "{rounding:?}");
+    }
+
+    // Shares rounded up never add up to more than the payment
+    client.set_referral_bps(&owners, &1, &5_000);
+    client.set_rounding(&owners, &1, &Rounding::Ceil);
+    buy(&critic, Some(diner.clone()));
+    assert_eq!(s.balance("treasury"), treasury + 2);
+    assert_eq!(s.balance("diner"), 1);
+    assert_eq!(client.get_claimable(&chef, &token), proceeds);
+}
+
+#[test]
+fn test_stuck_escrow_reclaimed_after_hard_timeout() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 0)
+        .with_funds("chef", 500)
+        .with_funds("fan", 10)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (admin, chef, fan) = (s.party("admin"), s.party("chef"), s.party("fan"));
+    client.post_bond(&owners, &1, &500, &100);
+    client.set_free_claim_policy(&owners, &1, &Some(FreeClaimPolicy { bond: 10, cooldown_secs: 0 }));
+    client.purchase_license(&fan, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+
+    // A legal hold nobody lifts and a license that never ends would otherwise
+    // keep both bonds in escrow forever
+    client.freeze_ip(&admin, &1, &String::from_str(&s.env, "claim"));
+    s.advance_time(200);
+    assert_eq!(client.try_release_bond(&1), Err(Ok(IPError::IPFrozen)));
+    let bond = StuckEscrow::IndemnityBond(1);
+    assert_eq!(client.try_reclaim_stuck_escrow(&fan, &bond), Err(Ok(IPError::Unauthorized)));
+    assert_eq!(client.try_reclaim_stuck_escrow(&chef, &bond), Err(Ok(IPError::BondLocked)));
+    assert_eq!(client.try_reclaim_stuck_escrow(&fan, &StuckEscrow::ClaimBond(1)), Err(Ok(IPError::BondLocked)));
+
+    s.advance_time(escape_hatch::ESCROW_HARD_TIMEOUT_SECS);
+    client.grant_role(&admin, &Role::Pauser, &admin);
+    client.pause(&admin);
+    assert_eq!(client.reclaim_stuck_escrow(&chef, &bond), 500);
+    assert_eq!(client.get_bond(&1).status, BondStatus::Reclaimed);
+    assert_eq!(client.reclaim_stuck_escrow(&fan, &StuckEscrow::ClaimBond(1)), 10);
+    assert_eq!((s.balance("chef"), s.balance("fan")), (500, 10));
+    assert_eq!(client.try_reclaim_stuck_escrow(&chef, &bond), Err(Ok(IPError::BondNotHeld)));
+}
+
+#[test]
+fn test_stuck_leading_bid_reclaimed_after_hard_timeout() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("studio", 2_000)
+        .with_funds("bistro", 2_000)
+        .build();
+    let client = s.client();
+    let owners = vec![&s.env, s.party("chef")];
+    let (admin, studio, bistro) = (s.party("admin"), s.party("studio"), s.party("bistro"));
+    let salt = BytesN::from_array(&s.env, &[3; 32]);
+    let auction_id = client.start_sealed_auction(&owners, &1, &300, &100, &100, &100);
+    client.commit_bid(&studio, &auction_id, &client.bid_commitment(&900, &salt));
+    client.commit_bid(&bistro, &auction_id, &client.bid_commitment(&600, &salt));
+    s.advance_time(100);
+    client.reveal_bid(&studio, &auction_id, &900, &salt);
+    client.reveal_bid(&bistro, &auction_id, &600, &salt);
+
+    // A pause that never lifts keeps the auction from settling, and with it
+    // the leader's bid; every other bid can still be withdrawn
+    client.grant_role(&admin, &Role::Pauser, &admin);
+    client.pause(&admin);
+    s.advance_time(100);
+    assert_eq!(client.try_settle_sealed_auction(&auction_id), Err(Ok(IPError::Paused)));
+    let bid = StuckEscrow::SealedBid(auction_id);
+    assert_eq!(client.try_reclaim_stuck_escrow(&studio, &bid), Err(Ok(IPError::BondLocked)));
+    let res = client.try_reclaim_stuck_escrow(&bistro, &bid);
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::AuctionPhase as u32))));
+    assert!(!client.withdraw_bid(&bistro, &auction_id));
+
+    s.advance_time(escape_hatch::ESCROW_HARD_TIMEOUT_SECS);
+    assert_eq!(client.reclaim_stuck_escrow(&studio, &bid), 900);
+    assert_eq!((s.balance("studio"), s.balance("bistro")), (2_000, 2_000));
+    assert!(client.get_auction(&auction_id).settled);
+    assert_eq!(client.get_open_auction(&1), None);
+    let res = client.try_reclaim_stuck_escrow(&studio, &bid);
+    assert_eq!(res, Err(Err(InvokeError::Contract(ExtError::BidNotFound as u32))));
+}
+
+/// A contract account that signs for itself only by invoking the IP contract
+/// directly, pre-authorizing any token transfer made deeper in the call
+#[contract]
+struct ContractWallet;
+
+#[contractimpl]
+impl ContractWallet {
+    pub fn register(env: Env, ip_contract: Address, ip_id: u64, token: Address, price: i128) -> IPAsset {
+        let uri = String::from_str(&env, "ipfs://dao-recipe");
+        let wallet = env.current_contract_address();
+        IPLicensingContractClient::new(&env, &ip_contract).register_ip(&wallet, &ip_id, &uri, &None, &0, &price, &token, &vec![&env])
+    }
+
+    pub fn set_referral(env: Env, ip_contract: Address, ip_id: u64, referral_bps: u32) {
+        let approvers = vec![&env, env.current_contract_address()];
+        IPLicensingContractClient::new(&env, &ip_contract).set_referral_bps(&approvers, &ip_id, &referral_bps);
+    }
+
+    pub fn buy(env: Env, ip_contract: Address, ip_id: u64, token: Address, price: i128, referrer: Option<Address>) -> License {
+        let wallet = env.current_contract_address();
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token,
+                    fn_name: symbol_short!("transfer"),
+                    args: (wallet.clone(), ip_contract.clone(), price).into_val(&env),
+                },
+                sub_invocations: vec![&env],
+            }),
+        ]);
+        let ip = IPLicensingContractClient::new(&env, &ip_contract);
+        ip.purchase_license(&wallet, &ip_id, &LicenseType::NonExclusive, &referrer, &Some(price), &u64::MAX, &None, &None)
+    }
+
+    pub fn claim(env: Env, ip_contract: Address, token: Address) -> i128 {
+        IPLicensingContractClient::new(&env, &ip_contract).claim(&env.current_contract_address(), &token)
+    }
+}
+
+#[test]
+fn test_contract_account_owns_and_licenses_without_signatures() {
+    let s = ScenarioBuilder::new()
+        .with_fee_bps(1_000)
+        .with_ip(1, "chef", 1_000, 100)
+        .with_funds("scout", 0)
+        .build();
+    let client = s.client();
+    let token = s.token_client().address;
+    let scout = s.party("scout");
+    client.set_referral_bps(&vec![&s.env, s.party("chef")], &1, &500);
+    let wallet_id = s.env.register(ContractWallet, ());
+    let wallet = ContractWalletClient::new(&s.env, &wallet_id);
+    token::StellarAssetClient::new(&s.env, &token).mint(&wallet_id, &200);
+
+    // From here on every authorization must be real: the wallet's own
+    // invocations and the single transfer it pre-authorizes per purchase
+    s.env.set_auths(&[]);
+    assert_eq!(wallet.register(&s.contract, &2, &token, &100).owner, wallet_id);
+    wallet.set_referral(&s.contract, &2, &500);
+    assert_eq!(client.get_ip(&2).referral_bps, 500);
+
+    // Fee and referral shares are paid out of the one transfer
+    let license = wallet.buy(&s.contract, &1, &token, &100, &Some(scout.clone()));
+    assert_eq!(license.licensee, wallet_id);
+    assert_eq!((s.balance("treasury"), s.balance("scout")), (10, 5));
+    assert_eq!(client.get_claimable(&s.party("chef"), &token), 85);
+
+    wallet.buy(&s.contract, &2, &token, &100, &None);
+    assert_eq!(wallet.claim(&s.contract, &token), 90);
+    assert_eq!(s.token_client().balance(&wallet_id), 90);
+
+    // Anything the wallet did not authorize is refused
+    let stray = s.env.register(ContractWallet, ());
+    assert!(client.try_purchase_license(&stray, &2, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None).is_err());
+}
+
+#[test]
+fn test_lookups_return_none_for_missing_records() {
+    let s = ScenarioBuilder::new()
+        .with_ip(1, "chef", 1_000, 100)
+        .with_license(1, "diner", LicenseType::NonExclusive)
+        .build();
+    let client = s.client();
+    let (diner, stranger) = (s.party("diner"), s.party("chef"));
+
+    assert_eq!(client.lookup_ip(&1), Some(client.get_ip(&1)));
+    assert_eq!(client.lookup_ip(&2), None);
+    let license = client.get_license(&1, &diner);
+    assert_eq!(client.lookup_license(&1, &diner), Some(license.clone()));
+    assert_eq!(client.lookup_license(&1, &stranger), None);
+    assert_eq!(client.lookup_license_by_id(&license.license_id), Some(license));
+    assert_eq!(client.lookup_license_by_id(&99), None);
 }
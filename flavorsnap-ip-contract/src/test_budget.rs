@@ -0,0 +1,65 @@
+#![cfg(test)]
+
+//! Resource budget regression tests.
+//!
+//! Baselines are the CPU instruction and memory costs of each entrypoint as
+//! measured by the native test environment. A change that grows either cost
+//! by more than `TOLERANCE_PCT` fails; if the growth is intended, re-measure
+//! and update the baseline in the same change.
+
+use super::*;
+use soroban_sdk::{testutils::Address as _, token, vec, Env, String};
+
+const TOLERANCE_PCT: u64 = 20;
+
+const REGISTER_IP_CPU: u64 = 279_000;
+const REGISTER_IP_MEM: u64 = 52_000;
+const PURCHASE_LICENSE_CPU: u64 = 795_000;
+const PURCHASE_LICENSE_MEM: u64 = 152_000;
+
+/// Assert the cost of the last top-level invocation stays within tolerance of its baseline
+fn assert_within_budget(env: &Env, name: &str, cpu_baseline: u64, mem_baseline: u64) {
+    let budget = env.cost_estimate().budget();
+    let cpu = budget.cpu_instruction_cost();
+    let mem = budget.memory_bytes_cost();
+
+    let cpu_limit = cpu_baseline * (100 + TOLERANCE_PCT) / 100;
+    let mem_limit = mem_baseline * (100 + TOLERANCE_PCT) / 100;
+    assert!(
+        cpu <= cpu_limit && mem <= mem_limit,
+        "{name}: measured cpu {cpu} / memory {mem} against baseline cpu {cpu_baseline} / memory {mem_baseline} (+{TOLERANCE_PCT}%)"
+    );
+}
+
+#[test]
+fn test_register_and_purchase_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_address = env.register(
+        IPLicensingContract,
+        (Address::generate(&env), Address::generate(&env), 250u32),
+    );
+    let client = IPLicensingContractClient::new(&env, &contract_address);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+
+    client.register_ip(
+        &owner,
+        &1,
+        &String::from_str(&env, "ipfs://metadata"),
+        &None,
+        &500,
+        &100,
+        &token_address,
+        &vec![&env]
+    );
+    assert_within_budget(&env, "register_ip", REGISTER_IP_CPU, REGISTER_IP_MEM);
+
+    // Fee-bearing purchase: two token transfers plus license bookkeeping
+    client.purchase_license(&buyer, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_within_budget(&env, "purchase_license", PURCHASE_LICENSE_CPU, PURCHASE_LICENSE_MEM);
+}
@@ -0,0 +1,133 @@
+//! Usage tiers: per-tier prices, the slots each tier takes on an IP, and
+//! moving non-exclusive licenses onto the Commercial tier
+
+use crate::{
+    config, events, storage, IPAsset, IPError, IPLicensingContract, IPLicensingContractArgs,
+    IPLicensingContractClient, LicenseType
+};
+use soroban_sdk::{contractimpl, Address, Env, Map, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Offer a usage tier at `price`, or withdraw it from sale with `None`
+    /// (Requires IP owner approval). Exclusive and NonExclusive are priced
+    /// through `set_prices` and rejected here with `InvalidTier`.
+    pub fn set_tier_price(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        tier: LicenseType,
+        price: Option<i128>,
+    ) -> Result<(), IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        if Self::is_legacy_type(tier) {
+            return Err(IPError::InvalidTier);
+        }
+        match price {
+            Some(price) => {
+                Self::require_sane_amount(price, ip.token_decimals)?;
+                ip.tier_prices.set(tier, price);
+            }
+            None => {
+                ip.tier_prices.remove(tier);
+            }
+        }
+        storage::write_ip(&env, ip_id, &ip);
+
+        events::tier_price_set(&env, ip_id, tier, price);
+        Ok(())
+    }
+
+    /// List prices of the usage tiers on offer
+    pub fn get_tier_prices(env: Env, ip_id: u64) -> Result<Map<LicenseType, i128>, IPError> {
+        Ok(storage::read_ip(&env, ip_id)?.tier_prices)
+    }
+
+    /// Move the NonExclusive licenses of `licensees` on `ip_id` onto the
+    /// Commercial tier, at most `max_batch_size` per call (Requires IP owner
+    /// approval). If the IP offers no Commercial price yet, its non-exclusive
+    /// price carries over. Other licenses are skipped. Returns how many moved.
+    /// Only the tier changes; this is not a storage migration, and records
+    /// written before the tier fields existed are not upgraded by it.
+    pub fn move_to_commercial_tier(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        licensees: Vec<Address>,
+    ) -> Result<u32, IPError> {
+        let mut ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+        if licensees.len() > config::max_batch_size(&env) {
+            return Err(IPError::CapReached);
+        }
+
+        if !ip.tier_prices.contains_key(LicenseType::Commercial) {
+            ip.tier_prices.set(LicenseType::Commercial, ip.price_non_exclusive);
+            storage::write_ip(&env, ip_id, &ip);
+        }
+
+        let mut moved = 0;
+        for licensee in licensees.iter() {
+            let Ok(mut license) = storage::read_license(&env, ip_id, &licensee) else {
+                continue;
+            };
+            if license.license_type == LicenseType::NonExclusive {
+                license.license_type = LicenseType::Commercial;
+                storage::write_license(&env, &license);
+                moved += 1;
+            }
+        }
+
+        events::moved_to_commercial(&env, ip_id, moved);
+        Ok(moved)
+    }
+}
+
+impl IPLicensingContract {
+    pub(crate) fn is_legacy_type(license_type: LicenseType) -> bool {
+        matches!(license_type, LicenseType::Exclusive | LicenseType::NonExclusive)
+    }
+
+    /// Take the place a new or reinstated license of `license_type` occupies on
+    /// the in-memory asset, or fail if the tier's exclusivity forbids it
+    pub(crate) fn take_license_slot(ip: &mut IPAsset, license_type: LicenseType) -> Result<(), IPError> {
+        if ip.has_exclusive {
+            return Err(IPError::ExclusiveAlreadyIssued);
+        }
+        match license_type {
+            LicenseType::Exclusive => {
+                if ip.active_licenses > 0 {
+                    return Err(IPError::ActiveLicensesExist);
+                }
+                ip.has_exclusive = true;
+            }
+            LicenseType::Broadcast => {
+                if ip.has_broadcast {
+                    return Err(IPError::BroadcastAlreadyIssued);
+                }
+                ip.has_broadcast = true;
+                ip.active_licenses += 1;
+            }
+            _ => ip.active_licenses += 1,
+        }
+        Ok(())
+    }
+
+    /// Release the place a license of `license_type` held on the in-memory asset
+    pub(crate) fn release_license_slot(ip: &mut IPAsset, license_type: LicenseType) {
+        match license_type {
+            LicenseType::Exclusive => ip.has_exclusive = false,
+            LicenseType::Broadcast => {
+                ip.has_broadcast = false;
+                ip.active_licenses -= 1;
+            }
+            _ => ip.active_licenses -= 1,
+        }
+    }
+}
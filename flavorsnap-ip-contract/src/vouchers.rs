@@ -0,0 +1,86 @@
+//! Promotional discount vouchers (`vouchers` feature)
+
+use crate::{
+    events, payments::Funding, storage, IPError, IPLicensingContract, IPLicensingContractArgs,
+    IPLicensingContractClient, License, LicenseType, Voucher, BPS_DENOMINATOR
+};
+use soroban_sdk::{contractimpl, Address, Bytes, BytesN, Env, Vec};
+
+#[contractimpl]
+impl IPLicensingContract {
+    /// Create or replace a discount voucher keyed by the sha256 of its code
+    /// (Requires IP owner approval)
+    pub fn create_voucher(
+        env: Env,
+        approvers: Vec<Address>,
+        ip_id: u64,
+        code_hash: BytesN<32>,
+        bps_off: u32,
+        max_uses: u32,
+        expires_at: u64,
+    ) -> Result<(), IPError> {
+        let ip = storage::read_ip(&env, ip_id)?;
+
+        Self::require_owner_approval(&ip, &approvers)?;
+        Self::require_not_frozen(&env, ip_id)?;
+
+        if bps_off == 0 || bps_off as i128 > BPS_DENOMINATOR || max_uses == 0 {
+            return Err(IPError::InvalidAmount);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            return Err(IPError::Expired);
+        }
+
+        let voucher = Voucher {
+            bps_off,
+            uses_left: max_uses,
+            expires_at,
+        };
+        storage::write_voucher(&env, ip_id, &code_hash, &voucher);
+        storage::mark_undeletable(&env, ip_id);
+
+        events::voucher_created(&env, ip_id, code_hash, bps_off, max_uses);
+        Ok(())
+    }
+
+    pub fn get_voucher(env: Env, ip_id: u64, code_hash: BytesN<32>) -> Option<Voucher> {
+        storage::read_voucher(&env, ip_id, &code_hash)
+    }
+
+    /// Purchase a license at the discount of the voucher whose code hashes
+    /// (sha256) to a voucher created on this IP, consuming one of its uses
+    pub fn purchase_license_with_voucher(
+        env: Env,
+        licensee: Address,
+        ip_id: u64,
+        license_type: LicenseType,
+        code: Bytes,
+        referrer: Option<Address>,
+        deadline: u64,
+        idempotency_key: Option<BytesN<32>>,
+    ) -> Result<License, IPError> {
+        if storage::is_paused(&env) {
+            return Err(IPError::Paused);
+        }
+        Self::require_before(&env, deadline)?;
+        licensee.require_auth();
+        Self::claim_idempotency_key(&env, &licensee, idempotency_key)?;
+
+        let code_hash: BytesN<32> = env.crypto().sha256(&code).into();
+        let mut voucher = storage::read_voucher(&env, ip_id, &code_hash).ok_or(IPError::VoucherNotFound)?;
+        if env.ledger().timestamp() >= voucher.expires_at {
+            return Err(IPError::Expired);
+        }
+        if voucher.uses_left == 0 {
+            return Err(IPError::CapReached);
+        }
+
+        voucher.uses_left -= 1;
+        storage::write_voucher(&env, ip_id, &code_hash, &voucher);
+
+        let license = Self::execute_purchase_at_discount(&env, &licensee, ip_id, license_type, referrer, voucher.bps_off, None, Funding::Direct)?;
+
+        events::voucher_redeemed(&env, ip_id, licensee, code_hash);
+        Ok(license)
+    }
+}
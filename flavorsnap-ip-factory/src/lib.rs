@@ -0,0 +1,162 @@
+#![no_std]
+
+use flavorsnap_common::{Bps, PageCursor, MAX_BPS};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, xdr::ToXdr, Address, BytesN,
+    Env, Vec
+};
+
+const MAX_PAGE_SIZE: u32 = 50;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FactoryError {
+    Unauthorized = 1,
+    AlreadyDeployed = 2,
+    InvalidFee = 3,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deployment {
+    pub creator: Address,
+    pub contract: Address,
+    pub salt: BytesN<32>,
+    pub deployed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FactoryConfig {
+    pub admin: Address,
+    pub wasm_hash: BytesN<32>,
+    pub treasury: Address,
+    pub fee_bps: Bps,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Config,
+    DeploymentCount,
+    Deployment(u32),
+    ByCreator(Address, BytesN<32>),
+}
+
+#[contract]
+pub struct IPFactoryContract;
+
+#[contractimpl]
+impl IPFactoryContract {
+    /// Set the factory admin, licensing wasm and the platform fee passed to every instance
+    pub fn __constructor(
+        env: Env,
+        admin: Address,
+        wasm_hash: BytesN<32>,
+        treasury: Address,
+        fee_bps: u32,
+    ) -> Result<(), FactoryError> {
+        if fee_bps > MAX_BPS {
+            return Err(FactoryError::InvalidFee);
+        }
+
+        let config = FactoryConfig {
+            admin,
+            wasm_hash,
+            treasury,
+            fee_bps,
+        };
+        env.storage().instance().set(&DataKey::Config, &config);
+        Ok(())
+    }
+
+    pub fn get_config(env: Env) -> FactoryConfig {
+        Self::read_config(&env)
+    }
+
+    /// Point future deployments at a new uploaded IP contract wasm (Only callable by admin)
+    pub fn set_wasm_hash(env: Env, admin: Address, wasm_hash: BytesN<32>) -> Result<(), FactoryError> {
+        admin.require_auth();
+        let mut config = Self::read_config(&env);
+        if config.admin != admin {
+            return Err(FactoryError::Unauthorized);
+        }
+
+        config.wasm_hash = wasm_hash;
+        env.storage().instance().set(&DataKey::Config, &config);
+        Ok(())
+    }
+
+    /// Deploy a dedicated IP licensing contract for `creator`, who becomes its admin.
+    /// `salt` distinguishes multiple instances (e.g. brands) of the same creator.
+    pub fn deploy(env: Env, creator: Address, salt: BytesN<32>) -> Result<Address, FactoryError> {
+        creator.require_auth();
+
+        let config = Self::read_config(&env);
+
+        let creator_key = DataKey::ByCreator(creator.clone(), salt.clone());
+        if env.storage().persistent().has(&creator_key) {
+            return Err(FactoryError::AlreadyDeployed);
+        }
+
+        // Bind the deployment salt to the creator so nobody can squat their address
+        let mut salt_preimage = creator.clone().to_xdr(&env);
+        salt_preimage.append(&salt.clone().into());
+        let deploy_salt = env.crypto().sha256(&salt_preimage).to_bytes();
+
+        let contract = env.deployer()
+            .with_current_contract(deploy_salt)
+            .deploy_v2(config.wasm_hash, (creator.clone(), config.treasury, config.fee_bps));
+
+        let index: u32 = env.storage().instance().get(&DataKey::DeploymentCount).unwrap_or(0);
+        let deployment = Deployment {
+            creator: creator.clone(),
+            contract: contract.clone(),
+            salt,
+            deployed_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::Deployment(index), &deployment);
+        env.storage().persistent().set(&creator_key, &index);
+        env.storage().instance().set(&DataKey::DeploymentCount, &(index + 1));
+
+        env.events().publish(
+            (symbol_short!("deployed"), creator),
+            contract.clone()
+        );
+
+        Ok(contract)
+    }
+
+    pub fn deployment_count(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::DeploymentCount).unwrap_or(0)
+    }
+
+    /// List deployments in creation order, at most 50 per call
+    pub fn list_deployments(env: Env, start: u32, limit: u32) -> Vec<Deployment> {
+        let count = Self::deployment_count(env.clone());
+        let cursor = PageCursor::new(start, limit, MAX_PAGE_SIZE, count);
+
+        let mut page = Vec::new(&env);
+        for index in cursor.range() {
+            if let Some(deployment) = env.storage().persistent().get(&DataKey::Deployment(index)) {
+                page.push_back(deployment);
+            }
+        }
+        page
+    }
+
+    pub fn get_deployment(env: Env, creator: Address, salt: BytesN<32>) -> Option<Deployment> {
+        let index: u32 = env.storage().persistent().get(&DataKey::ByCreator(creator, salt))?;
+        env.storage().persistent().get(&DataKey::Deployment(index))
+    }
+}
+
+impl IPFactoryContract {
+    fn read_config(env: &Env) -> FactoryConfig {
+        env.storage().instance().get(&DataKey::Config).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test;
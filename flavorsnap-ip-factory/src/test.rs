@@ -0,0 +1,103 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
+
+// Deployment tests execute a checked-in build of the licensing wasm. Refresh it
+// in every change to the licensing contract with
+// `cargo build -p flavorsnap-ip-contract --target wasm32v1-none --release`
+// and copy the output into `testdata/`.
+mod ip_contract {
+    pub const WASM: &[u8] = include_bytes!("../testdata/flavorsnap_ip_contract.wasm");
+    pub use flavorsnap_ip_contract::{IPLicensingContractClient as Client, Role};
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_fee_above_max_bps_rejected() {
+    let env = Env::default();
+    env.register(
+        IPFactoryContract,
+        (
+            Address::generate(&env),
+            BytesN::from_array(&env, &[1; 32]),
+            Address::generate(&env),
+            10_001u32,
+        ),
+    );
+}
+
+#[test]
+fn test_only_admin_sets_wasm_hash() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let wasm_hash = BytesN::from_array(&env, &[1; 32]);
+    let factory_address = env.register(
+        IPFactoryContract,
+        (admin.clone(), wasm_hash.clone(), Address::generate(&env), 0u32),
+    );
+    let client = IPFactoryContractClient::new(&env, &factory_address);
+    assert_eq!(client.get_config().wasm_hash, wasm_hash);
+
+    let new_hash = BytesN::from_array(&env, &[2; 32]);
+    let res = client.try_set_wasm_hash(&Address::generate(&env), &new_hash);
+    assert_eq!(res, Err(Ok(FactoryError::Unauthorized)));
+
+    client.set_wasm_hash(&admin, &new_hash);
+    assert_eq!(client.get_config().wasm_hash, new_hash);
+
+    assert_eq!(client.deployment_count(), 0);
+    assert_eq!(client.list_deployments(&0, &10).len(), 0);
+}
+
+#[test]
+fn test_deploy_per_creator_instances() {
+    let env = Env::default();
+    env.mock_all_auths();
+    // Instantiating the full licensing contract wasm exceeds the default
+    // test budget; this test checks wiring, not cost.
+    env.cost_estimate().budget().reset_unlimited();
+
+    let wasm_hash = env.deployer().upload_contract_wasm(ip_contract::WASM);
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let factory_address = env.register(
+        IPFactoryContract,
+        (admin.clone(), wasm_hash.clone(), treasury.clone(), 100u32),
+    );
+    let client = IPFactoryContractClient::new(&env, &factory_address);
+
+    let brand_a = BytesN::from_array(&env, &[1; 32]);
+    let brand_b = BytesN::from_array(&env, &[2; 32]);
+
+    let instance_a = client.deploy(&creator, &brand_a);
+    let instance_b = client.deploy(&creator, &brand_b);
+    assert_ne!(instance_a, instance_b);
+
+    // Same creator and salt cannot be deployed twice
+    let res = client.try_deploy(&creator, &brand_a);
+    assert_eq!(res, Err(Ok(FactoryError::AlreadyDeployed)));
+
+    // The creator administers their own instance
+    let ip_client = ip_contract::Client::new(&env, &instance_a);
+    assert!(ip_client.has_role(&ip_contract::Role::Admin, &creator));
+    let ip_config = ip_client.get_config();
+    assert_eq!(ip_config.treasury, treasury);
+    assert_eq!(ip_config.fee_bps, 100);
+
+    assert_eq!(client.deployment_count(), 2);
+    let page = client.list_deployments(&0, &10);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().contract, instance_a);
+    assert_eq!(page.get(1).unwrap().contract, instance_b);
+
+    let page = client.list_deployments(&1, &10);
+    assert_eq!(page.len(), 1);
+
+    let found = client.get_deployment(&creator, &brand_b).unwrap();
+    assert_eq!(found.contract, instance_b);
+}
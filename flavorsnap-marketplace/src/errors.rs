@@ -0,0 +1,54 @@
+use soroban_sdk::contracterror;
+
+/// Errors returned by `MarketplaceContract`.
+///
+/// Discriminants are part of the public ABI and must never be renumbered;
+/// new conditions are appended at the end.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MarketplaceError {
+    /// The caller is not the marketplace admin or the IP owner.
+    Unauthorized = 1,
+    /// The marketplace fee exceeds 10000 bps.
+    InvalidFee = 2,
+    /// The IP contract is not registered with the marketplace.
+    ContractNotRegistered = 3,
+    /// The IP contract is already registered, or the IP already listed.
+    AlreadyListed = 4,
+    /// The IP is not listed on the marketplace.
+    ListingNotFound = 5,
+    /// List price plus marketplace fee exceeds the buyer's maximum.
+    SlippageExceeded = 6,
+    /// The marketplace already aggregates `MAX_CONTRACTS` IP contracts.
+    CapReached = 7,
+}
+
+impl MarketplaceError {
+    pub fn from_code(code: u32) -> Option<Self> {
+        let error = match code {
+            1 => MarketplaceError::Unauthorized,
+            2 => MarketplaceError::InvalidFee,
+            3 => MarketplaceError::ContractNotRegistered,
+            4 => MarketplaceError::AlreadyListed,
+            5 => MarketplaceError::ListingNotFound,
+            6 => MarketplaceError::SlippageExceeded,
+            7 => MarketplaceError::CapReached,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Short human-readable description, surfaced through `error_message`
+    pub fn message(&self) -> &'static str {
+        match self {
+            MarketplaceError::Unauthorized => "caller is not authorized",
+            MarketplaceError::InvalidFee => "fee exceeds 10000 bps",
+            MarketplaceError::ContractNotRegistered => "ip contract not registered",
+            MarketplaceError::AlreadyListed => "already listed",
+            MarketplaceError::ListingNotFound => "listing not found",
+            MarketplaceError::SlippageExceeded => "price exceeds max payment",
+            MarketplaceError::CapReached => "cap reached",
+        }
+    }
+}
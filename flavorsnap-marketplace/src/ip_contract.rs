@@ -0,0 +1,22 @@
+use flavorsnap_common::{License, LicenseType};
+use soroban_sdk::{contractclient, Address, BytesN, Env, String};
+
+/// The subset of `IPLicensingContract` the marketplace forwards to
+#[allow(dead_code)]
+#[contractclient(name = "IPLicensingClient")]
+pub trait IPLicensing {
+    fn owner_of(env: Env, ip_id: u64) -> Address;
+    fn quote_price(env: Env, ip_id: u64, license_type: LicenseType) -> (i128, Address, u32);
+    #[allow(clippy::too_many_arguments)]
+    fn purchase_license(
+        env: Env,
+        licensee: Address,
+        ip_id: u64,
+        license_type: LicenseType,
+        referrer: Option<Address>,
+        max_payment: Option<i128>,
+        deadline: u64,
+        idempotency_key: Option<BytesN<32>>,
+        memo: Option<String>,
+    ) -> License;
+}
@@ -0,0 +1,260 @@
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, Env, String, Vec};
+
+mod errors;
+mod ip_contract;
+
+pub use errors::MarketplaceError;
+pub use flavorsnap_common::{License, LicenseType};
+use flavorsnap_common::{bps_of, Bps, PageCursor, Rounding, BPS_DENOMINATOR};
+use ip_contract::IPLicensingClient;
+
+/// Most IP contracts one marketplace aggregates
+pub const MAX_CONTRACTS: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketplaceConfig {
+    pub admin: Address,
+    pub treasury: Address,
+    /// Charged to buyers on top of the IP's list price
+    pub fee_bps: Bps,
+}
+
+/// An IP offered through the marketplace by its owner at listing time
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Listing {
+    pub ip_contract: Address,
+    pub ip_id: u64,
+    pub seller: Address,
+    pub listed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Config,
+    Contracts,
+    ListingCount,
+    Listing(u32),
+    /// Index of the live listing of (ip_contract, ip_id)
+    Listed(Address, u64),
+}
+
+/// Storefront over many deployed IP licensing contracts: one listing index
+/// across all of them and a single purchase entrypoint that forwards to the
+/// right instance and charges the marketplace fee.
+#[contract]
+pub struct MarketplaceContract;
+
+#[contractimpl]
+impl MarketplaceContract {
+    /// Set the marketplace admin and the fee it charges into `treasury`
+    pub fn __constructor(env: Env, admin: Address, treasury: Address, fee_bps: u32) -> Result<(), MarketplaceError> {
+        Self::require_valid_fee(fee_bps)?;
+        let config = MarketplaceConfig { admin, treasury, fee_bps };
+        env.storage().instance().set(&DataKey::Config, &config);
+        env.storage().instance().set(&DataKey::Contracts, &Vec::<Address>::new(&env));
+        Ok(())
+    }
+
+    pub fn get_config(env: Env) -> MarketplaceConfig {
+        Self::read_config(&env)
+    }
+
+    /// Describe a `MarketplaceError` code returned by this contract
+    pub fn error_message(env: Env, code: u32) -> String {
+        let message = MarketplaceError::from_code(code)
+            .map(|error| error.message())
+            .unwrap_or("unknown error");
+        String::from_str(&env, message)
+    }
+
+    /// Change the marketplace fee and where it is paid (Only callable by admin)
+    pub fn set_fee(env: Env, admin: Address, treasury: Address, fee_bps: u32) -> Result<(), MarketplaceError> {
+        let mut config = Self::require_admin(&env, &admin)?;
+        Self::require_valid_fee(fee_bps)?;
+
+        config.treasury = treasury;
+        config.fee_bps = fee_bps;
+        env.storage().instance().set(&DataKey::Config, &config);
+
+        env.events().publish((symbol_short!("fee_set"),), fee_bps);
+        Ok(())
+    }
+
+    /// Aggregate a deployed IP licensing contract (Only callable by admin)
+    pub fn add_contract(env: Env, admin: Address, ip_contract: Address) -> Result<(), MarketplaceError> {
+        Self::require_admin(&env, &admin)?;
+
+        let mut contracts = Self::contracts(env.clone());
+        if contracts.contains(&ip_contract) {
+            return Err(MarketplaceError::AlreadyListed);
+        }
+        if contracts.len() >= MAX_CONTRACTS {
+            return Err(MarketplaceError::CapReached);
+        }
+        contracts.push_back(ip_contract.clone());
+        env.storage().instance().set(&DataKey::Contracts, &contracts);
+
+        env.events().publish((symbol_short!("ctr_add"), ip_contract), ());
+        Ok(())
+    }
+
+    /// Stop aggregating an IP contract (Only callable by admin). Its listings
+    /// drop out of `get_listings` and can no longer be bought here.
+    pub fn remove_contract(env: Env, admin: Address, ip_contract: Address) -> Result<(), MarketplaceError> {
+        Self::require_admin(&env, &admin)?;
+
+        let mut contracts = Self::contracts(env.clone());
+        let index = contracts.first_index_of(&ip_contract).ok_or(MarketplaceError::ContractNotRegistered)?;
+        contracts.remove(index);
+        env.storage().instance().set(&DataKey::Contracts, &contracts);
+
+        env.events().publish((symbol_short!("ctr_rm"), ip_contract), ());
+        Ok(())
+    }
+
+    /// IP contracts currently aggregated, in registration order
+    pub fn contracts(env: Env) -> Vec<Address> {
+        env.storage().instance().get(&DataKey::Contracts).unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// List an IP of a registered contract (Only callable by the IP's owner)
+    pub fn list(env: Env, seller: Address, ip_contract: Address, ip_id: u64) -> Result<u32, MarketplaceError> {
+        seller.require_auth();
+        Self::require_registered(&env, &ip_contract)?;
+        if IPLicensingClient::new(&env, &ip_contract).owner_of(&ip_id) != seller {
+            return Err(MarketplaceError::Unauthorized);
+        }
+
+        let listed_key = DataKey::Listed(ip_contract.clone(), ip_id);
+        if env.storage().persistent().has(&listed_key) {
+            return Err(MarketplaceError::AlreadyListed);
+        }
+
+        let index: u32 = env.storage().instance().get(&DataKey::ListingCount).unwrap_or(0);
+        let listing = Listing {
+            ip_contract: ip_contract.clone(),
+            ip_id,
+            seller: seller.clone(),
+            listed_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::Listing(index), &listing);
+        env.storage().persistent().set(&listed_key, &index);
+        env.storage().instance().set(&DataKey::ListingCount, &(index + 1));
+
+        env.events().publish((symbol_short!("listed"), ip_contract, ip_id), seller);
+        Ok(index)
+    }
+
+    /// Withdraw a listing (Only callable by the IP's current owner)
+    pub fn delist(env: Env, seller: Address, ip_contract: Address, ip_id: u64) -> Result<(), MarketplaceError> {
+        seller.require_auth();
+        let listed_key = DataKey::Listed(ip_contract.clone(), ip_id);
+        let index: u32 = env.storage().persistent().get(&listed_key).ok_or(MarketplaceError::ListingNotFound)?;
+        if IPLicensingClient::new(&env, &ip_contract).owner_of(&ip_id) != seller {
+            return Err(MarketplaceError::Unauthorized);
+        }
+
+        env.storage().persistent().remove(&listed_key);
+        env.storage().persistent().remove(&DataKey::Listing(index));
+
+        env.events().publish((symbol_short!("delisted"), ip_contract, ip_id), seller);
+        Ok(())
+    }
+
+    /// Live listings across all registered contracts in listing order, scanning
+    /// at most 50 listing slots per call. Withdrawn listings and those of
+    /// removed contracts are skipped, so a page may be shorter than `limit`.
+    pub fn get_listings(env: Env, start: u32, limit: u32) -> Vec<Listing> {
+        let count: u32 = env.storage().instance().get(&DataKey::ListingCount).unwrap_or(0);
+        let cursor = PageCursor::new(start, limit, MAX_PAGE_SIZE, count);
+        let contracts = Self::contracts(env.clone());
+
+        let mut page = Vec::new(&env);
+        for index in cursor.range() {
+            let listing: Option<Listing> = env.storage().persistent().get(&DataKey::Listing(index));
+            if let Some(listing) = listing.filter(|listing| contracts.contains(&listing.ip_contract)) {
+                page.push_back(listing);
+            }
+        }
+        page
+    }
+
+    pub fn get_listing(env: Env, ip_contract: Address, ip_id: u64) -> Option<Listing> {
+        let index: u32 = env.storage().persistent().get(&DataKey::Listed(ip_contract, ip_id))?;
+        env.storage().persistent().get(&DataKey::Listing(index))
+    }
+
+    /// Buy a license from a registered IP contract at its list price plus the
+    /// marketplace fee, refusing if the total exceeds `max_total`. The license
+    /// is issued to `buyer` by the IP contract itself, which also publishes
+    /// `memo` with its purchase event.
+    pub fn purchase(
+        env: Env,
+        buyer: Address,
+        ip_contract: Address,
+        ip_id: u64,
+        license_type: LicenseType,
+        max_total: Option<i128>,
+        memo: Option<String>,
+    ) -> Result<License, MarketplaceError> {
+        buyer.require_auth();
+        Self::require_registered(&env, &ip_contract)?;
+
+        let ip = IPLicensingClient::new(&env, &ip_contract);
+        let (price, payment_token, _) = ip.quote_price(&ip_id, &license_type);
+        let config = Self::read_config(&env);
+        let fee = bps_of(price, config.fee_bps, Rounding::Floor);
+        if max_total.is_some_and(|max_total| price + fee > max_total) {
+            return Err(MarketplaceError::SlippageExceeded);
+        }
+
+        let license = ip.purchase_license(&buyer, &ip_id, &license_type, &None, &Some(price), &u64::MAX, &None, &memo);
+        if fee > 0 {
+            token::Client::new(&env, &payment_token).transfer(&buyer, &config.treasury, &fee);
+        }
+
+        env.events().publish(
+            (symbol_short!("mkt_buy"), ip_contract, ip_id, buyer),
+            (license.license_id, price, fee),
+        );
+        Ok(license)
+    }
+}
+
+impl MarketplaceContract {
+    fn read_config(env: &Env) -> MarketplaceConfig {
+        env.storage().instance().get(&DataKey::Config).unwrap()
+    }
+
+    fn require_admin(env: &Env, admin: &Address) -> Result<MarketplaceConfig, MarketplaceError> {
+        admin.require_auth();
+        let config = Self::read_config(env);
+        if config.admin != *admin {
+            return Err(MarketplaceError::Unauthorized);
+        }
+        Ok(config)
+    }
+
+    fn require_valid_fee(fee_bps: u32) -> Result<(), MarketplaceError> {
+        if fee_bps as i128 > BPS_DENOMINATOR {
+            return Err(MarketplaceError::InvalidFee);
+        }
+        Ok(())
+    }
+
+    fn require_registered(env: &Env, ip_contract: &Address) -> Result<(), MarketplaceError> {
+        if !Self::contracts(env.clone()).contains(ip_contract) {
+            return Err(MarketplaceError::ContractNotRegistered);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,107 @@
+#![cfg(test)]
+
+use super::*;
+use flavorsnap_ip_contract::{IPLicensingContract, IPLicensingContractClient};
+use soroban_sdk::{symbol_short, testutils::Address as _, token, vec, Address, Env, String};
+
+struct Setup<'a> {
+    env: Env,
+    admin: Address,
+    treasury: Address,
+    token: Address,
+    marketplace: MarketplaceContractClient<'a>,
+}
+
+/// A marketplace charging 5% and a payment token
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let marketplace_id = env.register(MarketplaceContract, (admin.clone(), treasury.clone(), 500u32));
+    let marketplace = MarketplaceContractClient::new(&env, &marketplace_id);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    Setup { env, admin, treasury, token, marketplace }
+}
+
+/// An IP licensing contract with fee-free IP 1 owned by `owner`
+fn deploy_ip_contract<'a>(s: &Setup, owner: &Address) -> IPLicensingContractClient<'a> {
+    let ip_id = s.env.register(IPLicensingContract, (Address::generate(&s.env), Address::generate(&s.env), 0u32));
+    let ip = IPLicensingContractClient::new(&s.env, &ip_id);
+    let metadata = String::from_str(&s.env, "ipfs://metadata");
+    ip.register_ip(owner, &1, &metadata, &None, &1_000, &200, &s.token, &vec![&s.env, symbol_short!("dish")]);
+    ip
+}
+
+#[test]
+fn test_error_codes_documented() {
+    let s = setup();
+    for code in 1..=7u32 {
+        let error = MarketplaceError::from_code(code).unwrap();
+        assert_eq!(error as u32, code);
+        assert_eq!(s.marketplace.error_message(&code), String::from_str(&s.env, error.message()));
+    }
+    assert_eq!(MarketplaceError::from_code(8), None);
+}
+
+#[test]
+fn test_listings_span_registered_contracts() {
+    let s = setup();
+    let chef = Address::generate(&s.env);
+    let baker = Address::generate(&s.env);
+    let first = deploy_ip_contract(&s, &chef);
+    let second = deploy_ip_contract(&s, &baker);
+
+    let res = s.marketplace.try_list(&chef, &first.address, &1);
+    assert_eq!(res, Err(Ok(MarketplaceError::ContractNotRegistered)));
+    let res = s.marketplace.try_add_contract(&chef, &first.address);
+    assert_eq!(res, Err(Ok(MarketplaceError::Unauthorized)));
+    s.marketplace.add_contract(&s.admin, &first.address);
+    s.marketplace.add_contract(&s.admin, &second.address);
+    assert_eq!(s.marketplace.try_add_contract(&s.admin, &first.address), Err(Ok(MarketplaceError::AlreadyListed)));
+
+    // Only the IP's owner lists it, once
+    assert_eq!(s.marketplace.try_list(&baker, &first.address, &1), Err(Ok(MarketplaceError::Unauthorized)));
+    s.marketplace.list(&chef, &first.address, &1);
+    s.marketplace.list(&baker, &second.address, &1);
+    assert_eq!(s.marketplace.try_list(&chef, &first.address, &1), Err(Ok(MarketplaceError::AlreadyListed)));
+
+    let listings = s.marketplace.get_listings(&0, &10);
+    assert_eq!(listings.len(), 2);
+    assert_eq!((listings.get(1).unwrap().ip_contract, listings.get(1).unwrap().seller), (second.address.clone(), baker.clone()));
+
+    assert_eq!(s.marketplace.try_delist(&baker, &first.address, &1), Err(Ok(MarketplaceError::Unauthorized)));
+    s.marketplace.delist(&chef, &first.address, &1);
+    assert_eq!(s.marketplace.get_listing(&first.address, &1), None);
+    assert_eq!(s.marketplace.try_delist(&chef, &first.address, &1), Err(Ok(MarketplaceError::ListingNotFound)));
+
+    // Removing a contract hides its listings
+    s.marketplace.remove_contract(&s.admin, &second.address);
+    assert_eq!(s.marketplace.get_listings(&0, &10).len(), 0);
+    assert_eq!(s.marketplace.contracts(), vec![&s.env, first.address.clone()]);
+}
+
+#[test]
+fn test_purchase_forwards_and_charges_fee() {
+    let s = setup();
+    let chef = Address::generate(&s.env);
+    let buyer = Address::generate(&s.env);
+    let ip = deploy_ip_contract(&s, &chef);
+    token::StellarAssetClient::new(&s.env, &s.token).mint(&buyer, &1_000);
+
+    let res = s.marketplace.try_purchase(&buyer, &ip.address, &1, &LicenseType::NonExclusive, &None, &None);
+    assert_eq!(res, Err(Ok(MarketplaceError::ContractNotRegistered)));
+    s.marketplace.add_contract(&s.admin, &ip.address);
+
+    let res = s.marketplace.try_purchase(&buyer, &ip.address, &1, &LicenseType::NonExclusive, &Some(209), &None);
+    assert_eq!(res, Err(Ok(MarketplaceError::SlippageExceeded)));
+    let license = s.marketplace.purchase(&buyer, &ip.address, &1, &LicenseType::NonExclusive, &Some(210), &None);
+    assert_eq!((license.licensee.clone(), license.price_paid), (buyer.clone(), 200));
+
+    let token = token::Client::new(&s.env, &s.token);
+    assert_eq!(token.balance(&buyer), 790);
+    assert_eq!(token.balance(&s.treasury), 10);
+    assert_eq!(ip.get_claimable(&chef, &s.token), 200);
+    assert!(ip.get_license(&1, &buyer).is_active);
+}
@@ -0,0 +1,38 @@
+use soroban_sdk::contracterror;
+
+/// Errors returned by `OracleContract`.
+///
+/// Discriminants are part of the public ABI and must never be renumbered;
+/// new conditions are appended at the end.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum OracleError {
+    /// The caller is not the oracle admin.
+    Unauthorized = 1,
+    /// No feed is configured under this key.
+    FeedNotFound = 2,
+    /// The registry has no public, current, unquarantined entry behind this feed.
+    NoData = 3,
+}
+
+impl OracleError {
+    pub fn from_code(code: u32) -> Option<Self> {
+        let error = match code {
+            1 => OracleError::Unauthorized,
+            2 => OracleError::FeedNotFound,
+            3 => OracleError::NoData,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Short human-readable description, surfaced through `error_message`
+    pub fn message(&self) -> &'static str {
+        match self {
+            OracleError::Unauthorized => "caller is not authorized",
+            OracleError::FeedNotFound => "feed not found",
+            OracleError::NoData => "no data available",
+        }
+    }
+}
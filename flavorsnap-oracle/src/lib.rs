@@ -0,0 +1,133 @@
+#![no_std]
+
+use flavorsnap_common::ConfidenceBps;
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, String, Symbol};
+
+mod errors;
+mod registry;
+
+pub use errors::OracleError;
+use registry::{EntryLookup, FoodRegistryClient};
+
+/// One observation of a feed. `round_id` increases every time the underlying
+/// registry entry is re-verified; `confidence` is in basis points and
+/// `timestamp` is the verification time.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundData {
+    pub round_id: u32,
+    pub value: String,
+    pub confidence: ConfidenceBps,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConfig {
+    pub admin: Address,
+    pub registry: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Config,
+    Feed(Symbol),
+    LatestRound(Symbol),
+    Round(Symbol, u32),
+}
+
+/// Key/value oracle over the food registry: each symbol key names a feed bound
+/// to one registry entry, and its value is that entry's classification.
+#[contract]
+pub struct OracleContract;
+
+#[contractimpl]
+impl OracleContract {
+    /// Set the oracle admin and the food registry it reads from
+    pub fn __constructor(env: Env, admin: Address, registry: Address) {
+        let config = OracleConfig { admin, registry };
+        env.storage().instance().set(&DataKey::Config, &config);
+    }
+
+    pub fn get_config(env: Env) -> OracleConfig {
+        Self::read_config(&env)
+    }
+
+    /// Describe an `OracleError` code returned by this contract
+    pub fn error_message(env: Env, code: u32) -> String {
+        let message = OracleError::from_code(code)
+            .map(|error| error.message())
+            .unwrap_or("unknown error");
+        String::from_str(&env, message)
+    }
+
+    /// Bind `key` to the registry entry for `image_hash` (Only callable by admin).
+    /// Rebinding a key keeps its round history and continues numbering.
+    pub fn set_feed(env: Env, admin: Address, key: Symbol, image_hash: String) -> Result<(), OracleError> {
+        admin.require_auth();
+        if Self::read_config(&env).admin != admin {
+            return Err(OracleError::Unauthorized);
+        }
+
+        env.storage().persistent().set(&DataKey::Feed(key.clone()), &image_hash);
+        env.events().publish((symbol_short!("feed_set"), key), image_hash);
+        Ok(())
+    }
+
+    pub fn get_feed(env: Env, key: Symbol) -> Option<String> {
+        env.storage().persistent().get(&DataKey::Feed(key))
+    }
+
+    /// Pull the feed's entry from the registry, recording a new round if it was
+    /// verified since the last one. Callable by anyone.
+    pub fn update(env: Env, key: Symbol) -> Result<RoundData, OracleError> {
+        let image_hash: String = env.storage().persistent()
+            .get(&DataKey::Feed(key.clone()))
+            .ok_or(OracleError::FeedNotFound)?;
+
+        let registry = FoodRegistryClient::new(&env, &Self::read_config(&env).registry);
+        let entry = match registry.lookup_food_entry(&image_hash) {
+            EntryLookup::Found(entry) if !entry.quarantined => entry,
+            _ => return Err(OracleError::NoData),
+        };
+
+        let latest = Self::latest(env.clone(), key.clone());
+        if let Some(latest) = &latest {
+            if latest.timestamp == entry.timestamp && latest.value == entry.classification {
+                return Ok(latest.clone());
+            }
+        }
+
+        let round = RoundData {
+            round_id: latest.map_or(1, |latest| latest.round_id + 1),
+            value: entry.classification,
+            confidence: entry.confidence,
+            timestamp: entry.timestamp,
+        };
+        env.storage().persistent().set(&DataKey::Round(key.clone(), round.round_id), &round);
+        env.storage().persistent().set(&DataKey::LatestRound(key.clone()), &round.round_id);
+
+        env.events().publish((symbol_short!("round"), key), round.round_id);
+        Ok(round)
+    }
+
+    /// The most recent recorded round of `key`
+    pub fn latest(env: Env, key: Symbol) -> Option<RoundData> {
+        let round_id: u32 = env.storage().persistent().get(&DataKey::LatestRound(key.clone()))?;
+        Self::get_round(env, key, round_id)
+    }
+
+    pub fn get_round(env: Env, key: Symbol, round_id: u32) -> Option<RoundData> {
+        env.storage().persistent().get(&DataKey::Round(key, round_id))
+    }
+}
+
+impl OracleContract {
+    fn read_config(env: &Env) -> OracleConfig {
+        env.storage().instance().get(&DataKey::Config).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test;
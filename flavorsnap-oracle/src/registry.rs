@@ -0,0 +1,38 @@
+use flavorsnap_common::ConfidenceBps;
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env, String};
+
+/// Mirror of the food registry's `FoodEntry`; field names and types must match
+/// the registry's definition for entries to decode.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FoodEntry {
+    pub classification: String,
+    pub category_id: Option<u32>,
+    pub confidence: ConfidenceBps,
+    pub model_id: u32,
+    pub timestamp: u64,
+    pub valid_until: Option<u64>,
+    pub verifier: Address,
+    pub submitter: Address,
+    pub private: bool,
+    pub metadata_hash: Option<BytesN<32>>,
+    pub quarantined: bool,
+}
+
+/// Mirror of the food registry's `EntryLookup`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum EntryLookup {
+    Found(FoodEntry),
+    NotFound,
+    Stale,
+    Private,
+}
+
+/// The subset of `FoodRegistryContract` the oracle reads from
+#[allow(dead_code)]
+#[contractclient(name = "FoodRegistryClient")]
+pub trait FoodRegistry {
+    fn lookup_food_entry(env: Env, image_hash: String) -> EntryLookup;
+}
@@ -0,0 +1,70 @@
+#![cfg(test)]
+
+use super::*;
+use flavorsnap_food_registry::{FoodRegistryContract, FoodRegistryContractClient, Role};
+use soroban_sdk::{testutils::{Address as _, Ledger}, Address, BytesN, Env, String};
+
+#[test]
+fn test_feed_rounds_track_registry_entry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let admin = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let submitter = Address::generate(&env);
+    let registry_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let registry = FoodRegistryContractClient::new(&env, &registry_id);
+    registry.grant_role(&admin, &Role::Verifier, &verifier);
+    let model_id = registry.register_model(
+        &admin,
+        &String::from_str(&env, "v1.0.0"),
+        &BytesN::from_array(&env, &[9; 32]),
+        &String::from_str(&env, "ipfs://model-v1"),
+    );
+
+    let img_hash = String::from_str(&env, "abc123hash");
+    let dish = String::from_str(&env, "Jollof");
+    let cuisine = registry.add_category(&admin, &String::from_str(&env, "West African"), &None);
+    let dish_id = registry.add_category(&admin, &dish, &Some(cuisine));
+    registry.register_food_entry(&verifier, &submitter, &img_hash, &dish_id, &90, &model_id, &None);
+
+    let oracle_id = env.register(OracleContract, (admin.clone(), registry_id.clone()));
+    let client = OracleContractClient::new(&env, &oracle_id);
+    let key = Symbol::new(&env, "lagos_dish_1");
+
+    assert_eq!(client.try_update(&key), Err(Ok(OracleError::FeedNotFound)));
+    let res = client.try_set_feed(&verifier, &key, &img_hash);
+    assert_eq!(res, Err(Ok(OracleError::Unauthorized)));
+    client.set_feed(&admin, &key, &img_hash);
+    assert_eq!(client.latest(&key), None);
+
+    let round = client.update(&key);
+    assert_eq!(round.round_id, 1);
+    assert_eq!(round.value, dish);
+    assert_eq!(round.confidence, 90);
+    assert_eq!(round.timestamp, 1_000);
+
+    // Nothing changed in the registry, so no new round
+    assert_eq!(client.update(&key).round_id, 1);
+
+    env.ledger().with_mut(|li| li.timestamp = 2_000);
+    registry.reverify_entry(&verifier, &img_hash, &97);
+    let round = client.update(&key);
+    assert_eq!(round.round_id, 2);
+    assert_eq!(round.confidence, 97);
+    assert_eq!(client.latest(&key), Some(round));
+    assert_eq!(client.get_round(&key, &1).unwrap().confidence, 90);
+
+    // Private entries are not exposed through the oracle
+    registry.set_entry_private(&submitter, &img_hash, &true);
+    assert_eq!(client.try_update(&key), Err(Ok(OracleError::NoData)));
+
+    // Nor are entries quarantined after their verifier was compromised
+    registry.set_entry_private(&submitter, &img_hash, &false);
+    registry.revoke_verifier(&admin, &verifier, &true, &0);
+    registry.quarantine_entries(&admin, &verifier, &0, &10);
+    assert_eq!(client.try_update(&key), Err(Ok(OracleError::NoData)));
+
+    assert_eq!(client.error_message(&3), String::from_str(&env, "no data available"));
+}
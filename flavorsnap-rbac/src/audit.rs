@@ -0,0 +1,56 @@
+//! Append-only log of privileged actions, shared by every contract that uses roles
+
+use soroban_sdk::{contracttype, Address, Env, Symbol, Val, Vec};
+
+/// One privileged action. `seq` starts at 0 and increases by one per entry;
+/// entries are never rewritten or removed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditEntry {
+    pub seq: u32,
+    pub action: Symbol,
+    pub actor: Address,
+    pub details: Vec<Val>,
+    pub ledger: u32,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuditKey {
+    Count,
+    Entry(u32),
+}
+
+/// Append `action` taken by `actor` to the log and return its sequence number
+pub fn record(env: &Env, action: Symbol, actor: &Address, details: Vec<Val>) -> u32 {
+    let seq: u32 = env.storage().persistent().get(&AuditKey::Count).unwrap_or(0);
+    let entry = AuditEntry {
+        seq,
+        action,
+        actor: actor.clone(),
+        details,
+        ledger: env.ledger().sequence(),
+        timestamp: env.ledger().timestamp(),
+    };
+    env.storage().persistent().set(&AuditKey::Entry(seq), &entry);
+    env.storage().persistent().set(&AuditKey::Count, &(seq + 1));
+    seq
+}
+
+/// Number of entries recorded so far
+pub fn len(env: &Env) -> u32 {
+    env.storage().persistent().get(&AuditKey::Count).unwrap_or(0)
+}
+
+/// Up to `limit` entries starting at sequence number `start`
+pub fn read(env: &Env, start: u32, limit: u32) -> Vec<AuditEntry> {
+    let end = len(env).min(start.saturating_add(limit));
+    let mut entries = Vec::new(env);
+    for seq in start..end {
+        if let Some(entry) = env.storage().persistent().get(&AuditKey::Entry(seq)) {
+            entries.push_back(entry);
+        }
+    }
+    entries
+}
@@ -0,0 +1,127 @@
+#![no_std]
+
+use soroban_sdk::{contracttype, symbol_short, Address, Env};
+
+pub mod audit;
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Role {
+    Admin,
+    Verifier,
+    Pauser,
+    FeeManager,
+    Arbiter,
+    /// Read-only access to restricted diagnostic views; grants no mutation rights
+    Auditor,
+    /// Confirms external ownership proofs attached to registered IPs
+    Attestor,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RoleKey {
+    Member(Role, Address),
+    /// Membership scoped to one tenant of a multi-tenant contract
+    TenantMember(u32, Role, Address),
+    /// Number of accounts holding a contract-wide role
+    Count(Role),
+}
+
+/// Check whether `account` currently holds `role`
+pub fn has_role(env: &Env, role: Role, account: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&RoleKey::Member(role, account.clone()))
+}
+
+/// Check whether `account` may read restricted diagnostic views, as an Admin
+/// or an Auditor
+pub fn can_audit(env: &Env, account: &Address) -> bool {
+    has_role(env, Role::Admin, account) || has_role(env, Role::Auditor, account)
+}
+
+/// Number of accounts currently holding `role` contract-wide
+pub fn role_count(env: &Env, role: Role) -> u32 {
+    env.storage().persistent().get(&RoleKey::Count(role)).unwrap_or(0)
+}
+
+/// Whether revoking `role` from `account` would leave the contract without an
+/// Admin, locking every admin-gated entrypoint for good
+pub fn is_last_admin(env: &Env, role: Role, account: &Address) -> bool {
+    role == Role::Admin && has_role(env, role, account) && role_count(env, role) <= 1
+}
+
+/// Grant `role` to `account`, emitting `role_grnt` if it was not already held
+pub fn grant_role(env: &Env, role: Role, account: &Address) {
+    let key = RoleKey::Member(role, account.clone());
+    if env.storage().persistent().has(&key) {
+        return;
+    }
+    env.storage().persistent().set(&key, &true);
+    env.storage().persistent().set(&RoleKey::Count(role), &(role_count(env, role) + 1));
+
+    env.events().publish(
+        (symbol_short!("role_grnt"), account.clone()),
+        role
+    );
+}
+
+/// Revoke `role` from `account`, emitting `role_rvk` if it was held. Panics
+/// if it is the last Admin; contracts check `is_last_admin` first to fail
+/// with their own error.
+pub fn revoke_role(env: &Env, role: Role, account: &Address) {
+    let key = RoleKey::Member(role, account.clone());
+    if !env.storage().persistent().has(&key) {
+        return;
+    }
+    assert!(!is_last_admin(env, role, account), "cannot revoke the last admin");
+    env.storage().persistent().remove(&key);
+    env.storage().persistent().set(&RoleKey::Count(role), &role_count(env, role).saturating_sub(1));
+
+    env.events().publish(
+        (symbol_short!("role_rvk"), account.clone()),
+        role
+    );
+}
+
+/// Check whether `account` holds `role` within `tenant`. Tenant roles are
+/// independent of the contract-wide ones checked by `has_role`.
+pub fn has_tenant_role(env: &Env, tenant: u32, role: Role, account: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&RoleKey::TenantMember(tenant, role, account.clone()))
+}
+
+/// Grant `role` within `tenant`, emitting `role_grnt` with the tenant as an
+/// extra topic if it was not already held
+pub fn grant_tenant_role(env: &Env, tenant: u32, role: Role, account: &Address) {
+    let key = RoleKey::TenantMember(tenant, role, account.clone());
+    if env.storage().persistent().has(&key) {
+        return;
+    }
+    env.storage().persistent().set(&key, &true);
+
+    env.events().publish(
+        (symbol_short!("role_grnt"), account.clone(), tenant),
+        role
+    );
+}
+
+/// Revoke `role` within `tenant`, emitting `role_rvk` with the tenant as an
+/// extra topic if it was held
+pub fn revoke_tenant_role(env: &Env, tenant: u32, role: Role, account: &Address) {
+    let key = RoleKey::TenantMember(tenant, role, account.clone());
+    if !env.storage().persistent().has(&key) {
+        return;
+    }
+    env.storage().persistent().remove(&key);
+
+    env.events().publish(
+        (symbol_short!("role_rvk"), account.clone(), tenant),
+        role
+    );
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,98 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{contract, testutils::Address as _, Address, Env, Vec};
+
+#[contract]
+pub struct HostContract;
+
+#[test]
+fn test_grant_and_revoke_role() {
+    let env = Env::default();
+    let contract_id = env.register(HostContract, ());
+    let account = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        assert!(!has_role(&env, Role::Verifier, &account));
+
+        grant_role(&env, Role::Verifier, &account);
+        assert!(has_role(&env, Role::Verifier, &account));
+        assert!(!has_role(&env, Role::Admin, &account));
+
+        revoke_role(&env, Role::Verifier, &account);
+        assert!(!has_role(&env, Role::Verifier, &account));
+    });
+}
+
+#[test]
+fn test_last_admin_cannot_be_revoked() {
+    let env = Env::default();
+    let contract_id = env.register(HostContract, ());
+    let (first, second) = (Address::generate(&env), Address::generate(&env));
+
+    env.as_contract(&contract_id, || {
+        grant_role(&env, Role::Admin, &first);
+        grant_role(&env, Role::Admin, &first);
+        assert_eq!(role_count(&env, Role::Admin), 1);
+        assert!(is_last_admin(&env, Role::Admin, &first));
+        assert!(!is_last_admin(&env, Role::Admin, &second));
+
+        grant_role(&env, Role::Admin, &second);
+        assert!(!is_last_admin(&env, Role::Admin, &first));
+        revoke_role(&env, Role::Admin, &first);
+        revoke_role(&env, Role::Admin, &first);
+        assert_eq!(role_count(&env, Role::Admin), 1);
+        assert!(is_last_admin(&env, Role::Admin, &second));
+    });
+}
+
+#[test]
+#[should_panic(expected = "cannot revoke the last admin")]
+fn test_revoking_last_admin_panics() {
+    let env = Env::default();
+    let contract_id = env.register(HostContract, ());
+    let admin = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        grant_role(&env, Role::Admin, &admin);
+        revoke_role(&env, Role::Admin, &admin);
+    });
+}
+
+#[test]
+fn test_tenant_roles_are_scoped() {
+    let env = Env::default();
+    let contract_id = env.register(HostContract, ());
+    let account = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        grant_tenant_role(&env, 1, Role::Verifier, &account);
+        assert!(has_tenant_role(&env, 1, Role::Verifier, &account));
+        assert!(!has_tenant_role(&env, 2, Role::Verifier, &account));
+        assert!(!has_role(&env, Role::Verifier, &account));
+
+        revoke_tenant_role(&env, 1, Role::Verifier, &account);
+        assert!(!has_tenant_role(&env, 1, Role::Verifier, &account));
+    });
+}
+
+#[test]
+fn test_audit_log_is_sequenced() {
+    let env = Env::default();
+    let contract_id = env.register(HostContract, ());
+    let admin = Address::generate(&env);
+
+    env.as_contract(&contract_id, || {
+        assert_eq!(audit::record(&env, symbol_short!("pause"), &admin, Vec::new(&env)), 0);
+        assert_eq!(audit::record(&env, symbol_short!("unpause"), &admin, Vec::new(&env)), 1);
+        assert_eq!(audit::len(&env), 2);
+
+        let log = audit::read(&env, 1, 10);
+        assert_eq!(log.len(), 1);
+        let entry = log.get(0).unwrap();
+        assert_eq!(entry.seq, 1);
+        assert_eq!(entry.action, symbol_short!("unpause"));
+        assert_eq!(entry.actor, admin);
+        assert!(audit::read(&env, 5, 10).is_empty());
+    });
+}
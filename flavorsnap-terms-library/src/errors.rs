@@ -0,0 +1,38 @@
+use soroban_sdk::contracterror;
+
+/// Errors returned by `TermsLibraryContract`.
+///
+/// Discriminants are part of the public ABI and must never be renumbered;
+/// new conditions are appended at the end.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TermsLibraryError {
+    /// No template was published under this id.
+    TemplateNotFound = 1,
+    /// A template with the same terms hash is already published.
+    DuplicateTemplate = 2,
+    /// The template URI is empty or longer than `MAX_URI_LEN` bytes.
+    InvalidUri = 3,
+}
+
+impl TermsLibraryError {
+    pub fn from_code(code: u32) -> Option<Self> {
+        let error = match code {
+            1 => TermsLibraryError::TemplateNotFound,
+            2 => TermsLibraryError::DuplicateTemplate,
+            3 => TermsLibraryError::InvalidUri,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Short human-readable description, surfaced through `error_message`
+    pub fn message(&self) -> &'static str {
+        match self {
+            TermsLibraryError::TemplateNotFound => "template not found",
+            TermsLibraryError::DuplicateTemplate => "template already published",
+            TermsLibraryError::InvalidUri => "invalid template uri",
+        }
+    }
+}
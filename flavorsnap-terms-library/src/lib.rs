@@ -0,0 +1,116 @@
+#![no_std]
+
+use flavorsnap_common::PageCursor;
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env, String, Vec};
+
+mod errors;
+
+pub use errors::TermsLibraryError;
+
+/// Longest human-readable terms URI accepted, in bytes
+pub const MAX_URI_LEN: u32 = 256;
+const MAX_PAGE_SIZE: u32 = 50;
+
+/// A reusable set of license terms. Templates are immutable once published,
+/// so an IP referencing one keeps the exact deal shape buyers were shown.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TermsTemplate {
+    pub template_id: u32,
+    /// sha256 of the canonical terms document
+    pub terms_hash: BytesN<32>,
+    pub uri: String,
+    pub creator: Address,
+    pub published_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    TemplateCount,
+    Template(u32),
+    /// Id of the template publishing a terms hash
+    TemplateByHash(BytesN<32>),
+}
+
+/// Shared library of standard license terms that IP owners reference by id
+/// instead of drafting bespoke terms for every asset.
+#[contract]
+pub struct TermsLibraryContract;
+
+#[contractimpl]
+impl TermsLibraryContract {
+    /// Describe a `TermsLibraryError` code returned by this contract
+    pub fn error_message(env: Env, code: u32) -> String {
+        let message = TermsLibraryError::from_code(code)
+            .map(|error| error.message())
+            .unwrap_or("unknown error");
+        String::from_str(&env, message)
+    }
+
+    /// Publish a terms template, returning its id. Ids start at 1 and each
+    /// terms hash can be published once.
+    pub fn publish_template(
+        env: Env,
+        creator: Address,
+        terms_hash: BytesN<32>,
+        uri: String,
+    ) -> Result<u32, TermsLibraryError> {
+        creator.require_auth();
+        if uri.is_empty() || uri.len() > MAX_URI_LEN {
+            return Err(TermsLibraryError::InvalidUri);
+        }
+        let hash_key = DataKey::TemplateByHash(terms_hash.clone());
+        if env.storage().persistent().has(&hash_key) {
+            return Err(TermsLibraryError::DuplicateTemplate);
+        }
+
+        let template_id = Self::template_count(env.clone()) + 1;
+        let template = TermsTemplate {
+            template_id,
+            terms_hash,
+            uri,
+            creator: creator.clone(),
+            published_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&DataKey::Template(template_id), &template);
+        env.storage().persistent().set(&hash_key, &template_id);
+        env.storage().instance().set(&DataKey::TemplateCount, &template_id);
+
+        env.events().publish((symbol_short!("tpl_pub"), template_id), (creator, template.terms_hash));
+        Ok(template_id)
+    }
+
+    pub fn get_template(env: Env, template_id: u32) -> Result<TermsTemplate, TermsLibraryError> {
+        Self::lookup_template(env, template_id).ok_or(TermsLibraryError::TemplateNotFound)
+    }
+
+    pub fn lookup_template(env: Env, template_id: u32) -> Option<TermsTemplate> {
+        env.storage().persistent().get(&DataKey::Template(template_id))
+    }
+
+    /// Id of the template publishing `terms_hash`, if any
+    pub fn template_by_hash(env: Env, terms_hash: BytesN<32>) -> Option<u32> {
+        env.storage().persistent().get(&DataKey::TemplateByHash(terms_hash))
+    }
+
+    pub fn template_count(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::TemplateCount).unwrap_or(0)
+    }
+
+    /// Templates in publication order, at most 50 per call, starting from the
+    /// `start`-th published (0 for the first)
+    pub fn get_templates(env: Env, start: u32, limit: u32) -> Vec<TermsTemplate> {
+        let cursor = PageCursor::new(start, limit, MAX_PAGE_SIZE, Self::template_count(env.clone()));
+        let mut page = Vec::new(&env);
+        for index in cursor.range() {
+            if let Some(template) = env.storage().persistent().get(&DataKey::Template(index + 1)) {
+                page.push_back(template);
+            }
+        }
+        page
+    }
+}
+
+#[cfg(test)]
+mod test;
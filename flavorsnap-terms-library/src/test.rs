@@ -0,0 +1,38 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String};
+
+#[test]
+fn test_templates_publish_once_per_terms_hash() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let creator = Address::generate(&env);
+    let library_id = env.register(TermsLibraryContract, ());
+    let library = TermsLibraryContractClient::new(&env, &library_id);
+
+    let cc_by = BytesN::from_array(&env, &[1; 32]);
+    let uri = String::from_str(&env, "ipfs://terms/cc-by");
+    assert_eq!(library.publish_template(&creator, &cc_by, &uri), 1);
+    let commercial = BytesN::from_array(&env, &[2; 32]);
+    assert_eq!(library.publish_template(&creator, &commercial, &String::from_str(&env, "ipfs://terms/commercial")), 2);
+
+    assert_eq!(
+        library.try_publish_template(&creator, &cc_by, &String::from_str(&env, "ipfs://terms/copy")),
+        Err(Ok(TermsLibraryError::DuplicateTemplate))
+    );
+    let empty = String::from_str(&env, "");
+    assert_eq!(library.try_publish_template(&creator, &BytesN::from_array(&env, &[3; 32]), &empty), Err(Ok(TermsLibraryError::InvalidUri)));
+
+    let template = library.get_template(&1);
+    assert_eq!((template.terms_hash, template.uri, template.creator), (cc_by.clone(), uri, creator));
+    assert_eq!(library.template_by_hash(&cc_by), Some(1));
+    assert_eq!(library.try_get_template(&3), Err(Ok(TermsLibraryError::TemplateNotFound)));
+    assert_eq!(library.lookup_template(&3), None);
+
+    assert_eq!(library.template_count(), 2);
+    let page = library.get_templates(&1, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get_unchecked(0).template_id, 2);
+}
@@ -0,0 +1,119 @@
+extern crate std;
+
+use ed25519_dalek::{Signer, SigningKey};
+use flavorsnap_account::{AccountSignature, CreatorAccountContract, CreatorAccountContractClient};
+use flavorsnap_ip_contract::{IPLicensingContract, IPLicensingContractClient, PreviewTerms};
+use soroban_sdk::{
+    symbol_short, testutils::{Address as _, Ledger}, vec, xdr::{
+        self, HashIdPreimage, HashIdPreimageSorobanAuthorization, InvokeContractArgs, Limits, ScVal,
+        SorobanAddressCredentials, SorobanAuthorizationEntry, SorobanAuthorizedFunction, SorobanAuthorizedInvocation,
+        SorobanCredentials, WriteXdr,
+    }, Address, Bytes, BytesN, Env, IntoVal, String, TryFromVal, Val, Vec,
+};
+
+fn public_key(env: &Env, key: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &key.verifying_key().to_bytes())
+}
+
+/// Authorization entry for `account` calling `function(args)` on `contract`,
+/// signed by `keys` the way a wallet would submit it with the transaction
+fn account_auth(
+    env: &Env,
+    account: &Address,
+    keys: &[&SigningKey],
+    nonce: i64,
+    contract: &Address,
+    function: &str,
+    args: Vec<Val>,
+) -> SorobanAuthorizationEntry {
+    let args: std::vec::Vec<ScVal> = args.iter().map(|arg| ScVal::try_from_val(env, &arg).unwrap()).collect();
+    let invocation = SorobanAuthorizedInvocation {
+        function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+            contract_address: contract.into(),
+            function_name: function.try_into().unwrap(),
+            args: args.try_into().unwrap(),
+        }),
+        sub_invocations: Default::default(),
+    };
+    let signature_expiration_ledger = env.ledger().sequence() + 100;
+
+    // The payload the host hands to `__check_auth`
+    let preimage = HashIdPreimage::SorobanAuthorization(HashIdPreimageSorobanAuthorization {
+        network_id: xdr::Hash(env.ledger().network_id().to_array()),
+        nonce,
+        signature_expiration_ledger,
+        invocation: invocation.clone(),
+    });
+    let payload = env.crypto().sha256(&Bytes::from_slice(env, &preimage.to_xdr(Limits::none()).unwrap())).to_array();
+
+    let mut keys = keys.to_vec();
+    keys.sort_by_key(|key| key.verifying_key().to_bytes());
+    let mut signatures = Vec::new(env);
+    for key in keys {
+        signatures.push_back(AccountSignature {
+            public_key: public_key(env, key),
+            signature: BytesN::from_array(env, &key.sign(&payload).to_bytes()),
+        });
+    }
+
+    SorobanAuthorizationEntry {
+        credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+            address: account.into(),
+            nonce,
+            signature_expiration_ledger,
+            signature: ScVal::try_from_val(env, &signatures.to_val()).unwrap(),
+        }),
+        root_invocation: invocation,
+    }
+}
+
+#[test]
+fn test_creator_account_owns_ip_with_two_of_three_keys_and_recovers_a_lost_one() {
+    let env = Env::default();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let ip_id = env.register(IPLicensingContract, (admin, treasury, 250u32));
+    let ip = IPLicensingContractClient::new(&env, &ip_id);
+    let token_id = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+
+    // The creator's phone, an offline backup and a trusted guardian guard the account
+    let phone = SigningKey::from_bytes(&[1; 32]);
+    let backup = SigningKey::from_bytes(&[2; 32]);
+    let guardian = SigningKey::from_bytes(&[3; 32]);
+    let signers = vec![&env, public_key(&env, &phone), public_key(&env, &backup), public_key(&env, &guardian)];
+    let account_id = env.register(CreatorAccountContract, (signers,));
+    let account = CreatorAccountContractClient::new(&env, &account_id);
+
+    // Register: the account owns the IP like any other address, phone and guardian signing
+    let uri = String::from_str(&env, "flavorsnap://suya-photo-1");
+    let tags = vec![&env, symbol_short!("suya")];
+    let args = (account_id.clone(), 1u64, uri.clone(), None::<BytesN<32>>, 5_000i128, 1_000i128, token_id.clone(), tags.clone());
+    env.set_auths(&[account_auth(&env, &account_id, &[&phone, &guardian], 1, &ip_id, "register_ip", args.into_val(&env))]);
+    ip.register_ip(&account_id, &1, &uri, &None, &5_000, &1_000, &token_id, &tags);
+    assert_eq!(ip.get_ip(&1).owner, account_id);
+
+    // Owner approval: a single key is not enough, whichever it is
+    let approvers = vec![&env, account_id.clone()];
+    let terms = Some(PreviewTerms { fee: 50, duration_secs: 3_600 });
+    let args: Vec<Val> = (approvers.clone(), 1u64, terms.clone()).into_val(&env);
+    env.set_auths(&[account_auth(&env, &account_id, &[&phone], 2, &ip_id, "set_preview_terms", args.clone())]);
+    assert!(ip.try_set_preview_terms(&approvers, &1, &terms).is_err());
+    assert_eq!(ip.get_preview_terms(&1), None);
+
+    // Recovery: the phone is lost, so the backup and guardian swap in a new key
+    let new_phone = SigningKey::from_bytes(&[4; 32]);
+    let replace_args: Vec<Val> = (public_key(&env, &phone), public_key(&env, &new_phone)).into_val(&env);
+    env.set_auths(&[account_auth(&env, &account_id, &[&backup, &guardian], 3, &account_id, "replace_signer", replace_args)]);
+    account.replace_signer(&public_key(&env, &phone), &public_key(&env, &new_phone));
+    assert!(account.signers().contains(public_key(&env, &new_phone)));
+
+    // Whoever holds the lost phone can no longer act for the creator, even with a second key
+    env.set_auths(&[account_auth(&env, &account_id, &[&phone, &backup], 4, &ip_id, "set_preview_terms", args.clone())]);
+    assert!(ip.try_set_preview_terms(&approvers, &1, &terms).is_err());
+
+    env.set_auths(&[account_auth(&env, &account_id, &[&new_phone, &backup], 5, &ip_id, "set_preview_terms", args)]);
+    ip.set_preview_terms(&approvers, &1, &terms);
+    assert_eq!(ip.get_preview_terms(&1), terms);
+}
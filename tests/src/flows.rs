@@ -0,0 +1,118 @@
+use flavorsnap_creator_vault::{CreatorVaultContract, CreatorVaultContractClient, Split};
+use flavorsnap_food_registry::{FoodRegistryContract, FoodRegistryContractClient};
+use flavorsnap_ip_contract::{IPError, IPLicensingContract, IPLicensingContractClient, LicenseType, Role};
+use soroban_sdk::{
+    symbol_short, testutils::{Address as _, Events, Ledger}, token, vec, Address, BytesN, Env, FromVal, IntoVal,
+    String, Val, Vec,
+};
+
+/// Topics and data of the last event `contract` published in the latest invocation
+fn last_event(env: &Env, contract: &Address) -> (Vec<Val>, Val) {
+    let event = env.events().all().iter().filter(|event| event.0 == *contract).last().unwrap();
+    (event.1, event.2)
+}
+
+#[test]
+fn test_verified_dish_licensed_paid_into_vault_then_challenged() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let editor = Address::generate(&env);
+    let studio = Address::generate(&env);
+
+    let registry_id = env.register(FoodRegistryContract, (admin.clone(),));
+    let registry = FoodRegistryContractClient::new(&env, &registry_id);
+    let ip_id = env.register(IPLicensingContract, (admin.clone(), treasury.clone(), 250u32));
+    let ip = IPLicensingContractClient::new(&env, &ip_id);
+    let vault_id = env.register(CreatorVaultContract, (creator.clone(),));
+    let vault = CreatorVaultContractClient::new(&env, &vault_id);
+    let token_id = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token = token::Client::new(&env, &token_id);
+    token::StellarAssetClient::new(&env, &token_id).mint(&studio, &10_000);
+
+    // Register: the creator's photo is classified by a verifier
+    registry.grant_role(&admin, &Role::Verifier, &verifier);
+    registry.grant_role(&admin, &Role::Arbiter, &arbiter);
+    let model_id = registry.register_model(
+        &admin,
+        &String::from_str(&env, "v1.0.0"),
+        &BytesN::from_array(&env, &[9; 32]),
+        &String::from_str(&env, "ipfs://model-v1"),
+    );
+    let cuisine = registry.add_category(&admin, &String::from_str(&env, "West African"), &None);
+    let dish = String::from_str(&env, "Jollof");
+    let dish_id = registry.add_category(&admin, &dish, &Some(cuisine));
+    let image_hash = String::from_str(&env, "jollof-photo-1");
+    let metadata_hash = BytesN::from_array(&env, &[7; 32]);
+    registry.register_food_entry(&verifier, &creator, &image_hash, &dish_id, &9_000, &model_id, &Some(metadata_hash.clone()));
+
+    // License dataset: the verified entry is offered as IP with the same metadata
+    ip.register_ip(
+        &creator,
+        &1,
+        &String::from_str(&env, "flavorsnap://jollof-photo-1"),
+        &Some(metadata_hash),
+        &5_000,
+        &1_000,
+        &token_id,
+        &vec![&env, symbol_short!("jollof")],
+    );
+    let listed = ip.get_ip(&1);
+    assert!(registry.verify_metadata(&image_hash, &listed.metadata_hash.unwrap()));
+    assert_eq!(registry.get_food_entry(&image_hash).submitter, listed.owner);
+
+    let splits = vec![
+        &env,
+        Split { member: creator.clone(), bps: 8_000 },
+        Split { member: editor.clone(), bps: 2_000 },
+    ];
+    vault.set_splits(&creator, &splits);
+    ip.set_revenue_recipient(&vec![&env, creator.clone()], &1, &Some(vault_id.clone()), &true);
+
+    let license = ip.purchase_license(&studio, &1, &LicenseType::NonExclusive, &None, &None, &u64::MAX, &None, &None);
+    assert_eq!(license.price_paid, 1_000);
+    let (topics, data) = last_event(&env, &ip_id);
+    assert_eq!(topics, (symbol_short!("purchase"), 1u64, studio.clone()).into_val(&env));
+    assert_eq!(<(u64, i128, Option<u32>)>::from_val(&env, &data), (license.license_id, 1_000, None));
+    let (topics, data) = last_event(&env, &vault_id);
+    assert_eq!(topics, (symbol_short!("deposit"), ip_id.clone(), 1u64).into_val(&env));
+    assert_eq!(<(Address, i128)>::from_val(&env, &data), (token_id.clone(), 975));
+
+    // Pay royalties: net of the platform fee, they land in the vault too
+    ip.pay_usage_royalty(&studio, &studio, &1, &400, &BytesN::from_array(&env, &[1; 32]), &None, &None);
+    let (topics, data) = last_event(&env, &ip_id);
+    assert_eq!(topics, (symbol_short!("royalty"), 1u64, studio.clone()).into_val(&env));
+    assert_eq!(i128::from_val(&env, &data), 400);
+
+    assert_eq!(token.balance(&studio), 8_600);
+    assert_eq!(token.balance(&treasury), 35);
+    assert_eq!(token.balance(&vault_id), 1_365);
+    assert_eq!(token.balance(&ip_id), 0);
+    assert_eq!(vault.ip_revenue(&ip_id, &1, &token_id), 1_365);
+    assert_eq!(vault.balance(&creator, &token_id), 1_092);
+    assert_eq!(vault.balance(&editor, &token_id), 273);
+    vault.withdraw(&editor, &token_id, &273);
+    assert_eq!(token.balance(&editor), 273);
+
+    // Challenge: an upheld challenge on the registry leads the platform to hold the IP
+    registry.uphold_challenge(&arbiter, &image_hash);
+    let (topics, _) = last_event(&env, &registry_id);
+    assert_eq!(topics, (symbol_short!("chal_up"), dish.clone(), image_hash.clone()).into_val(&env));
+    assert_eq!(registry.get_category_stats(&dish).successful_challenges, 1);
+
+    let reason = String::from_str(&env, "classification challenge upheld");
+    ip.freeze_ip(&admin, &1, &reason);
+    let (topics, _) = last_event(&env, &ip_id);
+    assert_eq!(topics, (symbol_short!("ip_frz"), 1u64).into_val(&env));
+
+    let res = ip.try_pay_usage_royalty(&studio, &studio, &1, &400, &BytesN::from_array(&env, &[1; 32]), &None, &None);
+    assert_eq!(res, Err(Ok(IPError::IPFrozen)));
+    assert_eq!(token.balance(&studio), 8_600);
+    assert_eq!(vault.ip_revenue(&ip_id, &1, &token_id), 1_365);
+}
@@ -0,0 +1,10 @@
+#![no_std]
+
+//! End-to-end flows deploying the food registry, IP licensing contract,
+//! creator vault, creator account and a token into one environment. Unit tests in each crate
+//! exercise one contract; these catch drift in the interfaces between them.
+
+#[cfg(test)]
+mod accounts;
+#[cfg(test)]
+mod flows;